@@ -1,4 +1,5 @@
 use codespan_reporting::files::{SimpleFile, SimpleFiles};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 use crate::FileManager;
@@ -34,7 +35,7 @@ impl From<&PathBuf> for PathString {
 pub struct FileMap(SimpleFiles<PathString, String>);
 
 // XXX: Note that we derive Default here due to ModuleOrigin requiring us to set a FileId
-#[derive(Default, Debug, Clone, PartialEq, Eq, Copy, Hash)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Copy, Hash, Serialize, Deserialize)]
 pub struct FileId(usize);
 
 impl FileId {
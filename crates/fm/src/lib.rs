@@ -33,9 +33,19 @@ pub struct FileManager {
     file_map: file_map::FileMap,
     id_to_path: HashMap<FileId, VirtualPath>,
     path_to_id: HashMap<VirtualPath, FileId>,
+    /// Contents to use in place of the files on disk, such as unsaved buffers in an editor.
+    overlays: HashMap<PathBuf, String>,
 }
 
 impl FileManager {
+    /// Uses `source` as the contents of the file at `path_to_file` rather than reading it from disk.
+    ///
+    /// Overlays must be added before the file is added to the file manager.
+    pub fn add_overlay(&mut self, path_to_file: &Path, source: String) {
+        let path = path_to_file.canonicalize().unwrap_or_else(|_| path_to_file.to_path_buf());
+        self.overlays.insert(path, source);
+    }
+
     // XXX: Maybe use a AsRef<Path> here, for API ergonomics
     pub fn add_file(&mut self, path_to_file: &Path, file_type: FileType) -> Option<FileId> {
         // Handle both relative file paths and std/lib virtual paths.
@@ -49,8 +59,11 @@ impl FileManager {
             return Some(*file_id);
         }
 
-        // Otherwise we add the file
-        let source = file_reader::read_file_to_string(resolved_path).ok()?;
+        // Otherwise we add the file, preferring any overlay to the contents on disk
+        let source = match self.overlays.get(&res) {
+            Some(source) => source.clone(),
+            None => file_reader::read_file_to_string(resolved_path).ok()?,
+        };
         let file_id = self.file_map.add_file(resolved_path.to_path_buf().into(), source);
         self.register_path(file_id, path_to_file);
         Some(file_id)
@@ -66,6 +79,18 @@ impl FileManager {
         assert!(old_value.is_none(), "ice: the same path was inserted into the file manager twice");
     }
 
+    /// Returns the id of the file at `path_to_file`, if it has been added to the file manager.
+    pub fn find_file(&self, path_to_file: &Path) -> Option<FileId> {
+        let path = path_to_file.canonicalize().unwrap_or_else(|_| path_to_file.to_path_buf());
+        let files = self.as_simple_files();
+        self.id_to_path.keys().copied().find(|file_id| {
+            let file =
+                files.get(file_id.as_usize()).expect("ice: file ids are always in the file map");
+            let name = PathBuf::from(file.name().to_string());
+            name.canonicalize().unwrap_or(name) == path
+        })
+    }
+
     pub fn fetch_file(&mut self, file_id: FileId) -> File {
         // Unwrap as we ensure that all file_id's map to a corresponding file in the file map
         self.file_map.get_file(file_id).unwrap()
@@ -137,6 +162,31 @@ mod tests {
         let _foo_file_path = dummy_file_path(&dir, "foo.nr");
         fm.resolve_path(file_id, "foo").unwrap();
     }
+    #[test]
+    fn overlay_replaces_file_contents() {
+        let dir = tempdir().unwrap();
+        let file_path = dummy_file_path(&dir, "my_dummy_file.nr");
+
+        let mut fm = FileManager::default();
+        fm.add_overlay(&file_path, "fn main() {}".to_owned());
+
+        let file_id = fm.add_file(&file_path, FileType::Root).unwrap();
+        assert_eq!(fm.fetch_file(file_id).source(), "fn main() {}");
+    }
+
+    #[test]
+    fn find_file_returns_id_of_added_file() {
+        let dir = tempdir().unwrap();
+        let file_path = dummy_file_path(&dir, "my_dummy_file.nr");
+        let other_path = dummy_file_path(&dir, "other_file.nr");
+
+        let mut fm = FileManager::default();
+        let file_id = fm.add_file(&file_path, FileType::Root).unwrap();
+
+        assert_eq!(fm.find_file(&file_path), Some(file_id));
+        assert_eq!(fm.find_file(&other_path), None);
+    }
+
     #[test]
     fn path_resolve_file_module_other_ext() {
         let dir = tempdir().unwrap();
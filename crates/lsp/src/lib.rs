@@ -1,8 +1,12 @@
 use std::{
-    future::Future,
+    collections::{HashMap, HashSet},
+    future::{self, Future},
     ops::{self, ControlFlow},
+    path::{Path, PathBuf},
     pin::Pin,
+    rc::Rc,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use async_lsp::{
@@ -14,49 +18,112 @@ use lsp_types::{
     notification, request, CodeLens, CodeLensOptions, CodeLensParams, Command, Diagnostic,
     DiagnosticSeverity, DidChangeConfigurationParams, DidChangeTextDocumentParams,
     DidCloseTextDocumentParams, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
-    InitializeParams, InitializeResult, InitializedParams, Position, PublishDiagnosticsParams,
-    Range, ServerCapabilities, TextDocumentSyncOptions,
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, InitializeParams, InitializeResult, InitializedParams, MarkupContent,
+    MarkupKind, MessageType, OneOf, Position, PublishDiagnosticsParams, Range, ReferenceParams,
+    ServerCapabilities, ShowMessageParams, TextDocumentSyncKind, TextDocumentSyncOptions, Url,
+};
+use noirc_driver::{Driver, FunctionNameMatch};
+use noirc_errors::{DiagnosticKind, FileDiagnostic, Location, Span};
+use noirc_frontend::{
+    graph::CrateType,
+    node_interner::{DefinitionId, NodeInterner},
+    Type,
 };
-use noirc_driver::Driver;
-use noirc_errors::{DiagnosticKind, FileDiagnostic};
-use noirc_frontend::graph::CrateType;
 use serde_json::Value as JsonValue;
 use tower::Service;
 
 const TEST_COMMAND: &str = "nargo.test";
 const TEST_CODELENS_TITLE: &str = "▶\u{fe0e} Run Test";
 
+/// Time to wait after a document changes before checking it, so that
+/// large projects aren't checked again on every keystroke.
+const DIAGNOSTICS_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Locates the packages which documents belong to, so that they are checked along with the
+/// rest of their package and its dependencies.
+pub trait PackageResolver {
+    /// Returns the root directory of the package containing `file_path`, resolving its dependencies,
+    /// or `None` if the file isn't part of a package.
+    fn resolve_package(&mut self, file_path: &Path) -> Result<Option<PathBuf>, String>;
+
+    /// Adds the crate of the package in `root_dir`, which has already been resolved,
+    /// to `driver` along with the crates of its dependencies.
+    fn add_package(&self, root_dir: &Path, driver: &mut Driver);
+}
+
+/// The crate which a document is checked as part of.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum CrateRoot {
+    /// The document belongs to the package in this directory
+    Package(PathBuf),
+    /// The document isn't part of a package so it is checked as a binary crate of its own
+    File(PathBuf),
+}
+
 // State for the LSP gets implemented on this struct and is internal to the implementation
-#[derive(Debug)]
 struct LspState {
     client: ClientSocket,
+    resolver: Box<dyn PackageResolver>,
+    /// The contents of each document open in the editor, which may not have been saved to disk
+    open_documents: HashMap<Url, String>,
+    /// Incremented whenever the contents of an open document change
+    documents_version: u64,
+    /// The crate each document belongs to, which is only resolved once per document
+    document_roots: HashMap<Url, CrateRoot>,
+    /// The last checked driver of each crate, along with the documents version it was checked at
+    checked_crates: HashMap<CrateRoot, (u64, Rc<Driver>)>,
+    /// Documents which diagnostics were last published for when checking each crate,
+    /// so that they can be cleared once fixed
+    documents_with_diagnostics: HashMap<CrateRoot, HashSet<Url>>,
+    /// Incremented on each change to a document so that outdated checks of it can be skipped
+    change_generations: HashMap<Url, u64>,
 }
 
 impl LspState {
-    fn new(client: &ClientSocket) -> Self {
-        Self { client: client.clone() }
+    fn new(client: &ClientSocket, resolver: Box<dyn PackageResolver>) -> Self {
+        Self {
+            client: client.clone(),
+            resolver,
+            open_documents: HashMap::new(),
+            documents_version: 0,
+            document_roots: HashMap::new(),
+            checked_crates: HashMap::new(),
+            documents_with_diagnostics: HashMap::new(),
+            change_generations: HashMap::new(),
+        }
     }
 }
 
+/// Emitted once a document has stopped changing to check it and publish its diagnostics.
+struct CheckDocument {
+    uri: Url,
+    generation: u64,
+}
+
 pub struct NargoLspService {
     router: Router<LspState>,
 }
 
 impl NargoLspService {
-    pub fn new(client: &ClientSocket) -> Self {
-        let state = LspState::new(client);
+    pub fn new(client: &ClientSocket, resolver: impl PackageResolver + 'static) -> Self {
+        let state = LspState::new(client, Box::new(resolver));
         let mut router = Router::new(state);
         router
             .request::<request::Initialize, _>(on_initialize)
             .request::<request::Shutdown, _>(on_shutdown)
             .request::<request::CodeLensRequest, _>(on_code_lens_request)
+            .request::<request::GotoDefinition, _>(on_goto_definition_request)
+            .request::<request::HoverRequest, _>(on_hover_request)
+            .request::<request::References, _>(on_references_request)
             .notification::<notification::Initialized>(on_initialized)
             .notification::<notification::DidChangeConfiguration>(on_did_change_configuration)
             .notification::<notification::DidOpenTextDocument>(on_did_open_text_document)
             .notification::<notification::DidChangeTextDocument>(on_did_change_text_document)
             .notification::<notification::DidCloseTextDocument>(on_did_close_text_document)
             .notification::<notification::DidSaveTextDocument>(on_did_save_text_document)
-            .notification::<notification::Exit>(on_exit);
+            .notification::<notification::Exit>(on_exit)
+            .event::<CheckDocument>(on_check_document);
         Self { router }
     }
 }
@@ -104,8 +171,12 @@ fn on_initialize(
     _params: InitializeParams,
 ) -> impl Future<Output = Result<InitializeResult, ResponseError>> {
     async {
-        let text_document_sync =
-            TextDocumentSyncOptions { save: Some(true.into()), ..Default::default() };
+        let text_document_sync = TextDocumentSyncOptions {
+            open_close: Some(true),
+            change: Some(TextDocumentSyncKind::FULL),
+            save: Some(true.into()),
+            ..Default::default()
+        };
 
         let code_lens = CodeLensOptions { resolve_provider: Some(false) };
 
@@ -113,6 +184,9 @@ fn on_initialize(
             capabilities: ServerCapabilities {
                 text_document_sync: Some(text_document_sync.into()),
                 code_lens_provider: Some(code_lens),
+                definition_provider: Some(OneOf::Left(true)),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                references_provider: Some(OneOf::Left(true)),
                 // Add capabilities before this spread when adding support for one
                 ..Default::default()
             },
@@ -129,37 +203,31 @@ fn on_shutdown(
 }
 
 fn on_code_lens_request(
-    _state: &mut LspState,
+    state: &mut LspState,
     params: CodeLensParams,
 ) -> impl Future<Output = Result<Option<Vec<CodeLens>>, ResponseError>> {
-    async move {
-        let mut driver = Driver::new();
-
-        let file_path = &params.text_document.uri.to_file_path().unwrap();
-
-        driver.create_local_crate(file_path, CrateType::Binary);
-
-        // We ignore the warnings and errors produced by compilation for producing codelenses
-        // because we can still get the test functions even if compilation fails
-        let _ = driver.check_crate(false);
+    let uri = params.text_document.uri;
 
+    // We ignore the warnings and errors produced by compilation for producing codelenses
+    // because we can still get the test functions even if compilation fails
+    let lenses = checked_crate(state, &uri).map(|driver| {
         let fm = driver.file_manager();
         let files = fm.as_simple_files();
-        let tests = driver.get_all_test_functions_in_crate_matching("");
+        let tests = driver.get_all_test_functions_in_crate_matching(FunctionNameMatch::Anything);
 
         let mut lenses: Vec<CodeLens> = vec![];
-        for func_id in tests {
+        for (func_id, _) in tests {
             let location = driver.function_meta(&func_id).name.location;
-            let file_id = location.file;
-            // TODO(#1681): This file_id never be 0 because the "path" where it maps is the directory, not a file
-            if file_id.as_usize() != 0 {
+            let file_id = location.file.as_usize();
+            // Only the tests within the requested document get a lens
+            if file_uri(files, file_id).as_ref() != Some(&uri) {
                 continue;
             }
 
             let func_name = driver.function_name(func_id);
 
-            let range = byte_span_to_range(files, file_id.as_usize(), location.span.into())
-                .unwrap_or_default();
+            let range =
+                byte_span_to_range(files, file_id, location.span.into()).unwrap_or_default();
 
             let command = Command {
                 title: TEST_CODELENS_TITLE.into(),
@@ -171,13 +239,103 @@ fn on_code_lens_request(
 
             lenses.push(lens);
         }
+        lenses
+    });
 
-        if lenses.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(lenses))
-        }
-    }
+    future::ready(Ok(lenses.filter(|lenses| !lenses.is_empty())))
+}
+
+fn on_goto_definition_request(
+    state: &mut LspState,
+    params: GotoDefinitionParams,
+) -> impl Future<Output = Result<Option<GotoDefinitionResponse>, ResponseError>> {
+    let position = params.text_document_position_params;
+    let response = check_document(state, &position.text_document.uri, position.position).and_then(
+        |(driver, location)| {
+            let interner = driver.def_interner();
+            let definition_id = find_definition(interner, location)?;
+            let definition_location = interner.definition_location(definition_id)?;
+
+            to_lsp_location(driver.file_manager().as_simple_files(), definition_location)
+        },
+    );
+
+    future::ready(Ok(response.map(GotoDefinitionResponse::Scalar)))
+}
+
+fn on_hover_request(
+    state: &mut LspState,
+    params: HoverParams,
+) -> impl Future<Output = Result<Option<Hover>, ResponseError>> {
+    let position = params.text_document_position_params;
+    let hover = check_document(state, &position.text_document.uri, position.position).and_then(
+        |(driver, location)| {
+            let interner = driver.def_interner();
+
+            // Prefer the type of the identifier at its use site as any generics will have been instantiated there
+            let (definition_id, typ, hovered_location) = match interner.find_identifier_at(location)
+            {
+                Some((expr_id, ident)) => (ident.id, interner.id_type(expr_id), ident.location),
+                None => {
+                    let definition_id = interner.find_declaration_at(location)?;
+                    let definition_location = interner.definition_location(definition_id)?;
+                    (definition_id, interner.id_type(definition_id), definition_location)
+                }
+            };
+
+            let name = interner.definition_name(definition_id);
+            let value = match typ {
+                Type::Error => format!("```noir\n{name}\n```"),
+                typ => format!("```noir\n{name}: {typ}\n```"),
+            };
+
+            let files = driver.file_manager().as_simple_files();
+            let range = byte_span_to_range(
+                files,
+                hovered_location.file.as_usize(),
+                hovered_location.span.into(),
+            );
+
+            let contents = MarkupContent { kind: MarkupKind::Markdown, value };
+            Some(Hover { contents: HoverContents::Markup(contents), range })
+        },
+    );
+
+    future::ready(Ok(hover))
+}
+
+fn on_references_request(
+    state: &mut LspState,
+    params: ReferenceParams,
+) -> impl Future<Output = Result<Option<Vec<lsp_types::Location>>, ResponseError>> {
+    let position = params.text_document_position;
+    let include_declaration = params.context.include_declaration;
+    let references = check_document(state, &position.text_document.uri, position.position)
+        .and_then(|(driver, location)| {
+            let interner = driver.def_interner();
+            let definition_id = find_definition(interner, location)?;
+
+            let mut locations = interner.find_references(definition_id);
+            if include_declaration {
+                if let Some(declaration) = interner.definition_location(definition_id) {
+                    locations.insert(0, declaration);
+                }
+            }
+
+            let files = driver.file_manager().as_simple_files();
+            let references: Vec<_> = locations
+                .into_iter()
+                .filter_map(|location| to_lsp_location(files, location))
+                .collect();
+
+            if references.is_empty() {
+                None
+            } else {
+                Some(references)
+            }
+        });
+
+    future::ready(Ok(references))
 }
 
 fn on_initialized(
@@ -195,23 +353,54 @@ fn on_did_change_configuration(
 }
 
 fn on_did_open_text_document(
-    _state: &mut LspState,
-    _params: DidOpenTextDocumentParams,
+    state: &mut LspState,
+    params: DidOpenTextDocumentParams,
 ) -> ControlFlow<Result<(), async_lsp::Error>> {
+    let uri = params.text_document.uri;
+    state.open_documents.insert(uri.clone(), params.text_document.text);
+    state.documents_version += 1;
+    publish_diagnostics(state, &uri);
+
     ControlFlow::Continue(())
 }
 
 fn on_did_change_text_document(
-    _state: &mut LspState,
-    _params: DidChangeTextDocumentParams,
+    state: &mut LspState,
+    params: DidChangeTextDocumentParams,
 ) -> ControlFlow<Result<(), async_lsp::Error>> {
+    // We only support full document sync so the last change holds the entire document
+    if let Some(change) = params.content_changes.into_iter().last() {
+        state.open_documents.insert(params.text_document.uri.clone(), change.text);
+        state.documents_version += 1;
+    }
+
+    // Wait for the document to stop changing before checking it, discarding this check
+    // in `on_check_document` if there has been another change in the meantime.
+    let uri = params.text_document.uri;
+    let generation = state.change_generations.entry(uri.clone()).or_default();
+    *generation += 1;
+    let event = CheckDocument { uri, generation: *generation };
+    let client = state.client.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(DIAGNOSTICS_DEBOUNCE).await;
+        // The client is closed if the server is shutting down, in which case the check isn't needed
+        let _ = client.emit(event);
+    });
+
     ControlFlow::Continue(())
 }
 
 fn on_did_close_text_document(
-    _state: &mut LspState,
-    _params: DidCloseTextDocumentParams,
+    state: &mut LspState,
+    params: DidCloseTextDocumentParams,
 ) -> ControlFlow<Result<(), async_lsp::Error>> {
+    // The document's contents are now read from disk, which may differ from its unsaved contents
+    if state.open_documents.remove(&params.text_document.uri).is_some() {
+        state.documents_version += 1;
+    }
+    // Any pending check of the document is no longer needed
+    state.change_generations.remove(&params.text_document.uri);
+
     ControlFlow::Continue(())
 }
 
@@ -219,64 +408,243 @@ fn on_did_save_text_document(
     state: &mut LspState,
     params: DidSaveTextDocumentParams,
 ) -> ControlFlow<Result<(), async_lsp::Error>> {
-    let mut driver = Driver::new();
+    let uri = params.text_document.uri;
+
+    // A changed manifest may change which package documents belong to, or their dependencies
+    let is_manifest =
+        uri.path_segments().and_then(|mut segments| segments.next_back()) == Some("Nargo.toml");
+    if is_manifest {
+        state.document_roots.clear();
+        state.checked_crates.clear();
+    } else {
+        publish_diagnostics(state, &uri);
+    }
 
-    let file_path = &params.text_document.uri.to_file_path().unwrap();
+    ControlFlow::Continue(())
+}
 
-    driver.create_local_crate(file_path, CrateType::Binary);
+fn on_check_document(
+    state: &mut LspState,
+    event: CheckDocument,
+) -> ControlFlow<Result<(), async_lsp::Error>> {
+    if is_latest_change(state, &event) {
+        publish_diagnostics(state, &event.uri);
+    }
 
-    let mut diagnostics = Vec::new();
+    ControlFlow::Continue(())
+}
 
-    let file_diagnostics = match driver.check_crate(false) {
-        Ok(warnings) => warnings,
-        Err(errors_and_warnings) => errors_and_warnings,
+/// Returns whether the document hasn't changed again since the change which `event` was emitted for.
+fn is_latest_change(state: &LspState, event: &CheckDocument) -> bool {
+    state.change_generations.get(&event.uri) == Some(&event.generation)
+}
+
+/// Checks the crate containing the document `uri` and publishes its diagnostics, grouped by the file
+/// they occur in. Files which no longer have any diagnostics have their previous diagnostics cleared.
+fn publish_diagnostics(state: &mut LspState, uri: &Url) {
+    let root = match crate_root(state, uri) {
+        Some(root) => root,
+        None => return,
     };
+    let (driver, file_diagnostics) = check_crate(state, root.clone());
+
+    let mut diagnostics_by_file: HashMap<Url, Vec<Diagnostic>> = HashMap::new();
+    // Always publish for the checked document so that its diagnostics are cleared once fixed
+    diagnostics_by_file.insert(uri.clone(), Vec::new());
+
+    let fm = driver.file_manager();
+    let files = fm.as_simple_files();
+    for FileDiagnostic { file_id, diagnostic } in file_diagnostics {
+        let file_uri = match file_uri(files, file_id.as_usize()) {
+            Some(file_uri) => file_uri,
+            None => continue,
+        };
 
-    if !file_diagnostics.is_empty() {
-        let fm = driver.file_manager();
-        let files = fm.as_simple_files();
+        let mut range = Range::default();
 
-        for FileDiagnostic { file_id, diagnostic } in file_diagnostics {
-            // TODO(#1681): This file_id never be 0 because the "path" where it maps is the directory, not a file
-            if file_id.as_usize() != 0 {
-                continue;
+        // TODO: Should this be the first item in secondaries? Should we bail when we find a range?
+        for sec in diagnostic.secondaries {
+            // Not using `unwrap_or_default` here because we don't want to overwrite a valid range with a default range
+            if let Some(r) = byte_span_to_range(files, file_id.as_usize(), sec.span.into()) {
+                range = r
             }
+        }
+        let severity = match diagnostic.kind {
+            DiagnosticKind::Error => Some(DiagnosticSeverity::ERROR),
+            DiagnosticKind::Warning => Some(DiagnosticSeverity::WARNING),
+        };
+        diagnostics_by_file.entry(file_uri).or_default().push(Diagnostic {
+            range,
+            severity,
+            message: diagnostic.message,
+            ..Diagnostic::default()
+        });
+    }
 
-            let mut range = Range::default();
+    // Only the diagnostics from previous checks of this crate are cleared, as other crates
+    // aren't affected by this check
+    let previous_files = state.documents_with_diagnostics.remove(&root).unwrap_or_default();
+    for previous_uri in previous_files {
+        diagnostics_by_file.entry(previous_uri).or_default();
+    }
 
-            // TODO: Should this be the first item in secondaries? Should we bail when we find a range?
-            for sec in diagnostic.secondaries {
-                // Not using `unwrap_or_default` here because we don't want to overwrite a valid range with a default range
-                if let Some(r) = byte_span_to_range(files, file_id.as_usize(), sec.span.into()) {
-                    range = r
-                }
-            }
-            let severity = match diagnostic.kind {
-                DiagnosticKind::Error => Some(DiagnosticSeverity::ERROR),
-                DiagnosticKind::Warning => Some(DiagnosticSeverity::WARNING),
-            };
-            diagnostics.push(Diagnostic {
-                range,
-                severity,
-                message: diagnostic.message,
-                ..Diagnostic::default()
-            })
+    let files_with_diagnostics = state.documents_with_diagnostics.entry(root).or_default();
+    for (uri, diagnostics) in diagnostics_by_file {
+        if !diagnostics.is_empty() {
+            files_with_diagnostics.insert(uri.clone());
         }
+        let _ = state.client.publish_diagnostics(PublishDiagnosticsParams {
+            uri,
+            version: None,
+            diagnostics,
+        });
     }
+}
 
-    let _ = state.client.publish_diagnostics(PublishDiagnosticsParams {
-        uri: params.text_document.uri,
-        version: None,
-        diagnostics,
-    });
+/// Returns the crate which the document `uri` is checked as part of, locating its package
+/// the first time the document is checked.
+fn crate_root(state: &mut LspState, uri: &Url) -> Option<CrateRoot> {
+    if let Some(root) = state.document_roots.get(uri) {
+        return Some(root.clone());
+    }
 
-    ControlFlow::Continue(())
+    let file_path = uri.to_file_path().ok()?;
+    let root = match state.resolver.resolve_package(&file_path) {
+        Ok(Some(root_dir)) => CrateRoot::Package(root_dir),
+        Ok(None) => CrateRoot::File(file_path),
+        Err(message) => {
+            // The document is still checked on its own so that it gets some diagnostics
+            let _ = state.client.show_message(ShowMessageParams {
+                typ: MessageType::ERROR,
+                message: format!("Failed to resolve the package of {uri}: {message}"),
+            });
+            CrateRoot::File(file_path)
+        }
+    };
+    state.document_roots.insert(uri.clone(), root.clone());
+    Some(root)
+}
+
+/// Checks `root` against the current contents of the open documents, caching the checked driver
+/// for requests made before any document changes again.
+fn check_crate(state: &mut LspState, root: CrateRoot) -> (Rc<Driver>, Vec<FileDiagnostic>) {
+    let mut driver = Driver::new();
+    add_document_overlays(&mut driver, &state.open_documents);
+    match &root {
+        CrateRoot::Package(root_dir) => state.resolver.add_package(root_dir, &mut driver),
+        CrateRoot::File(file_path) => {
+            driver.create_local_crate(file_path, CrateType::Binary);
+        }
+    }
+
+    let file_diagnostics = match driver.check_crate(false) {
+        Ok(warnings) => warnings,
+        Err(errors_and_warnings) => errors_and_warnings,
+    };
+
+    let driver = Rc::new(driver);
+    state.checked_crates.insert(root, (state.documents_version, driver.clone()));
+    (driver, file_diagnostics)
+}
+
+/// Returns the checked driver of the crate containing the document `uri`, reusing the
+/// last check of the crate if no documents have changed since.
+///
+/// Any errors found while checking are ignored so that the parts of the
+/// program which were successfully resolved can still be navigated.
+fn checked_crate(state: &mut LspState, uri: &Url) -> Option<Rc<Driver>> {
+    let root = crate_root(state, uri)?;
+    match state.checked_crates.get(&root) {
+        Some((version, driver)) if *version == state.documents_version => Some(driver.clone()),
+        _ => Some(check_crate(state, root).0),
+    }
+}
+
+/// Makes the driver read the unsaved contents of any documents open in the editor instead of the files on disk.
+fn add_document_overlays(driver: &mut Driver, open_documents: &HashMap<Url, String>) {
+    for (uri, source) in open_documents {
+        if let Ok(path) = uri.to_file_path() {
+            driver.add_file_overlay(&path, source.clone());
+        }
+    }
 }
 
 fn on_exit(_state: &mut LspState, _params: ()) -> ControlFlow<Result<(), async_lsp::Error>> {
     ControlFlow::Continue(())
 }
 
+/// Checks the crate containing the document `uri`, returning the driver along with the
+/// location of `position` within the document.
+fn check_document(
+    state: &mut LspState,
+    uri: &Url,
+    position: Position,
+) -> Option<(Rc<Driver>, Location)> {
+    let file_path = uri.to_file_path().ok()?;
+    let driver = checked_crate(state, uri)?;
+
+    let file_id = driver.file_manager().find_file(&file_path)?;
+    let files = driver.file_manager().as_simple_files();
+    let byte_index = position_to_byte_index(files, file_id.as_usize(), position)?;
+
+    let location = Location::new(Span::exclusive(byte_index, byte_index), file_id);
+    Some((driver, location))
+}
+
+/// Returns the definition which is either referred to or declared by the identifier at `location`.
+fn find_definition(interner: &NodeInterner, location: Location) -> Option<DefinitionId> {
+    interner
+        .find_identifier_at(location)
+        .map(|(_, ident)| ident.id)
+        .or_else(|| interner.find_declaration_at(location))
+}
+
+fn to_lsp_location<'a, F>(files: &'a F, location: Location) -> Option<lsp_types::Location>
+where
+    F: files::Files<'a, FileId = usize> + ?Sized,
+{
+    let file_id = location.file.as_usize();
+    let uri = file_uri(files, file_id)?;
+    let range = byte_span_to_range(files, file_id, location.span.into())?;
+    Some(lsp_types::Location { uri, range })
+}
+
+fn file_uri<'a, F>(files: &'a F, file_id: usize) -> Option<Url>
+where
+    F: files::Files<'a, FileId = usize> + ?Sized,
+{
+    // Files are named by their path relative to the directory the server was started in
+    let path = PathBuf::from(files.name(file_id).ok()?.to_string());
+    let path = if path.is_absolute() { path } else { std::env::current_dir().ok()?.join(path) };
+
+    Url::from_file_path(path).ok()
+}
+
+/// Converts an LSP position, whose character offset is counted in UTF-16 code units,
+/// into a byte index within the file.
+fn position_to_byte_index<'a, F: files::Files<'a> + ?Sized>(
+    files: &'a F,
+    file_id: F::FileId,
+    position: Position,
+) -> Option<u32> {
+    let line_range = files.line_range(file_id, position.line as usize).ok()?;
+    let source = files.source(file_id).ok()?;
+    let line = &source.as_ref()[line_range.clone()];
+
+    // Positions past the end of the line refer to the end of the line
+    let mut utf16_offset = 0;
+    let line_offset = line
+        .char_indices()
+        .find(|(_, char)| {
+            let reached = utf16_offset >= position.character as usize;
+            utf16_offset += char.len_utf16();
+            reached
+        })
+        .map_or(line.len(), |(byte_offset, _)| byte_offset);
+
+    (line_range.start + line_offset).try_into().ok()
+}
+
 fn byte_span_to_range<'a, F: files::Files<'a> + ?Sized>(
     files: &'a F,
     file_id: F::FileId,
@@ -304,28 +672,200 @@ fn byte_span_to_range<'a, F: files::Files<'a> + ?Sized>(
 
 #[cfg(test)]
 mod lsp_tests {
-    use lsp_types::TextDocumentSyncCapability;
+    use codespan_reporting::files::SimpleFiles;
+    use lsp_types::{
+        PartialResultParams, ReferenceContext, TextDocumentContentChangeEvent,
+        TextDocumentIdentifier, TextDocumentItem, TextDocumentPositionParams,
+        TextDocumentSyncCapability, VersionedTextDocumentIdentifier, WorkDoneProgressParams,
+    };
     use tokio::test;
 
     use super::*;
 
+    const SOURCE: &str = "fn main() {\n    let x = 1;\n    let _y = x;\n}\n\n#[test]\nfn test_main() {\n    main();\n}\n";
+
+    /// Checks every document as a crate of its own, as if none of them were part of a package.
+    struct NoPackages;
+
+    impl PackageResolver for NoPackages {
+        fn resolve_package(&mut self, _file_path: &Path) -> Result<Option<PathBuf>, String> {
+            Ok(None)
+        }
+
+        fn add_package(&self, _root_dir: &Path, _driver: &mut Driver) {
+            unreachable!("no packages are resolved")
+        }
+    }
+
+    /// Returns the state of a server with `SOURCE` open in an unsaved document, along with the document's uri.
+    fn state_with_open_document() -> (LspState, Url) {
+        let client = ClientSocket::new_closed();
+        let mut state = LspState::new(&client, Box::new(NoPackages));
+
+        // The document only exists in the editor so that it is never read from disk
+        let uri = Url::from_file_path("/nargo_lsp_tests/src/main.nr").unwrap();
+        let text_document = TextDocumentItem {
+            uri: uri.clone(),
+            language_id: "noir".to_owned(),
+            version: 0,
+            text: SOURCE.to_owned(),
+        };
+        let _ = on_did_open_text_document(&mut state, DidOpenTextDocumentParams { text_document });
+        (state, uri)
+    }
+
+    fn position_params(uri: &Url, line: u32, character: u32) -> TextDocumentPositionParams {
+        TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            position: Position { line, character },
+        }
+    }
+
     #[test]
     async fn test_on_initialize() {
         // Not available in published release yet
         let client = ClientSocket::new_closed();
-        let mut state = LspState::new(&client);
+        let mut state = LspState::new(&client, Box::new(NoPackages));
         let params = InitializeParams::default();
         let response = on_initialize(&mut state, params).await.unwrap();
         assert!(matches!(
             response.capabilities,
             ServerCapabilities {
                 text_document_sync: Some(TextDocumentSyncCapability::Options(
-                    TextDocumentSyncOptions { save: Some(_), .. }
+                    TextDocumentSyncOptions {
+                        open_close: Some(true),
+                        change: Some(TextDocumentSyncKind::FULL),
+                        save: Some(_),
+                        ..
+                    }
                 )),
                 code_lens_provider: Some(CodeLensOptions { resolve_provider: Some(false) }),
+                definition_provider: Some(OneOf::Left(true)),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                references_provider: Some(OneOf::Left(true)),
                 ..
             }
         ));
         assert!(response.server_info.is_none());
     }
+
+    #[test]
+    async fn test_goto_definition_of_local_variable() {
+        let (mut state, uri) = state_with_open_document();
+        let params = GotoDefinitionParams {
+            text_document_position_params: position_params(&uri, 2, 13),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        };
+
+        let response = on_goto_definition_request(&mut state, params).await.unwrap();
+        let location = match response {
+            Some(GotoDefinitionResponse::Scalar(location)) => location,
+            _ => panic!("expected a single definition, found {response:?}"),
+        };
+        assert_eq!(location.uri, uri);
+        assert_eq!(location.range.start, Position { line: 1, character: 8 });
+    }
+
+    #[test]
+    async fn test_hover_shows_type_of_local_variable() {
+        let (mut state, uri) = state_with_open_document();
+        let params = HoverParams {
+            text_document_position_params: position_params(&uri, 2, 13),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+
+        let hover = on_hover_request(&mut state, params).await.unwrap().expect("should hover");
+        let contents = match hover.contents {
+            HoverContents::Markup(contents) => contents,
+            _ => panic!("expected markup contents"),
+        };
+        assert_eq!(contents.value, "```noir\nx: Field\n```");
+    }
+
+    #[test]
+    async fn test_references_include_declaration() {
+        let (mut state, uri) = state_with_open_document();
+        let params = ReferenceParams {
+            text_document_position: position_params(&uri, 1, 8),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+            context: ReferenceContext { include_declaration: true },
+        };
+
+        let references = on_references_request(&mut state, params).await.unwrap().unwrap();
+        let starts: Vec<_> = references.iter().map(|location| location.range.start).collect();
+        assert_eq!(
+            starts,
+            vec![Position { line: 1, character: 8 }, Position { line: 2, character: 13 }]
+        );
+    }
+
+    #[test]
+    async fn test_code_lens_for_tests_in_document() {
+        let (mut state, uri) = state_with_open_document();
+        let params = CodeLensParams {
+            text_document: TextDocumentIdentifier { uri },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        };
+
+        let lenses = on_code_lens_request(&mut state, params).await.unwrap().unwrap();
+        assert_eq!(lenses.len(), 1);
+        assert_eq!(lenses[0].range.start, Position { line: 6, character: 3 });
+    }
+
+    #[test]
+    async fn test_checked_crate_is_reused_until_a_document_changes() {
+        let (mut state, uri) = state_with_open_document();
+        let first = checked_crate(&mut state, &uri).unwrap();
+        let second = checked_crate(&mut state, &uri).unwrap();
+        assert!(Rc::ptr_eq(&first, &second));
+
+        state.open_documents.insert(uri.clone(), SOURCE.replace("1", "2"));
+        state.documents_version += 1;
+        let third = checked_crate(&mut state, &uri).unwrap();
+        assert!(!Rc::ptr_eq(&second, &third));
+    }
+
+    #[test]
+    async fn test_changes_to_other_documents_do_not_skip_checks() {
+        let (mut state, uri) = state_with_open_document();
+        let other_uri = Url::from_file_path("/nargo_lsp_tests/other/src/main.nr").unwrap();
+
+        let change = |uri: &Url, version| DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier { uri: uri.clone(), version },
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: SOURCE.to_owned(),
+            }],
+        };
+        let _ = on_did_change_text_document(&mut state, change(&uri, 1));
+        let _ = on_did_change_text_document(&mut state, change(&other_uri, 1));
+
+        let first_check = CheckDocument { uri: uri.clone(), generation: 1 };
+        let other_check = CheckDocument { uri: other_uri, generation: 1 };
+        assert!(is_latest_change(&state, &first_check));
+        assert!(is_latest_change(&state, &other_check));
+
+        // A further change to the document makes its first check outdated
+        let _ = on_did_change_text_document(&mut state, change(&uri, 2));
+        assert!(!is_latest_change(&state, &first_check));
+        assert!(is_latest_change(&state, &other_check));
+    }
+
+    #[test]
+    async fn test_position_to_byte_index_counts_utf16_code_units() {
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("main.nr", "fn main() {\nlet s = \"é😀\"; x\n}\n");
+
+        // `x` is 15 UTF-16 code units into its line but 18 bytes, as `é` and `😀` take more bytes
+        let position = Position { line: 1, character: 15 };
+        assert_eq!(position_to_byte_index(&files, file_id, position), Some(12 + 18));
+
+        // Positions past the end of a line are clamped to it
+        let position = Position { line: 2, character: 10 };
+        assert_eq!(position_to_byte_index(&files, file_id, position), Some(32 + 2));
+    }
 }
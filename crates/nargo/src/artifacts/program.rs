@@ -1,5 +1,6 @@
 use acvm::acir::circuit::Circuit;
 use noirc_abi::Abi;
+use noirc_errors::DebugInfo;
 use serde::{Deserialize, Serialize};
 
 /// `PreprocessedProgram` represents a Noir program which has been preprocessed by a particular backend proving system.
@@ -20,4 +21,9 @@ pub struct PreprocessedProgram {
 
     pub proving_key: Vec<u8>,
     pub verification_key: Vec<u8>,
+
+    /// The source locations of the opcodes in `bytecode`, used to report failing constraints,
+    /// and the names of the functions in its brillig opcodes, used when disassembling them.
+    #[serde(default)]
+    pub debug: DebugInfo,
 }
@@ -10,4 +10,33 @@ pub enum NargoError {
     /// ACIR circuit solving error
     #[error(transparent)]
     SolvingError(#[from] OpcodeResolutionError),
+
+    /// The circuit's inputs do not satisfy the constraint of one of its opcodes
+    #[error("{error} at opcode {opcode_index}")]
+    UnsatisfiedConstraint { opcode_index: usize, error: OpcodeResolutionError },
+
+    /// Error while resolving a foreign call made by the circuit
+    #[error(transparent)]
+    ForeignCallError(#[from] ForeignCallError),
+}
+
+#[derive(Debug, Error)]
+pub enum ForeignCallError {
+    #[error("No handler could be found for the foreign call `{0}`")]
+    NoHandler(String),
+
+    #[error("The oracle `{function}` returned {actual} values but {expected} were expected")]
+    IncorrectOutputCount { function: String, expected: usize, actual: usize },
+
+    #[error("Invalid foreign call resolver: {0}")]
+    InvalidResolver(String),
+
+    #[error("Failed to communicate with the foreign call resolver: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid response from the foreign call resolver for `{function}`: {reason}")]
+    InvalidResponse { function: String, reason: String },
+
+    #[error("The foreign call resolver failed to resolve `{function}`: {message}")]
+    ResolverError { function: String, message: String },
 }
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The version of the lockfile format written by this version of nargo.
+const LOCKFILE_VERSION: u32 = 1;
+
+const LOCKFILE_HEADER: &str =
+    "# This file is automatically generated by nargo.\n# It is not intended for manual editing.\n";
+
+/// Errors covering situations where a lockfile cannot be read.
+#[derive(Debug, Error)]
+pub enum LockfileError {
+    /// Lockfile is unreadable.
+    #[error("Nargo.lock is badly formed, could not parse.\n\n {0}")]
+    MalformedLockfile(#[from] toml::de::Error),
+
+    /// Lockfile was written by an incompatible version of nargo.
+    #[error("Nargo.lock has version {0} which is not supported by this version of nargo")]
+    UnsupportedVersion(u32),
+}
+
+/// The contents of a Nargo.lock, recording the exact source used for each git dependency
+/// so that later builds resolve to the same code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    version: u32,
+    #[serde(default, rename = "package")]
+    packages: Vec<LockedPackage>,
+}
+
+/// A git dependency pinned to the commit which was checked out for it.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub git: String,
+    pub tag: String,
+    pub commit: String,
+    /// Checksum of the contents of the dependency at `commit`
+    pub checksum: String,
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Lockfile { version: LOCKFILE_VERSION, packages: Vec::new() }
+    }
+}
+
+impl Lockfile {
+    pub fn from_toml_str(toml_as_string: &str) -> Result<Self, LockfileError> {
+        let lockfile = toml::from_str::<Lockfile>(toml_as_string)?;
+        if lockfile.version != LOCKFILE_VERSION {
+            return Err(LockfileError::UnsupportedVersion(lockfile.version));
+        }
+        Ok(lockfile)
+    }
+
+    pub fn to_toml_string(&self) -> String {
+        let contents = toml::to_string(self).expect("lockfile should always serialize");
+        format!("{LOCKFILE_HEADER}{contents}")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    /// Returns the pinned version of the git dependency at `git` with the given `tag`, if there is one.
+    pub fn get(&self, git: &str, tag: &str) -> Option<&LockedPackage> {
+        self.packages.iter().find(|package| package.git == git && package.tag == tag)
+    }
+
+    /// Pins a git dependency, replacing any existing entry for the same repository and tag.
+    pub fn insert(&mut self, package: LockedPackage) {
+        self.packages.retain(|existing| existing.git != package.git || existing.tag != package.tag);
+        self.packages.push(package);
+        // Keep entries sorted so that the written lockfile doesn't depend on resolution order
+        self.packages.sort();
+    }
+}
+
+#[test]
+fn lockfile_roundtrip() {
+    let mut lockfile = Lockfile::default();
+    lockfile.insert(LockedPackage {
+        git: "https://github.com/noir-lang/dep".to_owned(),
+        tag: "v0.1.0".to_owned(),
+        commit: "0123456789abcdef0123456789abcdef01234567".to_owned(),
+        checksum: "00".repeat(32),
+    });
+
+    let parsed = Lockfile::from_toml_str(&lockfile.to_toml_string()).unwrap();
+    assert_eq!(parsed, lockfile);
+    assert!(parsed.get("https://github.com/noir-lang/dep", "v0.1.0").is_some());
+    assert!(parsed.get("https://github.com/noir-lang/dep", "v0.2.0").is_none());
+}
+
+#[test]
+fn rejects_unknown_lockfile_version() {
+    let src = "version = 2";
+    assert!(matches!(Lockfile::from_toml_str(src), Err(LockfileError::UnsupportedVersion(2))));
+}
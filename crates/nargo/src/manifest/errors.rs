@@ -23,4 +23,12 @@ pub enum InvalidPackageError {
     /// Package has both a `main.nr` (for binaries) and `lib.nr` (for libraries)
     #[error("package cannot contain both a `lib.nr` and a `main.nr`")]
     ContainsMultipleCrates,
+
+    /// A workspace manifest was found where a package was expected.
+    #[error("expected {} to describe a package but found a workspace. Nested workspaces are not supported", .0.display())]
+    ExpectedPackageManifest(PathBuf),
+
+    /// No member of the workspace has the requested name.
+    #[error("cannot find a package named `{0}` in the workspace")]
+    UnknownPackage(String),
 }
@@ -1,13 +1,43 @@
 use serde::Deserialize;
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, path::PathBuf};
 
 mod errors;
 pub use self::errors::InvalidPackageError;
 
+/// The contents of a Nargo.toml, which describes either a single package
+/// or a workspace made up of several packages.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Manifest {
+    Package(PackageManifest),
+    Workspace(WorkspaceManifest),
+}
+
+impl Manifest {
+    pub fn from_toml_str(toml_as_string: &str) -> Result<Self, InvalidPackageError> {
+        let manifest = toml::from_str::<Manifest>(toml_as_string)?;
+        Ok(manifest)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct WorkspaceManifest {
+    pub workspace: WorkspaceConfig,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct WorkspaceConfig {
+    /// Paths to the member packages of the workspace, relative to the workspace root
+    pub members: Vec<PathBuf>,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct PackageManifest {
     pub package: PackageMetadata,
     pub dependencies: BTreeMap<String, Dependency>,
+    /// Options for `nargo fmt`, set in the `[fmt]` table
+    #[serde(default)]
+    pub fmt: nargo_fmt::Config,
 }
 
 impl PackageManifest {
@@ -27,6 +57,8 @@ impl PackageManifest {
 #[allow(dead_code)]
 #[derive(Debug, Deserialize, Clone)]
 pub struct PackageMetadata {
+    /// Used to select a package within a workspace, defaulting to the name of the package's directory
+    pub name: Option<String>,
     // Note: a package name is not needed unless there is a registry
     authors: Vec<String>,
     // If not compiler version is supplied, the latest is used
@@ -64,3 +96,60 @@ fn parse_standard_toml() {
 
     assert!(PackageManifest::from_toml_str(src).is_ok());
 }
+
+#[test]
+fn parse_workspace_toml() {
+    let src = r#"
+        [workspace]
+        members = ["crates/a", "crates/b"]
+    "#;
+
+    let manifest = Manifest::from_toml_str(src).unwrap();
+    assert!(
+        matches!(manifest, Manifest::Workspace(workspace) if workspace.workspace.members.len() == 2)
+    );
+}
+
+#[test]
+fn parse_package_toml_as_manifest() {
+    let src = r#"
+        [package]
+        name = "circuit"
+        authors = [""]
+
+        [dependencies]
+        lib = { path = "../lib" }
+    "#;
+
+    let manifest = Manifest::from_toml_str(src).unwrap();
+    assert!(
+        matches!(manifest, Manifest::Package(package) if package.package.name.as_deref() == Some("circuit"))
+    );
+}
+
+#[test]
+fn parse_fmt_config() {
+    let src = r#"
+        [package]
+        authors = [""]
+
+        [dependencies]
+
+        [fmt]
+        max_width = 80
+    "#;
+
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    assert_eq!(manifest.fmt, nargo_fmt::Config { max_width: 80, ..Default::default() });
+
+    let src = r#"
+        [package]
+        authors = [""]
+
+        [dependencies]
+
+        [fmt]
+        width = 80
+    "#;
+    assert!(PackageManifest::from_toml_str(src).is_err());
+}
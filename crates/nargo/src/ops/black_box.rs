@@ -0,0 +1,122 @@
+use acvm::acir::brillig_vm::{ForeignCallOutput, ForeignCallResult, ForeignCallWaitInfo, Value};
+use acvm::acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+use acvm::acir::circuit::Opcode;
+use acvm::acir::native_types::{Witness, WitnessMap};
+use acvm::acir::BlackBoxFunc;
+use acvm::pwg::{solve, Blocks, OpcodeResolutionError, PartialWitnessGeneratorStatus};
+use acvm::{FieldElement, PartialWitnessGenerator};
+
+/// Resolves a foreign call made by Brillig to one of the black box functions which depend on the
+/// proving backend, and so are not computed by Brillig bytecode.
+///
+/// The foreign call is named after the black box function and has one input per argument of the
+/// function, with arrays flattened into their elements. It is solved by `backend` as a circuit
+/// made of the single corresponding black box opcode.
+///
+/// Returns `None` if the foreign call is not to such a black box function.
+pub(super) fn solve_black_box_call(
+    backend: &impl PartialWitnessGenerator,
+    foreign_call: &ForeignCallWaitInfo,
+) -> Option<Result<ForeignCallResult, OpcodeResolutionError>> {
+    let black_box = BlackBoxFunc::lookup(&foreign_call.function)?;
+    let inputs = &foreign_call.inputs;
+
+    let mut circuit = BlackBoxCircuit::default();
+    let (black_box_func_call, outputs) = match black_box {
+        BlackBoxFunc::Pedersen => {
+            let outputs = circuit.outputs(2);
+            let black_box_func_call = BlackBoxFuncCall::Pedersen {
+                inputs: circuit.inputs(&inputs[0], FieldElement::max_num_bits()),
+                outputs: outputs.clone(),
+                domain_separator: inputs[1][0].to_field().to_u128() as u32,
+            };
+            (black_box_func_call, vec![outputs])
+        }
+        BlackBoxFunc::SchnorrVerify => {
+            let output = circuit.outputs(1);
+            let black_box_func_call = BlackBoxFuncCall::SchnorrVerify {
+                public_key_x: circuit.inputs(&inputs[0], FieldElement::max_num_bits())[0],
+                public_key_y: circuit.inputs(&inputs[1], FieldElement::max_num_bits())[0],
+                signature: circuit.inputs(&inputs[2], 8),
+                message: circuit.inputs(&inputs[3], 8),
+                output: output[0],
+            };
+            (black_box_func_call, vec![output])
+        }
+        BlackBoxFunc::EcdsaSecp256k1 => {
+            let output = circuit.outputs(1);
+            let black_box_func_call = BlackBoxFuncCall::EcdsaSecp256k1 {
+                public_key_x: circuit.inputs(&inputs[0], 8),
+                public_key_y: circuit.inputs(&inputs[1], 8),
+                signature: circuit.inputs(&inputs[2], 8),
+                hashed_message: circuit.inputs(&inputs[3], 8),
+                output: output[0],
+            };
+            (black_box_func_call, vec![output])
+        }
+        _ => return None,
+    };
+
+    Some(circuit.solve(backend, black_box_func_call, outputs))
+}
+
+/// The witnesses of a circuit made of a single black box opcode.
+#[derive(Default)]
+struct BlackBoxCircuit {
+    witness_map: WitnessMap,
+    next_witness_index: u32,
+}
+
+impl BlackBoxCircuit {
+    /// Assigns a new witness to each of the values, which are passed to the black box function
+    /// as inputs of `num_bits` bits.
+    fn inputs(&mut self, values: &[Value], num_bits: u32) -> Vec<FunctionInput> {
+        values
+            .iter()
+            .map(|value| {
+                let witness = self.next_witness();
+                self.witness_map.insert(witness, value.to_field());
+                FunctionInput { witness, num_bits }
+            })
+            .collect()
+    }
+
+    /// Creates `count` new witnesses to hold outputs of the black box function.
+    fn outputs(&mut self, count: usize) -> Vec<Witness> {
+        (0..count).map(|_| self.next_witness()).collect()
+    }
+
+    fn next_witness(&mut self) -> Witness {
+        self.next_witness_index += 1;
+        Witness(self.next_witness_index)
+    }
+
+    /// Solves the black box opcode, returning the values of each group of `outputs` as one
+    /// output of the foreign call. A group of a single witness is returned as a single value.
+    fn solve(
+        mut self,
+        backend: &impl PartialWitnessGenerator,
+        black_box_func_call: BlackBoxFuncCall,
+        outputs: Vec<Vec<Witness>>,
+    ) -> Result<ForeignCallResult, OpcodeResolutionError> {
+        let opcodes = vec![Opcode::BlackBoxFuncCall(black_box_func_call)];
+        let solver_status = solve(backend, &mut self.witness_map, &mut Blocks::default(), opcodes)?;
+        if !matches!(solver_status, PartialWitnessGeneratorStatus::Solved) {
+            unreachable!("ICE: black box opcodes do not require oracle data");
+        }
+
+        let output_value = |witness: &Witness| {
+            let value = self.witness_map.get(witness).expect("ICE: output of solved black box");
+            Value::from(*value)
+        };
+        let values = outputs
+            .iter()
+            .map(|witnesses| match witnesses.as_slice() {
+                [witness] => ForeignCallOutput::Single(output_value(witness)),
+                witnesses => ForeignCallOutput::Array(witnesses.iter().map(output_value).collect()),
+            })
+            .collect();
+
+        Ok(ForeignCallResult { values })
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use acvm::acir::brillig_vm::{
+    BinaryFieldOp, BinaryIntOp, Opcode as BrilligOpcode, RegisterIndex, RegisterOrMemory, Value,
+};
+use acvm::acir::circuit::{Circuit, Opcode};
+use noirc_errors::DebugInfo;
+
+/// Disassembles the bytecode of each brillig opcode of the circuit.
+///
+/// The functions linked into the bytecode are named using `debug`, when it knows about them.
+pub fn disassemble_brillig_opcodes(circuit: &Circuit, debug: &DebugInfo) -> String {
+    let no_function_names = BTreeMap::new();
+    let mut output = String::new();
+    for (opcode_index, opcode) in circuit.opcodes.iter().enumerate() {
+        if let Opcode::Brillig(brillig) = opcode {
+            let function_names =
+                debug.brillig_function_names(opcode_index).unwrap_or(&no_function_names);
+            output += &format!(
+                "brillig opcode {opcode_index} ({} instructions):\n",
+                brillig.bytecode.len()
+            );
+            output += &disassemble_brillig(&brillig.bytecode, function_names);
+            output += "\n";
+        }
+    }
+    output
+}
+
+/// Disassembles brillig bytecode, one instruction per line.
+///
+/// Jump targets are labelled by their position, while the start of each function is labelled
+/// with its name in `function_names`, keyed by the position of its first instruction.
+/// Functions which are called but have no known name are named after their position.
+pub fn disassemble_brillig(
+    bytecode: &[BrilligOpcode],
+    function_names: &BTreeMap<usize, String>,
+) -> String {
+    let mut function_names = function_names.clone();
+    let mut jump_targets = BTreeSet::new();
+    for opcode in bytecode {
+        match opcode {
+            BrilligOpcode::Jump { location }
+            | BrilligOpcode::JumpIf { location, .. }
+            | BrilligOpcode::JumpIfNot { location, .. } => {
+                jump_targets.insert(*location);
+            }
+            BrilligOpcode::Call { location } => {
+                function_names.entry(*location).or_insert_with(|| format!("fn_{location}"));
+            }
+            _ => (),
+        }
+    }
+
+    let label = |location: usize| match function_names.get(&location) {
+        Some(name) => name.clone(),
+        None => format!("L{location}"),
+    };
+
+    let mut output = String::new();
+    for (position, opcode) in bytecode.iter().enumerate() {
+        if let Some(name) = function_names.get(&position) {
+            output += &format!("{name}:\n");
+        } else if jump_targets.contains(&position) {
+            output += &format!("{}:\n", label(position));
+        }
+        output += &format!("{position:>6}: {}\n", disassemble_opcode(opcode, &label));
+    }
+    output
+}
+
+fn disassemble_opcode(opcode: &BrilligOpcode, label: &impl Fn(usize) -> String) -> String {
+    match opcode {
+        BrilligOpcode::BinaryFieldOp { destination, op, lhs, rhs } => format!(
+            "{} = {} {} {}",
+            register(destination),
+            register(lhs),
+            field_op(op),
+            register(rhs)
+        ),
+        BrilligOpcode::BinaryIntOp { destination, op, bit_size, lhs, rhs } => format!(
+            "{} = {} i{bit_size}::{} {}",
+            register(destination),
+            register(lhs),
+            int_op(op),
+            register(rhs)
+        ),
+        BrilligOpcode::JumpIfNot { condition, location } => {
+            format!("JUMP_IF_NOT {} TO {}", register(condition), label(*location))
+        }
+        BrilligOpcode::JumpIf { condition, location } => {
+            format!("JUMP_IF {} TO {}", register(condition), label(*location))
+        }
+        BrilligOpcode::Jump { location } => format!("JUMP_TO {}", label(*location)),
+        BrilligOpcode::Call { location } => format!("CALL {}", label(*location)),
+        BrilligOpcode::Const { destination, value } => {
+            format!("CONST {} = {}", register(destination), constant(value))
+        }
+        BrilligOpcode::Return => "RETURN".to_string(),
+        BrilligOpcode::ForeignCall { function, destinations, inputs } => format!(
+            "FOREIGN_CALL {function} ({}) => {}",
+            register_or_memory_list(inputs),
+            register_or_memory_list(destinations)
+        ),
+        BrilligOpcode::Mov { destination, source } => {
+            format!("MOV {}, {}", register(destination), register(source))
+        }
+        BrilligOpcode::Load { destination, source_pointer } => {
+            format!("LOAD {} = *{}", register(destination), register(source_pointer))
+        }
+        BrilligOpcode::Store { destination_pointer, source } => {
+            format!("STORE *{} = {}", register(destination_pointer), register(source))
+        }
+        BrilligOpcode::Trap => "TRAP".to_string(),
+        BrilligOpcode::Stop => "STOP".to_string(),
+    }
+}
+
+fn register(register: &RegisterIndex) -> String {
+    format!("R{}", register.to_usize())
+}
+
+fn register_or_memory_list(values: &[RegisterOrMemory]) -> String {
+    let values: Vec<_> = values
+        .iter()
+        .map(|value| match value {
+            RegisterOrMemory::RegisterIndex(index) => register(index),
+            RegisterOrMemory::HeapArray(pointer, size) => {
+                format!("{}[0..{size}]", register(pointer))
+            }
+            RegisterOrMemory::HeapVector(pointer, size) => {
+                format!("{}[0..*{}]", register(pointer), register(size))
+            }
+        })
+        .collect();
+    values.join(", ")
+}
+
+/// Shows small constants in decimal and the others, such as field elements, in hexadecimal.
+fn constant(value: &Value) -> String {
+    let field = value.to_field();
+    if field.num_bits() <= 64 {
+        field.to_u128().to_string()
+    } else {
+        format!("0x{}", field.to_hex())
+    }
+}
+
+fn field_op(op: &BinaryFieldOp) -> &'static str {
+    match op {
+        BinaryFieldOp::Add => "f+",
+        BinaryFieldOp::Sub => "f-",
+        BinaryFieldOp::Mul => "f*",
+        BinaryFieldOp::Div => "f/",
+        BinaryFieldOp::Equals => "f==",
+    }
+}
+
+fn int_op(op: &BinaryIntOp) -> &'static str {
+    match op {
+        BinaryIntOp::Add => "+",
+        BinaryIntOp::Sub => "-",
+        BinaryIntOp::Mul => "*",
+        BinaryIntOp::Equals => "==",
+        BinaryIntOp::SignedDiv => "/",
+        BinaryIntOp::UnsignedDiv => "//",
+        BinaryIntOp::LessThan => "<",
+        BinaryIntOp::LessThanEquals => "<=",
+        BinaryIntOp::And => "&&",
+        BinaryIntOp::Or => "||",
+        BinaryIntOp::Xor => "^",
+        BinaryIntOp::Shl => "<<",
+        BinaryIntOp::Shr => ">>",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::acir::brillig_vm::{Opcode as BrilligOpcode, RegisterIndex, Value};
+
+    use super::disassemble_brillig;
+
+    #[test]
+    fn labels_jump_targets_and_functions() {
+        let r0 = RegisterIndex::from(0);
+        let bytecode = vec![
+            BrilligOpcode::Call { location: 3 },
+            BrilligOpcode::Call { location: 5 },
+            BrilligOpcode::Stop,
+            BrilligOpcode::Const { destination: r0, value: Value::from(7_usize) },
+            BrilligOpcode::JumpIf { condition: r0, location: 6 },
+            BrilligOpcode::Return,
+            BrilligOpcode::Trap,
+        ];
+        let function_names = BTreeMap::from([(3, "main".to_string())]);
+
+        assert_eq!(
+            disassemble_brillig(&bytecode, &function_names),
+            [
+                "     0: CALL main",
+                "     1: CALL fn_5",
+                "     2: STOP",
+                "main:",
+                "     3: CONST R0 = 7",
+                "     4: JUMP_IF R0 TO L6",
+                "fn_5:",
+                "     5: RETURN",
+                "L6:",
+                "     6: TRAP",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -1,64 +1,159 @@
+use acvm::acir::brillig_vm::{ForeignCallOutput, ForeignCallWaitInfo, Value};
+use acvm::acir::circuit::opcodes::OracleData;
 use acvm::acir::circuit::Opcode;
-use acvm::pwg::{solve, Blocks, PartialWitnessGeneratorStatus, UnresolvedBrilligCall};
+use acvm::pwg::{
+    solve, Blocks, OpcodeResolutionError, PartialWitnessGeneratorStatus, UnresolvedBrilligCall,
+};
 use acvm::PartialWitnessGenerator;
 use acvm::{acir::circuit::Circuit, acir::native_types::WitnessMap};
 
-use crate::NargoError;
+use crate::{ForeignCallError, NargoError};
 
+use super::black_box::solve_black_box_call;
+use super::ForeignCallExecutor;
+
+/// Solves the given circuit, resolving any foreign calls it makes with `foreign_call_executor`.
+///
+/// The opcodes are solved one at a time so that, if the inputs do not satisfy the circuit,
+/// the index of the failing opcode is known and the failure can be traced back to the source
+/// code. Opcodes which cannot be solved yet, as they use witnesses defined by later opcodes,
+/// are retried once the rest of the circuit has been solved.
 pub fn execute_circuit(
     backend: &impl PartialWitnessGenerator,
     circuit: Circuit,
     mut initial_witness: WitnessMap,
+    foreign_call_executor: &mut impl ForeignCallExecutor,
 ) -> Result<WitnessMap, NargoError> {
     let mut blocks = Blocks::default();
-    let solver_status = solve(backend, &mut initial_witness, &mut blocks, circuit.opcodes)?;
-
-    // TODO(#1615): Nargo only supports "oracle_print_**_impl" functions  that print a singular value or an array and nothing else
-    // This should be expanded in a general logging refactor
-    if let PartialWitnessGeneratorStatus::RequiresOracleData {
-        unresolved_brillig_calls,
-        required_oracle_data,
-        unsolved_opcodes,
-    } = solver_status
-    {
-        if !required_oracle_data.is_empty() {
-            unreachable!("oracles are not supported by nargo execute")
+    let mut unsolved_opcodes: Vec<_> = circuit.opcodes.into_iter().enumerate().collect();
+
+    while !unsolved_opcodes.is_empty() {
+        let opcode_count = unsolved_opcodes.len();
+        let mut not_solvable_opcodes = Vec::new();
+        let mut first_not_solvable_error = None;
+
+        for (opcode_index, opcode) in unsolved_opcodes {
+            match solve_opcode(
+                backend,
+                &mut initial_witness,
+                &mut blocks,
+                opcode.clone(),
+                foreign_call_executor,
+            )? {
+                Ok(()) => (),
+                Err(error @ OpcodeResolutionError::OpcodeNotSolvable { .. }) => {
+                    first_not_solvable_error.get_or_insert(error);
+                    not_solvable_opcodes.push((opcode_index, opcode));
+                }
+                Err(error) => {
+                    return Err(NargoError::UnsatisfiedConstraint { opcode_index, error });
+                }
+            }
+        }
+
+        // None of the remaining opcodes can be solved with the witnesses known so far
+        if not_solvable_opcodes.len() == opcode_count {
+            let error = first_not_solvable_error.expect("at least one opcode is not solvable");
+            return Err(NargoError::SolvingError(error));
+        }
+        unsolved_opcodes = not_solvable_opcodes;
+    }
+
+    Ok(initial_witness)
+}
+
+/// Solves a single opcode, resolving any foreign calls it makes with `foreign_call_executor`.
+///
+/// The solver is re-run after each round of foreign calls until the opcode is solved, as the
+/// results of one foreign call may be needed before the solver can reach the next.
+///
+/// Foreign calls made by Brillig to the black box functions which depend on the proving backend
+/// are solved with `backend` rather than passed to `foreign_call_executor`.
+///
+/// Errors from the solver are returned in the inner result so that the caller can tell which
+/// opcode they come from, while errors from resolving the foreign calls are returned directly.
+fn solve_opcode(
+    backend: &impl PartialWitnessGenerator,
+    witness_map: &mut WitnessMap,
+    blocks: &mut Blocks,
+    opcode: Opcode,
+    foreign_call_executor: &mut impl ForeignCallExecutor,
+) -> Result<Result<(), OpcodeResolutionError>, NargoError> {
+    let mut opcodes = vec![opcode];
+
+    loop {
+        let solver_status = match solve(backend, witness_map, blocks, opcodes) {
+            Ok(solver_status) => solver_status,
+            Err(error) => return Ok(Err(error)),
+        };
+
+        let (unresolved_brillig_calls, required_oracle_data, unsolved_opcodes) = match solver_status
+        {
+            PartialWitnessGeneratorStatus::Solved => return Ok(Ok(())),
+            PartialWitnessGeneratorStatus::RequiresOracleData {
+                unresolved_brillig_calls,
+                required_oracle_data,
+                unsolved_opcodes,
+            } => (unresolved_brillig_calls, required_oracle_data, unsolved_opcodes),
+        };
+
+        // Each resolved opcode is placed before the remaining unsolved opcodes
+        // so that the solver can make use of its results.
+        let mut next_opcodes_for_solving = Vec::new();
+
+        for oracle in required_oracle_data {
+            let oracle = resolve_oracle(oracle, foreign_call_executor)?;
+            next_opcodes_for_solving.push(Opcode::Oracle(oracle));
         }
+
         for unresolved_brillig_call in unresolved_brillig_calls {
             let UnresolvedBrilligCall { foreign_call_wait_info, mut brillig } =
                 unresolved_brillig_call;
 
-            // Execute foreign calls
-            // TODO(#1615): "oracle_print_impl" and "oracle_print_array_impl" are just identity funcs
-            if foreign_call_wait_info.function == "oracle_print_impl" {
-                let values = &foreign_call_wait_info.inputs[0];
-                println!("{:?}", values[0].to_field().to_hex());
-                brillig.foreign_call_results.push(foreign_call_wait_info.inputs[0][0].into());
-            } else if foreign_call_wait_info.function == "oracle_print_array_impl" {
-                let mut outputs_hex = Vec::new();
-                for values in foreign_call_wait_info.inputs.clone() {
-                    for value in values {
-                        outputs_hex.push(value.to_field().to_hex());
-                    }
-                }
-                // Join all of the hex strings using a comma
-                let comma_separated_elements = outputs_hex.join(", ");
-                let output_witnesses_string = "[".to_owned() + &comma_separated_elements + "]";
-                println!("{output_witnesses_string}");
-                brillig.foreign_call_results.push(foreign_call_wait_info.inputs[0][0].into());
-            }
+            let result = match solve_black_box_call(backend, &foreign_call_wait_info) {
+                Some(Ok(result)) => result,
+                Some(Err(error)) => return Ok(Err(error)),
+                None => foreign_call_executor.execute(&foreign_call_wait_info)?,
+            };
+            brillig.foreign_call_results.push(result);
+            next_opcodes_for_solving.push(Opcode::Brillig(brillig));
+        }
 
-            let mut next_opcodes_for_solving = vec![Opcode::Brillig(brillig)];
-            next_opcodes_for_solving.extend_from_slice(&unsolved_opcodes[..]);
+        next_opcodes_for_solving.extend(unsolved_opcodes);
+        opcodes = next_opcodes_for_solving;
+    }
+}
+
+/// Oracles are resolved by the same executor as brillig foreign calls, with each of the
+/// oracle's inputs passed as a separate single-valued input.
+fn resolve_oracle(
+    mut oracle: OracleData,
+    foreign_call_executor: &mut impl ForeignCallExecutor,
+) -> Result<OracleData, ForeignCallError> {
+    let inputs = oracle.input_values.iter().map(|value| vec![Value::from(*value)]).collect();
+    let foreign_call = ForeignCallWaitInfo { function: oracle.name.clone(), inputs };
+
+    let result = foreign_call_executor.execute(&foreign_call)?;
 
-            let solver_status =
-                solve(backend, &mut initial_witness, &mut blocks, next_opcodes_for_solving)?;
-            if matches!(solver_status, PartialWitnessGeneratorStatus::RequiresOracleData { .. }) {
-                todo!("Add multiple foreign call support to nargo execute")
-                // TODO 1557
+    let output_values: Vec<_> = result
+        .values
+        .into_iter()
+        .flat_map(|output| match output {
+            ForeignCallOutput::Single(value) => vec![value.to_field()],
+            ForeignCallOutput::Array(values) => {
+                values.into_iter().map(|value| value.to_field()).collect()
             }
-        }
+        })
+        .collect();
+
+    if output_values.len() != oracle.outputs.len() {
+        return Err(ForeignCallError::IncorrectOutputCount {
+            function: oracle.name,
+            expected: oracle.outputs.len(),
+            actual: output_values.len(),
+        });
     }
 
-    Ok(initial_witness)
+    oracle.output_values = output_values;
+    Ok(oracle)
 }
@@ -0,0 +1,212 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use acvm::acir::brillig_vm::{ForeignCallOutput, ForeignCallResult, ForeignCallWaitInfo, Value};
+use acvm::FieldElement;
+use serde_json::json;
+
+use crate::errors::ForeignCallError;
+
+/// Resolves the foreign calls (oracles) made by a circuit while it is being executed.
+///
+/// `execute_circuit` calls into the executor each time the solver is unable to progress
+/// without the result of a foreign call, resuming the solver once a result is returned.
+pub trait ForeignCallExecutor {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<ForeignCallResult, ForeignCallError>;
+}
+
+/// The foreign calls which nargo is able to resolve itself.
+enum ForeignCall {
+    Print,
+    PrintArray,
+}
+
+impl ForeignCall {
+    fn lookup(name: &str) -> Option<ForeignCall> {
+        match name {
+            "oracle_print_impl" => Some(ForeignCall::Print),
+            "oracle_print_array_impl" => Some(ForeignCall::PrintArray),
+            _ => None,
+        }
+    }
+
+    fn execute(&self, foreign_call: &ForeignCallWaitInfo) -> ForeignCallResult {
+        // TODO(#1615): The print oracles only print a single value or an array and are identity
+        // functions otherwise. This should be expanded in a general logging refactor.
+        match self {
+            ForeignCall::Print => {
+                let values = &foreign_call.inputs[0];
+                println!("{:?}", values[0].to_field().to_hex());
+            }
+            ForeignCall::PrintArray => {
+                let outputs_hex: Vec<_> = foreign_call
+                    .inputs
+                    .iter()
+                    .flatten()
+                    .map(|value| value.to_field().to_hex())
+                    .collect();
+
+                println!("[{}]", outputs_hex.join(", "));
+            }
+        }
+
+        foreign_call.inputs[0][0].into()
+    }
+}
+
+/// Resolves nargo's built-in foreign calls such as `println`, forwarding any
+/// other foreign call to an external executor if one was provided.
+#[derive(Default)]
+pub struct DefaultForeignCallExecutor {
+    external: Option<Box<dyn ForeignCallExecutor>>,
+}
+
+impl DefaultForeignCallExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an executor which forwards any foreign call unknown to nargo to `external`.
+    pub fn with_external_executor(external: impl ForeignCallExecutor + 'static) -> Self {
+        DefaultForeignCallExecutor { external: Some(Box::new(external)) }
+    }
+}
+
+impl ForeignCallExecutor for DefaultForeignCallExecutor {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<ForeignCallResult, ForeignCallError> {
+        if let Some(builtin) = ForeignCall::lookup(&foreign_call.function) {
+            return Ok(builtin.execute(foreign_call));
+        }
+
+        match &mut self.external {
+            Some(external) => external.execute(foreign_call),
+            None => Err(ForeignCallError::NoHandler(foreign_call.function.clone())),
+        }
+    }
+}
+
+/// Forwards foreign calls to an external process as JSON-RPC 2.0 requests.
+///
+/// Each request and response is a single line of JSON written to the process' stdin and
+/// read from its stdout respectively. The request's `params` hold one array of hex-encoded
+/// field elements per input of the foreign call, and the response's `result` is expected
+/// to hold one entry per output, each either a hex string or an array of hex strings.
+pub struct JsonRpcForeignCallExecutor {
+    process: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_request_id: u64,
+}
+
+impl JsonRpcForeignCallExecutor {
+    /// Starts the process described by `command`, a program followed by its whitespace-separated arguments.
+    pub fn spawn(command: &str) -> Result<Self, ForeignCallError> {
+        let mut words = command.split_whitespace();
+        let program = words
+            .next()
+            .ok_or_else(|| ForeignCallError::InvalidResolver("empty command".to_owned()))?;
+
+        let mut process = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let stdin = process.stdin.take().expect("stdin was set to be piped");
+        let stdout = BufReader::new(process.stdout.take().expect("stdout was set to be piped"));
+
+        Ok(JsonRpcForeignCallExecutor { process, stdin, stdout, next_request_id: 0 })
+    }
+
+    fn parse_output(
+        function: &str,
+        output: &serde_json::Value,
+    ) -> Result<ForeignCallOutput, ForeignCallError> {
+        let parse_value = |value: &serde_json::Value| {
+            value.as_str().and_then(FieldElement::from_hex).map(Value::from).ok_or_else(|| {
+                ForeignCallError::InvalidResponse {
+                    function: function.to_owned(),
+                    reason: format!("expected a hex-encoded field element, found {value}"),
+                }
+            })
+        };
+
+        match output {
+            serde_json::Value::Array(values) => {
+                let values = values.iter().map(parse_value).collect::<Result<_, _>>()?;
+                Ok(ForeignCallOutput::Array(values))
+            }
+            single => Ok(ForeignCallOutput::Single(parse_value(single)?)),
+        }
+    }
+}
+
+impl ForeignCallExecutor for JsonRpcForeignCallExecutor {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo,
+    ) -> Result<ForeignCallResult, ForeignCallError> {
+        let function = &foreign_call.function;
+        let params: Vec<Vec<String>> = foreign_call
+            .inputs
+            .iter()
+            .map(|input| input.iter().map(|value| value.to_field().to_hex()).collect())
+            .collect();
+
+        let id = self.next_request_id;
+        self.next_request_id += 1;
+
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": function, "params": params });
+        writeln!(self.stdin, "{request}")?;
+        self.stdin.flush()?;
+
+        let mut line = String::new();
+        if self.stdout.read_line(&mut line)? == 0 {
+            return Err(ForeignCallError::InvalidResponse {
+                function: function.clone(),
+                reason: "the resolver exited before responding".to_owned(),
+            });
+        }
+
+        let invalid_response = |reason: String| ForeignCallError::InvalidResponse {
+            function: function.clone(),
+            reason,
+        };
+
+        let response: serde_json::Value =
+            serde_json::from_str(&line).map_err(|error| invalid_response(error.to_string()))?;
+
+        if let Some(error) = response.get("error") {
+            let message = error.get("message").and_then(|message| message.as_str());
+            let message = message.map_or_else(|| error.to_string(), ToOwned::to_owned);
+            return Err(ForeignCallError::ResolverError { function: function.clone(), message });
+        }
+
+        let outputs = response
+            .get("result")
+            .and_then(|result| result.as_array())
+            .ok_or_else(|| invalid_response("expected an array of outputs".to_owned()))?;
+
+        let values = outputs
+            .iter()
+            .map(|output| Self::parse_output(function, output))
+            .collect::<Result<_, _>>()?;
+
+        Ok(ForeignCallResult { values })
+    }
+}
+
+impl Drop for JsonRpcForeignCallExecutor {
+    fn drop(&mut self) {
+        // The resolver is not expected to exit on its own so we must stop it once
+        // execution has finished. It may have already exited so any error is ignored.
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
@@ -1,11 +1,18 @@
 pub use self::codegen_verifier::codegen_verifier;
+pub use self::disassemble::{disassemble_brillig, disassemble_brillig_opcodes};
 pub use self::execute::execute_circuit;
+pub use self::foreign_calls::{
+    DefaultForeignCallExecutor, ForeignCallExecutor, JsonRpcForeignCallExecutor,
+};
 pub use self::preprocess::{preprocess_contract_function, preprocess_program};
 pub use self::prove::prove_execution;
 pub use self::verify::verify_proof;
 
+mod black_box;
 mod codegen_verifier;
+mod disassemble;
 mod execute;
+mod foreign_calls;
 mod preprocess;
 mod prove;
 mod verify;
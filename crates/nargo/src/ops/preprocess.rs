@@ -23,6 +23,7 @@ pub fn preprocess_program<B: ProofSystemCompiler>(
         bytecode: optimized_bytecode,
         proving_key,
         verification_key,
+        debug: compiled_program.debug,
     })
 }
 
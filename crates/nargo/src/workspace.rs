@@ -2,3 +2,43 @@
 // Then we use workspace to allow more than one. In the future, do not allow there to be
 // both a binary and a library.
 // - library will be default
+use std::path::PathBuf;
+
+use crate::manifest::{InvalidPackageError, PackageManifest};
+
+/// A package which is being built as part of a workspace.
+#[derive(Debug, Clone)]
+pub struct Package {
+    pub name: String,
+    /// The directory containing the package's Nargo.toml
+    pub root_dir: PathBuf,
+    pub manifest: PackageManifest,
+}
+
+/// A collection of packages which are built together.
+///
+/// A Nargo.toml which describes a single package is treated as a workspace with one member.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    /// The directory containing the workspace's Nargo.toml
+    pub root_dir: PathBuf,
+    pub members: Vec<Package>,
+}
+
+impl Workspace {
+    /// Returns the member named `package_name`, or all members if no name is given.
+    pub fn select_members(
+        &self,
+        package_name: Option<&str>,
+    ) -> Result<Vec<&Package>, InvalidPackageError> {
+        match package_name {
+            None => Ok(self.members.iter().collect()),
+            Some(name) => self
+                .members
+                .iter()
+                .find(|member| member.name == name)
+                .map(|member| vec![member])
+                .ok_or_else(|| InvalidPackageError::UnknownPackage(name.to_owned())),
+        }
+    }
+}
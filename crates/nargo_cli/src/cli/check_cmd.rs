@@ -1,9 +1,9 @@
-use crate::{errors::CliError, resolver::Resolver};
+use crate::errors::CliError;
 use acvm::Backend;
 use clap::Args;
 use iter_extended::btree_map;
 use noirc_abi::{AbiParameter, AbiType, MAIN_RETURN_NAME};
-use noirc_driver::CompileOptions;
+use noirc_driver::{CompileOptions, Driver};
 use noirc_errors::reporter::ReportedErrors;
 use std::path::{Path, PathBuf};
 
@@ -22,8 +22,9 @@ pub(crate) fn run<B: Backend>(
     backend: &B,
     args: CheckCommand,
     config: NargoConfig,
+    mut driver: Driver,
 ) -> Result<(), CliError<B>> {
-    check_from_path(backend, &config.program_dir, &args.compile_options)?;
+    check_from_path(backend, &mut driver, &config.program_dir, &args.compile_options)?;
     println!("Constraint system successfully built!");
     Ok(())
 }
@@ -32,11 +33,11 @@ fn check_from_path<B: Backend>(
     // Backend isn't used but keeping it in the signature allows for better type inference
     // TODO: This function doesn't need to exist but requires a little more refactoring
     _backend: &B,
+    driver: &mut Driver,
     program_dir: &Path,
     compile_options: &CompileOptions,
 ) -> Result<(), CliError<B>> {
-    let mut driver = Resolver::resolve_root_manifest(program_dir)?;
-    check_crate_and_report_errors(&mut driver, compile_options.deny_warnings)?;
+    check_crate_and_report_errors(driver, compile_options.deny_warnings)?;
 
     // XXX: We can have a --overwrite flag to determine if you want to overwrite the Prover/Verifier.toml files
     if let Some((parameters, return_type)) = driver.compute_function_signature() {
@@ -86,6 +87,10 @@ fn create_input_toml_template(
                 );
                 toml::Value::Table(default_value_map)
             }
+            AbiType::Tuple { fields } => {
+                let default_value_vec = fields.into_iter().map(default_value).collect();
+                toml::Value::Array(default_value_vec)
+            }
             _ => toml::Value::String("".to_owned()),
         }
     }
@@ -108,6 +113,7 @@ mod tests {
     use noirc_driver::CompileOptions;
 
     use super::create_input_toml_template;
+    use crate::cli::resolve_driver;
 
     const TEST_DATA_DIR: &str = "tests/target_tests_data";
 
@@ -135,6 +141,15 @@ mod tests {
                 },
             ),
             typed_param("e", AbiType::Boolean),
+            typed_param(
+                "f",
+                AbiType::Tuple {
+                    fields: vec![
+                        AbiType::Field,
+                        AbiType::Array { length: 2, typ: Box::new(AbiType::Boolean) },
+                    ],
+                },
+            ),
         ];
 
         let toml_str = create_input_toml_template(parameters, None);
@@ -143,6 +158,7 @@ mod tests {
 b = ""
 c = ["", ""]
 e = ""
+f = ["", ["", ""]]
 
 [d]
 d1 = ""
@@ -162,7 +178,8 @@ d2 = ["", "", ""]
         for path in paths.flatten() {
             let path = path.path();
             assert!(
-                super::check_from_path(&backend, &path, &config).is_ok(),
+                super::check_from_path(&backend, &mut resolve_driver(&path), &path, &config)
+                    .is_ok(),
                 "path: {}",
                 path.display()
             );
@@ -181,7 +198,8 @@ d2 = ["", "", ""]
         for path in paths.flatten() {
             let path = path.path();
             assert!(
-                super::check_from_path(&backend, &path, &config).is_err(),
+                super::check_from_path(&backend, &mut resolve_driver(&path), &path, &config)
+                    .is_err(),
                 "path: {}",
                 path.display()
             );
@@ -200,7 +218,8 @@ d2 = ["", "", ""]
         for path in paths.flatten() {
             let path = path.path();
             assert!(
-                super::check_from_path(&backend, &path, &config).is_ok(),
+                super::check_from_path(&backend, &mut resolve_driver(&path), &path, &config)
+                    .is_ok(),
                 "path: {}",
                 path.display()
             );
@@ -15,7 +15,7 @@ use crate::{
 use acvm::Backend;
 use clap::Args;
 use nargo::ops::{codegen_verifier, preprocess_program};
-use noirc_driver::CompileOptions;
+use noirc_driver::{CompileOptions, Driver};
 
 /// Generates a Solidity verifier smart contract for the program
 #[derive(Debug, Clone, Args)]
@@ -31,6 +31,7 @@ pub(crate) fn run<B: Backend>(
     backend: &B,
     args: CodegenVerifierCommand,
     config: NargoConfig,
+    mut driver: Driver,
 ) -> Result<(), CliError<B>> {
     // TODO(#1201): Should this be a utility function?
     let circuit_build_path = args
@@ -51,8 +52,7 @@ pub(crate) fn run<B: Backend>(
             (common_reference_string, program)
         }
         None => {
-            let program =
-                compile_circuit(backend, config.program_dir.as_ref(), &args.compile_options)?;
+            let program = compile_circuit(backend, &mut driver, &args.compile_options)?;
             let common_reference_string =
                 update_common_reference_string(backend, &common_reference_string, &program.circuit)
                     .map_err(CliError::CommonReferenceStringError)?;
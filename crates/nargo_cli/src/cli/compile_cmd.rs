@@ -3,13 +3,12 @@ use iter_extended::try_vecmap;
 use nargo::artifacts::contract::PreprocessedContract;
 use noirc_driver::{CompileOptions, CompiledProgram, Driver, ErrorsAndWarnings, Warnings};
 use noirc_errors::reporter::ReportedErrors;
-use std::path::Path;
 
 use clap::Args;
 
 use nargo::ops::{preprocess_contract_function, preprocess_program};
 
-use crate::{constants::TARGET_DIR, errors::CliError, resolver::Resolver};
+use crate::{constants::TARGET_DIR, errors::CliError};
 
 use super::fs::{
     common_reference_string::{
@@ -41,6 +40,7 @@ pub(crate) fn run<B: Backend>(
     backend: &B,
     args: CompileCommand,
     config: NargoConfig,
+    mut driver: Driver,
 ) -> Result<(), CliError<B>> {
     let circuit_dir = config.program_dir.join(TARGET_DIR);
 
@@ -48,8 +48,6 @@ pub(crate) fn run<B: Backend>(
 
     // If contracts is set we're compiling every function in a 'contract' rather than just 'main'.
     if args.contracts {
-        let mut driver = Resolver::resolve_root_manifest(&config.program_dir)?;
-
         let result = driver.compile_contracts(
             backend.np_language(),
             &|op| backend.supports_opcode(op),
@@ -89,7 +87,7 @@ pub(crate) fn run<B: Backend>(
             );
         }
     } else {
-        let program = compile_circuit(backend, &config.program_dir, &args.compile_options)?;
+        let program = compile_circuit(backend, &mut driver, &args.compile_options)?;
         common_reference_string =
             update_common_reference_string(backend, &common_reference_string, &program.circuit)
                 .map_err(CliError::CommonReferenceStringError)?;
@@ -106,16 +104,15 @@ pub(crate) fn run<B: Backend>(
 
 pub(crate) fn compile_circuit<B: Backend>(
     backend: &B,
-    program_dir: &Path,
+    driver: &mut Driver,
     compile_options: &CompileOptions,
 ) -> Result<CompiledProgram, CliError<B>> {
-    let mut driver = Resolver::resolve_root_manifest(program_dir)?;
     let result = driver.compile_main(
         backend.np_language(),
         &|op| backend.supports_opcode(op),
         compile_options,
     );
-    report_errors(result, &driver, compile_options.deny_warnings).map_err(Into::into)
+    report_errors(result, driver, compile_options.deny_warnings).map_err(Into::into)
 }
 
 /// Helper function for reporting any errors in a Result<(T, Warnings), ErrorsAndWarnings>
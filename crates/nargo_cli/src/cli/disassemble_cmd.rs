@@ -0,0 +1,32 @@
+use acvm::Backend;
+use clap::Args;
+use nargo::ops::disassemble_brillig_opcodes;
+
+use super::fs::program::read_program_from_file;
+use super::NargoConfig;
+use crate::{constants::TARGET_DIR, errors::CliError};
+
+/// Prints the brillig bytecode of a compiled program, with its jump targets and functions labelled
+#[derive(Debug, Clone, Args)]
+pub(crate) struct DisassembleCommand {
+    /// The name of the circuit build file to disassemble
+    circuit_name: String,
+}
+
+pub(crate) fn run<B: Backend>(
+    _backend: &B,
+    args: DisassembleCommand,
+    config: NargoConfig,
+) -> Result<(), CliError<B>> {
+    let circuit_build_path = config.program_dir.join(TARGET_DIR).join(&args.circuit_name);
+    let program = read_program_from_file(circuit_build_path)?;
+
+    let disassembly = disassemble_brillig_opcodes(&program.bytecode, &program.debug);
+    if disassembly.is_empty() {
+        println!("Circuit {} contains no brillig code", args.circuit_name);
+    } else {
+        print!("{disassembly}");
+    }
+
+    Ok(())
+}
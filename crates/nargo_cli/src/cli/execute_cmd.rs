@@ -3,14 +3,17 @@ use std::path::Path;
 use acvm::acir::{circuit::Circuit, native_types::WitnessMap};
 use acvm::Backend;
 use clap::Args;
+use nargo::ops::{DefaultForeignCallExecutor, ForeignCallExecutor, JsonRpcForeignCallExecutor};
+use nargo::NargoError;
 use noirc_abi::input_parser::{Format, InputValue};
 use noirc_abi::{Abi, InputMap};
-use noirc_driver::{CompileOptions, CompiledProgram};
+use noirc_driver::{CompileOptions, CompiledProgram, Driver};
+use noirc_errors::{reporter, CustomDiagnostic, DebugInfo};
 
 use super::fs::{inputs::read_inputs_from_file, witness::save_witness_to_dir};
 use super::NargoConfig;
 use crate::{
-    cli::compile_cmd::compile_circuit,
+    cli::{compile_cmd::compile_circuit, profile_cmd::source_line},
     constants::{PROVER_INPUT_FILE, TARGET_DIR},
     errors::CliError,
 };
@@ -25,6 +28,11 @@ pub(crate) struct ExecuteCommand {
     #[clap(long, short, default_value = PROVER_INPUT_FILE)]
     prover_name: String,
 
+    /// Command to start a process which resolves any foreign calls unknown to nargo.
+    /// Foreign calls are sent to the process as JSON-RPC requests over its stdin.
+    #[clap(long)]
+    oracle_resolver: Option<String>,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
@@ -33,9 +41,24 @@ pub(crate) fn run<B: Backend>(
     backend: &B,
     args: ExecuteCommand,
     config: NargoConfig,
+    mut driver: Driver,
 ) -> Result<(), CliError<B>> {
-    let (return_value, solved_witness) =
-        execute_with_path(backend, &config.program_dir, args.prover_name, &args.compile_options)?;
+    let mut foreign_call_executor = match &args.oracle_resolver {
+        Some(command) => {
+            let resolver = JsonRpcForeignCallExecutor::spawn(command).map_err(NargoError::from)?;
+            DefaultForeignCallExecutor::with_external_executor(resolver)
+        }
+        None => DefaultForeignCallExecutor::new(),
+    };
+
+    let (return_value, solved_witness) = execute_with_path(
+        backend,
+        &mut driver,
+        &config.program_dir,
+        args.prover_name,
+        &mut foreign_call_executor,
+        &args.compile_options,
+    )?;
 
     println!("Circuit witness successfully solved");
     if let Some(return_value) = return_value {
@@ -53,17 +76,27 @@ pub(crate) fn run<B: Backend>(
 
 fn execute_with_path<B: Backend>(
     backend: &B,
+    driver: &mut Driver,
     program_dir: &Path,
     prover_name: String,
+    foreign_call_executor: &mut impl ForeignCallExecutor,
     compile_options: &CompileOptions,
 ) -> Result<(Option<InputValue>, WitnessMap), CliError<B>> {
-    let CompiledProgram { abi, circuit } = compile_circuit(backend, program_dir, compile_options)?;
+    let CompiledProgram { abi, circuit, debug } =
+        compile_circuit(backend, driver, compile_options)?;
 
     // Parse the initial witness values from Prover.toml
     let (inputs_map, _) =
         read_inputs_from_file(program_dir, prover_name.as_str(), Format::Toml, &abi)?;
 
-    let solved_witness = execute_program(backend, circuit, &abi, &inputs_map)?;
+    let solved_witness =
+        match execute_program(backend, circuit, &abi, &inputs_map, foreign_call_executor) {
+            Ok(solved_witness) => solved_witness,
+            Err(error) => {
+                report_unsatisfied_constraint(driver, program_dir, &debug, &error);
+                return Err(error);
+            }
+        };
 
     let public_abi = abi.public_abi();
     let (_, return_value) = public_abi.decode(&solved_witness)?;
@@ -71,15 +104,56 @@ fn execute_with_path<B: Backend>(
     Ok((return_value, solved_witness))
 }
 
+/// Solves the circuit for the given inputs.
 pub(crate) fn execute_program<B: Backend>(
     backend: &B,
     circuit: Circuit,
     abi: &Abi,
     inputs_map: &InputMap,
+    foreign_call_executor: &mut impl ForeignCallExecutor,
 ) -> Result<WitnessMap, CliError<B>> {
     let initial_witness = abi.encode(inputs_map, None)?;
 
-    let solved_witness = nargo::ops::execute_circuit(backend, circuit, initial_witness)?;
+    let solved_witness =
+        nargo::ops::execute_circuit(backend, circuit, initial_witness, foreign_call_executor)?;
 
     Ok(solved_witness)
 }
+
+/// Reports the source location of the constraint which failed to execute, if `error` is an
+/// unsatisfied constraint and its location is known.
+///
+/// The circuit's debug information refers to the files loaded by `driver` when compiling it.
+pub(crate) fn report_unsatisfied_constraint<B: Backend>(
+    driver: &Driver,
+    program_dir: &Path,
+    debug: &DebugInfo,
+    error: &CliError<B>,
+) {
+    let opcode_index = match error {
+        CliError::NargoError(NargoError::UnsatisfiedConstraint { opcode_index, .. }) => {
+            *opcode_index
+        }
+        _ => return,
+    };
+    let (location, callers) = match debug.opcode_location(opcode_index) {
+        Some([callers @ .., location]) => (location, callers),
+        _ => return,
+    };
+
+    let mut diagnostic = CustomDiagnostic::simple_error(
+        "Failed constraint".to_owned(),
+        "the inputs do not satisfy this constraint".to_owned(),
+        location.span,
+    );
+    for caller in callers.iter().rev() {
+        if caller.file == location.file {
+            diagnostic.add_secondary("called from here".to_owned(), caller.span);
+        } else {
+            let (file, line) = source_line(driver, program_dir, *caller);
+            diagnostic.add_note(format!("called from {file}:{line}"));
+        }
+    }
+
+    reporter::report(driver.file_manager(), &diagnostic, Some(location.file), false);
+}
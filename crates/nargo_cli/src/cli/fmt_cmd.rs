@@ -0,0 +1,100 @@
+use std::path::{Path, PathBuf};
+
+use acvm::Backend;
+use clap::Args;
+use fm::{FileManager, FileType};
+use noirc_errors::reporter;
+
+use crate::{
+    constants::{PKG_FILE, SRC_DIR},
+    errors::{CliError, FilesystemError},
+    resolver::DependencyResolutionError,
+};
+
+use super::NargoConfig;
+
+/// Formats the Noir source files of this package
+#[derive(Debug, Clone, Args)]
+pub(crate) struct FmtCommand {
+    /// Check whether the files are formatted rather than formatting them,
+    /// erroring if any file would be changed
+    #[arg(long)]
+    check: bool,
+}
+
+pub(crate) fn run<B: Backend>(
+    _backend: &B,
+    args: FmtCommand,
+    config: NargoConfig,
+) -> Result<(), CliError<B>> {
+    let manifest = crate::manifest::parse_package(config.program_dir.join(PKG_FILE))
+        .map_err(DependencyResolutionError::from)?;
+
+    let mut files = Vec::new();
+    collect_source_files(&config.program_dir.join(SRC_DIR), &mut files)
+        .map_err(|error| CliError::Generic(format!("Failed to read source files: {error}")))?;
+    files.sort();
+
+    let mut unformatted_files = Vec::new();
+    for path in files {
+        let source = std::fs::read_to_string(&path)
+            .map_err(|_| FilesystemError::PathNotValid(path.clone()))?;
+
+        let formatted = match nargo_fmt::format(&source, &manifest.fmt) {
+            Ok(formatted) => formatted,
+            Err(diagnostics) => return Err(report_parse_errors(&path, diagnostics)),
+        };
+        if formatted == source {
+            continue;
+        }
+
+        if args.check {
+            unformatted_files.push(path);
+        } else {
+            std::fs::write(&path, formatted).map_err(|error| {
+                CliError::Generic(format!("Failed to write {}: {error}", path.display()))
+            })?;
+        }
+    }
+
+    if unformatted_files.is_empty() {
+        return Ok(());
+    }
+    for path in &unformatted_files {
+        let relative_path = path.strip_prefix(&config.program_dir).unwrap_or(path);
+        println!("{}", relative_path.display());
+    }
+    let plural = if unformatted_files.len() == 1 { "" } else { "s" };
+    Err(CliError::Generic(format!(
+        "{} file{plural} would be reformatted, run `nargo fmt` to format them",
+        unformatted_files.len()
+    )))
+}
+
+/// Reports the errors in a file which could not be parsed, and so could not be formatted.
+fn report_parse_errors<B: Backend>(
+    path: &Path,
+    diagnostics: Vec<noirc_errors::CustomDiagnostic>,
+) -> CliError<B> {
+    let mut file_manager = FileManager::default();
+    let file_id = match file_manager.add_file(path, FileType::Normal) {
+        Some(file_id) => file_id,
+        None => return FilesystemError::PathNotValid(path.to_path_buf()).into(),
+    };
+
+    let diagnostics: Vec<_> =
+        diagnostics.into_iter().map(|diagnostic| diagnostic.in_file(file_id)).collect();
+    reporter::report_all(&file_manager, &diagnostics, false).into()
+}
+
+fn collect_source_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_source_files(&path, files)?;
+        } else if path.extension().map_or(false, |extension| extension == fm::FILE_EXTENSION) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
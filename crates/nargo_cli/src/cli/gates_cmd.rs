@@ -1,7 +1,6 @@
 use acvm::Backend;
 use clap::Args;
-use noirc_driver::CompileOptions;
-use std::path::Path;
+use noirc_driver::{CompileOptions, Driver};
 
 use crate::cli::compile_cmd::compile_circuit;
 use crate::errors::CliError;
@@ -18,17 +17,18 @@ pub(crate) struct GatesCommand {
 pub(crate) fn run<B: Backend>(
     backend: &B,
     args: GatesCommand,
-    config: NargoConfig,
+    _config: NargoConfig,
+    mut driver: Driver,
 ) -> Result<(), CliError<B>> {
-    count_gates_with_path(backend, config.program_dir, &args.compile_options)
+    count_gates(backend, &mut driver, &args.compile_options)
 }
 
-fn count_gates_with_path<B: Backend, P: AsRef<Path>>(
+fn count_gates<B: Backend>(
     backend: &B,
-    program_dir: P,
+    driver: &mut Driver,
     compile_options: &CompileOptions,
 ) -> Result<(), CliError<B>> {
-    let compiled_program = compile_circuit(backend, program_dir.as_ref(), compile_options)?;
+    let compiled_program = compile_circuit(backend, driver, compile_options)?;
     let num_opcodes = compiled_program.circuit.opcodes.len();
 
     println!(
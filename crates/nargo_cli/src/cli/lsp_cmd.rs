@@ -1,16 +1,26 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
 use acvm::Backend;
 use async_lsp::{
     client_monitor::ClientProcessMonitorLayer, concurrency::ConcurrencyLayer,
     panic::CatchUnwindLayer, server::LifecycleLayer, tracing::TracingLayer,
 };
 use clap::Args;
-use noir_lsp::NargoLspService;
-use noirc_driver::CompileOptions;
+use noir_lsp::{NargoLspService, PackageResolver};
+use noirc_driver::{CompileOptions, Driver};
 use tokio::io::BufReader;
 use tower::ServiceBuilder;
 
 use super::NargoConfig;
-use crate::errors::CliError;
+use crate::{
+    errors::CliError,
+    find_package_manifest,
+    resolver::{ResolutionOptions, ResolvedWorkspace, Resolver},
+};
 
 #[derive(Debug, Clone, Args)]
 pub(crate) struct LspCommand {
@@ -30,7 +40,7 @@ pub(crate) fn run<B: Backend>(
 
     runtime.block_on(async {
         let (server, _) = async_lsp::Frontend::new_server(|client| {
-            let router = NargoLspService::new(&client);
+            let router = NargoLspService::new(&client, WorkspaceResolver::default());
 
             ServiceBuilder::new()
                 .layer(TracingLayer::default())
@@ -56,3 +66,50 @@ pub(crate) fn run<B: Backend>(
         server.run(stdin, stdout).await.map_err(CliError::LspError)
     })
 }
+
+/// Resolves the packages of the documents opened in the editor in the same way as the other commands,
+/// so that their dependencies and Nargo.lock are taken into account.
+#[derive(Default)]
+struct WorkspaceResolver {
+    /// The resolved workspace containing each package, keyed by the package's root directory
+    workspaces: HashMap<PathBuf, Rc<ResolvedWorkspace>>,
+}
+
+impl PackageResolver for WorkspaceResolver {
+    fn resolve_package(&mut self, file_path: &Path) -> Result<Option<PathBuf>, String> {
+        let dir = match file_path.parent() {
+            Some(dir) if find_package_manifest(dir).is_ok() => dir,
+            _ => return Ok(None),
+        };
+
+        let resolved = Resolver::resolve_workspace(dir, ResolutionOptions::default())
+            .map_err(|error| error.to_string())?;
+        let root_dir = match resolved
+            .workspace
+            .members
+            .iter()
+            .find(|member| file_path.starts_with(&member.root_dir))
+        {
+            Some(package) => package.root_dir.clone(),
+            None => return Ok(None),
+        };
+
+        // Other members of the workspace are resolved along with this package
+        let resolved = Rc::new(resolved);
+        for member in &resolved.workspace.members {
+            self.workspaces.insert(member.root_dir.clone(), resolved.clone());
+        }
+        Ok(Some(root_dir))
+    }
+
+    fn add_package(&self, root_dir: &Path, driver: &mut Driver) {
+        let resolved = &self.workspaces[root_dir];
+        let package = resolved
+            .workspace
+            .members
+            .iter()
+            .find(|member| member.root_dir == root_dir)
+            .expect("packages are only added once they have been resolved");
+        resolved.add_to_driver(package, driver);
+    }
+}
@@ -1,21 +1,27 @@
 use clap::{Args, Parser, Subcommand};
 use const_format::formatcp;
-use noirc_driver::CompileOptions;
+use noirc_driver::{CompileOptions, Driver};
 use std::path::{Path, PathBuf};
 
 use color_eyre::eyre;
 
-use crate::find_package_root;
+use crate::{
+    backends::ConcreteBackend,
+    resolver::{ResolutionOptions, Resolver},
+};
 
 mod fs;
 
 mod check_cmd;
 mod codegen_verifier_cmd;
 mod compile_cmd;
+mod disassemble_cmd;
 mod execute_cmd;
+mod fmt_cmd;
 mod gates_cmd;
 mod lsp_cmd;
 mod new_cmd;
+mod profile_cmd;
 mod prove_cmd;
 mod test_cmd;
 mod verify_cmd;
@@ -42,6 +48,18 @@ struct NargoCli {
 pub(crate) struct NargoConfig {
     #[arg(short, long, hide=true, default_value_os_t = std::env::current_dir().unwrap())]
     program_dir: PathBuf,
+
+    /// The name of the workspace member to run the command on, by default every member is used
+    #[arg(long, global = true)]
+    package: Option<String>,
+
+    /// Require Nargo.lock to be up to date, erroring rather than updating it
+    #[arg(long, global = true)]
+    locked: bool,
+
+    /// Only use dependencies which have already been downloaded
+    #[arg(long, global = true)]
+    offline: bool,
 }
 
 #[non_exhaustive]
@@ -50,41 +68,89 @@ enum NargoCommand {
     Check(check_cmd::CheckCommand),
     CodegenVerifier(codegen_verifier_cmd::CodegenVerifierCommand),
     Compile(compile_cmd::CompileCommand),
+    Disassemble(disassemble_cmd::DisassembleCommand),
     New(new_cmd::NewCommand),
     Execute(execute_cmd::ExecuteCommand),
+    Fmt(fmt_cmd::FmtCommand),
     Prove(prove_cmd::ProveCommand),
     Verify(verify_cmd::VerifyCommand),
     Test(test_cmd::TestCommand),
     Gates(gates_cmd::GatesCommand),
+    Profile(profile_cmd::ProfileCommand),
     Lsp(lsp_cmd::LspCommand),
 }
 
 pub fn start_cli() -> eyre::Result<()> {
-    let NargoCli { command, mut config } = NargoCli::parse();
+    let NargoCli { command, config } = NargoCli::parse();
 
-    // Search through parent directories to find package root if necessary.
-    if !matches!(command, NargoCommand::New(_) | NargoCommand::Lsp(_)) {
-        config.program_dir = find_package_root(&config.program_dir)?;
+    let backend = crate::backends::ConcreteBackend::default();
+
+    // These commands don't operate on an existing package
+    match command {
+        NargoCommand::New(args) => return Ok(new_cmd::run(&backend, args, config)?),
+        NargoCommand::Lsp(args) => return Ok(lsp_cmd::run(&backend, args, config)?),
+        _ => (),
     }
 
-    let backend = crate::backends::ConcreteBackend::default();
+    // Search through parent directories to find the workspace and run the command on each selected member.
+    let options = ResolutionOptions { locked: config.locked, offline: config.offline };
+    let resolved = Resolver::resolve_workspace(&config.program_dir, options)?;
+    let workspace = &resolved.workspace;
+    for package in workspace.select_members(config.package.as_deref())? {
+        if workspace.members.len() > 1 {
+            eprintln!("[{}]", package.name);
+        }
+        let config = NargoConfig { program_dir: package.root_dir.clone(), ..config.clone() };
+        let driver = resolved.create_driver(package);
+        run_command(&backend, command.clone(), config, driver)?;
+    }
+
+    Ok(())
+}
 
+fn run_command(
+    backend: &ConcreteBackend,
+    command: NargoCommand,
+    config: NargoConfig,
+    driver: Driver,
+) -> eyre::Result<()> {
     match command {
-        NargoCommand::New(args) => new_cmd::run(&backend, args, config),
-        NargoCommand::Check(args) => check_cmd::run(&backend, args, config),
-        NargoCommand::Compile(args) => compile_cmd::run(&backend, args, config),
-        NargoCommand::Execute(args) => execute_cmd::run(&backend, args, config),
-        NargoCommand::Prove(args) => prove_cmd::run(&backend, args, config),
-        NargoCommand::Verify(args) => verify_cmd::run(&backend, args, config),
-        NargoCommand::Test(args) => test_cmd::run(&backend, args, config),
-        NargoCommand::Gates(args) => gates_cmd::run(&backend, args, config),
-        NargoCommand::CodegenVerifier(args) => codegen_verifier_cmd::run(&backend, args, config),
-        NargoCommand::Lsp(args) => lsp_cmd::run(&backend, args, config),
+        NargoCommand::New(_) | NargoCommand::Lsp(_) => {
+            unreachable!(
+                "commands which don't operate on a package are run before resolving the workspace"
+            )
+        }
+        NargoCommand::Check(args) => check_cmd::run(backend, args, config, driver),
+        NargoCommand::Compile(args) => compile_cmd::run(backend, args, config, driver),
+        NargoCommand::Disassemble(args) => disassemble_cmd::run(backend, args, config),
+        NargoCommand::Execute(args) => execute_cmd::run(backend, args, config, driver),
+        NargoCommand::Fmt(args) => fmt_cmd::run(backend, args, config),
+        NargoCommand::Prove(args) => prove_cmd::run(backend, args, config, driver),
+        NargoCommand::Verify(args) => verify_cmd::run(backend, args, config, driver),
+        NargoCommand::Test(args) => test_cmd::run(backend, args, config, driver),
+        NargoCommand::Gates(args) => gates_cmd::run(backend, args, config, driver),
+        NargoCommand::Profile(args) => profile_cmd::run(backend, args, config, driver),
+        NargoCommand::CodegenVerifier(args) => {
+            codegen_verifier_cmd::run(backend, args, config, driver)
+        }
     }?;
 
     Ok(())
 }
 
+/// Resolves the package in `program_dir` with the default resolution options and returns its driver.
+pub(crate) fn resolve_driver(program_dir: &Path) -> Driver {
+    let resolved = Resolver::resolve_workspace(program_dir, ResolutionOptions::default())
+        .expect("Dependencies should resolve");
+    let package = resolved
+        .workspace
+        .members
+        .iter()
+        .find(|member| member.root_dir == program_dir)
+        .expect("Directory should contain a package");
+    resolved.create_driver(package)
+}
+
 // helper function which tests noir programs by trying to generate a proof and verify it without reading/writing to the filesystem
 pub fn prove_and_verify(program_dir: &Path, experimental_ssa: bool) -> bool {
     use compile_cmd::compile_circuit;
@@ -99,10 +165,15 @@ pub fn prove_and_verify(program_dir: &Path, experimental_ssa: bool) -> bool {
         deny_warnings: false,
         show_output: false,
         experimental_ssa,
+        validate_ssa: false,
+        ssa_passes: None,
+        skip_ssa_passes: Vec::new(),
+        dump_ssa_dir: None,
     };
 
+    let mut driver = resolve_driver(program_dir);
     let program =
-        compile_circuit(&backend, program_dir, &compile_options).expect("Compile should succeed");
+        compile_circuit(&backend, &mut driver, &compile_options).expect("Compile should succeed");
     let common_reference_string = update_common_reference_string(
         &backend,
         // Empty CRS is always used since we don't read/write a cached version in these tests
@@ -130,12 +201,18 @@ pub fn prove_and_verify(program_dir: &Path, experimental_ssa: bool) -> bool {
     )
     .expect("Should read inputs");
 
-    let solved_witness =
-        match execute_cmd::execute_program(&backend, bytecode.clone(), &abi, &inputs_map) {
-            Ok(witness) => witness,
-            // Failure to execute is an invalid proof
-            Err(_) => return false,
-        };
+    let mut foreign_call_executor = nargo::ops::DefaultForeignCallExecutor::new();
+    let solved_witness = match execute_cmd::execute_program(
+        &backend,
+        bytecode.clone(),
+        &abi,
+        &inputs_map,
+        &mut foreign_call_executor,
+    ) {
+        Ok(witness) => witness,
+        // Failure to execute is an invalid proof
+        Err(_) => return false,
+    };
 
     let public_abi = abi.public_abi();
     let (public_inputs, return_value) =
@@ -0,0 +1,177 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use acvm::{acir::circuit::Opcode, Backend};
+use clap::Args;
+use noirc_driver::{CompileOptions, Driver};
+use noirc_errors::{reporter, Location};
+
+use crate::{cli::check_cmd::check_crate_and_report_errors, errors::CliError};
+
+use super::NargoConfig;
+
+/// The number of source lines generating the most opcodes which are listed in the report
+const REPORTED_SOURCE_LINES: usize = 10;
+
+/// Attributes the ACIR opcodes of the circuit to the functions and source lines they came from
+#[derive(Debug, Clone, Args)]
+pub(crate) struct ProfileCommand {
+    /// Write the opcode count of each call stack to the given file, in the folded stack
+    /// format read by flamegraph tools
+    #[arg(long)]
+    folded: Option<PathBuf>,
+
+    #[clap(flatten)]
+    compile_options: CompileOptions,
+}
+
+/// The opcodes attributed to a single function.
+#[derive(Debug, Default)]
+struct FunctionProfile {
+    /// Opcodes generated by the function's own body
+    self_opcodes: usize,
+    /// Opcodes generated by the function's body and by every function it calls
+    total_opcodes: usize,
+    /// Black box function calls made by the function's own body
+    black_box_calls: usize,
+}
+
+pub(crate) fn run<B: Backend>(
+    backend: &B,
+    args: ProfileCommand,
+    config: NargoConfig,
+    mut driver: Driver,
+) -> Result<(), CliError<B>> {
+    // Source locations are only tracked by the experimental SSA pass
+    let compile_options = CompileOptions { experimental_ssa: true, ..args.compile_options };
+
+    check_crate_and_report_errors(&mut driver, compile_options.deny_warnings)?;
+
+    let main = driver.main_function().ok_or_else(|| {
+        CliError::Generic("cannot profile a crate which is not a binary".to_owned())
+    })?;
+    let main_name = driver.function_name(main);
+
+    let (circuit, debug_info, _) = driver
+        .compile_debug_no_check(&compile_options, main)
+        .map_err(|error| reporter::report_all(driver.file_manager(), &[error], false))?;
+
+    let mut functions: BTreeMap<&str, FunctionProfile> = BTreeMap::new();
+    let mut source_lines: HashMap<(String, usize), usize> = HashMap::new();
+    let mut folded_stacks: BTreeMap<String, usize> = BTreeMap::new();
+
+    for (index, opcode) in circuit.opcodes.iter().enumerate() {
+        let call_stack = debug_info.opcode_location(index).unwrap_or_default();
+
+        // Each location in the call stack is within the function called by the previous one.
+        // Opcodes with no location, such as those constraining main's inputs, belong to main.
+        let mut stack: Vec<&str> =
+            call_stack.iter().map(|location| function_name_at(&driver, *location)).collect();
+        if stack.is_empty() {
+            stack.push(main_name);
+        }
+
+        let own_function = functions.entry(stack[stack.len() - 1]).or_default();
+        own_function.self_opcodes += 1;
+        if matches!(opcode, Opcode::BlackBoxFuncCall(_)) {
+            own_function.black_box_calls += 1;
+        }
+
+        // Recursive functions appear in a stack more than once but only count its opcodes once
+        let unique_functions: HashSet<&str> = stack.iter().copied().collect();
+        for function in unique_functions {
+            functions.entry(function).or_default().total_opcodes += 1;
+        }
+
+        if let Some(location) = call_stack.last() {
+            let source_line = source_line(&driver, &config.program_dir, *location);
+            *source_lines.entry(source_line).or_default() += 1;
+        }
+
+        *folded_stacks.entry(stack.join(";")).or_default() += 1;
+    }
+
+    println!(
+        "ACIR opcodes generated for `{main_name}` before backend optimizations: {}",
+        circuit.opcodes.len()
+    );
+    println!();
+
+    let mut functions: Vec<_> = functions.into_iter().collect();
+    functions.sort_by(|(_, a), (_, b)| b.total_opcodes.cmp(&a.total_opcodes));
+    println!("{:<40} {:>10} {:>10} {:>10}", "Function", "Self", "Total", "Black box");
+    for (name, profile) in functions {
+        println!(
+            "{name:<40} {:>10} {:>10} {:>10}",
+            profile.self_opcodes, profile.total_opcodes, profile.black_box_calls
+        );
+    }
+    println!();
+
+    let mut source_lines: Vec<_> = source_lines.into_iter().collect();
+    source_lines.sort_by(|(a_line, a_count), (b_line, b_count)| {
+        b_count.cmp(a_count).then_with(|| a_line.cmp(b_line))
+    });
+    println!("{:<40} {:>10}", "Source line", "Opcodes");
+    for ((file, line), count) in source_lines.into_iter().take(REPORTED_SOURCE_LINES) {
+        println!("{:<40} {count:>10}", format!("{file}:{line}"));
+    }
+    println!();
+
+    if let Some(path) = &args.folded {
+        let folded: String =
+            folded_stacks.iter().map(|(stack, count)| format!("{stack} {count}\n")).collect();
+        std::fs::write(path, folded).map_err(|error| {
+            CliError::Generic(format!("Failed to write {}: {error}", path.display()))
+        })?;
+    }
+
+    let compiled_program = driver
+        .compile_no_check(&compile_options, main, backend.np_language(), &|op| {
+            backend.supports_opcode(op)
+        })
+        .map_err(|error| reporter::report_all(driver.file_manager(), &[error], false))?;
+    println!(
+        "Total ACIR opcodes generated for language {:?}: {}",
+        backend.np_language(),
+        compiled_program.circuit.opcodes.len()
+    );
+
+    let exact_circuit_size = backend
+        .get_exact_circuit_size(&compiled_program.circuit)
+        .map_err(CliError::ProofSystemCompilerError)?;
+    println!("Backend circuit size: {exact_circuit_size}");
+
+    Ok(())
+}
+
+/// Returns the name of the function whose body contains the given location.
+fn function_name_at(driver: &Driver, location: Location) -> &str {
+    match driver.def_interner().find_function_at(location) {
+        Some(function) => driver.function_name(function),
+        None => "<unknown>",
+    }
+}
+
+/// Returns the path of the file containing the given location, relative to the package
+/// directory, along with the location's line number.
+pub(crate) fn source_line(
+    driver: &Driver,
+    program_dir: &Path,
+    location: Location,
+) -> (String, usize) {
+    let file = driver
+        .file_manager()
+        .as_simple_files()
+        .get(location.file.as_usize())
+        .expect("ice: opcode locations should be within known files");
+
+    let path = PathBuf::from(file.name().to_string());
+    let path = path.strip_prefix(program_dir).unwrap_or(&path).display().to_string();
+
+    let preceding_chars = file.source().chars().take(location.span.start() as usize);
+    let line = preceding_chars.filter(|char| *char == '\n').count() + 1;
+    (path, line)
+}
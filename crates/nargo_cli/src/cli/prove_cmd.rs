@@ -3,9 +3,9 @@ use std::path::{Path, PathBuf};
 use acvm::Backend;
 use clap::Args;
 use nargo::artifacts::program::PreprocessedProgram;
-use nargo::ops::{preprocess_program, prove_execution, verify_proof};
+use nargo::ops::{preprocess_program, prove_execution, verify_proof, DefaultForeignCallExecutor};
 use noirc_abi::input_parser::Format;
-use noirc_driver::CompileOptions;
+use noirc_driver::{CompileOptions, Driver};
 
 use super::NargoConfig;
 use super::{
@@ -21,7 +21,7 @@ use super::{
     },
 };
 use crate::{
-    cli::execute_cmd::execute_program,
+    cli::execute_cmd::{execute_program, report_unsatisfied_constraint},
     constants::{PROOFS_DIR, PROVER_INPUT_FILE, TARGET_DIR, VERIFIER_INPUT_FILE},
     errors::CliError,
 };
@@ -55,6 +55,7 @@ pub(crate) fn run<B: Backend>(
     backend: &B,
     args: ProveCommand,
     config: NargoConfig,
+    mut driver: Driver,
 ) -> Result<(), CliError<B>> {
     let proof_dir = config.program_dir.join(PROOFS_DIR);
 
@@ -64,6 +65,7 @@ pub(crate) fn run<B: Backend>(
 
     prove_with_path(
         backend,
+        &mut driver,
         args.proof_name,
         args.prover_name,
         args.verifier_name,
@@ -80,6 +82,7 @@ pub(crate) fn run<B: Backend>(
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn prove_with_path<B: Backend, P: AsRef<Path>>(
     backend: &B,
+    driver: &mut Driver,
     proof_name: Option<String>,
     prover_name: String,
     verifier_name: String,
@@ -91,6 +94,7 @@ pub(crate) fn prove_with_path<B: Backend, P: AsRef<Path>>(
 ) -> Result<Option<PathBuf>, CliError<B>> {
     let common_reference_string = read_cached_common_reference_string();
 
+    let from_saved_circuit = circuit_build_path.is_some();
     let (common_reference_string, preprocessed_program) = match circuit_build_path {
         Some(circuit_build_path) => {
             let program = read_program_from_file(circuit_build_path)?;
@@ -103,7 +107,7 @@ pub(crate) fn prove_with_path<B: Backend, P: AsRef<Path>>(
             (common_reference_string, program)
         }
         None => {
-            let program = compile_circuit(backend, program_dir.as_ref(), compile_options)?;
+            let program = compile_circuit(backend, driver, compile_options)?;
             let common_reference_string =
                 update_common_reference_string(backend, &common_reference_string, &program.circuit)
                     .map_err(CliError::CommonReferenceStringError)?;
@@ -115,14 +119,31 @@ pub(crate) fn prove_with_path<B: Backend, P: AsRef<Path>>(
 
     write_cached_common_reference_string(&common_reference_string);
 
-    let PreprocessedProgram { abi, bytecode, proving_key, verification_key, .. } =
+    let PreprocessedProgram { abi, bytecode, proving_key, verification_key, debug, .. } =
         preprocessed_program;
 
     // Parse the initial witness values from Prover.toml
     let (inputs_map, _) =
         read_inputs_from_file(&program_dir, prover_name.as_str(), Format::Toml, &abi)?;
 
-    let solved_witness = execute_program(backend, bytecode.clone(), &abi, &inputs_map)?;
+    let mut foreign_call_executor = DefaultForeignCallExecutor::new();
+    let solved_witness = match execute_program(
+        backend,
+        bytecode.clone(),
+        &abi,
+        &inputs_map,
+        &mut foreign_call_executor,
+    ) {
+        Ok(solved_witness) => solved_witness,
+        Err(error) => {
+            // A saved circuit was not compiled here, so its sources are only loaded now
+            if from_saved_circuit {
+                let _ = driver.check_crate(false);
+            }
+            report_unsatisfied_constraint(driver, program_dir.as_ref(), &debug, &error);
+            return Err(error);
+        }
+    };
 
     // Write public inputs into Verifier.toml
     let public_abi = abi.public_abi();
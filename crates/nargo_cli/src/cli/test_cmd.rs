@@ -1,13 +1,27 @@
-use std::{io::Write, path::Path};
+use std::{
+    io::Write,
+    num::NonZeroUsize,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
-use acvm::{acir::native_types::WitnessMap, Backend};
-use clap::Args;
-use nargo::ops::execute_circuit;
-use noirc_driver::{CompileOptions, Driver};
-use noirc_frontend::node_interner::FuncId;
+use acvm::{
+    acir::{circuit::Circuit, native_types::WitnessMap},
+    Backend,
+};
+use clap::{Args, ValueEnum};
+use nargo::ops::{execute_circuit, DefaultForeignCallExecutor};
+use noirc_driver::{CompileOptions, Driver, FunctionNameMatch};
+use noirc_frontend::{node_interner::FuncId, token::TestScope};
+use serde_json::json;
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
-use crate::{cli::check_cmd::check_crate_and_report_errors, errors::CliError, resolver::Resolver};
+use crate::{cli::check_cmd::check_crate_and_report_errors, errors::CliError};
 
 use super::NargoConfig;
 
@@ -17,55 +31,155 @@ pub(crate) struct TestCommand {
     /// If given, only tests with names containing this string will be run
     test_name: Option<String>,
 
+    /// Only run tests whose names exactly match the given test name
+    #[clap(long)]
+    exact: bool,
+
+    /// List the matching tests without running them
+    #[clap(long)]
+    list: bool,
+
+    /// The format in which to report the outcome of the tests
+    #[clap(long, value_enum, default_value_t = ReportFormat::Pretty)]
+    format: ReportFormat,
+
     #[clap(flatten)]
     compile_options: CompileOptions,
 }
 
-pub(crate) fn run<B: Backend>(
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    /// Human readable output
+    Pretty,
+    /// A JSON array containing one object per test
+    Json,
+    /// A JUnit XML report
+    Junit,
+}
+
+/// The outcome of running a single test function.
+struct TestReport {
+    name: String,
+    duration: Duration,
+    /// The reason for the test failing, if it did not pass.
+    failure: Option<String>,
+}
+
+pub(crate) fn run<B: Backend + Sync>(
     backend: &B,
     args: TestCommand,
     config: NargoConfig,
+    mut driver: Driver,
 ) -> Result<(), CliError<B>> {
-    let test_name: String = args.test_name.unwrap_or_else(|| "".to_owned());
+    let pattern = match &args.test_name {
+        Some(name) if args.exact => FunctionNameMatch::Exact(name),
+        Some(name) => FunctionNameMatch::Contains(name),
+        None => FunctionNameMatch::Anything,
+    };
+
+    check_crate_and_report_errors(&mut driver, args.compile_options.deny_warnings)?;
+
+    let test_functions = driver.get_all_test_functions_in_crate_matching(pattern);
+
+    if args.list {
+        for (test_function, _) in test_functions {
+            println!("{}: test", driver.function_name(test_function));
+        }
+        return Ok(());
+    }
 
-    run_tests(backend, &config.program_dir, &test_name, &args.compile_options)
+    run_tests(
+        backend,
+        &config.program_dir,
+        &driver,
+        test_functions,
+        &args.compile_options,
+        args.format,
+    )
 }
 
-fn run_tests<B: Backend>(
+fn run_tests<B: Backend + Sync>(
     backend: &B,
     program_dir: &Path,
-    test_name: &str,
+    driver: &Driver,
+    test_functions: Vec<(FuncId, TestScope)>,
     compile_options: &CompileOptions,
+    format: ReportFormat,
 ) -> Result<(), CliError<B>> {
-    let mut driver = Resolver::resolve_root_manifest(program_dir)?;
-    check_crate_and_report_errors(&mut driver, compile_options.deny_warnings)?;
+    if format == ReportFormat::Pretty {
+        println!("Running {} test functions...", test_functions.len());
+    }
 
-    let test_functions = driver.get_all_test_functions_in_crate_matching(test_name);
-    println!("Running {} test functions...", test_functions.len());
-    let mut failing = 0;
+    // The driver cannot be shared between threads so each test is compiled up front,
+    // leaving only the execution of the compiled circuits to be run in parallel.
+    let compiled_tests: Vec<_> = test_functions
+        .into_iter()
+        .map(|(test_function, scope)| {
+            let name = driver.function_name(test_function).to_owned();
+            let start = Instant::now();
+            let circuit = driver
+                .compile_no_check(compile_options, test_function, backend.np_language(), &|op| {
+                    backend.supports_opcode(op)
+                })
+                .map(|program| program.circuit)
+                .map_err(|error| format!("failed to compile: {}", error.diagnostic.message));
+            (name, scope, circuit, start.elapsed())
+        })
+        .collect();
 
     let writer = StandardStream::stderr(ColorChoice::Always);
     let mut writer = writer.lock();
 
-    for test_function in test_functions {
-        let test_name = driver.function_name(test_function);
-        writeln!(writer, "Testing {test_name}...").expect("Failed to write to stdout");
-        writer.flush().ok();
+    let mut reports: Vec<Option<TestReport>> = compiled_tests.iter().map(|_| None).collect();
+    let next_test = AtomicUsize::new(0);
+    let num_threads = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(compiled_tests.len())
+        .max(1);
+
+    thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..num_threads {
+            let sender = sender.clone();
+            let (compiled_tests, next_test) = (&compiled_tests, &next_test);
+            scope.spawn(move || loop {
+                let index = next_test.fetch_add(1, Ordering::SeqCst);
+                let (name, test_scope, circuit, compile_time) = match compiled_tests.get(index) {
+                    Some(test) => test,
+                    None => break,
+                };
+                let report = run_test(backend, name, test_scope, circuit, *compile_time);
+                if sender.send((index, report)).is_err() {
+                    break;
+                }
+            });
+        }
+        // Drop the original sender so that the receiver is closed once every worker is done.
+        drop(sender);
 
-        match run_test(backend, test_name, test_function, &driver, compile_options) {
-            Ok(_) => {
-                writer.set_color(ColorSpec::new().set_fg(Some(Color::Green))).ok();
-                writeln!(writer, "ok").ok();
+        for (index, report) in receiver {
+            if format == ReportFormat::Pretty {
+                write_pretty_report(&mut writer, &report);
             }
-            // Assume an error was already printed to stdout
-            Err(_) => failing += 1,
+            reports[index] = Some(report);
         }
-        writer.reset().ok();
+    });
+
+    let reports: Vec<TestReport> =
+        reports.into_iter().map(|report| report.expect("Every test should be run")).collect();
+    let failing = reports.iter().filter(|report| report.failure.is_some()).count();
+
+    match format {
+        ReportFormat::Pretty => {}
+        ReportFormat::Json => println!("{}", json_report(&reports)),
+        ReportFormat::Junit => println!("{}", junit_report(program_dir, &reports)),
     }
 
     if failing == 0 {
-        writer.set_color(ColorSpec::new().set_fg(Some(Color::Green))).unwrap();
-        writeln!(writer, "All tests passed").ok();
+        if format == ReportFormat::Pretty {
+            writer.set_color(ColorSpec::new().set_fg(Some(Color::Green))).unwrap();
+            writeln!(writer, "All tests passed").ok();
+        }
     } else {
         let plural = if failing == 1 { "" } else { "s" };
         return Err(CliError::Generic(format!("{failing} test{plural} failed")));
@@ -78,25 +192,118 @@ fn run_tests<B: Backend>(
 fn run_test<B: Backend>(
     backend: &B,
     test_name: &str,
-    main: FuncId,
-    driver: &Driver,
-    config: &CompileOptions,
-) -> Result<(), CliError<B>> {
-    let program = driver
-        .compile_no_check(config, main, backend.np_language(), &|op| backend.supports_opcode(op))
-        .map_err(|_| CliError::Generic(format!("Test '{test_name}' failed to compile")))?;
+    test_scope: &TestScope,
+    circuit: &Result<Circuit, String>,
+    compile_time: Duration,
+) -> TestReport {
+    let start = Instant::now();
+
+    // A test which fails to compile is a failure, even if it is expected to fail: only a
+    // failure during execution, such as an unsatisfied constraint, satisfies `should_fail`.
+    let circuit = match circuit {
+        Ok(circuit) => circuit.clone(),
+        Err(message) => {
+            let duration = compile_time + start.elapsed();
+            return TestReport {
+                name: test_name.to_owned(),
+                duration,
+                failure: Some(message.clone()),
+            };
+        }
+    };
 
     // Run the backend to ensure the PWG evaluates functions like std::hash::pedersen,
     // otherwise constraints involving these expressions will not error.
-    match execute_circuit(backend, program.circuit, WitnessMap::new()) {
-        Ok(_) => Ok(()),
-        Err(error) => {
-            let writer = StandardStream::stderr(ColorChoice::Always);
-            let mut writer = writer.lock();
+    let mut foreign_call_executor = DefaultForeignCallExecutor::new();
+    let result = execute_circuit(backend, circuit, WitnessMap::new(), &mut foreign_call_executor)
+        .map(|_| ())
+        .map_err(|error| error.to_string());
+
+    let failure = match (test_scope, result) {
+        (TestScope::None, Ok(())) => None,
+        (TestScope::None, Err(message)) => Some(message),
+        (TestScope::ShouldFail { .. }, Ok(())) => {
+            Some("test passed but was expected to fail".to_owned())
+        }
+        (TestScope::ShouldFail { reason: None }, Err(_)) => None,
+        (TestScope::ShouldFail { reason: Some(reason) }, Err(message)) => {
+            if message.contains(reason.as_str()) {
+                None
+            } else {
+                Some(format!("expected failure containing {reason:?}, found {message:?}"))
+            }
+        }
+    };
+
+    TestReport { name: test_name.to_owned(), duration: compile_time + start.elapsed(), failure }
+}
+
+fn write_pretty_report(writer: &mut impl WriteColor, report: &TestReport) {
+    write!(writer, "Testing {}... ", report.name).ok();
+    match &report.failure {
+        None => {
+            writer.set_color(ColorSpec::new().set_fg(Some(Color::Green))).ok();
+            writeln!(writer, "ok").ok();
+        }
+        Some(message) => {
             writer.set_color(ColorSpec::new().set_fg(Some(Color::Red))).ok();
             writeln!(writer, "failed").ok();
             writer.reset().ok();
-            Err(error.into())
+            writeln!(writer, "{message}").ok();
         }
     }
+    writer.reset().ok();
+    writer.flush().ok();
+}
+
+fn json_report(reports: &[TestReport]) -> serde_json::Value {
+    let reports: Vec<_> = reports
+        .iter()
+        .map(|report| {
+            json!({
+                "name": report.name,
+                "outcome": if report.failure.is_none() { "pass" } else { "fail" },
+                "duration_ms": report.duration.as_millis() as u64,
+                "message": report.failure,
+            })
+        })
+        .collect();
+    serde_json::Value::Array(reports)
+}
+
+fn junit_report(program_dir: &Path, reports: &[TestReport]) -> String {
+    let suite_name = program_dir.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let failures = reports.iter().filter(|report| report.failure.is_some()).count();
+    let total_time: Duration = reports.iter().map(|report| report.duration).sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+    xml += &format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\" time=\"{:.3}\">\n",
+        escape_xml(&suite_name),
+        reports.len(),
+        total_time.as_secs_f64()
+    );
+    for report in reports {
+        let name = escape_xml(&report.name);
+        let time = report.duration.as_secs_f64();
+        match &report.failure {
+            None => xml += &format!("    <testcase name=\"{name}\" time=\"{time:.3}\"/>\n"),
+            Some(message) => {
+                xml += &format!("    <testcase name=\"{name}\" time=\"{time:.3}\">\n");
+                xml += &format!("      <failure message=\"{}\"/>\n", escape_xml(message));
+                xml += "    </testcase>\n";
+            }
+        }
+    }
+    xml += "  </testsuite>\n</testsuites>";
+    xml
+}
+
+fn escape_xml(string: &str) -> String {
+    string
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
 }
@@ -19,7 +19,7 @@ use clap::Args;
 use nargo::artifacts::program::PreprocessedProgram;
 use nargo::ops::{preprocess_program, verify_proof};
 use noirc_abi::input_parser::Format;
-use noirc_driver::CompileOptions;
+use noirc_driver::{CompileOptions, Driver};
 use std::path::{Path, PathBuf};
 
 /// Given a proof and a program, verify whether the proof is valid
@@ -43,6 +43,7 @@ pub(crate) fn run<B: Backend>(
     backend: &B,
     args: VerifyCommand,
     config: NargoConfig,
+    mut driver: Driver,
 ) -> Result<(), CliError<B>> {
     let proof_path =
         config.program_dir.join(PROOFS_DIR).join(&args.proof).with_extension(PROOF_EXT);
@@ -53,6 +54,7 @@ pub(crate) fn run<B: Backend>(
 
     verify_with_path(
         backend,
+        &mut driver,
         &config.program_dir,
         proof_path,
         circuit_build_path.as_ref(),
@@ -63,6 +65,7 @@ pub(crate) fn run<B: Backend>(
 
 fn verify_with_path<B: Backend, P: AsRef<Path>>(
     backend: &B,
+    driver: &mut Driver,
     program_dir: P,
     proof_path: PathBuf,
     circuit_build_path: Option<P>,
@@ -83,7 +86,7 @@ fn verify_with_path<B: Backend, P: AsRef<Path>>(
             (common_reference_string, program)
         }
         None => {
-            let program = compile_circuit(backend, program_dir.as_ref(), compile_options)?;
+            let program = compile_circuit(backend, driver, compile_options)?;
             let common_reference_string =
                 update_common_reference_string(backend, &common_reference_string, &program.circuit)
                     .map_err(CliError::CommonReferenceStringError)?;
@@ -15,6 +15,8 @@ pub(crate) const PROVER_INPUT_FILE: &str = "Prover";
 pub(crate) const VERIFIER_INPUT_FILE: &str = "Verifier";
 /// The package definition file for a Noir project.
 pub(crate) const PKG_FILE: &str = "Nargo.toml";
+/// The file pinning the exact versions of a workspace's git dependencies.
+pub(crate) const LOCK_FILE: &str = "Nargo.lock";
 
 // Extensions
 /// The extension for files containing circuit proofs.
@@ -1,4 +1,5 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 pub(crate) fn git_dep_location(base: &url::Url, tag: &str) -> PathBuf {
     let folder_name = super::resolver::resolve_folder_name(base, tag);
@@ -12,9 +13,9 @@ pub(crate) fn git_dep_location(base: &url::Url, tag: &str) -> PathBuf {
 /// github-rs looks promising, however it seems to require an API token
 ///
 /// One advantage of using "git clone" is that there is effectively no rate limit
-pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
-    use std::process::Command;
-
+///
+/// When `offline` is set, only repositories which have already been cloned can be used.
+pub(crate) fn clone_git_repo(url: &str, tag: &str, offline: bool) -> Result<PathBuf, String> {
     let base = match url::Url::parse(url) {
         Ok(base) => base,
         Err(err) => return Err(err.to_string()),
@@ -25,7 +26,13 @@ pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
         return Ok(loc);
     }
 
-    Command::new("git")
+    if offline {
+        return Err(format!(
+            "{url} at tag {tag} has not been downloaded and cannot be fetched in offline mode"
+        ));
+    }
+
+    let status = Command::new("git")
         .arg("-c")
         .arg("advice.detachedHead=false")
         .arg("clone")
@@ -38,5 +45,66 @@ pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
         .status()
         .expect("git clone command failed to start");
 
+    if !status.success() {
+        return Err(format!("failed to clone {url} at tag {tag}"));
+    }
+
     Ok(loc)
 }
+
+/// Returns the hash of the commit currently checked out in the repository at `repo_dir`.
+pub(crate) fn git_commit_hash(repo_dir: &Path) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_dir)
+        .arg("rev-parse")
+        .arg("HEAD")
+        .output()
+        .expect("git rev-parse command failed to start");
+
+    if !output.status.success() {
+        return Err(format!("could not read the commit checked out in {}", repo_dir.display()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Checks out `commit` in the repository at `repo_dir`, fetching it first unless `offline` is set.
+pub(crate) fn checkout_git_commit(
+    repo_dir: &Path,
+    commit: &str,
+    offline: bool,
+) -> Result<(), String> {
+    if !offline {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(repo_dir)
+            .arg("fetch")
+            .arg("--depth")
+            .arg("1")
+            .arg("origin")
+            .arg(commit)
+            .status()
+            .expect("git fetch command failed to start");
+
+        if !status.success() {
+            return Err(format!("failed to fetch commit {commit} into {}", repo_dir.display()));
+        }
+    }
+
+    let status = Command::new("git")
+        .arg("-c")
+        .arg("advice.detachedHead=false")
+        .arg("-C")
+        .arg(repo_dir)
+        .arg("checkout")
+        .arg(commit)
+        .status()
+        .expect("git checkout command failed to start");
+
+    if !status.success() {
+        return Err(format!("failed to check out commit {commit} in {}", repo_dir.display()));
+    }
+
+    Ok(())
+}
@@ -18,6 +18,7 @@ pub mod cli;
 mod constants;
 mod errors;
 mod git;
+mod lockfile;
 mod manifest;
 mod resolver;
 
@@ -27,18 +28,6 @@ fn nargo_crates() -> PathBuf {
     dirs::home_dir().unwrap().join("nargo")
 }
 
-/// Returns the path of the root directory of the package containing `current_path`.
-///
-/// Returns a `CliError` if no parent directories of `current_path` contain a manifest file.
-fn find_package_root(current_path: &Path) -> Result<PathBuf, InvalidPackageError> {
-    let manifest_path = find_package_manifest(current_path)?;
-
-    let package_root =
-        manifest_path.parent().expect("infallible: manifest file path can't be root directory");
-
-    Ok(package_root.to_path_buf())
-}
-
 /// Returns the path of the manifest file (`Nargo.toml`) of the package containing `current_path`.
 ///
 /// Returns a `CliError` if no parent directories of `current_path` contain a manifest file.
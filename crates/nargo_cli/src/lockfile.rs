@@ -0,0 +1,56 @@
+use std::path::{Path, PathBuf};
+
+use nargo::lockfile::{Lockfile, LockfileError};
+use sha2::{Digest, Sha256};
+
+use crate::constants::LOCK_FILE;
+
+/// Reads the Nargo.lock in `workspace_root`, returning `None` if it does not exist.
+pub(crate) fn read_lockfile(workspace_root: &Path) -> Result<Option<Lockfile>, LockfileError> {
+    match std::fs::read_to_string(workspace_root.join(LOCK_FILE)) {
+        Ok(toml_as_string) => Lockfile::from_toml_str(&toml_as_string).map(Some),
+        Err(_) => Ok(None),
+    }
+}
+
+pub(crate) fn write_lockfile(workspace_root: &Path, lockfile: &Lockfile) {
+    let path = workspace_root.join(LOCK_FILE);
+    std::fs::write(&path, lockfile.to_toml_string())
+        .unwrap_or_else(|why| panic!("couldn't write to {}: {why}", path.display()));
+}
+
+/// Computes a checksum over the contents of every file in `dir`, ignoring git metadata.
+///
+/// Files are hashed in order of their path relative to `dir` so that the checksum
+/// does not depend on the order in which the filesystem lists them.
+pub(crate) fn checksum_dir(dir: &Path) -> std::io::Result<String> {
+    let mut files = Vec::new();
+    collect_files(dir, &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for file in files {
+        let relative_path = file.strip_prefix(dir).expect("file should be within the directory");
+        hasher.update(relative_path.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(std::fs::read(&file)?);
+        hasher.update([0]);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if path.file_name().map_or(false, |name| name == ".git") {
+                continue;
+            }
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
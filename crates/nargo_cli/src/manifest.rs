@@ -1,15 +1,25 @@
 use std::path::Path;
 
-use nargo::manifest::{InvalidPackageError, PackageManifest};
+use nargo::manifest::{InvalidPackageError, Manifest, PackageManifest};
 
 /// Parses a Nargo.toml file from it's path
 /// The path to the toml file must be present.
 /// Calling this function without this guarantee is an ICE.
-pub(crate) fn parse<P: AsRef<Path>>(
-    path_to_toml: P,
-) -> Result<PackageManifest, InvalidPackageError> {
+pub(crate) fn parse<P: AsRef<Path>>(path_to_toml: P) -> Result<Manifest, InvalidPackageError> {
     let toml_as_string =
         std::fs::read_to_string(&path_to_toml).expect("ice: path given for toml file is invalid");
 
-    PackageManifest::from_toml_str(&toml_as_string)
+    Manifest::from_toml_str(&toml_as_string)
+}
+
+/// Parses a Nargo.toml file which is expected to describe a package rather than a workspace.
+pub(crate) fn parse_package<P: AsRef<Path>>(
+    path_to_toml: P,
+) -> Result<PackageManifest, InvalidPackageError> {
+    match parse(&path_to_toml)? {
+        Manifest::Package(manifest) => Ok(manifest),
+        Manifest::Workspace(_) => {
+            Err(InvalidPackageError::ExpectedPackageManifest(path_to_toml.as_ref().to_path_buf()))
+        }
+    }
 }
@@ -1,14 +1,23 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
-use nargo::manifest::{Dependency, PackageManifest};
+use iter_extended::vecmap;
+use nargo::{
+    lockfile::{LockedPackage, Lockfile, LockfileError},
+    manifest::{Dependency, Manifest, PackageManifest},
+    workspace::{Package, Workspace},
+};
 use noirc_driver::Driver;
 use noirc_frontend::graph::{CrateId, CrateType};
 use thiserror::Error;
 
-use crate::{git::clone_git_repo, InvalidPackageError};
+use crate::{
+    git::{checkout_git_commit, clone_git_repo, git_commit_hash},
+    lockfile::{checksum_dir, read_lockfile, write_lockfile},
+    InvalidPackageError,
+};
 
 /// Creates a unique folder name for a GitHub repo
 /// by using it's URL and tag
@@ -38,6 +47,66 @@ pub(crate) enum DependencyResolutionError {
     /// Dependency is not a valid crate
     #[error(transparent)]
     MalformedDependency(#[from] InvalidPackageError),
+
+    /// Nargo.lock is not a valid lockfile
+    #[error(transparent)]
+    MalformedLockfile(#[from] LockfileError),
+
+    /// Nargo.lock would need to be updated but updating it was disallowed.
+    #[error("Nargo.lock needs to be updated but --locked was passed: {0}")]
+    LockfileOutOfDate(String),
+
+    /// The contents of a git dependency differ from those recorded in Nargo.lock.
+    #[error("checksum of {git} at tag {tag} does not match Nargo.lock.
+expected {expected}, found {found}.
+the cached copy at {} may have been modified", dependency_path.display())]
+    ChecksumMismatch {
+        git: String,
+        tag: String,
+        expected: String,
+        found: String,
+        dependency_path: PathBuf,
+    },
+
+    /// Failed to read the contents of a dependency in order to compute its checksum.
+    #[error("could not read dependency located at {}: {error}", dependency_path.display())]
+    UnreadableDependency { dependency_path: PathBuf, error: std::io::Error },
+}
+
+/// Controls how git dependencies are fetched and pinned when resolving a workspace.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct ResolutionOptions {
+    /// Error rather than write Nargo.lock if it is missing or out of date
+    pub(crate) locked: bool,
+    /// Error rather than access the network to fetch a dependency which is not already cached
+    pub(crate) offline: bool,
+}
+
+/// The pins of git dependencies as read from Nargo.lock and as resolved during this run.
+#[derive(Debug, Default)]
+struct LockState {
+    previous: Option<Lockfile>,
+    resolved: Lockfile,
+}
+
+/// A workspace along with the resolved dependencies of each of its members.
+pub(crate) struct ResolvedWorkspace {
+    pub(crate) workspace: Workspace,
+    resolver: Resolver,
+}
+
+impl ResolvedWorkspace {
+    /// Returns a Driver for a member of the workspace, with its dependencies added to the crate graph.
+    pub(crate) fn create_driver(&self, package: &Package) -> Driver {
+        let mut driver = Driver::new();
+        self.add_to_driver(package, &mut driver);
+        driver
+    }
+
+    /// Adds a member of the workspace as the local crate of `driver`, along with its dependencies.
+    pub(crate) fn add_to_driver(&self, package: &Package, driver: &mut Driver) {
+        self.resolver.add_package_to_driver(&package.root_dir, driver);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -45,121 +114,239 @@ struct CachedDep {
     entry_path: PathBuf,
     crate_type: CrateType,
     manifest: PackageManifest,
-    // Whether the dependency came from
-    // a remote dependency
-    remote: bool,
+    /// The root directories of this package's dependencies, keyed by the name they are imported under
+    dependencies: BTreeMap<String, PathBuf>,
 }
 
 /// Resolves a toml file by either downloading the necessary git repo
 /// or it uses the repo on the cache.
 /// Downloading will be recursive, so if a package contains packages
 /// We need to download those too
-pub(crate) struct Resolver<'a> {
-    driver: &'a mut Driver,
-}
+///
+/// A single resolver is used for every member of a workspace so that
+/// any dependencies they share are only resolved once.
+#[derive(Default)]
+pub(crate) struct Resolver {
+    /// Every package resolved so far, keyed by its root directory
+    packages: HashMap<PathBuf, CachedDep>,
 
-impl<'a> Resolver<'a> {
-    fn with_driver(driver: &mut Driver) -> Resolver {
-        Resolver { driver }
-    }
+    options: ResolutionOptions,
 
-    /// Returns the Driver and the backend to use
-    /// Note that the backend is ignored in the dependencies.
-    /// Since Noir is backend agnostic, this is okay to do.
-    /// XXX: Need to handle when a local package changes!
-    pub(crate) fn resolve_root_manifest(
-        dir_path: &std::path::Path,
-    ) -> Result<Driver, DependencyResolutionError> {
-        let mut driver = Driver::new();
-        let (entry_path, crate_type) = super::lib_or_bin(dir_path)?;
+    /// Only tracked when resolving a whole workspace, as that is where Nargo.lock is kept
+    lock_state: Option<LockState>,
+}
 
+impl Resolver {
+    /// Resolves the package or workspace whose manifest is found in `dir_path` or one of its parents.
+    ///
+    /// A manifest describing a single package results in a workspace containing only that package.
+    ///
+    /// Git dependencies are pinned to the commits recorded in the workspace's Nargo.lock,
+    /// which is updated with any new dependencies unless `options.locked` is set.
+    pub(crate) fn resolve_workspace(
+        dir_path: &Path,
+        options: ResolutionOptions,
+    ) -> Result<ResolvedWorkspace, DependencyResolutionError> {
         let manifest_path = super::find_package_manifest(dir_path)?;
-        let manifest = super::manifest::parse(&manifest_path)?;
+        let root_dir =
+            manifest_path.parent().expect("Every manifest path has a parent.").to_path_buf();
 
-        let crate_id = driver.create_local_crate(entry_path, crate_type);
+        let member_dirs = match super::manifest::parse(&manifest_path)? {
+            Manifest::Package(_) => vec![root_dir.clone()],
+            Manifest::Workspace(manifest) => {
+                vecmap(manifest.workspace.members, |member| root_dir.join(member))
+            }
+        };
+
+        let previous = read_lockfile(&root_dir)?;
+        let lock_state = LockState { previous, resolved: Lockfile::default() };
+        let mut resolver =
+            Resolver { options, lock_state: Some(lock_state), ..Resolver::default() };
+
+        let mut members = Vec::with_capacity(member_dirs.len());
+        for member_dir in member_dirs {
+            resolver.resolve_package(&member_dir, false)?;
+
+            let manifest = resolver.packages[&member_dir].manifest.clone();
+            let name = match &manifest.package.name {
+                Some(name) => name.clone(),
+                None => member_dir
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+            };
+            members.push(Package { name, root_dir: member_dir, manifest });
+        }
 
-        let mut resolver = Resolver::with_driver(&mut driver);
-        let pkg_root = manifest_path.parent().expect("Every manifest path has a parent.");
-        resolver.resolve_manifest(crate_id, manifest, pkg_root)?;
+        let LockState { previous, resolved } =
+            resolver.lock_state.take().expect("lock state was set above");
+        let lockfile_changed = match &previous {
+            Some(previous) => previous != &resolved,
+            // Packages without git dependencies don't need a lockfile
+            None => !resolved.is_empty(),
+        };
+        if lockfile_changed {
+            if options.locked {
+                return Err(DependencyResolutionError::LockfileOutOfDate(
+                    "the resolved git dependencies differ from those in Nargo.lock".to_owned(),
+                ));
+            }
+            write_lockfile(&root_dir, &resolved);
+        }
 
-        Ok(driver)
+        Ok(ResolvedWorkspace { workspace: Workspace { root_dir, members }, resolver })
     }
 
-    // Resolves a config file by recursively resolving the dependencies in the config
+    // Resolves a package by recursively resolving the dependencies in its config
     // Need to solve the case of a project trying to use itself as a dep
     //
     // We do not need to add stdlib, as it's implicitly
     // imported. However, it may be helpful to have the stdlib imported by the
     // package manager.
-    fn resolve_manifest(
+    fn resolve_package(
         &mut self,
-        parent_crate: CrateId,
-        manifest: PackageManifest,
         pkg_root: &Path,
+        remote: bool,
     ) -> Result<(), DependencyResolutionError> {
-        let mut cached_packages: HashMap<PathBuf, (CrateId, CachedDep)> = HashMap::new();
+        if self.packages.contains_key(pkg_root) {
+            return Ok(());
+        }
 
-        // First download and add these top level dependencies crates to the Driver
-        for (dep_pkg_name, pkg_src) in manifest.dependencies.iter() {
-            let (dir_path, dep_meta) = Resolver::cache_dep(pkg_src, pkg_root)?;
+        let (entry_path, crate_type) = super::lib_or_bin(pkg_root)?;
+        let manifest_path = super::find_package_manifest(pkg_root)?;
+        let manifest = super::manifest::parse_package(manifest_path)?;
 
-            let (entry_path, crate_type) = (&dep_meta.entry_path, &dep_meta.crate_type);
+        if remote && manifest.has_local_dependency() {
+            return Err(DependencyResolutionError::RemoteDepWithLocalDep {
+                dependency_path: pkg_root.to_path_buf(),
+            });
+        }
+
+        let mut dependencies = BTreeMap::new();
+        for (dep_pkg_name, pkg_src) in manifest.dependencies.iter() {
+            let (dir_path, remote) = self.cache_dep(pkg_src, pkg_root)?;
+            self.resolve_package(&dir_path, remote)?;
 
-            if crate_type == &CrateType::Binary {
+            if self.packages[&dir_path].crate_type == CrateType::Binary {
                 return Err(DependencyResolutionError::BinaryDependency {
                     dep_pkg_name: dep_pkg_name.to_string(),
                 });
             }
+            dependencies.insert(dep_pkg_name.clone(), dir_path);
+        }
 
-            let crate_id = self.driver.create_non_local_crate(entry_path, *crate_type);
-            self.driver.add_dep(parent_crate, crate_id, dep_pkg_name);
+        let package = CachedDep { entry_path, crate_type, manifest, dependencies };
+        self.packages.insert(pkg_root.to_path_buf(), package);
+        Ok(())
+    }
 
-            cached_packages.insert(dir_path, (crate_id, dep_meta));
-        }
+    /// Creates a Driver for the already resolved package at `pkg_root`,
+    /// adding all of its dependencies to the crate graph.
+    fn add_package_to_driver(&self, pkg_root: &Path, driver: &mut Driver) {
+        let package = &self.packages[pkg_root];
+        let crate_id = driver.create_local_crate(&package.entry_path, package.crate_type);
+        self.add_dependencies(driver, crate_id, package, &mut HashSet::new());
+    }
+
+    fn add_dependencies(
+        &self,
+        driver: &mut Driver,
+        parent_crate: CrateId,
+        package: &CachedDep,
+        visited: &mut HashSet<PathBuf>,
+    ) {
+        for (dep_pkg_name, dep_root) in &package.dependencies {
+            let dependency = &self.packages[dep_root];
+            let crate_id =
+                driver.create_non_local_crate(&dependency.entry_path, dependency.crate_type);
+            driver.add_dep(parent_crate, crate_id, dep_pkg_name);
 
-        // Resolve all transitive dependencies
-        for (dependency_path, (crate_id, dep_meta)) in cached_packages {
-            if dep_meta.remote && dep_meta.manifest.has_local_dependency() {
-                return Err(DependencyResolutionError::RemoteDepWithLocalDep { dependency_path });
+            // Dependencies shared between several packages only need their own dependencies added once
+            if visited.insert(dep_root.clone()) {
+                self.add_dependencies(driver, crate_id, dependency, visited);
             }
-            let mut new_res = Resolver::with_driver(self.driver);
-            new_res.resolve_manifest(crate_id, dep_meta.manifest, &dependency_path)?;
         }
-        Ok(())
     }
 
     /// If the dependency is remote, download the dependency
-    /// and return the directory path along with the metadata
-    /// Needed to fill the CachedDep struct
+    /// and return the directory path along with whether it is remote
     ///
     /// If it's a local path, the same applies, however it will not
     /// be downloaded
     fn cache_dep(
+        &mut self,
         dep: &Dependency,
         pkg_root: &Path,
-    ) -> Result<(PathBuf, CachedDep), DependencyResolutionError> {
-        fn retrieve_meta(
-            dir_path: &Path,
-            remote: bool,
-        ) -> Result<CachedDep, DependencyResolutionError> {
-            let (entry_path, crate_type) = super::lib_or_bin(dir_path)?;
-            let manifest_path = super::find_package_manifest(dir_path)?;
-            let manifest = super::manifest::parse(manifest_path)?;
-            Ok(CachedDep { entry_path, crate_type, manifest, remote })
-        }
-
+    ) -> Result<(PathBuf, bool), DependencyResolutionError> {
         match dep {
             Dependency::Github { git, tag } => {
-                let dir_path =
-                    clone_git_repo(git, tag).map_err(DependencyResolutionError::GitError)?;
-                let meta = retrieve_meta(&dir_path, true)?;
-                Ok((dir_path, meta))
+                let dir_path = clone_git_repo(git, tag, self.options.offline)
+                    .map_err(DependencyResolutionError::GitError)?;
+                self.lock_git_dep(git, tag, &dir_path)?;
+                Ok((dir_path, true))
             }
-            Dependency::Path { path } => {
-                let dir_path = pkg_root.join(path);
-                let meta = retrieve_meta(&dir_path, false)?;
-                Ok((dir_path, meta))
+            Dependency::Path { path } => Ok((pkg_root.join(path), false)),
+        }
+    }
+
+    /// Ensures that the git dependency cloned into `dir_path` matches its entry in Nargo.lock,
+    /// checking out the pinned commit if necessary, and records it in the resolved lockfile.
+    fn lock_git_dep(
+        &mut self,
+        git: &str,
+        tag: &str,
+        dir_path: &Path,
+    ) -> Result<(), DependencyResolutionError> {
+        let offline = self.options.offline;
+        let locked = self.options.locked;
+        let lock_state = match &mut self.lock_state {
+            Some(lock_state) => lock_state,
+            None => return Ok(()),
+        };
+
+        // Dependencies shared between packages only need to be checked once
+        if lock_state.resolved.get(git, tag).is_some() {
+            return Ok(());
+        }
+
+        let pinned = lock_state.previous.as_ref().and_then(|lockfile| lockfile.get(git, tag));
+
+        let mut commit = git_commit_hash(dir_path).map_err(DependencyResolutionError::GitError)?;
+        match pinned {
+            Some(pinned) if pinned.commit != commit => {
+                checkout_git_commit(dir_path, &pinned.commit, offline)
+                    .map_err(DependencyResolutionError::GitError)?;
+                commit = pinned.commit.clone();
+            }
+            Some(_) => (),
+            None if locked => {
+                return Err(DependencyResolutionError::LockfileOutOfDate(format!(
+                    "{git} at tag {tag} is missing from Nargo.lock"
+                )));
+            }
+            None => (),
+        }
+
+        let checksum = checksum_dir(dir_path).map_err(|error| {
+            DependencyResolutionError::UnreadableDependency {
+                dependency_path: dir_path.to_path_buf(),
+                error,
+            }
+        })?;
+        if let Some(pinned) = pinned {
+            if pinned.checksum != checksum {
+                return Err(DependencyResolutionError::ChecksumMismatch {
+                    git: git.to_owned(),
+                    tag: tag.to_owned(),
+                    expected: pinned.checksum.clone(),
+                    found: checksum,
+                    dependency_path: dir_path.to_path_buf(),
+                });
             }
         }
+
+        let package = LockedPackage { git: git.to_owned(), tag: tag.to_owned(), commit, checksum };
+        lock_state.resolved.insert(package);
+        Ok(())
     }
 }
@@ -0,0 +1,21 @@
+use serde::Deserialize;
+
+/// Options controlling the style of formatted code.
+///
+/// These are read from the `[fmt]` table of a package's Nargo.toml, with
+/// any options which aren't given taking their default value.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The maximum width of each line. Lists such as function arguments
+    /// which don't fit within this width are split over multiple lines.
+    pub max_width: usize,
+    /// The number of spaces used for each level of indentation.
+    pub tab_spaces: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config { max_width: 100, tab_spaces: 4 }
+    }
+}
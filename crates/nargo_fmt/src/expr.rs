@@ -0,0 +1,516 @@
+use noirc_frontend::{
+    ArrayLiteral, AssignStatement, BinaryOpKind, BinaryTypeOperator, Expression, ExpressionKind,
+    ForExpression, IfExpression, LValue, Lambda, Literal, MatchExpression, MatchPattern, Path,
+    PathKind, Pattern, Signedness, Statement, UnaryOp, UnresolvedType, UnresolvedTypeExpression,
+    UseTree, UseTreeKind, WhileExpression,
+};
+
+use crate::formatter::{pattern_span, Formatter};
+
+impl Formatter<'_> {
+    pub(crate) fn format_expression(&mut self, expression: &Expression) -> String {
+        match &expression.kind {
+            ExpressionKind::Literal(literal) => self.format_literal(literal, expression),
+            ExpressionKind::Block(block) => match self.format_for_in_array(block) {
+                Some(for_loop) => for_loop,
+                None => {
+                    let span = self.find_block(expression.span.start());
+                    self.format_block(block, span)
+                }
+            },
+            ExpressionKind::Prefix(prefix) => {
+                let operator = match prefix.operator {
+                    UnaryOp::Minus => "-",
+                    UnaryOp::Not => "!",
+                    UnaryOp::MutableReference => "&mut ",
+                    UnaryOp::Dereference => "*",
+                };
+                let needs_parens =
+                    matches!(prefix.rhs.kind, ExpressionKind::Infix(_) | ExpressionKind::Lambda(_));
+                format!("{operator}{}", self.format_operand(&prefix.rhs, needs_parens))
+            }
+            ExpressionKind::Index(index) => {
+                let collection = self.format_postfix_operand(&index.collection);
+                format!("{collection}[{}]", self.format_expression(&index.index))
+            }
+            ExpressionKind::Call(call) => {
+                let function = self.format_postfix_operand(&call.func);
+                let opener = format!("{function}(");
+                self.format_list(&opener, ")", &call.arguments, false, Self::format_expression)
+            }
+            ExpressionKind::MethodCall(call) => {
+                let object = self.format_postfix_operand(&call.object);
+                let opener = format!("{object}.{}(", call.method_name);
+                self.format_list(&opener, ")", &call.arguments, false, Self::format_expression)
+            }
+            ExpressionKind::Constructor(constructor) => {
+                let opener = format!("{} {{", self.format_path(&constructor.type_name));
+                self.format_list(&opener, "}", &constructor.fields, true, |this, (name, value)| {
+                    match &value.kind {
+                        ExpressionKind::Variable(path) if is_single_segment(path, name) => {
+                            name.to_string()
+                        }
+                        _ => format!("{name}: {}", this.format_expression(value)),
+                    }
+                })
+            }
+            ExpressionKind::MemberAccess(access) => {
+                format!("{}.{}", self.format_postfix_operand(&access.lhs), access.rhs)
+            }
+            ExpressionKind::Cast(cast) => {
+                let needs_parens = matches!(
+                    cast.lhs.kind,
+                    ExpressionKind::Prefix(_)
+                        | ExpressionKind::Infix(_)
+                        | ExpressionKind::Lambda(_)
+                );
+                let lhs = self.format_operand(&cast.lhs, needs_parens);
+                format!("{lhs} as {}", self.format_type(&cast.r#type))
+            }
+            ExpressionKind::Infix(infix) => {
+                let operator = precedence(infix.operator.contents);
+                let lhs_needs_parens = match &infix.lhs.kind {
+                    ExpressionKind::Infix(lhs) => precedence(lhs.operator.contents) < operator,
+                    ExpressionKind::Lambda(_) => true,
+                    _ => false,
+                };
+                let rhs_needs_parens = match &infix.rhs.kind {
+                    ExpressionKind::Infix(rhs) => precedence(rhs.operator.contents) <= operator,
+                    _ => false,
+                };
+                let lhs = self.format_operand(&infix.lhs, lhs_needs_parens);
+                let rhs = self.format_operand(&infix.rhs, rhs_needs_parens);
+                format!("{lhs} {} {rhs}", infix.operator.contents.as_string())
+            }
+            ExpressionKind::For(for_loop) => self.format_for(for_loop),
+            ExpressionKind::While(while_loop) => self.format_while(while_loop),
+            ExpressionKind::If(if_expression) => self.format_if(if_expression),
+            ExpressionKind::Match(match_expression) => self.format_match(match_expression),
+            ExpressionKind::Variable(path) => self.format_path(path),
+            ExpressionKind::Tuple(elements) => {
+                let tuple = self.format_list("(", ")", elements, false, Self::format_expression);
+                // A parenthesized expression is not a tuple without a trailing comma
+                if elements.len() == 1 && !tuple.contains('\n') {
+                    format!("{},)", &tuple[..tuple.len() - 1])
+                } else {
+                    tuple
+                }
+            }
+            ExpressionKind::Lambda(lambda) => self.format_lambda(lambda),
+            ExpressionKind::Error => unreachable!("Programs with errors are never formatted"),
+        }
+    }
+
+    /// Formats an operand of an operator, wrapping it in parentheses if required.
+    fn format_operand(&mut self, expression: &Expression, needs_parens: bool) -> String {
+        let operand = self.format_expression(expression);
+        if needs_parens {
+            format!("({operand})")
+        } else {
+            operand
+        }
+    }
+
+    /// Formats the left-hand side of a call, index, or member access.
+    fn format_postfix_operand(&mut self, expression: &Expression) -> String {
+        let needs_parens = matches!(
+            expression.kind,
+            ExpressionKind::Prefix(_)
+                | ExpressionKind::Infix(_)
+                | ExpressionKind::Cast(_)
+                | ExpressionKind::Lambda(_)
+        );
+        self.format_operand(expression, needs_parens)
+    }
+
+    fn format_literal(&mut self, literal: &Literal, expression: &Expression) -> String {
+        match literal {
+            Literal::Array(ArrayLiteral::Standard(elements)) => {
+                self.format_list("[", "]", elements, false, Self::format_expression)
+            }
+            Literal::Array(ArrayLiteral::Repeated { repeated_element, length }) => {
+                let element = self.format_expression(repeated_element);
+                format!("[{element}; {}]", self.format_expression(length))
+            }
+            Literal::Bool(value) => value.to_string(),
+            Literal::Integer(value) => {
+                // Keep integers in the base they were written in
+                let text = self.token_text(expression.span.start());
+                if text.starts_with(|ch: char| ch.is_ascii_digit()) {
+                    text
+                } else {
+                    value.to_string()
+                }
+            }
+            Literal::Str(string) => format!("\"{string}\""),
+        }
+    }
+
+    fn format_for(&mut self, for_loop: &ForExpression) -> String {
+        let start = self.format_expression(&for_loop.start_range);
+        let end = self.format_expression(&for_loop.end_range);
+        let body = self.format_expression(&for_loop.block);
+        format!("for {} in {start}..{end} {body}", for_loop.identifier)
+    }
+
+    fn format_while(&mut self, while_loop: &WhileExpression) -> String {
+        let condition = self.format_expression(&while_loop.condition);
+        let max_iterations = match &while_loop.max_iterations {
+            Some(max) => format!(" max {}", self.format_type_expression(max)),
+            None => String::new(),
+        };
+        let body = self.format_expression(&while_loop.block);
+        format!("while {condition}{max_iterations} {body}")
+    }
+
+    /// The parser desugars `for x in array { .. }` into a block which binds the array and
+    /// loops over its indices. This recovers the original loop if `block` is such a desugaring.
+    fn format_for_in_array(&mut self, block: &noirc_frontend::BlockExpression) -> Option<String> {
+        let (array, for_loop) = match block.0.as_slice() {
+            [Statement::Let(array), Statement::Expression(for_loop)] => (array, for_loop),
+            _ => return None,
+        };
+        let is_desugared = matches!(
+            &array.pattern,
+            Pattern::Identifier(name) if name.0.contents.starts_with("$i")
+        );
+        let for_loop = match &for_loop.kind {
+            ExpressionKind::For(for_loop) if is_desugared => for_loop,
+            _ => return None,
+        };
+        let (element, body) = match &for_loop.block.kind {
+            ExpressionKind::Block(block) => match block.0.as_slice() {
+                [Statement::Let(element), Statement::Expression(body)] => (element, body),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let element = match &element.pattern {
+            Pattern::Identifier(name) => name,
+            _ => return None,
+        };
+
+        let array = self.format_expression(&array.expression);
+        let body = self.format_expression(body);
+        Some(format!("for {element} in {array} {body}"))
+    }
+
+    fn format_if(&mut self, if_expression: &IfExpression) -> String {
+        let condition = self.format_expression(&if_expression.condition);
+        let consequence = self.format_expression(&if_expression.consequence);
+        let mut result = format!("if {condition} {consequence}");
+
+        if let Some(alternative) = &if_expression.alternative {
+            result.push_str(" else ");
+            // `else if` is parsed as an else block containing only the inner if expression
+            let else_if = match &alternative.kind {
+                ExpressionKind::Block(block) => match block.0.as_slice() {
+                    [Statement::Expression(inner)] if inner.span == alternative.span => {
+                        match &inner.kind {
+                            ExpressionKind::If(inner) => Some(inner),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                },
+                _ => None,
+            };
+            match else_if {
+                Some(inner) => result.push_str(&self.format_if(inner)),
+                None => result.push_str(&self.format_expression(alternative)),
+            }
+        }
+        result
+    }
+
+    fn format_match(&mut self, match_expression: &MatchExpression) -> String {
+        let expression = self.format_expression(&match_expression.expression);
+        let opener = format!("match {expression} ");
+        let body = self.find_block(match_expression.expression.span.end());
+
+        self.format_braced(
+            &opener,
+            body,
+            &match_expression.rules,
+            |this, result, (pattern, branch)| {
+                let span = match pattern {
+                    MatchPattern::Variant(_, _, span) => *span,
+                    MatchPattern::Catchall(pattern) => pattern_span(pattern),
+                };
+                this.format_line(result, span.merge(branch.span), |this| {
+                    let pattern = this.format_match_pattern(pattern);
+                    format!("{pattern} => {},", this.format_expression(branch))
+                });
+            },
+        )
+    }
+
+    fn format_match_pattern(&mut self, pattern: &MatchPattern) -> String {
+        match pattern {
+            // A lone identifier would be parsed as a catch-all pattern instead
+            MatchPattern::Variant(path, fields, _) if fields.is_empty() => {
+                if path.segments.len() == 1 {
+                    format!("{}()", self.format_path(path))
+                } else {
+                    self.format_path(path)
+                }
+            }
+            MatchPattern::Variant(path, fields, _) => {
+                let opener = format!("{}(", self.format_path(path));
+                self.format_list(&opener, ")", fields, false, Self::format_pattern)
+            }
+            MatchPattern::Catchall(pattern) => self.format_pattern(pattern),
+        }
+    }
+
+    fn format_lambda(&mut self, lambda: &Lambda) -> String {
+        let parameters = lambda.parameters.iter().map(|(pattern, typ)| {
+            let pattern = self.format_pattern(pattern);
+            match typ {
+                UnresolvedType::Unspecified => pattern,
+                typ => format!("{pattern}: {}", self.format_type(typ)),
+            }
+        });
+        let parameters: Vec<_> = parameters.collect();
+        let return_type = match &lambda.return_type {
+            UnresolvedType::Unspecified => String::new(),
+            typ => format!("-> {} ", self.format_type(typ)),
+        };
+        let body = self.format_expression(&lambda.body);
+        format!("|{}| {return_type}{body}", parameters.join(", "))
+    }
+
+    pub(crate) fn format_assignment(&mut self, assign: &AssignStatement) -> String {
+        let lvalue = self.format_lvalue(&assign.lvalue);
+
+        // `a += b` is desugared into `a = a + b` with every span set to that of the statement
+        if let ExpressionKind::Infix(infix) = &assign.expression.kind {
+            let span = assign.expression.span;
+            if infix.lhs.span == span && infix.operator.span() == span {
+                let operator = infix.operator.contents.as_string();
+                return format!("{lvalue} {operator}= {};", self.format_expression(&infix.rhs));
+            }
+        }
+        format!("{lvalue} = {};", self.format_expression(&assign.expression))
+    }
+
+    fn format_lvalue(&mut self, lvalue: &LValue) -> String {
+        match lvalue {
+            LValue::Ident(name) => name.to_string(),
+            LValue::MemberAccess { object, field_name } => {
+                format!("{}.{field_name}", self.format_lvalue(object))
+            }
+            LValue::Index { array, index } => {
+                format!("{}[{}]", self.format_lvalue(array), self.format_expression(index))
+            }
+            LValue::Dereference(lvalue) => format!("*{}", self.format_lvalue(lvalue)),
+        }
+    }
+
+    pub(crate) fn format_pattern(&mut self, pattern: &Pattern) -> String {
+        match pattern {
+            Pattern::Identifier(name) => name.to_string(),
+            Pattern::Mutable(pattern, _) => format!("mut {}", self.format_pattern(pattern)),
+            Pattern::Tuple(patterns, _) => {
+                self.format_list("(", ")", patterns, false, Self::format_pattern)
+            }
+            Pattern::Struct(path, fields, _) => {
+                let opener = format!("{} {{", self.format_path(path));
+                self.format_list(
+                    &opener,
+                    "}",
+                    fields,
+                    true,
+                    |this, (name, pattern)| match pattern {
+                        Pattern::Identifier(binding) if binding == name => name.to_string(),
+                        _ => format!("{name}: {}", this.format_pattern(pattern)),
+                    },
+                )
+            }
+        }
+    }
+
+    pub(crate) fn format_type(&mut self, typ: &UnresolvedType) -> String {
+        match typ {
+            UnresolvedType::FieldElement(comptime) => format!("{comptime}Field"),
+            UnresolvedType::Integer(comptime, Signedness::Signed, bits) => {
+                format!("{comptime}i{bits}")
+            }
+            UnresolvedType::Integer(comptime, Signedness::Unsigned, bits) => {
+                format!("{comptime}u{bits}")
+            }
+            UnresolvedType::Bool(comptime) => format!("{comptime}bool"),
+            UnresolvedType::Array(None, element) => format!("[{}]", self.format_type(element)),
+            UnresolvedType::Array(Some(length), element) => {
+                let element = self.format_type(element);
+                format!("[{element}; {}]", self.format_type_expression(length))
+            }
+            UnresolvedType::Expression(expression) => self.format_type_expression(expression),
+            UnresolvedType::String(None) => "str".to_owned(),
+            UnresolvedType::String(Some(length)) => {
+                format!("str<{}>", self.format_type_expression(length))
+            }
+            UnresolvedType::Unit => "()".to_owned(),
+            UnresolvedType::Named(path, args) => {
+                let path = self.format_path(path);
+                self.format_type_args(path, args)
+            }
+            UnresolvedType::Vec(args, _) => self.format_type_args("Vec".to_owned(), args),
+            UnresolvedType::MutableReference(element) => {
+                format!("&mut {}", self.format_type(element))
+            }
+            UnresolvedType::Tuple(elements) => {
+                self.format_list("(", ")", elements, false, Self::format_type)
+            }
+            UnresolvedType::Function(args, ret, env) => {
+                let open = match env.as_ref() {
+                    UnresolvedType::Unit => "fn(".to_owned(),
+                    env => format!("fn[{}](", self.format_type(env)),
+                };
+                let args = self.format_list(&open, ")", args, false, Self::format_type);
+                format!("{args} -> {}", self.format_type(ret))
+            }
+            UnresolvedType::Unspecified => String::new(),
+            UnresolvedType::Error => unreachable!("Programs with errors are never formatted"),
+        }
+    }
+
+    fn format_type_args(&mut self, name: String, args: &[UnresolvedType]) -> String {
+        if args.is_empty() {
+            name
+        } else {
+            let args: Vec<_> = args.iter().map(|arg| self.format_type(arg)).collect();
+            format!("{name}<{}>", args.join(", "))
+        }
+    }
+
+    fn format_type_expression(&mut self, expression: &UnresolvedTypeExpression) -> String {
+        match expression {
+            UnresolvedTypeExpression::Variable(path) => self.format_path(path),
+            UnresolvedTypeExpression::Constant(value, _) => value.to_string(),
+            UnresolvedTypeExpression::BinaryOperation(lhs, operator, rhs, _) => {
+                let precedence = type_precedence(*operator);
+                let lhs_needs_parens = matches!(
+                    lhs.as_ref(),
+                    UnresolvedTypeExpression::BinaryOperation(_, lhs, _, _)
+                        if type_precedence(*lhs) < precedence
+                );
+                let rhs_needs_parens = matches!(
+                    rhs.as_ref(),
+                    UnresolvedTypeExpression::BinaryOperation(_, rhs, _, _)
+                        if type_precedence(*rhs) <= precedence
+                );
+
+                let mut lhs = self.format_type_expression(lhs);
+                let mut rhs = self.format_type_expression(rhs);
+                if lhs_needs_parens {
+                    lhs = format!("({lhs})");
+                }
+                if rhs_needs_parens {
+                    rhs = format!("({rhs})");
+                }
+                format!("{lhs} {operator} {rhs}")
+            }
+            UnresolvedTypeExpression::Expression(expression) => self.format_expression(expression),
+        }
+    }
+
+    pub(crate) fn format_path(&self, path: &Path) -> String {
+        let mut result = match path.kind {
+            PathKind::Crate => "crate::".to_owned(),
+            PathKind::Dep => "dep::".to_owned(),
+            PathKind::Plain => String::new(),
+        };
+        let segments: Vec<_> = path.segments.iter().map(ToString::to_string).collect();
+        result.push_str(&segments.join("::"));
+        result
+    }
+
+    pub(crate) fn format_use_tree(&self, use_tree: &UseTree) -> String {
+        let mut result = self.format_path(&use_tree.prefix);
+        if !use_tree.prefix.segments.is_empty() {
+            result.push_str("::");
+        }
+
+        match &use_tree.kind {
+            UseTreeKind::Path(name, None) => result.push_str(&name.to_string()),
+            UseTreeKind::Path(name, Some(alias)) => result.push_str(&format!("{name} as {alias}")),
+            UseTreeKind::List(trees) => {
+                let trees: Vec<_> = trees.iter().map(|tree| self.format_use_tree(tree)).collect();
+                result.push_str(&format!("{{{}}}", trees.join(", ")));
+            }
+        }
+        result
+    }
+
+    /// Formats a comma-separated list between `opener` and `closer`, on a single line if it fits
+    /// or otherwise with each element on its own line. If `spaced` is set, single line lists are
+    /// padded with a space inside the delimiters as in `Foo { a, b }`.
+    pub(crate) fn format_list<T>(
+        &mut self,
+        opener: &str,
+        closer: &str,
+        elements: &[T],
+        spaced: bool,
+        mut format_element: impl FnMut(&mut Self, &T) -> String,
+    ) -> String {
+        if elements.is_empty() {
+            return format!("{opener}{closer}");
+        }
+
+        let comments = self.save_comments();
+        let formatted: Vec<_> =
+            elements.iter().map(|element| format_element(self, element)).collect();
+
+        // Only the last element may span multiple lines, as with a trailing closure or block
+        let multiline =
+            formatted[..formatted.len() - 1].iter().any(|element| element.contains('\n'));
+        let padding = if spaced { " " } else { "" };
+        let single_line = format!("{opener}{padding}{}{padding}{closer}", formatted.join(", "));
+        if !multiline && self.fits(single_line.lines().next().unwrap_or_default()) {
+            return single_line;
+        }
+
+        self.restore_comments(comments);
+        self.indent += 1;
+        let mut result = format!("{opener}\n");
+        for element in elements {
+            let element = format_element(self, element);
+            result.push_str(&format!("{}{element},\n", self.indentation()));
+        }
+        self.indent -= 1;
+        result.push_str(&self.indentation());
+        result.push_str(closer);
+        result
+    }
+}
+
+fn is_single_segment(path: &Path, name: &noirc_frontend::Ident) -> bool {
+    path.kind == PathKind::Plain && path.segments.len() == 1 && path.segments[0] == *name
+}
+
+/// The binding power of each operator, matching the parser's precedence levels.
+fn precedence(operator: BinaryOpKind) -> u8 {
+    match operator {
+        BinaryOpKind::Equal | BinaryOpKind::NotEqual => 0,
+        BinaryOpKind::Or => 1,
+        BinaryOpKind::Xor => 2,
+        BinaryOpKind::And => 3,
+        BinaryOpKind::Less
+        | BinaryOpKind::LessEqual
+        | BinaryOpKind::Greater
+        | BinaryOpKind::GreaterEqual => 4,
+        BinaryOpKind::ShiftLeft | BinaryOpKind::ShiftRight => 5,
+        BinaryOpKind::Add | BinaryOpKind::Subtract => 6,
+        BinaryOpKind::Multiply | BinaryOpKind::Divide | BinaryOpKind::Modulo => 7,
+    }
+}
+
+fn type_precedence(operator: BinaryTypeOperator) -> u8 {
+    match operator {
+        BinaryTypeOperator::Addition | BinaryTypeOperator::Subtraction => 0,
+        BinaryTypeOperator::Multiplication
+        | BinaryTypeOperator::Division
+        | BinaryTypeOperator::Modulo => 1,
+    }
+}
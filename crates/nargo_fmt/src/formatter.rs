@@ -0,0 +1,676 @@
+use std::collections::VecDeque;
+
+use noirc_abi::{AbiDistinctness, AbiVisibility};
+use noirc_errors::Span;
+use noirc_frontend::{
+    lexer::Lexer,
+    parser::{Item, ItemKind, ParsedSubModule},
+    token::{Keyword, SpannedToken, Token},
+    BlockExpression, CompTime, Ident, LetStatement, NoirEnum, NoirFunction, NoirImpl, NoirStruct,
+    NoirTrait, NoirTraitImpl, ParsedModule, Pattern, Statement, TraitConstraint, TraitItem,
+    UnresolvedType,
+};
+
+use crate::Config;
+
+/// A `// comment` in the source program.
+#[derive(Debug, Clone)]
+struct Comment {
+    text: String,
+    span: Span,
+}
+
+/// Prints a ParsedModule back out as source code.
+///
+/// The parser discards comments, so they are lexed separately and printed before
+/// whichever item, statement or field follows them. Comments which end up inside
+/// an expression are moved to after the statement containing them. Blank lines
+/// between items and statements are preserved, collapsing runs of them into one.
+///
+/// Formatting methods return the formatted code rather than writing it out, with each
+/// line after the first indented to the current `indent` level so that the result
+/// can be placed anywhere within the current line.
+pub(crate) struct Formatter<'a> {
+    config: &'a Config,
+    source: Vec<char>,
+    /// The start position of each line in `source`
+    line_starts: Vec<u32>,
+    /// Every token in the source other than comments
+    tokens: Vec<SpannedToken>,
+    /// The comments which have yet to be printed, in the order they appear in the source
+    comments: VecDeque<Comment>,
+    /// The end of the code which has most recently been printed
+    last_position: u32,
+    pub(crate) indent: usize,
+}
+
+impl<'a> Formatter<'a> {
+    pub(crate) fn new(source: &str, config: &'a Config) -> Self {
+        let mut tokens = Vec::new();
+        let mut comments = VecDeque::new();
+        // Any lexing errors have already been reported by the parser
+        for token in Lexer::new(source).skip_comments(false).flatten() {
+            match token.token() {
+                Token::LineComment(text) => {
+                    let text = format!("//{}", text.trim_end());
+                    comments.push_back(Comment { text, span: token.to_span() });
+                }
+                _ => tokens.push(token),
+            }
+        }
+
+        let source: Vec<char> = source.chars().collect();
+        let line_starts = std::iter::once(0)
+            .chain((0..).zip(&source).filter(|(_, ch)| **ch == '\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Formatter { config, source, line_starts, tokens, comments, last_position: 0, indent: 0 }
+    }
+
+    pub(crate) fn format_module(mut self, module: ParsedModule) -> String {
+        let mut result = String::new();
+        self.format_items(&mut result, &module.items);
+        self.format_comments_before(&mut result, u32::MAX);
+
+        let trimmed_len = result.trim_end().len();
+        result.truncate(trimmed_len);
+        result.push('\n');
+        result
+    }
+
+    pub(crate) fn indentation(&self) -> String {
+        " ".repeat(self.indent * self.config.tab_spaces)
+    }
+
+    /// Returns true if `text` fits within the maximum width when placed at the current indentation.
+    pub(crate) fn fits(&self, text: &str) -> bool {
+        let width = text.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+        self.indent * self.config.tab_spaces + width <= self.config.max_width
+    }
+
+    /// Formatting some code twice, such as when trying to fit a list on a single line, would
+    /// otherwise print the comments within it twice. Instead the state of the comments is saved
+    /// beforehand and restored in between each attempt.
+    pub(crate) fn save_comments(&self) -> CommentState {
+        CommentState { comments: self.comments.clone(), last_position: self.last_position }
+    }
+
+    pub(crate) fn restore_comments(&mut self, state: CommentState) {
+        self.comments = state.comments;
+        self.last_position = state.last_position;
+    }
+
+    fn line(&self, position: u32) -> usize {
+        self.line_starts.partition_point(|start| *start <= position).saturating_sub(1)
+    }
+
+    /// Returns the source code of the single token starting at `position`.
+    pub(crate) fn token_text(&self, position: u32) -> String {
+        self.source
+            .iter()
+            .skip(position as usize)
+            .take_while(|ch| ch.is_ascii_alphanumeric() || **ch == '_')
+            .collect()
+    }
+
+    fn token_index_at(&self, position: u32) -> usize {
+        self.tokens.partition_point(|token| token.to_span().start() < position)
+    }
+
+    /// Finds the span of the first block starting after `position`, from its opening brace up
+    /// to its closing brace. Braces within parentheses, such as those of struct patterns in a
+    /// function's parameters, are skipped over.
+    pub(crate) fn find_block(&self, position: u32) -> Span {
+        let mut tokens = self.tokens.iter().skip(self.token_index_at(position));
+        let mut parens = 0;
+        let start = tokens
+            .find(|token| match token.token() {
+                Token::LeftParen => {
+                    parens += 1;
+                    false
+                }
+                Token::RightParen => {
+                    parens -= 1;
+                    false
+                }
+                Token::LeftBrace => parens <= 0,
+                _ => false,
+            })
+            .map_or(position, |token| token.to_span().start());
+
+        let mut depth = 1;
+        for token in tokens {
+            match token.token() {
+                Token::LeftBrace => depth += 1,
+                Token::RightBrace if depth == 1 => {
+                    return Span::exclusive(start, token.to_span().start())
+                }
+                Token::RightBrace => depth -= 1,
+                _ => (),
+            }
+        }
+        Span::exclusive(start, self.source.len() as u32)
+    }
+
+    /// Returns the position of the first `;` or block following `position` which is not
+    /// nested within any brackets, such as the end of a trait method declaration.
+    fn find_item_end(&self, position: u32) -> u32 {
+        let mut depth = 0;
+        for token in self.tokens.iter().skip(self.token_index_at(position)) {
+            match token.token() {
+                Token::LeftParen | Token::LeftBracket => depth += 1,
+                Token::RightParen | Token::RightBracket => depth -= 1,
+                Token::Semicolon if depth == 0 => return token.to_span().start(),
+                Token::LeftBrace if depth == 0 => return self.find_block(position).end(),
+                _ => (),
+            }
+        }
+        self.source.len() as u32
+    }
+
+    /// Finds the start of the function whose name is at `name`, including any attribute.
+    fn function_start(&self, name: Span) -> u32 {
+        let index = self.token_index_at(name.start());
+        let mut start = name.start();
+        for token in self.tokens[..index].iter().rev() {
+            match token.token() {
+                Token::Keyword(Keyword::Fn | Keyword::Open | Keyword::Unconstrained)
+                | Token::Attribute(_) => start = token.to_span().start(),
+                _ => break,
+            }
+        }
+        start
+    }
+
+    /// Prints all comments starting before `position`, on their own lines unless they
+    /// follow code on the same line.
+    pub(crate) fn format_comments_before(&mut self, result: &mut String, position: u32) {
+        while self.comments.front().map_or(false, |comment| comment.span.start() < position) {
+            let comment = self.comments.pop_front().expect("a comment was just found");
+
+            if !result.is_empty()
+                && self.line(comment.span.start()) == self.line(self.last_position)
+            {
+                result.pop();
+                result.push(' ');
+            } else {
+                self.format_blank_line_before(result, comment.span.start());
+                result.push_str(&self.indentation());
+            }
+
+            result.push_str(&comment.text);
+            result.push('\n');
+            self.last_position = comment.span.start();
+        }
+    }
+
+    /// Keeps a single blank line before `position` if there was at least one in the source.
+    fn format_blank_line_before(&self, result: &mut String, position: u32) {
+        let at_start = result.is_empty() || result.ends_with("{\n");
+        if !at_start && self.line(position) > self.line(self.last_position) + 1 {
+            result.push('\n');
+        }
+    }
+
+    /// Prints an entry of some list of items or statements on its own line, along
+    /// with any comments and blank lines preceding it. The comments are printed before
+    /// the entry is formatted so that they are not mistaken for comments within it.
+    pub(crate) fn format_line(
+        &mut self,
+        result: &mut String,
+        span: Span,
+        format: impl FnOnce(&mut Self) -> String,
+    ) {
+        self.format_comments_before(result, span.start());
+        self.format_blank_line_before(result, span.start());
+        let text = format(self);
+        result.push_str(&self.indentation());
+        result.push_str(&text);
+        result.push('\n');
+        self.last_position = span.end().max(self.last_position);
+    }
+
+    /// Formats entries within braces, each on their own line.
+    /// `span` runs from the opening brace up to the closing brace.
+    pub(crate) fn format_braced<T>(
+        &mut self,
+        opener: &str,
+        span: Span,
+        entries: &[T],
+        mut format_entry: impl FnMut(&mut Self, &mut String, &T),
+    ) -> String {
+        let mut result = format!("{opener}{{\n");
+        self.last_position = span.start();
+        self.indent += 1;
+
+        for entry in entries {
+            format_entry(self, &mut result, entry);
+        }
+        self.format_comments_before(&mut result, span.end());
+
+        self.indent -= 1;
+        self.last_position = span.end();
+        if result.ends_with("{\n") {
+            result.pop();
+        } else {
+            result.push_str(&self.indentation());
+        }
+        result.push('}');
+        result
+    }
+
+    fn format_items(&mut self, result: &mut String, items: &[Item]) {
+        for item in items {
+            self.format_line(result, item.span, |this| this.format_item(item));
+        }
+    }
+
+    fn format_item(&mut self, item: &Item) -> String {
+        match &item.kind {
+            ItemKind::Function(function) => self.format_function(function),
+            ItemKind::ModuleDecl(name) => format!("mod {name};"),
+            ItemKind::Import(use_tree) => format!("use {};", self.format_use_tree(use_tree)),
+            ItemKind::Struct(noir_struct) => self.format_struct(noir_struct, item.span),
+            ItemKind::Enum(noir_enum) => self.format_enum(noir_enum, item.span),
+            ItemKind::Impl(noir_impl) => self.format_impl(noir_impl, item.span),
+            ItemKind::Trait(noir_trait) => self.format_trait(noir_trait, item.span),
+            ItemKind::TraitImpl(trait_impl) => self.format_trait_impl(trait_impl, item.span),
+            ItemKind::Submodule(submodule) => self.format_submodule(submodule, item.span),
+            ItemKind::Global(global) => self.format_global(global),
+        }
+    }
+
+    fn format_submodule(&mut self, submodule: &ParsedSubModule, span: Span) -> String {
+        let keyword = if submodule.is_contract { "contract" } else { "mod" };
+        let opener = format!("{keyword} {} ", submodule.name);
+        let body = self.find_block(submodule.name.span().start());
+        let body = Span::exclusive(body.start(), span.end());
+
+        self.format_braced(&opener, body, &submodule.contents.items, |this, result, item| {
+            this.format_line(result, item.span, |this| this.format_item(item));
+        })
+    }
+
+    fn format_global(&mut self, global: &LetStatement) -> String {
+        // Globals are implicitly `comptime` so this is left out of their type
+        let typ = match &global.r#type {
+            UnresolvedType::FieldElement(_) => UnresolvedType::FieldElement(CompTime::No(None)),
+            UnresolvedType::Bool(_) => UnresolvedType::Bool(CompTime::No(None)),
+            UnresolvedType::Integer(_, sign, bits) => {
+                UnresolvedType::Integer(CompTime::No(None), *sign, *bits)
+            }
+            other => other.clone(),
+        };
+        let typ = self.format_type(&typ);
+        let pattern = self.format_pattern(&global.pattern);
+        let expression = self.format_expression(&global.expression);
+        if global.r#type == UnresolvedType::Unspecified {
+            format!("global {pattern} = {expression};")
+        } else {
+            format!("global {pattern}: {typ} = {expression};")
+        }
+    }
+
+    fn format_struct(&mut self, noir_struct: &NoirStruct, span: Span) -> String {
+        let opener =
+            format!("struct {}{} ", noir_struct.name, self.format_generics(&noir_struct.generics));
+        let body = self.find_block(noir_struct.name.span().end());
+        let body = Span::exclusive(body.start(), span.end());
+
+        self.format_braced(&opener, body, &noir_struct.fields, |this, result, (name, typ)| {
+            this.format_line(result, name.span(), |this| {
+                format!("{name}: {},", this.format_type(typ))
+            });
+        })
+    }
+
+    fn format_enum(&mut self, noir_enum: &NoirEnum, span: Span) -> String {
+        let opener =
+            format!("enum {}{} ", noir_enum.name, self.format_generics(&noir_enum.generics));
+        let body = self.find_block(noir_enum.name.span().end());
+        let body = Span::exclusive(body.start(), span.end());
+
+        self.format_braced(&opener, body, &noir_enum.variants, |this, result, (name, fields)| {
+            this.format_line(result, name.span(), |this| {
+                if fields.is_empty() {
+                    format!("{name},")
+                } else {
+                    let fields: Vec<_> =
+                        fields.iter().map(|field| this.format_type(field)).collect();
+                    format!("{name}({}),", fields.join(", "))
+                }
+            });
+        })
+    }
+
+    fn format_impl(&mut self, noir_impl: &NoirImpl, span: Span) -> String {
+        let generics = self.format_generics(&noir_impl.generics);
+        let opener = format!("impl{generics} {} ", self.format_type(&noir_impl.object_type));
+        let body = self.find_block(noir_impl.type_span.end());
+        let body = Span::exclusive(body.start(), span.end());
+        self.format_braced(&opener, body, &noir_impl.methods, Self::format_method)
+    }
+
+    fn format_trait_impl(&mut self, trait_impl: &NoirTraitImpl, span: Span) -> String {
+        let generics = self.format_generics(&trait_impl.generics);
+        let trait_name = self.format_path(&trait_impl.trait_name);
+        let object_type = self.format_type(&trait_impl.object_type);
+        let opener = format!("impl{generics} {trait_name} for {object_type} ");
+        let body = self.find_block(trait_impl.type_span.end());
+        let body = Span::exclusive(body.start(), span.end());
+        self.format_braced(&opener, body, &trait_impl.methods, Self::format_method)
+    }
+
+    fn format_method(&mut self, result: &mut String, method: &NoirFunction) {
+        let name = method.name_ident().span();
+        let start = self.function_start(name);
+        let end = self.find_block(name.end()).end();
+        self.format_line(result, Span::exclusive(start, end), |this| this.format_function(method));
+    }
+
+    fn format_trait(&mut self, noir_trait: &NoirTrait, span: Span) -> String {
+        let opener = format!("trait {} ", noir_trait.name);
+        let body = self.find_block(noir_trait.name.span().end());
+        let body = Span::exclusive(body.start(), span.end());
+
+        self.format_braced(&opener, body, &noir_trait.items, |this, result, item| match item {
+            TraitItem::Function { name, generics, parameters, return_type, where_clause, body } => {
+                let start = this.function_start(name.span());
+                let end = this.find_item_end(name.span().end());
+
+                this.format_line(result, Span::exclusive(start, end), |this| {
+                    let mut text = this.format_signature(name, generics, parameters, where_clause);
+                    if *return_type != UnresolvedType::Unit {
+                        let return_type = this.format_type(return_type);
+                        text = insert_return_type(text, &return_type, where_clause.is_empty());
+                    }
+
+                    match body {
+                        Some(body) => {
+                            let body_span = this.find_block(name.span().end());
+                            text.push(' ');
+                            text.push_str(&this.format_block(body, body_span));
+                        }
+                        None => text.push(';'),
+                    }
+                    text
+                });
+            }
+        })
+    }
+
+    fn format_function(&mut self, function: &NoirFunction) -> String {
+        let def = function.def();
+        let mut result = String::new();
+
+        if let Some(attribute) = &def.attribute {
+            result.push_str(&format!("{attribute}\n{}", self.indentation()));
+        }
+        if def.is_unconstrained {
+            result.push_str("unconstrained ");
+        }
+        if def.is_open {
+            result.push_str("open ");
+        }
+
+        let mut signature =
+            self.format_signature(&def.name, &def.generics, &def.parameters, &def.where_clause);
+
+        let has_return_type = def.return_type != UnresolvedType::Unit
+            || def.return_visibility == AbiVisibility::Public
+            || def.return_distinctness == AbiDistinctness::Distinct;
+        if has_return_type {
+            let mut return_type = String::new();
+            if def.return_distinctness == AbiDistinctness::Distinct {
+                return_type.push_str("distinct ");
+            }
+            if def.return_visibility == AbiVisibility::Public {
+                return_type.push_str("pub ");
+            }
+            return_type.push_str(&self.format_type(&def.return_type));
+            signature = insert_return_type(signature, &return_type, def.where_clause.is_empty());
+        }
+        result.push_str(&signature);
+
+        let body_span = self.find_block(def.name.span().end());
+        result.push(' ');
+        result.push_str(&self.format_block(&def.body, body_span));
+        result
+    }
+
+    /// Formats a function's name, generics, parameters and where clause.
+    /// The return type is inserted afterward by [insert_return_type].
+    fn format_signature(
+        &mut self,
+        name: &Ident,
+        generics: &[Ident],
+        parameters: &[(Pattern, UnresolvedType, AbiVisibility)],
+        where_clause: &[TraitConstraint],
+    ) -> String {
+        // Bounds written inline on a generic are desugared into the where clause using the
+        // generic's own identifier, so they can be told apart by their span.
+        let is_inline_bound = |constraint: &TraitConstraint, generic: &Ident| match &constraint.typ
+        {
+            UnresolvedType::Named(path, args) => {
+                args.is_empty()
+                    && path.segments.len() == 1
+                    && path.segments[0] == *generic
+                    && path.segments[0].span() == generic.span()
+            }
+            _ => false,
+        };
+
+        let formatted_generics: Vec<_> = generics
+            .iter()
+            .map(|generic| {
+                let bounds: Vec<_> = where_clause
+                    .iter()
+                    .filter(|constraint| is_inline_bound(constraint, generic))
+                    .map(|constraint| self.format_path(&constraint.trait_name))
+                    .collect();
+                if bounds.is_empty() {
+                    generic.to_string()
+                } else {
+                    format!("{generic}: {}", bounds.join(" + "))
+                }
+            })
+            .collect();
+        let formatted_generics = if formatted_generics.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", formatted_generics.join(", "))
+        };
+
+        let opener = format!("fn {name}{formatted_generics}(");
+        let parameters = self.format_list(&opener, ")", parameters, false, |this, parameter| {
+            this.format_parameter(parameter)
+        });
+
+        // Group the remaining bounds on each type together, as in `where T: Eq + Default`
+        let mut constraints: Vec<(&UnresolvedType, Vec<String>)> = Vec::new();
+        for constraint in where_clause {
+            if generics.iter().any(|generic| is_inline_bound(constraint, generic)) {
+                continue;
+            }
+            let bound = self.format_path(&constraint.trait_name);
+            match constraints.iter_mut().find(|(typ, _)| **typ == constraint.typ) {
+                Some((_, bounds)) => bounds.push(bound),
+                None => constraints.push((&constraint.typ, vec![bound])),
+            }
+        }
+
+        if constraints.is_empty() {
+            parameters
+        } else {
+            let constraints: Vec<_> = constraints
+                .into_iter()
+                .map(|(typ, bounds)| format!("{}: {}", self.format_type(typ), bounds.join(" + ")))
+                .collect();
+            format!("{parameters} where {}", constraints.join(", "))
+        }
+    }
+
+    fn format_parameter(
+        &mut self,
+        (pattern, typ, visibility): &(Pattern, UnresolvedType, AbiVisibility),
+    ) -> String {
+        let is_self_type = |typ: &UnresolvedType| match typ {
+            UnresolvedType::Named(path, args) => {
+                args.is_empty() && path.segments.len() == 1 && path.segments[0] == *"Self"
+            }
+            _ => false,
+        };
+
+        match (pattern, typ) {
+            (Pattern::Identifier(name), UnresolvedType::MutableReference(typ))
+                if *name == *"self" && is_self_type(typ) =>
+            {
+                "&mut self".to_owned()
+            }
+            (Pattern::Identifier(name), typ) if *name == *"self" && is_self_type(typ) => {
+                "self".to_owned()
+            }
+            (Pattern::Mutable(inner, _), typ)
+                if matches!(inner.as_ref(), Pattern::Identifier(name) if *name == *"self")
+                    && is_self_type(typ) =>
+            {
+                "mut self".to_owned()
+            }
+            _ => {
+                let visibility = match visibility {
+                    AbiVisibility::Public => "pub ",
+                    AbiVisibility::Private => "",
+                };
+                format!("{}: {visibility}{}", self.format_pattern(pattern), self.format_type(typ))
+            }
+        }
+    }
+
+    fn format_generics(&self, generics: &[Ident]) -> String {
+        if generics.is_empty() {
+            String::new()
+        } else {
+            let generics: Vec<_> = generics.iter().map(ToString::to_string).collect();
+            format!("<{}>", generics.join(", "))
+        }
+    }
+
+    /// Formats a block of statements. `span` runs from the opening brace up to the closing brace.
+    pub(crate) fn format_block(&mut self, block: &BlockExpression, span: Span) -> String {
+        let last = block.len().saturating_sub(1);
+        let statements: Vec<_> = block.0.iter().enumerate().collect();
+
+        self.format_braced("", span, &statements, |this, result, (index, statement)| {
+            let span = match statement_span(statement) {
+                Some(span) => span,
+                None => return,
+            };
+            this.format_line(result, span, |this| this.format_statement(statement, *index == last));
+        })
+    }
+
+    fn format_statement(&mut self, statement: &Statement, is_last: bool) -> String {
+        match statement {
+            Statement::Let(let_statement) => {
+                let pattern = self.format_pattern(&let_statement.pattern);
+                let expression = self.format_expression(&let_statement.expression);
+                match &let_statement.r#type {
+                    UnresolvedType::Unspecified => format!("let {pattern} = {expression};"),
+                    typ => format!("let {pattern}: {} = {expression};", self.format_type(typ)),
+                }
+            }
+            Statement::Constrain(constrain) => {
+                format!("assert({});", self.format_expression(&constrain.0))
+            }
+            Statement::Expression(expression) => {
+                let text = self.format_expression(expression);
+                // Expressions other than the last in a block must be followed by a
+                // semicolon unless they end in a block.
+                if is_last || ends_with_block(expression) {
+                    text
+                } else {
+                    format!("{text};")
+                }
+            }
+            Statement::Semi(expression) => {
+                let text = self.format_expression(expression);
+                // The semicolon is only significant after a block if it is the last statement
+                if is_last || !ends_with_block(expression) {
+                    format!("{text};")
+                } else {
+                    text
+                }
+            }
+            Statement::Assign(assign) => self.format_assignment(assign),
+            Statement::Break(_) => "break;".to_string(),
+            Statement::Continue(_) => "continue;".to_string(),
+            Statement::Error => unreachable!("Programs with errors are never formatted"),
+        }
+    }
+}
+
+/// The comments yet to be printed, saved by [Formatter::save_comments].
+pub(crate) struct CommentState {
+    comments: VecDeque<Comment>,
+    last_position: u32,
+}
+
+/// Inserts `-> return_type` before any where clause of a formatted function signature.
+fn insert_return_type(signature: String, return_type: &str, no_where_clause: bool) -> String {
+    if no_where_clause {
+        return format!("{signature} -> {return_type}");
+    }
+    match signature.rfind(" where ") {
+        Some(index) => {
+            format!("{} -> {return_type}{}", &signature[..index], &signature[index..])
+        }
+        None => format!("{signature} -> {return_type}"),
+    }
+}
+
+/// Returns true if `expression` ends with a block and so does not need a semicolon after it.
+fn ends_with_block(expression: &noirc_frontend::Expression) -> bool {
+    use noirc_frontend::ExpressionKind;
+    matches!(
+        expression.kind,
+        ExpressionKind::Block(_)
+            | ExpressionKind::For(_)
+            | ExpressionKind::While(_)
+            | ExpressionKind::If(_)
+            | ExpressionKind::Match(_)
+    )
+}
+
+/// Approximates the span of a statement from the spans of its parts, as statements
+/// don't track their own spans.
+fn statement_span(statement: &Statement) -> Option<Span> {
+    match statement {
+        Statement::Let(let_statement) => {
+            Some(pattern_span(&let_statement.pattern).merge(let_statement.expression.span))
+        }
+        Statement::Constrain(constrain) => Some(constrain.0.span),
+        Statement::Expression(expression) | Statement::Semi(expression) => Some(expression.span),
+        Statement::Assign(assign) => {
+            Some(lvalue_span(&assign.lvalue).merge(assign.expression.span))
+        }
+        Statement::Break(span) | Statement::Continue(span) => Some(*span),
+        Statement::Error => None,
+    }
+}
+
+pub(crate) fn pattern_span(pattern: &Pattern) -> Span {
+    match pattern {
+        Pattern::Identifier(name) => name.span(),
+        Pattern::Mutable(_, span) | Pattern::Tuple(_, span) | Pattern::Struct(_, _, span) => *span,
+    }
+}
+
+fn lvalue_span(lvalue: &noirc_frontend::LValue) -> Span {
+    use noirc_frontend::LValue;
+    match lvalue {
+        LValue::Ident(name) => name.span(),
+        LValue::MemberAccess { object, field_name } => lvalue_span(object).merge(field_name.span()),
+        LValue::Index { array, index } => lvalue_span(array).merge(index.span),
+        LValue::Dereference(lvalue) => lvalue_span(lvalue),
+    }
+}
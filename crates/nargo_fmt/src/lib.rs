@@ -0,0 +1,221 @@
+#![forbid(unsafe_code)]
+#![warn(unused_crate_dependencies, unused_extern_crates)]
+#![warn(unreachable_pub)]
+#![warn(clippy::semicolon_if_nothing_returned)]
+
+//! Formats Noir source code into a canonical style.
+//!
+//! The formatter prints the [ParsedModule][noirc_frontend::ParsedModule] of a file back out
+//! as source code, so formatting a program never changes its meaning. Comments and single
+//! blank lines between items and statements are carried over from the original source.
+
+mod config;
+mod expr;
+mod formatter;
+
+use noirc_errors::CustomDiagnostic;
+
+pub use config::Config;
+
+use formatter::Formatter;
+
+/// Formats the source code of a single file.
+///
+/// Returns the diagnostics from parsing `source` if it contains any errors, as a program
+/// which failed to parse cannot be printed back out faithfully.
+pub fn format(source: &str, config: &Config) -> Result<String, Vec<CustomDiagnostic>> {
+    let (module, diagnostics) = noirc_frontend::parse_program(source);
+    if diagnostics.iter().any(CustomDiagnostic::is_error) {
+        return Err(diagnostics);
+    }
+    Ok(Formatter::new(source, config).format_module(module))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format, Config};
+
+    fn assert_format(source: &str, expected: &str) {
+        let config = Config::default();
+        let formatted = format(source, &config).expect("source should parse");
+        assert_eq!(formatted, expected);
+
+        let reformatted = format(&formatted, &config).expect("formatted source should parse");
+        assert_eq!(reformatted, expected, "formatting should be idempotent");
+    }
+
+    #[test]
+    fn formats_functions() {
+        let source = "fn main(x : Field,y:pub Field)->pub Field{let z=x+y*2;assert(z!=0);z}";
+        let expected = "\
+fn main(x: Field, y: pub Field) -> pub Field {
+    let z = x + y * 2;
+    assert(z != 0);
+    z
+}
+";
+        assert_format(source, expected);
+    }
+
+    #[test]
+    fn preserves_comments_and_blank_lines() {
+        let source = "\
+// The entry point
+fn main(x: Field) {
+    let y = x; // copied
+
+
+
+    // Check it
+    assert(y == x);
+}
+fn empty() {}
+";
+        let expected = "\
+// The entry point
+fn main(x: Field) {
+    let y = x; // copied
+
+    // Check it
+    assert(y == x);
+}
+fn empty() {}
+";
+        assert_format(source, expected);
+    }
+
+    #[test]
+    fn recovers_desugared_syntax() {
+        let source = "\
+fn main(mut a: [u8; 3]) {
+    for elem in a { let b = elem; }
+    for i in 0..3 { a[i] += 0x1; }
+    if a[0] == 1 { } else if a[1] == 2 { } else { }
+    constrain a[2] == 3;
+    let t = (a[0],);
+}
+";
+        let expected = "\
+fn main(mut a: [u8; 3]) {
+    for elem in a {
+        let b = elem;
+    }
+    for i in 0..3 {
+        a[i] += 0x1;
+    }
+    if a[0] == 1 {} else if a[1] == 2 {} else {}
+    assert(a[2] == 3);
+    let t = (a[0],);
+}
+";
+        assert_format(source, expected);
+    }
+
+    #[test]
+    fn keeps_required_parentheses() {
+        let source = "fn main(a: i32, b: i32) -> i32 { (a - (b - 1)) * -(a + b) - (-a) as i32 }";
+        let expected = "\
+fn main(a: i32, b: i32) -> i32 {
+    (a - (b - 1)) * -(a + b) - (-a) as i32
+}
+";
+        assert_format(source, expected);
+    }
+
+    #[test]
+    fn formats_structs_and_impls() {
+        let source = "\
+use dep::std::{hash, println as print};
+struct Point { x: Field,
+  // The second coordinate
+  y: Field }
+impl Point {
+    fn new(x: Field, y: Field) -> Self { Point { x: x, y } }
+    fn sum(self) -> Field { let Point { x, y: other } = self; x + other }
+}
+";
+        let expected = "\
+use dep::std::{hash, println as print};
+struct Point {
+    x: Field,
+    // The second coordinate
+    y: Field,
+}
+impl Point {
+    fn new(x: Field, y: Field) -> Self {
+        Point { x, y }
+    }
+    fn sum(self) -> Field {
+        let Point { x, y: other } = self;
+        x + other
+    }
+}
+";
+        assert_format(source, expected);
+    }
+
+    #[test]
+    fn formats_compile_time_expressions() {
+        let source = "\
+global DEPTH: u32 = 3;
+global SIZE: u32=1<<DEPTH;
+fn main(x: [Field; tree_size( DEPTH )], y: [Field; 2 * DEPTH]) {}
+";
+        let expected = "\
+global DEPTH: u32 = 3;
+global SIZE: u32 = 1 << DEPTH;
+fn main(x: [Field; tree_size(DEPTH)], y: [Field; 2 * DEPTH]) {}
+";
+        assert_format(source, expected);
+    }
+
+    #[test]
+    fn formats_loops() {
+        let source = "\
+fn main(x: u32) {
+    let mut i = 0;
+    while i<x max 2*N { i += 1; if i == 3 { continue } for j in 0..i { if j == 2 { break } } }
+}
+";
+        let expected = "\
+fn main(x: u32) {
+    let mut i = 0;
+    while i < x max 2 * N {
+        i += 1;
+        if i == 3 {
+            continue;
+        }
+        for j in 0..i {
+            if j == 2 {
+                break;
+            }
+        }
+    }
+}
+";
+        assert_format(source, expected);
+    }
+
+    #[test]
+    fn breaks_long_lists() {
+        let source = "fn main() { foo(aaaaaaaaaaaaaaaaaaaa, bbbbbbbbbbbbbbbbbbbb, cccccccccccccccccccc, dddddddddddddddddddd, eeeeeeeeee); }";
+        let expected = "\
+fn main() {
+    foo(
+        aaaaaaaaaaaaaaaaaaaa,
+        bbbbbbbbbbbbbbbbbbbb,
+        cccccccccccccccccccc,
+        dddddddddddddddddddd,
+        eeeeeeeeee,
+    );
+}
+";
+        assert_format(source, expected);
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        let config = Config::default();
+        assert!(format("fn main( {", &config).is_err());
+    }
+}
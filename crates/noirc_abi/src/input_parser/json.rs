@@ -1,7 +1,7 @@
 use super::{parse_str_to_field, InputValue};
 use crate::{errors::InputParserError, Abi, AbiType, MAIN_RETURN_NAME};
 use acvm::FieldElement;
-use iter_extended::{try_btree_map, try_vecmap, vecmap};
+use iter_extended::{try_btree_map, try_vecmap};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
@@ -71,12 +71,8 @@ enum JsonTypes {
     Integer(u64),
     // Simple boolean flag
     Bool(bool),
-    // Array of regular integers
-    ArrayNum(Vec<u64>),
-    // Array of hexadecimal integers
-    ArrayString(Vec<String>),
-    // Array of booleans
-    ArrayBool(Vec<bool>),
+    // Array of JsonTypes, for both arrays and tuples
+    Array(Vec<JsonTypes>),
     // Struct of JsonTypes
     Table(BTreeMap<String, JsonTypes>),
 }
@@ -93,17 +89,18 @@ impl JsonTypes {
             }
             (InputValue::Field(f), AbiType::Boolean) => JsonTypes::Bool(f.is_one()),
 
-            (InputValue::Vec(v), AbiType::Array { typ, .. }) => match typ.as_ref() {
-                AbiType::Field | AbiType::Integer { .. } => {
-                    let array = v.iter().map(|i| format!("0x{}", i.to_hex())).collect();
-                    JsonTypes::ArrayString(array)
-                }
-                AbiType::Boolean => {
-                    let array = v.iter().map(|i| i.is_one()).collect();
-                    JsonTypes::ArrayBool(array)
-                }
-                _ => return Err(InputParserError::AbiTypeMismatch(abi_type.clone())),
-            },
+            (InputValue::Vec(vector), AbiType::Array { typ, .. }) => {
+                let array =
+                    try_vecmap(vector, |value| JsonTypes::try_from_input_value(value, typ))?;
+                JsonTypes::Array(array)
+            }
+
+            (InputValue::Vec(vector), AbiType::Tuple { fields }) => {
+                let array = try_vecmap(vector.iter().zip(fields), |(value, typ)| {
+                    JsonTypes::try_from_input_value(value, typ)
+                })?;
+                JsonTypes::Array(array)
+            }
 
             (InputValue::String(s), AbiType::String { .. }) => JsonTypes::String(s.to_string()),
 
@@ -145,33 +142,25 @@ impl InputValue {
 
             (JsonTypes::Bool(boolean), AbiType::Boolean) => InputValue::Field(boolean.into()),
 
-            (JsonTypes::ArrayNum(arr_num), AbiType::Array { typ, .. })
-                if matches!(
-                    typ.as_ref(),
-                    AbiType::Field | AbiType::Integer { .. } | AbiType::Boolean
-                ) =>
-            {
-                let array_elements =
-                    vecmap(arr_num, |elem_num| FieldElement::from(i128::from(elem_num)));
+            (JsonTypes::Array(array), AbiType::Array { typ, .. }) => {
+                let array_elements = try_vecmap(array.into_iter().enumerate(), |(i, value)| {
+                    InputValue::try_from_json(value, typ, &format!("{arg_name}[{i}]"))
+                })?;
 
                 InputValue::Vec(array_elements)
             }
-            (JsonTypes::ArrayString(arr_str), AbiType::Array { typ, .. })
-                if matches!(
-                    typ.as_ref(),
-                    AbiType::Field | AbiType::Integer { .. } | AbiType::Boolean
-                ) =>
-            {
-                let array_elements = try_vecmap(arr_str, |elem_str| parse_str_to_field(&elem_str))?;
 
-                InputValue::Vec(array_elements)
-            }
-            (JsonTypes::ArrayBool(arr_bool), AbiType::Array { typ, .. })
-                if matches!(typ.as_ref(), AbiType::Boolean) =>
-            {
-                let array_elements = vecmap(arr_bool, FieldElement::from);
+            (JsonTypes::Array(array), AbiType::Tuple { fields }) => {
+                if array.len() != fields.len() {
+                    return Err(InputParserError::AbiTypeMismatch(param_type.clone()));
+                }
 
-                InputValue::Vec(array_elements)
+                let tuple_elements =
+                    try_vecmap(array.into_iter().zip(fields).enumerate(), |(i, (value, typ))| {
+                        InputValue::try_from_json(value, typ, &format!("{arg_name}.{i}"))
+                    })?;
+
+                InputValue::Vec(tuple_elements)
             }
 
             (JsonTypes::Table(table), AbiType::Struct { fields }) => {
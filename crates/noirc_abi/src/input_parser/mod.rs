@@ -14,7 +14,8 @@ use crate::{Abi, AbiType};
 #[derive(Debug, Clone, Serialize, PartialEq)]
 pub enum InputValue {
     Field(FieldElement),
-    Vec(Vec<FieldElement>),
+    /// The elements of an array, or the fields of a tuple
+    Vec(Vec<InputValue>),
     String(String),
     Struct(BTreeMap<String, InputValue>),
 }
@@ -32,14 +33,12 @@ impl InputValue {
                 field_element.is_one() || field_element.is_zero()
             }
 
-            (InputValue::Vec(field_elements), AbiType::Array { length, typ, .. }) => {
-                if field_elements.len() != *length as usize {
+            (InputValue::Vec(array_elements), AbiType::Array { length, typ, .. }) => {
+                if array_elements.len() != *length as usize {
                     return false;
                 }
                 // Check that all of the array's elements' values match the ABI as well.
-                field_elements
-                    .iter()
-                    .all(|field_element| Self::Field(*field_element).matches_abi(typ))
+                array_elements.iter().all(|element| element.matches_abi(typ))
             }
 
             (InputValue::String(string), AbiType::String { length }) => {
@@ -63,6 +62,18 @@ impl InputValue {
                 })
             }
 
+            (InputValue::Vec(tuple_elements), AbiType::Tuple { fields }) => {
+                if tuple_elements.len() != fields.len() {
+                    return false;
+                }
+
+                // Check that all of the tuple's fields' values match the ABI as well.
+                tuple_elements
+                    .iter()
+                    .zip(fields)
+                    .all(|(element, field_type)| element.matches_abi(field_type))
+            }
+
             // All other InputValue-AbiType combinations are fundamentally incompatible.
             _ => false,
         }
@@ -151,6 +162,21 @@ mod serialization_tests {
                     },
                     visibility: AbiVisibility::Private,
                 },
+                AbiParameter {
+                    name: "baz".into(),
+                    typ: AbiType::Array {
+                        length: 2,
+                        typ: Box::new(AbiType::Tuple {
+                            fields: vec![
+                                AbiType::Array { length: 2, typ: Box::new(AbiType::Field) },
+                                AbiType::Struct {
+                                    fields: vec![("inner".into(), AbiType::Boolean)],
+                                },
+                            ],
+                        }),
+                    },
+                    visibility: AbiVisibility::Private,
+                },
             ],
             return_type: Some(AbiType::String { length: 5 }),
             // These two fields are unused when serializing/deserializing to file.
@@ -158,15 +184,36 @@ mod serialization_tests {
             return_witnesses: Vec::new(),
         };
 
+        // A `([Field; 2], { inner: bool })` tuple
+        let baz_element = |first: u128, inner: bool| {
+            InputValue::Vec(vec![
+                InputValue::Vec(vec![
+                    InputValue::Field(first.into()),
+                    InputValue::Field((first + 1).into()),
+                ]),
+                InputValue::Struct(BTreeMap::from([(
+                    "inner".into(),
+                    InputValue::Field(inner.into()),
+                )])),
+            ])
+        };
+
         let input_map: BTreeMap<String, InputValue> = BTreeMap::from([
             ("foo".into(), InputValue::Field(FieldElement::one())),
             (
                 "bar".into(),
                 InputValue::Struct(BTreeMap::from([
                     ("field1".into(), InputValue::Field(255u128.into())),
-                    ("field2".into(), InputValue::Vec(vec![true.into(), false.into()])),
+                    (
+                        "field2".into(),
+                        InputValue::Vec(vec![
+                            InputValue::Field(true.into()),
+                            InputValue::Field(false.into()),
+                        ]),
+                    ),
                 ])),
             ),
+            ("baz".into(), InputValue::Vec(vec![baz_element(1, true), baz_element(3, false)])),
             (MAIN_RETURN_NAME.into(), InputValue::String("hello".to_owned())),
         ]);
 
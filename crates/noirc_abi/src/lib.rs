@@ -64,6 +64,9 @@ pub enum AbiType {
     String {
         length: u64,
     },
+    Tuple {
+        fields: Vec<AbiType>,
+    },
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -123,6 +126,7 @@ impl AbiType {
             AbiType::Array { length, typ: _ } => *length as usize,
             AbiType::Struct { fields, .. } => fields.len(),
             AbiType::String { length } => *length as usize,
+            AbiType::Tuple { fields } => fields.len(),
         }
     }
 
@@ -135,6 +139,9 @@ impl AbiType {
                 fields.iter().fold(0, |acc, (_, field_type)| acc + field_type.field_count())
             }
             AbiType::String { length } => *length as u32,
+            AbiType::Tuple { fields } => {
+                fields.iter().fold(0, |acc, field_type| acc + field_type.field_count())
+            }
         }
     }
 }
@@ -305,7 +312,11 @@ impl Abi {
         match (value, abi_type) {
             (InputValue::Field(elem), _) => encoded_value.push(elem),
 
-            (InputValue::Vec(vec_elem), _) => encoded_value.extend(vec_elem),
+            (InputValue::Vec(vec_elements), AbiType::Array { typ, .. }) => {
+                for elem in vec_elements {
+                    encoded_value.extend(Self::encode_value(elem, typ)?);
+                }
+            }
 
             (InputValue::String(string), _) => {
                 let str_as_fields =
@@ -318,6 +329,12 @@ impl Abi {
                     encoded_value.extend(Self::encode_value(object[field].clone(), typ)?);
                 }
             }
+
+            (InputValue::Vec(vec_elements), AbiType::Tuple { fields }) => {
+                for (value, typ) in vec_elements.into_iter().zip(fields) {
+                    encoded_value.extend(Self::encode_value(value, typ)?);
+                }
+            }
             _ => unreachable!("value should have already been checked to match abi type"),
         }
         Ok(encoded_value)
@@ -384,11 +401,14 @@ impl Abi {
 
                 InputValue::Field(field_element)
             }
-            AbiType::Array { length, .. } => {
-                let field_elements: Vec<FieldElement> =
-                    field_iterator.take(*length as usize).collect();
+            AbiType::Array { length, typ } => {
+                let length = *length as usize;
+                let mut array_elements = Vec::with_capacity(length);
+                for _ in 0..length {
+                    array_elements.push(Self::decode_value(field_iterator, typ)?);
+                }
 
-                InputValue::Vec(field_elements)
+                InputValue::Vec(array_elements)
             }
             AbiType::String { length } => {
                 let field_elements: Vec<FieldElement> =
@@ -407,6 +427,14 @@ impl Abi {
 
                 InputValue::Struct(struct_map)
             }
+            AbiType::Tuple { fields } => {
+                let mut tuple_elements = Vec::with_capacity(fields.len());
+                for field_type in fields {
+                    tuple_elements.push(Self::decode_value(field_iterator, field_type)?);
+                }
+
+                InputValue::Vec(tuple_elements)
+            }
         };
 
         Ok(value)
@@ -459,7 +487,13 @@ mod test {
 
         // Note we omit return value from inputs
         let inputs: InputMap = BTreeMap::from([
-            ("thing1".to_string(), InputValue::Vec(vec![FieldElement::one(), FieldElement::one()])),
+            (
+                "thing1".to_string(),
+                InputValue::Vec(vec![
+                    InputValue::Field(FieldElement::one()),
+                    InputValue::Field(FieldElement::one()),
+                ]),
+            ),
             ("thing2".to_string(), InputValue::Field(FieldElement::zero())),
         ]);
 
@@ -473,4 +507,73 @@ mod test {
         // We also decode the return value (we can do this immediately as we know it shares a witness with an input).
         assert_eq!(return_value.unwrap(), reconstructed_inputs["thing2"]);
     }
+
+    #[test]
+    fn nested_witness_encoding_roundtrip() {
+        let point = AbiType::Struct {
+            fields: vec![("x".into(), AbiType::Field), ("y".into(), AbiType::Field)],
+        };
+        let abi = Abi {
+            parameters: vec![
+                AbiParameter {
+                    name: "points".to_string(),
+                    typ: AbiType::Array { length: 2, typ: Box::new(point) },
+                    visibility: AbiVisibility::Private,
+                },
+                AbiParameter {
+                    name: "pair".to_string(),
+                    typ: AbiType::Tuple {
+                        fields: vec![
+                            AbiType::Field,
+                            AbiType::Array {
+                                length: 2,
+                                typ: Box::new(AbiType::Array {
+                                    length: 2,
+                                    typ: Box::new(AbiType::Field),
+                                }),
+                            },
+                        ],
+                    },
+                    visibility: AbiVisibility::Private,
+                },
+            ],
+            param_witnesses: BTreeMap::from([
+                ("points".to_string(), (1..5).map(Witness).collect()),
+                ("pair".to_string(), (5..10).map(Witness).collect()),
+            ]),
+            return_type: None,
+            return_witnesses: Vec::new(),
+        };
+
+        let field = |value: u128| InputValue::Field(value.into());
+        let point = |x, y| {
+            InputValue::Struct(BTreeMap::from([
+                ("x".to_string(), field(x)),
+                ("y".to_string(), field(y)),
+            ]))
+        };
+        let inputs: InputMap = BTreeMap::from([
+            ("points".to_string(), InputValue::Vec(vec![point(1, 2), point(3, 4)])),
+            (
+                "pair".to_string(),
+                InputValue::Vec(vec![
+                    field(5),
+                    InputValue::Vec(vec![
+                        InputValue::Vec(vec![field(6), field(7)]),
+                        InputValue::Vec(vec![field(8), field(9)]),
+                    ]),
+                ]),
+            ),
+        ]);
+
+        let witness_map = abi.encode(&inputs, None).unwrap();
+
+        // Each value is written to the witness with the same index, as values are encoded in order.
+        for index in 1..10 {
+            assert_eq!(witness_map.get(&Witness(index)), Some(&FieldElement::from(index as u128)));
+        }
+
+        let (reconstructed_inputs, _) = abi.decode(&witness_map).unwrap();
+        assert_eq!(reconstructed_inputs, inputs);
+    }
 }
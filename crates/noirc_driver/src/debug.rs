@@ -0,0 +1,73 @@
+use acvm::{
+    acir::circuit::{
+        directives::{Directive, LogInfo},
+        Circuit, Opcode,
+    },
+    compiler::CircuitSimplifier,
+    Language,
+};
+
+/// Prefix of the log directives marking the start of the opcodes generated from an original opcode.
+const OPCODE_MARKER_PREFIX: &str = "__noirc_opcode_marker_";
+
+/// Applies the backend optimizations to `circuit`, returning the optimized circuit along with,
+/// for each of its opcodes, the index of the opcode of `circuit` which it was generated from.
+///
+/// The optimizations rewrite each opcode in place, so a marker opcode is placed before each
+/// original opcode and the opcodes found after a marker are those generated from its opcode.
+/// The markers are log directives without any witnesses, which the optimizations leave untouched,
+/// and they are removed from the optimized circuit.
+///
+/// Returns `None` if the backend optimizations fail.
+pub(crate) fn compile_with_opcode_indices(
+    circuit: Circuit,
+    np_language: Language,
+    is_opcode_supported: &impl Fn(&Opcode) -> bool,
+    simplifier: &CircuitSimplifier,
+) -> Option<(Circuit, Vec<Option<usize>>)> {
+    let marked_opcodes = circuit
+        .opcodes
+        .iter()
+        .enumerate()
+        .flat_map(|(index, opcode)| [opcode_marker(index), opcode.clone()])
+        .collect();
+    let marked_circuit = Circuit { opcodes: marked_opcodes, ..circuit };
+
+    let is_opcode_supported =
+        |opcode: &Opcode| marker_index(opcode).is_some() || is_opcode_supported(opcode);
+    let mut optimized_circuit =
+        acvm::compiler::compile(marked_circuit, np_language, is_opcode_supported, simplifier)
+            .ok()?;
+
+    let mut opcodes = Vec::with_capacity(optimized_circuit.opcodes.len());
+    let mut original_indices = Vec::with_capacity(optimized_circuit.opcodes.len());
+    let mut original_index = None;
+    for opcode in optimized_circuit.opcodes {
+        match marker_index(&opcode) {
+            Some(index) => original_index = Some(index),
+            None => {
+                opcodes.push(opcode);
+                original_indices.push(original_index);
+            }
+        }
+    }
+    optimized_circuit.opcodes = opcodes;
+
+    Some((optimized_circuit, original_indices))
+}
+
+fn opcode_marker(index: usize) -> Opcode {
+    Opcode::Directive(Directive::Log(LogInfo::FinalizedOutput(format!(
+        "{OPCODE_MARKER_PREFIX}{index}"
+    ))))
+}
+
+/// Returns the index of the original opcode if `opcode` is a marker placed by `opcode_marker`.
+fn marker_index(opcode: &Opcode) -> Option<usize> {
+    match opcode {
+        Opcode::Directive(Directive::Log(LogInfo::FinalizedOutput(output))) => {
+            output.strip_prefix(OPCODE_MARKER_PREFIX)?.parse().ok()
+        }
+        _ => None,
+    }
+}
@@ -3,29 +3,53 @@
 #![warn(unreachable_pub)]
 #![warn(clippy::semicolon_if_nothing_returned)]
 
-use acvm::acir::circuit::Opcode;
+use acvm::acir::circuit::{Circuit, Opcode};
 use acvm::compiler::CircuitSimplifier;
 use acvm::Language;
 use clap::Args;
 use fm::{FileId, FileManager, FileType};
-use noirc_abi::FunctionSignature;
-use noirc_errors::{CustomDiagnostic, FileDiagnostic};
-use noirc_evaluator::{create_circuit, ssa_refactor::experimental_create_circuit};
+use noirc_abi::{Abi, FunctionSignature};
+use noirc_errors::{CustomDiagnostic, DebugInfo, FileDiagnostic};
+use noirc_evaluator::errors::RuntimeError;
+use noirc_evaluator::{
+    create_circuit,
+    ssa_refactor::{experimental_create_circuit, SsaOptions},
+};
 use noirc_frontend::graph::{CrateId, CrateName, CrateType, LOCAL_CRATE};
 use noirc_frontend::hir::def_map::{Contract, CrateDefMap};
 use noirc_frontend::hir::Context;
 use noirc_frontend::hir_def::function::FuncMeta;
 use noirc_frontend::monomorphization::monomorphize;
-use noirc_frontend::node_interner::FuncId;
+use noirc_frontend::node_interner::{FuncId, NodeInterner};
+use noirc_frontend::token::TestScope;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
 mod contract;
+mod debug;
 mod program;
 
 pub use contract::{CompiledContract, ContractFunction, ContractFunctionType};
 pub use program::CompiledProgram;
 
+/// Describes which function names are selected by a filter such as `nargo test`'s test name.
+#[derive(Debug, Clone, Copy)]
+pub enum FunctionNameMatch<'a> {
+    Anything,
+    Exact(&'a str),
+    Contains(&'a str),
+}
+
+impl FunctionNameMatch<'_> {
+    pub fn matches(&self, function_name: &str) -> bool {
+        match self {
+            FunctionNameMatch::Anything => true,
+            FunctionNameMatch::Exact(pattern) => function_name == *pattern,
+            FunctionNameMatch::Contains(pattern) => function_name.contains(pattern),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct Driver {
     context: Context,
@@ -52,6 +76,30 @@ pub struct CompileOptions {
     /// Compile and optimize using the new experimental SSA pass
     #[arg(long)]
     pub experimental_ssa: bool,
+
+    /// Check that the SSA is well formed after each pass of the experimental SSA pipeline.
+    /// This is always done in debug builds.
+    #[arg(long)]
+    #[serde(default)]
+    pub validate_ssa: bool,
+
+    /// Run these comma-separated passes in order instead of the default experimental SSA pipeline
+    #[arg(long, value_delimiter = ',')]
+    #[serde(default)]
+    pub ssa_passes: Option<Vec<String>>,
+
+    /// Skip these comma-separated passes of the experimental SSA pipeline.
+    /// The inline, unroll, flatten_cfg and mem2reg passes cannot be skipped.
+    #[arg(long, value_delimiter = ',')]
+    #[serde(default)]
+    pub skip_ssa_passes: Vec<String>,
+
+    /// Write the SSA after each pass of the experimental SSA pipeline, the final ACIR and the
+    /// number of blocks and instructions before and after each pass into this directory,
+    /// prefixing each file with the name of the compiled function
+    #[arg(long)]
+    #[serde(default)]
+    pub dump_ssa_dir: Option<PathBuf>,
 }
 
 impl Default for CompileOptions {
@@ -62,10 +110,36 @@ impl Default for CompileOptions {
             deny_warnings: false,
             show_output: true,
             experimental_ssa: false,
+            validate_ssa: false,
+            ssa_passes: None,
+            skip_ssa_passes: Vec::new(),
+            dump_ssa_dir: None,
+        }
+    }
+}
+
+impl CompileOptions {
+    /// Returns the options of the experimental SSA pipeline.
+    fn ssa_options(&self) -> SsaOptions {
+        SsaOptions {
+            print_ssa_passes: self.show_ssa,
+            validate_ssa: self.validate_ssa,
+            passes: self.ssa_passes.clone(),
+            skipped_passes: self.skip_ssa_passes.clone(),
+            dump_dir: self.dump_ssa_dir.clone(),
         }
     }
 }
 
+/// Converts an error of either SSA pipeline into a diagnostic. Some of these errors, such as an
+/// unknown SSA pass or a `break` unsupported by the old SSA, do not have a location in the program.
+fn ssa_error_diagnostic(error: RuntimeError) -> FileDiagnostic {
+    match error.location {
+        Some(_) => error.into(),
+        None => FileDiagnostic { file_id: FileId::dummy(), diagnostic: error.into() },
+    }
+}
+
 /// Helper type used to signify where only warnings are expected in file diagnostics
 pub type Warnings = Vec<FileDiagnostic>;
 
@@ -82,6 +156,22 @@ impl Driver {
         &self.context.file_manager
     }
 
+    /// Uses `source` as the contents of the file at `path` instead of reading it from disk.
+    /// This must be called before the file is added to the crate graph.
+    pub fn add_file_overlay(&mut self, path: &Path, source: String) {
+        self.context.file_manager.add_overlay(path, source);
+    }
+
+    /// Returns the interner holding the definitions, types and locations found while checking the crate.
+    pub fn def_interner(&self) -> &NodeInterner {
+        &self.context.def_interner
+    }
+
+    /// Returns the FileId of the local crate's root file.
+    pub fn root_file_id(&self) -> FileId {
+        self.context.crate_graph[LOCAL_CRATE].root_file_id
+    }
+
     // This is here for backwards compatibility
     // with the restricted version which only uses one file
     pub fn compile_file(
@@ -96,9 +186,9 @@ impl Driver {
 
     /// Adds the File with the local crate root to the file system
     /// and adds the local crate to the graph
-    /// XXX: This may pose a problem with workspaces, where you can change the local crate and where
-    /// we have multiple binaries in one workspace
-    /// A Fix would be for the driver instance to store the local crate id.
+    ///
+    /// Each member of a workspace is compiled using its own driver
+    /// so there is only ever a single local crate per driver.
     // Granted that this is the only place which relies on the local crate being first
     pub fn create_local_crate<P: AsRef<Path>>(
         &mut self,
@@ -366,35 +456,62 @@ impl Driver {
     ) -> Result<CompiledProgram, FileDiagnostic> {
         let program = monomorphize(main_function, &self.context.def_interner);
 
-        let (circuit, abi) = if options.experimental_ssa {
-            experimental_create_circuit(program, options.show_ssa, options.show_output)?
+        let (circuit, mut debug, abi) = if options.experimental_ssa {
+            experimental_create_circuit(program, options.show_output, &options.ssa_options())
+                .map_err(ssa_error_diagnostic)?
         } else {
-            create_circuit(program, options.show_ssa, options.show_output)?
+            // Only the experimental SSA pass tracks the source locations of the opcodes
+            let (circuit, abi) = create_circuit(program, options.show_ssa, options.show_output)
+                .map_err(ssa_error_diagnostic)?;
+            (circuit, DebugInfo::default(), abi)
         };
 
         let abi_len = abi.field_count();
 
         let simplifier = CircuitSimplifier::new(abi_len);
-        let optimized_circuit =
-            acvm::compiler::compile(circuit, np_language, is_opcode_supported, &simplifier)
-                .map_err(|_| FileDiagnostic {
-                    file_id: FileId::dummy(),
-                    diagnostic: CustomDiagnostic::from_message("produced an acvm compile error"),
-                })?;
-
-        Ok(CompiledProgram { circuit: optimized_circuit, abi })
+        let (optimized_circuit, original_indices) = debug::compile_with_opcode_indices(
+            circuit,
+            np_language,
+            is_opcode_supported,
+            &simplifier,
+        )
+        .ok_or_else(|| FileDiagnostic {
+            file_id: FileId::dummy(),
+            diagnostic: CustomDiagnostic::from_message("produced an acvm compile error"),
+        })?;
+        debug.update_acir(&original_indices);
+
+        Ok(CompiledProgram { circuit: optimized_circuit, abi, debug })
+    }
+
+    /// Compile the given function into a circuit without the backend specific optimizations
+    /// applied by `compile_no_check`, alongside the source locations of each of its opcodes.
+    /// Assumes self.check_crate is called beforehand!
+    ///
+    /// This always uses the experimental SSA pass, as it is the only one which tracks the
+    /// source locations of the opcodes it generates.
+    pub fn compile_debug_no_check(
+        &self,
+        options: &CompileOptions,
+        main_function: FuncId,
+    ) -> Result<(Circuit, DebugInfo, Abi), FileDiagnostic> {
+        let program = monomorphize(main_function, &self.context.def_interner);
+        experimental_create_circuit(program, options.show_output, &options.ssa_options())
+            .map_err(ssa_error_diagnostic)
     }
 
     /// Returns a list of all functions in the current crate marked with #[test]
-    /// whose names contain the given pattern string. An empty pattern string
-    /// will return all functions marked with #[test].
-    pub fn get_all_test_functions_in_crate_matching(&self, pattern: &str) -> Vec<FuncId> {
+    /// whose names match the given pattern, along with their expected outcome.
+    pub fn get_all_test_functions_in_crate_matching(
+        &self,
+        pattern: FunctionNameMatch,
+    ) -> Vec<(FuncId, TestScope)> {
         let interner = &self.context.def_interner;
         self.context
             .def_map(LOCAL_CRATE)
             .expect("The local crate should be analyzed already")
             .get_all_test_functions(interner)
-            .filter_map(|id| interner.function_name(&id).contains(pattern).then_some(id))
+            .filter(|(id, _)| pattern.matches(interner.function_name(id)))
             .collect()
     }
 
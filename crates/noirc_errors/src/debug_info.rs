@@ -0,0 +1,66 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Location;
+
+/// Maps the opcodes of a compiled circuit back to the source code they were generated from.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebugInfo {
+    /// The source locations of each opcode, keyed by the opcode's index within the circuit.
+    ///
+    /// Each entry is a call stack, starting with the call site in `main` and ending with the
+    /// expression the opcode was generated from. Opcodes with no known location are absent.
+    pub locations: BTreeMap<usize, Vec<Location>>,
+    /// The names of the functions linked into each brillig opcode, keyed by the opcode's index
+    /// within the circuit and then by the position of the function's first opcode within the
+    /// brillig bytecode.
+    #[serde(default)]
+    pub brillig_functions: BTreeMap<usize, BTreeMap<usize, String>>,
+}
+
+impl DebugInfo {
+    pub fn new(
+        locations: BTreeMap<usize, Vec<Location>>,
+        brillig_functions: BTreeMap<usize, BTreeMap<usize, String>>,
+    ) -> Self {
+        DebugInfo { locations, brillig_functions }
+    }
+
+    /// Updates the opcode indices after the circuit has been transformed.
+    ///
+    /// `original_indices` holds, for each opcode of the transformed circuit, the index of the
+    /// opcode it was generated from in the original circuit, if known.
+    pub fn update_acir(&mut self, original_indices: &[Option<usize>]) {
+        let locations = original_indices
+            .iter()
+            .enumerate()
+            .filter_map(|(index, original_index)| {
+                let call_stack = self.locations.get(original_index.as_ref()?)?;
+                Some((index, call_stack.clone()))
+            })
+            .collect();
+        self.locations = locations;
+
+        let brillig_functions = original_indices
+            .iter()
+            .enumerate()
+            .filter_map(|(index, original_index)| {
+                let function_names = self.brillig_functions.get(original_index.as_ref()?)?;
+                Some((index, function_names.clone()))
+            })
+            .collect();
+        self.brillig_functions = brillig_functions;
+    }
+
+    /// Returns the call stack of source locations the given opcode was generated from.
+    pub fn opcode_location(&self, opcode_index: usize) -> Option<&[Location]> {
+        self.locations.get(&opcode_index).map(Vec::as_slice)
+    }
+
+    /// Returns the names of the functions linked into the given brillig opcode, keyed by the
+    /// position of their first opcode within its bytecode.
+    pub fn brillig_function_names(&self, opcode_index: usize) -> Option<&BTreeMap<usize, String>> {
+        self.brillig_functions.get(&opcode_index)
+    }
+}
@@ -3,8 +3,10 @@
 #![warn(unreachable_pub)]
 #![warn(clippy::semicolon_if_nothing_returned)]
 
+pub mod debug_info;
 mod position;
 pub mod reporter;
+pub use debug_info::DebugInfo;
 pub use position::{Location, Position, Span, Spanned};
 pub use reporter::{CustomDiagnostic, DiagnosticKind};
 
@@ -1,5 +1,6 @@
 use codespan::Span as ByteSpan;
 use fm::FileId;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
     hash::{Hash, Hasher},
     ops::Range,
@@ -86,6 +87,24 @@ impl Span {
     pub fn end(&self) -> u32 {
         self.0.end().into()
     }
+
+    /// Returns true if `other` lies entirely within this span.
+    pub fn contains(&self, other: &Span) -> bool {
+        self.start() <= other.start() && other.end() <= self.end()
+    }
+}
+
+/// Spans are serialized as the range of positions they cover.
+impl Serialize for Span {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.start()..self.end()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Span {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Range::<u32>::deserialize(deserializer).map(Span::new)
+    }
 }
 
 impl From<Span> for Range<usize> {
@@ -114,7 +133,7 @@ impl chumsky::Span for Span {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Location {
     pub span: Span,
     pub file: FileId,
@@ -124,4 +143,9 @@ impl Location {
     pub fn new(span: Span, file: FileId) -> Self {
         Self { span, file }
     }
+
+    /// Returns true if `other` lies entirely within this location.
+    pub fn contains(&self, other: &Location) -> bool {
+        self.file == other.file && self.span.contains(&other.span)
+    }
 }
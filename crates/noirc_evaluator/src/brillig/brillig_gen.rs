@@ -15,13 +15,16 @@ use super::brillig_ir::{artifact::BrilligArtifact, BrilligContext};
 /// TODO: Change this to use `dfg.basic_blocks_iter` which will return an
 /// TODO iterator of all of the basic blocks.
 /// TODO(Jake): what order is this ^
-pub(crate) fn convert_ssa_function(func: &Function) -> BrilligArtifact {
+pub(crate) fn convert_ssa_function(func: &Function, allow_log_ops: bool) -> BrilligArtifact {
     let mut reverse_post_order = Vec::new();
     reverse_post_order.extend_from_slice(PostOrder::with_function(func).as_slice());
     reverse_post_order.reverse();
 
-    let mut function_context =
-        FunctionContext { function_id: func.id(), ssa_value_to_register: HashMap::new() };
+    let mut function_context = FunctionContext {
+        function_id: func.id(),
+        ssa_value_to_register: HashMap::new(),
+        allow_log_ops,
+    };
 
     let mut brillig_context = BrilligContext::new(
         FunctionContext::parameters(func),
@@ -33,5 +36,7 @@ pub(crate) fn convert_ssa_function(func: &Function) -> BrilligArtifact {
         BrilligBlock::compile(&mut function_context, &mut brillig_context, block, &func.dfg);
     }
 
-    brillig_context.artifact()
+    let mut artifact = brillig_context.artifact();
+    artifact.optimize();
+    artifact
 }
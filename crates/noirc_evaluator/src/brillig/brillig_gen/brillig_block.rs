@@ -6,11 +6,14 @@ use crate::ssa_refactor::ir::types::CompositeType;
 use crate::ssa_refactor::ir::{
     basic_block::{BasicBlock, BasicBlockId},
     dfg::DataFlowGraph,
-    instruction::{Binary, BinaryOp, Instruction, InstructionId, TerminatorInstruction},
+    instruction::{
+        Binary, BinaryOp, Endian, Instruction, InstructionId, Intrinsic, TerminatorInstruction,
+    },
     types::{NumericType, Type},
     value::{Value, ValueId},
 };
 use acvm::acir::brillig_vm::{BinaryFieldOp, BinaryIntOp, RegisterIndex, RegisterOrMemory};
+use acvm::acir::BlackBoxFunc;
 use acvm::FieldElement;
 use iter_extended::vecmap;
 
@@ -138,11 +141,15 @@ impl<'block> BrilligBlock<'block> {
                 // Simple parameters and arrays are passed as already filled registers
                 // In the case of arrays, the values should already be in memory and the register should
                 // Be a valid pointer to the array.
-                Type::Numeric(_) | Type::Array(..) => {
+                // References are pointers to the memory slot holding the referenced value.
+                // Slices are pointers to their length, followed by their items.
+                Type::Numeric(_) | Type::Array(..) | Type::Slice(_) | Type::Reference => {
                     self.function_context.get_or_create_register(self.brillig_context, *param_id);
                 }
-                _ => {
-                    todo!("ICE: Param type not supported")
+                Type::Function => {
+                    unreachable!(
+                        "ICE: Function values are rejected by check_for_unsupported_brillig"
+                    )
                 }
             }
         }
@@ -164,26 +171,32 @@ impl<'block> BrilligBlock<'block> {
                 let condition = self.convert_ssa_value(*value, dfg);
                 self.brillig_context.constrain_instruction(condition);
             }
+            // References point to a single memory slot, which holds the referenced value.
+            // Arrays are immutable, so a reference to an array holds a pointer to it.
             Instruction::Allocate => {
-                let value: crate::ssa_refactor::ir::map::Id<Value> =
-                    dfg.instruction_results(instruction_id)[0];
-                self.function_context.get_or_create_register(self.brillig_context, value);
+                let address_register = self.function_context.get_or_create_register(
+                    self.brillig_context,
+                    dfg.instruction_results(instruction_id)[0],
+                );
+                self.brillig_context.allocate_fixed_length_array(address_register, 1);
             }
             Instruction::Store { address, value } => {
-                let target_register = self.convert_ssa_value(*address, dfg);
+                let address_register = self.convert_ssa_value(*address, dfg);
                 let source_register = self.convert_ssa_value(*value, dfg);
 
-                self.brillig_context.mov_instruction(target_register, source_register);
+                self.brillig_context.store_instruction(address_register, source_register);
             }
             Instruction::Load { address } => {
                 let target_register = self.function_context.get_or_create_register(
                     self.brillig_context,
                     dfg.instruction_results(instruction_id)[0],
                 );
-                let source_register = self.convert_ssa_value(*address, dfg);
+                let address_register = self.convert_ssa_value(*address, dfg);
 
-                self.brillig_context.mov_instruction(target_register, source_register);
+                self.brillig_context.load_instruction(target_register, address_register);
             }
+            // Brillig functions are not flattened, so there are no side effects to disable
+            Instruction::EnableSideEffects { .. } => (),
             Instruction::Not(value) => {
                 let condition = self.convert_ssa_value(*value, dfg);
                 let result_ids = dfg.instruction_results(instruction_id);
@@ -201,6 +214,7 @@ impl<'block> BrilligBlock<'block> {
                         self.convert_ssa_value_to_register_value_or_array(*value_id, dfg)
                     });
                     let output_registers = vecmap(result_ids, |value_id| {
+                        self.allocate_result(*value_id, dfg);
                         self.convert_ssa_value_to_register_value_or_array(*value_id, dfg)
                     });
 
@@ -209,6 +223,9 @@ impl<'block> BrilligBlock<'block> {
                         &input_registers,
                         &output_registers,
                     );
+                    for value in input_registers.into_iter().chain(output_registers) {
+                        self.deallocate_slice_registers(value);
+                    }
                 }
                 Value::Function(func_id) => {
                     let function_arguments: Vec<RegisterIndex> =
@@ -237,8 +254,14 @@ impl<'block> BrilligBlock<'block> {
                     self.brillig_context
                         .post_call_prep_returns_load_registers(&result_registers, &saved_registers);
                 }
+                Value::Intrinsic(intrinsic) => {
+                    let result_ids = dfg.instruction_results(instruction_id);
+                    self.convert_ssa_intrinsic_call(*intrinsic, arguments, result_ids, dfg);
+                }
                 _ => {
-                    unreachable!("only foreign function calls supported in unconstrained functions")
+                    unreachable!(
+                        "ICE: Only functions, foreign functions and intrinsics can be called"
+                    )
                 }
             },
             Instruction::Truncate { value, .. } => {
@@ -264,19 +287,35 @@ impl<'block> BrilligBlock<'block> {
                     .get_or_create_register(self.brillig_context, result_ids[0]);
                 let array_register = self.convert_ssa_value(*array, dfg);
                 let index_register = self.convert_ssa_value(*index, dfg);
-                self.brillig_context.array_get(array_register, index_register, destination);
+                let offset_register =
+                    self.convert_array_index(&dfg.type_of_value(*array), index_register);
+
+                match dfg.type_of_value(result_ids[0]) {
+                    // Nested arrays are stored inline, so the result points into the array
+                    Type::Array(..) => self.brillig_context.binary_instruction(
+                        array_register,
+                        offset_register,
+                        destination,
+                        BrilligBinaryOp::Integer {
+                            op: BinaryIntOp::Add,
+                            bit_size: BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+                        },
+                    ),
+                    _ => {
+                        self.brillig_context.array_get(array_register, offset_register, destination)
+                    }
+                }
+                self.brillig_context.deallocate_register(offset_register);
             }
             // Array set operation in SSA returns a new array that is a copy of the parameter array
             // With a specific value changed.
             Instruction::ArraySet { array, index, value } => {
                 let result_ids = dfg.instruction_results(instruction_id);
-                let destination = self
-                    .function_context
-                    .get_or_create_register(self.brillig_context, result_ids[0]);
+                let destination = self.allocate_result(result_ids[0], dfg);
 
                 // First issue a array copy to the destination
-                let array_size = compute_size_of_type(&dfg.type_of_value(*array));
-                self.brillig_context.allocate_fixed_length_array(destination, array_size);
+                let array_type = dfg.type_of_value(*array);
+                let array_size = compute_size_of_type(&array_type);
                 let source_array_register: RegisterIndex = self.convert_ssa_value(*array, dfg);
                 let size_register = self.brillig_context.make_constant(array_size.into());
                 self.brillig_context.copy_array_instruction(
@@ -284,16 +323,532 @@ impl<'block> BrilligBlock<'block> {
                     destination,
                     size_register,
                 );
+                self.brillig_context.deallocate_register(size_register);
 
                 // Then set the value in the newly created array
                 let index_register = self.convert_ssa_value(*index, dfg);
+                let offset_register = self.convert_array_index(&array_type, index_register);
                 let value_register = self.convert_ssa_value(*value, dfg);
-                self.brillig_context.array_set(destination, index_register, value_register);
+                match dfg.type_of_value(*value) {
+                    // Nested arrays are stored inline, so their elements are copied into place
+                    value_type @ Type::Array(..) => {
+                        let element_address = self.brillig_context.allocate_register();
+                        self.brillig_context.binary_instruction(
+                            destination,
+                            offset_register,
+                            element_address,
+                            BrilligBinaryOp::Integer {
+                                op: BinaryIntOp::Add,
+                                bit_size: BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+                            },
+                        );
+                        let value_size_register = self
+                            .brillig_context
+                            .make_constant(compute_size_of_type(&value_type).into());
+                        self.brillig_context.copy_array_instruction(
+                            value_register,
+                            element_address,
+                            value_size_register,
+                        );
+                        self.brillig_context.deallocate_register(value_size_register);
+                        self.brillig_context.deallocate_register(element_address);
+                    }
+                    _ => {
+                        self.brillig_context.array_set(destination, offset_register, value_register)
+                    }
+                }
+                self.brillig_context.deallocate_register(offset_register);
             }
-            _ => todo!("ICE: Instruction not supported {instruction:?}"),
         };
     }
 
+    /// Gets the register holding the result of an instruction, allocating the memory it points
+    /// to if the result is an array.
+    fn allocate_result(&mut self, result_id: ValueId, dfg: &DataFlowGraph) -> RegisterIndex {
+        let register =
+            self.function_context.get_or_create_register(self.brillig_context, result_id);
+        let typ = dfg.type_of_value(result_id);
+        if let Type::Array(..) = typ {
+            self.brillig_context.allocate_fixed_length_array(register, compute_size_of_type(&typ));
+        }
+        register
+    }
+
+    /// Converts an SSA index into an array of the given type into the offset in memory of the
+    /// indexed element from the start of the array, held in a new register.
+    ///
+    /// SSA indices count each field of the items of an array separately, while the fields are
+    /// stored in memory one after the other with nested arrays stored inline, so a field may
+    /// take more than one memory slot.
+    fn convert_array_index(&mut self, array_type: &Type, index: RegisterIndex) -> RegisterIndex {
+        let element_types = match array_type {
+            Type::Array(element_types, _) => element_types,
+            _ => unreachable!("ICE: Expected an array type, found {array_type:?}"),
+        };
+        let field_sizes = vecmap(element_types.iter(), compute_size_of_type);
+        let offset = self.brillig_context.allocate_register();
+
+        // When all fields have the same size, the offset is simply a multiple of the index
+        if field_sizes.iter().all(|size| *size == field_sizes[0]) {
+            self.brillig_context.mov_instruction(offset, index);
+            if field_sizes[0] != 1 {
+                self.brillig_context.usize_op(offset, BinaryIntOp::Mul, field_sizes[0]);
+            }
+            return offset;
+        }
+
+        // Otherwise the index is split into the index of the item and of the field within it.
+        // The offset of the field within the item is the sum of the sizes of the fields before it,
+        // which is computed arithmetically so that no memory is needed.
+        let field_count = self.brillig_context.make_constant(field_sizes.len().into());
+        let field = self.brillig_context.allocate_register();
+        self.brillig_context.binary_instruction(
+            index,
+            field_count,
+            offset,
+            BrilligBinaryOp::Integer {
+                op: BinaryIntOp::UnsignedDiv,
+                bit_size: BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+            },
+        );
+        self.brillig_context.mov_instruction(field, offset);
+        self.brillig_context.usize_op(field, BinaryIntOp::Mul, field_sizes.len());
+        self.brillig_context.binary_instruction(
+            index,
+            field,
+            field,
+            BrilligBinaryOp::Integer {
+                op: BinaryIntOp::Sub,
+                bit_size: BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+            },
+        );
+        self.brillig_context.usize_op(offset, BinaryIntOp::Mul, field_sizes.iter().sum());
+
+        // The last field is never before another one
+        let is_before_field = self.brillig_context.allocate_register();
+        for (field_index, size) in field_sizes.iter().enumerate().take(field_sizes.len() - 1) {
+            let field_index = self.brillig_context.make_constant(field_index.into());
+            self.brillig_context.binary_instruction(
+                field_index,
+                field,
+                is_before_field,
+                BrilligBinaryOp::Integer {
+                    op: BinaryIntOp::LessThan,
+                    bit_size: BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+                },
+            );
+            self.brillig_context.deallocate_register(field_index);
+            self.brillig_context.usize_op(is_before_field, BinaryIntOp::Mul, *size);
+            self.brillig_context.binary_instruction(
+                offset,
+                is_before_field,
+                offset,
+                BrilligBinaryOp::Integer {
+                    op: BinaryIntOp::Add,
+                    bit_size: BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+                },
+            );
+        }
+
+        self.brillig_context.deallocate_register(is_before_field);
+        self.brillig_context.deallocate_register(field);
+        self.brillig_context.deallocate_register(field_count);
+        offset
+    }
+
+    /// Converts a call to an intrinsic function into the Brillig opcodes computing its results.
+    fn convert_ssa_intrinsic_call(
+        &mut self,
+        intrinsic: Intrinsic,
+        arguments: &[ValueId],
+        result_ids: &[ValueId],
+        dfg: &DataFlowGraph,
+    ) {
+        match intrinsic {
+            Intrinsic::Println => {
+                if !self.function_context.allow_log_ops {
+                    return;
+                }
+                // Printing is done by the executor of the program, through the same oracles
+                // as the ACIR generation uses.
+                let is_single_value = arguments.len() == 1
+                    && matches!(dfg.type_of_value(arguments[0]), Type::Numeric(_));
+                let function =
+                    if is_single_value { "oracle_print_impl" } else { "oracle_print_array_impl" };
+                let inputs = vecmap(arguments, |value_id| {
+                    self.convert_ssa_value_to_register_value_or_array(*value_id, dfg)
+                });
+                self.brillig_context.foreign_call_instruction(function.to_owned(), &inputs, &[]);
+                for value in inputs {
+                    self.deallocate_slice_registers(value);
+                }
+            }
+            Intrinsic::Sort => {
+                let (element_types, item_count) = match dfg.type_of_value(arguments[0]) {
+                    Type::Array(element_types, item_count) => (element_types, item_count),
+                    typ => unreachable!("ICE: Cannot sort a value of type {typ:?}"),
+                };
+                assert_eq!(element_types.len(), 1, "ICE: Can only sort arrays of numeric values");
+                let bit_size = Self::get_bit_size_from_ssa_type(element_types[0].clone());
+
+                let source = self.convert_ssa_value(arguments[0], dfg);
+                let destination = self.allocate_result(result_ids[0], dfg);
+                self.brillig_context.sort_instruction(source, destination, item_count, bit_size);
+            }
+            Intrinsic::ToBits(endian) => {
+                let radix = self.brillig_context.make_constant(2_u128.into());
+                self.convert_radix_decompose(
+                    endian,
+                    arguments[0],
+                    radix,
+                    arguments[1],
+                    result_ids[0],
+                    dfg,
+                );
+                self.brillig_context.deallocate_register(radix);
+            }
+            Intrinsic::ToRadix(endian) => {
+                let radix = self.convert_ssa_value(arguments[1], dfg);
+                self.convert_radix_decompose(
+                    endian,
+                    arguments[0],
+                    radix,
+                    arguments[2],
+                    result_ids[0],
+                    dfg,
+                );
+            }
+            Intrinsic::BlackBox(black_box) => {
+                self.convert_black_box_call(black_box, arguments, result_ids, dfg);
+            }
+            Intrinsic::SliceNew
+            | Intrinsic::SliceLen
+            | Intrinsic::SliceGet
+            | Intrinsic::SlicePush
+            | Intrinsic::SlicePop => {
+                self.convert_slice_intrinsic_call(intrinsic, arguments, result_ids, dfg);
+            }
+        }
+    }
+
+    /// Converts a call to one of the slice intrinsics into Brillig opcodes.
+    ///
+    /// A slice is a pointer to a memory slot holding its length, followed by its items. As in
+    /// arrays, the fields of each item are stored one after the other, with nested arrays stored
+    /// inline. Slices are immutable, so pushing to or popping from a slice copies it.
+    fn convert_slice_intrinsic_call(
+        &mut self,
+        intrinsic: Intrinsic,
+        arguments: &[ValueId],
+        result_ids: &[ValueId],
+        dfg: &DataFlowGraph,
+    ) {
+        if intrinsic == Intrinsic::SliceNew {
+            let slice =
+                self.function_context.get_or_create_register(self.brillig_context, result_ids[0]);
+            self.brillig_context.allocate_fixed_length_array(slice, 1);
+            self.brillig_context.store_constant_at(slice, 0, 0_u128.into());
+            return;
+        }
+
+        let item_size = match dfg.type_of_value(arguments[0]) {
+            Type::Slice(element_types) => compute_size_of_composite_type(&element_types),
+            typ => unreachable!("ICE: Expected a slice, found {typ:?}"),
+        };
+        let slice = self.convert_ssa_value(arguments[0], dfg);
+
+        match intrinsic {
+            Intrinsic::SliceLen => {
+                let length = self
+                    .function_context
+                    .get_or_create_register(self.brillig_context, result_ids[0]);
+                self.brillig_context.load_instruction(length, slice);
+            }
+            Intrinsic::SliceGet => {
+                let index = self.convert_ssa_value(arguments[1], dfg);
+                let length = self.brillig_context.allocate_register();
+                self.brillig_context.load_instruction(length, slice);
+                self.constrain_less_than(index, length);
+
+                let item = self.brillig_context.allocate_register();
+                self.brillig_context.mov_instruction(item, index);
+                self.brillig_context.usize_op(item, BinaryIntOp::Mul, item_size);
+                self.brillig_context.usize_op(item, BinaryIntOp::Add, 1);
+                self.add_to_pointer(item, slice);
+                self.load_item_fields(item, result_ids, dfg);
+
+                self.brillig_context.deallocate_register(item);
+                self.brillig_context.deallocate_register(length);
+            }
+            Intrinsic::SlicePush => {
+                let new_slice = self
+                    .function_context
+                    .get_or_create_register(self.brillig_context, result_ids[0]);
+                let length = self.brillig_context.allocate_register();
+                self.brillig_context.load_instruction(length, slice);
+
+                // Copy the length and the items of the slice, leaving room for the new item
+                let copy_size = self.slice_memory_size(length, item_size);
+                let new_size = self.brillig_context.allocate_register();
+                self.brillig_context.mov_instruction(new_size, copy_size);
+                self.brillig_context.usize_op(new_size, BinaryIntOp::Add, item_size);
+                self.brillig_context.allocate_array_instruction(new_slice, new_size);
+                self.brillig_context.copy_array_instruction(slice, new_slice, copy_size);
+                self.brillig_context.usize_op(length, BinaryIntOp::Add, 1);
+                self.brillig_context.store_instruction(new_slice, length);
+
+                // Then store the fields of the new item after them
+                let field_address = copy_size;
+                self.add_to_pointer(field_address, new_slice);
+                for field_id in &arguments[1..] {
+                    self.store_in_memory(field_address, *field_id, dfg);
+                    let field_size = compute_size_of_type(&dfg.type_of_value(*field_id));
+                    self.brillig_context.usize_op(field_address, BinaryIntOp::Add, field_size);
+                }
+
+                self.brillig_context.deallocate_register(new_size);
+                self.brillig_context.deallocate_register(copy_size);
+                self.brillig_context.deallocate_register(length);
+            }
+            Intrinsic::SlicePop => {
+                let new_slice = self
+                    .function_context
+                    .get_or_create_register(self.brillig_context, result_ids[0]);
+                let length = self.brillig_context.allocate_register();
+                self.brillig_context.load_instruction(length, slice);
+                let zero = self.brillig_context.make_constant(0_u128.into());
+                self.constrain_less_than(zero, length);
+                self.brillig_context.deallocate_register(zero);
+
+                // Copy the length and all the items of the slice but the last one
+                self.brillig_context.usize_op(length, BinaryIntOp::Sub, 1);
+                let copy_size = self.slice_memory_size(length, item_size);
+                self.brillig_context.allocate_array_instruction(new_slice, copy_size);
+                self.brillig_context.copy_array_instruction(slice, new_slice, copy_size);
+                self.brillig_context.store_instruction(new_slice, length);
+
+                // The popped item is left in place in the original slice
+                let item = copy_size;
+                self.add_to_pointer(item, slice);
+                self.load_item_fields(item, &result_ids[1..], dfg);
+
+                self.brillig_context.deallocate_register(copy_size);
+                self.brillig_context.deallocate_register(length);
+            }
+            _ => unreachable!("ICE: {intrinsic} is not a slice intrinsic"),
+        }
+    }
+
+    /// Computes the number of memory slots taken by a slice of `length` items of `item_size`
+    /// slots each, including the slot holding its length, into a new register.
+    fn slice_memory_size(&mut self, length: RegisterIndex, item_size: usize) -> RegisterIndex {
+        let size = self.brillig_context.allocate_register();
+        self.brillig_context.mov_instruction(size, length);
+        self.brillig_context.usize_op(size, BinaryIntOp::Mul, item_size);
+        self.brillig_context.usize_op(size, BinaryIntOp::Add, 1);
+        size
+    }
+
+    /// Deallocates the registers holding the items and size of a slice passed to a foreign call
+    /// by `convert_ssa_value_to_register_value_or_array`, once the call is made.
+    fn deallocate_slice_registers(&mut self, value: RegisterOrMemory) {
+        if let RegisterOrMemory::HeapVector(items, size) = value {
+            self.brillig_context.deallocate_register(items);
+            self.brillig_context.deallocate_register(size);
+        }
+    }
+
+    /// Adds `pointer` to the offset held in `offset`.
+    fn add_to_pointer(&mut self, offset: RegisterIndex, pointer: RegisterIndex) {
+        self.brillig_context.binary_instruction(
+            pointer,
+            offset,
+            offset,
+            BrilligBinaryOp::Integer {
+                op: BinaryIntOp::Add,
+                bit_size: BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+            },
+        );
+    }
+
+    /// Fails unless `lhs < rhs`, both being memory addresses or lengths.
+    fn constrain_less_than(&mut self, lhs: RegisterIndex, rhs: RegisterIndex) {
+        let condition = self.brillig_context.allocate_register();
+        self.brillig_context.binary_instruction(
+            lhs,
+            rhs,
+            condition,
+            BrilligBinaryOp::Integer {
+                op: BinaryIntOp::LessThan,
+                bit_size: BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+            },
+        );
+        self.brillig_context.constrain_instruction(condition);
+        self.brillig_context.deallocate_register(condition);
+    }
+
+    /// Loads the fields of the item stored in memory at `item` into the given results.
+    /// Nested arrays are stored inline, so the results holding them point into the item.
+    fn load_item_fields(
+        &mut self,
+        item: RegisterIndex,
+        result_ids: &[ValueId],
+        dfg: &DataFlowGraph,
+    ) {
+        let mut offset = 0;
+        for result_id in result_ids {
+            let destination =
+                self.function_context.get_or_create_register(self.brillig_context, *result_id);
+            let typ = dfg.type_of_value(*result_id);
+            match typ {
+                Type::Array(..) => self.brillig_context.offset_pointer(destination, item, offset),
+                _ => self.brillig_context.load_at(destination, item, offset),
+            }
+            offset += compute_size_of_type(&typ);
+        }
+    }
+
+    /// Decomposes the field element `source_id` into as many digits in the given radix as the
+    /// constant `limb_count_id`. As in the ACIR generation, the resulting array is padded with
+    /// zero digits up to its length.
+    fn convert_radix_decompose(
+        &mut self,
+        endian: Endian,
+        source_id: ValueId,
+        radix: RegisterIndex,
+        limb_count_id: ValueId,
+        result_id: ValueId,
+        dfg: &DataFlowGraph,
+    ) {
+        let limb_count = dfg
+            .get_numeric_constant(limb_count_id)
+            .expect("ICE: limb_size should be a constant")
+            .to_u128() as usize;
+        let result_length = compute_size_of_type(&dfg.type_of_value(result_id));
+
+        let source = self.convert_ssa_value(source_id, dfg);
+        let destination = self.allocate_result(result_id, dfg);
+        self.brillig_context.radix_decompose_instruction(
+            source,
+            radix,
+            destination,
+            limb_count,
+            endian == Endian::Big,
+        );
+
+        if result_length > limb_count {
+            let padding = self.brillig_context.allocate_register();
+            self.brillig_context.offset_pointer(padding, destination, limb_count);
+            let padding_length =
+                self.brillig_context.make_constant((result_length - limb_count).into());
+            let zero = self.brillig_context.make_constant(0_u128.into());
+            self.brillig_context.loop_instruction(padding_length, |ctx, iterator| {
+                ctx.array_set(padding, iterator, zero);
+            });
+            self.brillig_context.deallocate_register(zero);
+            self.brillig_context.deallocate_register(padding_length);
+            self.brillig_context.deallocate_register(padding);
+        }
+    }
+
+    /// Converts a call to a black box function into Brillig opcodes.
+    ///
+    /// Hashes and the fixed base scalar multiplication are computed by Brillig bytecode.
+    /// Pedersen hashes and signature verifications depend on the proving backend, so they are
+    /// emitted as foreign calls named after the black box function, which the executor of the
+    /// program resolves with the backend. Recursive aggregation is rejected by
+    /// `check_for_unsupported_brillig`, as it cannot be solved outside of a proof.
+    fn convert_black_box_call(
+        &mut self,
+        black_box: BlackBoxFunc,
+        arguments: &[ValueId],
+        result_ids: &[ValueId],
+        dfg: &DataFlowGraph,
+    ) {
+        let array_length = |value_id: ValueId| compute_size_of_type(&dfg.type_of_value(value_id));
+        match black_box {
+            BlackBoxFunc::AND | BlackBoxFunc::XOR => {
+                let op = if black_box == BlackBoxFunc::AND {
+                    BinaryIntOp::And
+                } else {
+                    BinaryIntOp::Xor
+                };
+                let bit_size = Self::get_bit_size_from_ssa_type(dfg.type_of_value(arguments[0]));
+                let lhs = self.convert_ssa_value(arguments[0], dfg);
+                let rhs = self.convert_ssa_value(arguments[1], dfg);
+                let destination = self.allocate_result(result_ids[0], dfg);
+                self.brillig_context.integer_op(lhs, op, rhs, destination, bit_size);
+            }
+            BlackBoxFunc::RANGE => {
+                let bit_size = Self::get_bit_size_from_ssa_type(dfg.type_of_value(arguments[0]));
+                let value = self.convert_ssa_value(arguments[0], dfg);
+                self.brillig_context.range_check_instruction(value, bit_size);
+            }
+            BlackBoxFunc::SHA256 | BlackBoxFunc::Blake2s => {
+                let input = self.convert_ssa_value(arguments[0], dfg);
+                let output = self.allocate_result(result_ids[0], dfg);
+                if black_box == BlackBoxFunc::SHA256 {
+                    self.brillig_context.sha256_instruction(
+                        input,
+                        array_length(arguments[0]),
+                        output,
+                    );
+                } else {
+                    self.brillig_context.blake2s_instruction(
+                        input,
+                        array_length(arguments[0]),
+                        output,
+                    );
+                }
+            }
+            BlackBoxFunc::Keccak256 => {
+                let input = self.convert_ssa_value(arguments[0], dfg);
+                let message_size = self.convert_ssa_value(arguments[1], dfg);
+                let output = self.allocate_result(result_ids[0], dfg);
+                self.brillig_context.keccak256_instruction(
+                    input,
+                    array_length(arguments[0]),
+                    message_size,
+                    output,
+                );
+            }
+            BlackBoxFunc::HashToField128Security => {
+                let input = self.convert_ssa_value(arguments[0], dfg);
+                let result = self.allocate_result(result_ids[0], dfg);
+                self.brillig_context.hash_to_field_128_security_instruction(
+                    input,
+                    array_length(arguments[0]),
+                    result,
+                );
+            }
+            BlackBoxFunc::FixedBaseScalarMul => {
+                let scalar = self.convert_ssa_value(arguments[0], dfg);
+                let output = self.allocate_result(result_ids[0], dfg);
+                self.brillig_context.fixed_base_scalar_mul_instruction(scalar, output);
+            }
+            BlackBoxFunc::Pedersen | BlackBoxFunc::SchnorrVerify | BlackBoxFunc::EcdsaSecp256k1 => {
+                let inputs = vecmap(arguments, |value_id| {
+                    self.convert_ssa_value_to_register_value_or_array(*value_id, dfg)
+                });
+                let outputs = vecmap(result_ids, |value_id| {
+                    self.allocate_result(*value_id, dfg);
+                    self.convert_ssa_value_to_register_value_or_array(*value_id, dfg)
+                });
+                self.brillig_context.foreign_call_instruction(
+                    black_box.to_string(),
+                    &inputs,
+                    &outputs,
+                );
+                for value in inputs.into_iter().chain(outputs) {
+                    self.deallocate_slice_registers(value);
+                }
+            }
+            BlackBoxFunc::RecursiveAggregation => {
+                unreachable!("ICE: {black_box} is rejected by check_for_unsupported_brillig")
+            }
+        }
+    }
+
     /// This function allows storing a Value in memory starting at the address specified by the
     /// address_register. The value can be a single value or an array. The function will recursively
     /// store the value in memory.
@@ -307,32 +862,39 @@ impl<'block> BrilligBlock<'block> {
         match value {
             Value::Param { .. } | Value::Instruction { .. } | Value::NumericConstant { .. } => {
                 let value_register = self.convert_ssa_value(value_id, dfg);
-                self.brillig_context.store_instruction(address_register, value_register);
+                match dfg.type_of_value(value_id) {
+                    // Nested arrays are stored inline, so their elements are copied into place
+                    typ @ Type::Array(..) => {
+                        let size_register =
+                            self.brillig_context.make_constant(compute_size_of_type(&typ).into());
+                        self.brillig_context.copy_array_instruction(
+                            value_register,
+                            address_register,
+                            size_register,
+                        );
+                        self.brillig_context.deallocate_register(size_register);
+                    }
+                    _ => self.brillig_context.store_instruction(address_register, value_register),
+                }
             }
-            Value::Array { array, element_type } => {
+            Value::Array { array, .. } => {
                 // Allocate a register for the iterator
                 let iterator_register = self.brillig_context.allocate_register();
                 // Set the iterator to the address of the array
                 self.brillig_context.mov_instruction(iterator_register, address_register);
 
-                let size_of_item_register = self
-                    .brillig_context
-                    .make_constant(compute_size_of_composite_type(element_type).into());
-
                 for element_id in array.iter() {
-                    // Store the item in memory
+                    // Store the element in memory
                     self.store_in_memory(iterator_register, *element_id, dfg);
-                    // Increment the iterator by the size of the items
-                    self.brillig_context.binary_instruction(
+                    // Increment the iterator by the size of the element
+                    let element_size = compute_size_of_type(&dfg.type_of_value(*element_id));
+                    self.brillig_context.usize_op(
                         iterator_register,
-                        size_of_item_register,
-                        iterator_register,
-                        BrilligBinaryOp::Integer {
-                            op: BinaryIntOp::Add,
-                            bit_size: BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
-                        },
+                        BinaryIntOp::Add,
+                        element_size,
                     );
                 }
+                self.brillig_context.deallocate_register(iterator_register);
             }
             _ => unimplemented!("ICE: Value {:?} not storeable in memory", value),
         }
@@ -440,7 +1002,23 @@ impl<'block> BrilligBlock<'block> {
         let typ = dfg[value_id].get_type();
         match typ {
             Type::Numeric(_) => RegisterOrMemory::RegisterIndex(register_index),
-            Type::Array(_, size) => RegisterOrMemory::HeapArray(register_index, size),
+            Type::Array(..) => {
+                RegisterOrMemory::HeapArray(register_index, compute_size_of_type(&typ))
+            }
+            Type::Reference => RegisterOrMemory::RegisterIndex(register_index),
+            // Slices are passed as the vector of the memory slots taken by their items, whose
+            // registers are deallocated by `deallocate_slice_registers` after the call
+            Type::Slice(element_types) => {
+                let length = self.brillig_context.allocate_register();
+                self.brillig_context.load_instruction(length, register_index);
+                let size =
+                    self.slice_memory_size(length, compute_size_of_composite_type(&element_types));
+                self.brillig_context.usize_op(size, BinaryIntOp::Sub, 1);
+                let items = self.brillig_context.allocate_register();
+                self.brillig_context.offset_pointer(items, register_index, 1);
+                self.brillig_context.deallocate_register(length);
+                RegisterOrMemory::HeapVector(items, size)
+            }
             _ => {
                 unreachable!("type not supported for conversion into brillig register")
             }
@@ -552,8 +1130,11 @@ fn compute_size_of_composite_type(typ: &CompositeType) -> usize {
 /// This is needed to store values in memory
 pub(crate) fn compute_size_of_type(typ: &Type) -> usize {
     match typ {
-        Type::Numeric(_) => 1,
+        // References and slices are pointers
+        Type::Numeric(_) | Type::Reference | Type::Slice(_) => 1,
         Type::Array(types, item_count) => compute_size_of_composite_type(types) * item_count,
-        _ => todo!("ICE: Type not supported {typ:?}"),
+        Type::Function => {
+            unreachable!("ICE: Function values are rejected by check_for_unsupported_brillig")
+        }
     }
 }
@@ -21,6 +21,8 @@ pub(crate) struct FunctionContext {
     pub(crate) function_id: FunctionId,
     /// Map from SSA values to Register Indices.
     pub(crate) ssa_value_to_register: HashMap<ValueId, RegisterIndex>,
+    /// Whether calls to `println` are compiled, or are removed from the bytecode.
+    pub(crate) allow_log_ops: bool,
 }
 
 impl FunctionContext {
@@ -61,7 +63,10 @@ impl FunctionContext {
             .map(|&value_id| {
                 let typ = func.dfg.type_of_value(value_id);
                 match typ {
-                    Type::Numeric(_) => BrilligParameter::Register,
+                    // Slices are only passed between Brillig functions, as pointers
+                    Type::Numeric(_) | Type::Reference | Type::Slice(_) => {
+                        BrilligParameter::Register
+                    }
                     Type::Array(..) => BrilligParameter::HeapArray(compute_size_of_type(&typ)),
                     _ => unimplemented!("Unsupported function parameter type {typ:?}"),
                 }
@@ -86,7 +91,10 @@ impl FunctionContext {
             .map(|&value_id| {
                 let typ = func.dfg.type_of_value(value_id);
                 match typ {
-                    Type::Numeric(_) => BrilligParameter::Register,
+                    // Slices are only passed between Brillig functions, as pointers
+                    Type::Numeric(_) | Type::Reference | Type::Slice(_) => {
+                        BrilligParameter::Register
+                    }
                     Type::Array(..) => BrilligParameter::HeapArray(compute_size_of_type(&typ)),
                     _ => unimplemented!("Unsupported return value type {typ:?}"),
                 }
@@ -5,7 +5,9 @@
 //! ssa types and types in this module.
 //! A similar paradigm can be seen with the `acir_ir` module.
 pub(crate) mod artifact;
+pub(crate) mod black_box;
 pub(crate) mod debug_show;
+pub(crate) mod intrinsics;
 pub(crate) mod registers;
 
 use self::{
@@ -39,6 +41,9 @@ pub(crate) const BRILLIG_MEMORY_ADDRESSING_BIT_SIZE: u32 = 64;
 pub(crate) enum ReservedRegisters {
     /// This register stores the stack pointer. Allocations must be done after this pointer.
     StackPointer = 0,
+    /// This register points to the memory where the registers of the caller were saved by the
+    /// innermost call in progress. They are restored from there once the call returns.
+    SavedRegistersPointer = 1,
 }
 
 impl ReservedRegisters {
@@ -46,7 +51,7 @@ impl ReservedRegisters {
     ///
     /// This is used to offset the general registers
     /// which should not overwrite the special register
-    const NUM_RESERVED_REGISTERS: usize = 1;
+    const NUM_RESERVED_REGISTERS: usize = 2;
 
     /// Returns the length of the reserved registers
     pub(crate) fn len() -> usize {
@@ -58,6 +63,11 @@ impl ReservedRegisters {
         RegisterIndex::from(ReservedRegisters::StackPointer as usize)
     }
 
+    /// Returns the register pointing to the registers saved before the current call.
+    pub(crate) fn saved_registers_pointer() -> RegisterIndex {
+        RegisterIndex::from(ReservedRegisters::SavedRegistersPointer as usize)
+    }
+
     /// Returns a user defined (non-reserved) register index.
     fn user_register_index(index: usize) -> RegisterIndex {
         RegisterIndex::from(index + ReservedRegisters::len())
@@ -273,6 +283,76 @@ impl BrilligContext {
         self.compute_section_label(self.section_label)
     }
 
+    /// Reserves a new section label, to be placed later on with `enter_section`.
+    ///
+    /// This allows jumping to the end of a region of code which itself contains sections.
+    fn new_section_label(&mut self) -> String {
+        self.section_label += 1;
+        self.current_section_label()
+    }
+
+    /// Adds a section label reserved by `new_section_label` to the next opcode
+    fn enter_section(&mut self, label: String) {
+        self.obj.add_label_at_position(label, self.obj.index_of_next_opcode());
+    }
+
+    /// Emits a loop running the code emitted by `on_iteration` `iteration_count` times.
+    ///
+    /// `on_iteration` is given a register holding the current iteration, counting from zero,
+    /// which it must not modify.
+    pub(crate) fn loop_instruction(
+        &mut self,
+        iteration_count: RegisterIndex,
+        on_iteration: impl FnOnce(&mut BrilligContext, RegisterIndex),
+    ) {
+        let iterator_register = self.make_constant(0_u128.into());
+        let loop_label = self.new_section_label();
+        let exit_loop_label = self.new_section_label();
+
+        self.enter_section(loop_label.clone());
+
+        // Exit the loop once iterator == iteration_count
+        let iterator_less_than_iterations = self.allocate_register();
+        self.binary_instruction(
+            iterator_register,
+            iteration_count,
+            iterator_less_than_iterations,
+            BrilligBinaryOp::Integer {
+                op: BinaryIntOp::LessThan,
+                bit_size: BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+            },
+        );
+        self.not_instruction(iterator_less_than_iterations, 1, iterator_less_than_iterations);
+        self.jump_if_instruction(iterator_less_than_iterations, exit_loop_label.clone());
+        self.deallocate_register(iterator_less_than_iterations);
+
+        on_iteration(self, iterator_register);
+
+        self.usize_op(iterator_register, BinaryIntOp::Add, 1);
+        self.jump_instruction(loop_label);
+
+        self.enter_section(exit_loop_label);
+        self.deallocate_register(iterator_register);
+    }
+
+    /// Emits the code emitted by `on_true` such that it only runs if `condition` is true.
+    pub(crate) fn branch_instruction(
+        &mut self,
+        condition: RegisterIndex,
+        on_true: impl FnOnce(&mut BrilligContext),
+    ) {
+        let skip_label = self.new_section_label();
+
+        let negated_condition = self.allocate_register();
+        self.not_instruction(condition, 1, negated_condition);
+        self.jump_if_instruction(negated_condition, skip_label.clone());
+        self.deallocate_register(negated_condition);
+
+        on_true(self);
+
+        self.enter_section(skip_label);
+    }
+
     /// Adds a unresolved `Jump` instruction to the bytecode.
     pub(crate) fn jump_instruction<T: ToString>(&mut self, target_label: T) {
         debug_show::jump_instruction(target_label.to_string());
@@ -611,12 +691,25 @@ impl BrilligContext {
     }
 
     /// Saves all of the registers that have been used up until this point.
+    ///
+    /// The registers are saved in memory after the previous value of the saved registers
+    /// pointer, which is then set to point to them.
     fn save_all_used_registers(&mut self) -> Vec<RegisterIndex> {
         // Save all of the used registers at this point in memory
         // because the function call will/may overwrite them.
         //
         // Note that here it is important that the stack pointer register is at register 0,
         // as after the first register save we add to the pointer.
+        self.store_instruction(
+            ReservedRegisters::stack_pointer(),
+            ReservedRegisters::saved_registers_pointer(),
+        );
+        self.mov_instruction(
+            ReservedRegisters::saved_registers_pointer(),
+            ReservedRegisters::stack_pointer(),
+        );
+        self.usize_op(ReservedRegisters::stack_pointer(), BinaryIntOp::Add, 1);
+
         let used_registers: Vec<_> = self.registers.used_registers_iter().collect();
         for register in used_registers.iter() {
             self.store_instruction(ReservedRegisters::stack_pointer(), *register);
@@ -627,15 +720,24 @@ impl BrilligContext {
     }
 
     /// Loads all of the registers that have been save by save_all_used_registers.
+    ///
+    /// They are read through the saved registers pointer rather than the stack pointer,
+    /// as the function called may have allocated memory. The stack pointer is left as is
+    /// so that this memory, which may be pointed to by the values returned, is not reused.
     fn load_all_saved_registers(&mut self, used_registers: &[RegisterIndex]) {
-        // Load all of the used registers that we saved.
-        // We do all the reverse operations of save_all_used_registers.
-        // Iterate our registers in reverse
-        for register in used_registers.iter().rev() {
-            // Subtract one from our stack pointer
-            self.usize_op(ReservedRegisters::stack_pointer(), BinaryIntOp::Sub, 1);
-            self.load_instruction(*register, ReservedRegisters::stack_pointer());
+        let iterator_register = self.allocate_register();
+        self.mov_instruction(iterator_register, ReservedRegisters::saved_registers_pointer());
+        for register in used_registers.iter() {
+            self.usize_op(iterator_register, BinaryIntOp::Add, 1);
+            self.load_instruction(*register, iterator_register);
         }
+        self.deallocate_register(iterator_register);
+
+        // Restore the saved registers pointer of the caller
+        self.load_instruction(
+            ReservedRegisters::saved_registers_pointer(),
+            ReservedRegisters::saved_registers_pointer(),
+        );
     }
 
     /// Utility method to perform a binary instruction with a constant value
@@ -656,6 +758,80 @@ impl BrilligContext {
         self.deallocate_register(const_register);
     }
 
+    /// Utility method to perform a binary integer operation of the given bit size
+    pub(crate) fn integer_op(
+        &mut self,
+        lhs: RegisterIndex,
+        op: BinaryIntOp,
+        rhs: RegisterIndex,
+        destination: RegisterIndex,
+        bit_size: u32,
+    ) {
+        self.binary_instruction(lhs, rhs, destination, BrilligBinaryOp::Integer { op, bit_size });
+    }
+
+    /// Utility method to perform a binary field operation
+    pub(crate) fn field_op(
+        &mut self,
+        lhs: RegisterIndex,
+        op: BinaryFieldOp,
+        rhs: RegisterIndex,
+        destination: RegisterIndex,
+    ) {
+        self.binary_instruction(lhs, rhs, destination, BrilligBinaryOp::Field { op });
+    }
+
+    /// Computes `pointer + offset` into `destination`, where `offset` is known at compile time
+    pub(crate) fn offset_pointer(
+        &mut self,
+        destination: RegisterIndex,
+        pointer: RegisterIndex,
+        offset: usize,
+    ) {
+        self.mov_instruction(destination, pointer);
+        if offset != 0 {
+            self.usize_op(destination, BinaryIntOp::Add, offset);
+        }
+    }
+
+    /// Loads the value at `pointer + offset` into `destination`
+    pub(crate) fn load_at(
+        &mut self,
+        destination: RegisterIndex,
+        pointer: RegisterIndex,
+        offset: usize,
+    ) {
+        let address = self.allocate_register();
+        self.offset_pointer(address, pointer, offset);
+        self.load_instruction(destination, address);
+        self.deallocate_register(address);
+    }
+
+    /// Stores `source` at `pointer + offset`
+    pub(crate) fn store_at(
+        &mut self,
+        pointer: RegisterIndex,
+        offset: usize,
+        source: RegisterIndex,
+    ) {
+        let address = self.allocate_register();
+        self.offset_pointer(address, pointer, offset);
+        self.store_instruction(address, source);
+        self.deallocate_register(address);
+    }
+
+    /// Stores `constant` at `pointer + offset`
+    pub(crate) fn store_constant_at(
+        &mut self,
+        pointer: RegisterIndex,
+        offset: usize,
+        constant: Value,
+    ) {
+        let constant = self.make_constant(constant);
+        self.store_at(pointer, offset, constant);
+        self.deallocate_register(constant);
+    }
+
     // Used before a call instruction.
     // Save all the registers we have used to the stack.
     // Move argument values to the front of the register indices.
@@ -710,12 +886,12 @@ pub(crate) enum BrilligBinaryOp {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use std::vec;
 
     use acvm::acir::brillig_vm::{
-        BinaryIntOp, ForeignCallOutput, ForeignCallResult, RegisterIndex, RegisterOrMemory,
-        Registers, VMStatus, Value, VM,
+        BinaryFieldOp, BinaryIntOp, ForeignCallOutput, ForeignCallResult, RegisterIndex,
+        RegisterOrMemory, Registers, VMStatus, Value, VM,
     };
 
     use crate::brillig::brillig_ir::{BrilligContext, BRILLIG_MEMORY_ADDRESSING_BIT_SIZE};
@@ -790,4 +966,64 @@ mod tests {
         let status = vm.process_opcodes();
         assert_eq!(status, VMStatus::Finished);
     }
+
+    /// Stops the program of the context and runs it, returning the status of the VM
+    pub(crate) fn run(mut context: BrilligContext) -> VMStatus {
+        context.stop_instruction();
+        let bytecode = context.artifact().finish();
+        let mut vm = VM::new(Registers { inner: vec![] }, vec![], bytecode, vec![]);
+        vm.process_opcodes()
+    }
+
+    /// Returns a context whose stack pointer starts at 0
+    pub(crate) fn new_context() -> BrilligContext {
+        let mut context = BrilligContext::new(vec![], vec![]);
+        context.enter_context("test");
+        context.const_instruction(ReservedRegisters::stack_pointer(), Value::from(0_usize));
+        context
+    }
+
+    /// Allocates an array holding the given values, returning the register pointing to it
+    pub(crate) fn make_array(context: &mut BrilligContext, values: &[u128]) -> RegisterIndex {
+        let pointer = context.allocate_register();
+        context.allocate_fixed_length_array(pointer, values.len());
+        for (i, value) in values.iter().enumerate() {
+            context.store_constant_at(pointer, i, Value::from(*value));
+        }
+        pointer
+    }
+
+    /// Constrains the values of the array at `pointer` to be the expected ones
+    pub(crate) fn constrain_array(
+        context: &mut BrilligContext,
+        pointer: RegisterIndex,
+        expected: &[u128],
+    ) {
+        let value = context.allocate_register();
+        for (i, expected) in expected.iter().enumerate() {
+            context.load_at(value, pointer, i);
+            let expected = context.make_constant(Value::from(*expected));
+            context.field_op(value, BinaryFieldOp::Equals, expected, value);
+            context.constrain_instruction(value);
+            context.deallocate_register(expected);
+        }
+        context.deallocate_register(value);
+    }
+
+    #[test]
+    fn test_brillig_ir_radix_decompose_and_sort() {
+        let mut context = new_context();
+        let value = context.make_constant(Value::from(0xc0de_u128));
+        let radix = context.make_constant(Value::from(16_u128));
+        let digits = context.allocate_register();
+        context.allocate_fixed_length_array(digits, 6);
+        context.radix_decompose_instruction(value, radix, digits, 6, true);
+        constrain_array(&mut context, digits, &[0, 0, 0xc, 0, 0xd, 0xe]);
+
+        let sorted = context.allocate_register();
+        context.allocate_fixed_length_array(sorted, 6);
+        context.sort_instruction(digits, sorted, 6, 8);
+        constrain_array(&mut context, sorted, &[0, 0, 0, 0xc, 0xd, 0xe]);
+        assert_eq!(run(context), VMStatus::Finished);
+    }
 }
@@ -3,6 +3,8 @@ use std::collections::HashMap;
 
 use crate::brillig::brillig_ir::ReservedRegisters;
 
+mod optimizer;
+
 /// Represents a parameter or a return value of a function.
 #[derive(Debug, Clone)]
 pub(crate) enum BrilligParameter {
@@ -0,0 +1,226 @@
+//! Peephole optimizations over the bytecode of a single brillig function.
+//!
+//! These run before the function is linked, while its jumps still refer to labels,
+//! so removing an opcode only requires shifting the positions of the labels and jumps after it.
+use std::collections::{HashMap, HashSet};
+
+use acvm::acir::brillig_vm::{Opcode as BrilligOpcode, Value};
+
+use super::{BrilligArtifact, OpcodeLocation};
+
+impl BrilligArtifact {
+    /// Removes the opcodes which have no effect on the execution of the function:
+    /// - moves of a register into itself,
+    /// - constants loaded into a register which is known to already hold them,
+    /// - jumps to the opcode which follows them.
+    pub(crate) fn optimize(&mut self) {
+        let mut removed = vec![false; self.byte_code.len()];
+        self.find_redundant_moves_and_constants(&mut removed);
+
+        // Removing a jump can leave another jump pointing to the opcode following it,
+        // so we keep going until no more jumps can be removed.
+        while self.find_jumps_to_next_opcode(&mut removed) {}
+
+        self.remove_opcodes(&removed);
+    }
+
+    /// Marks the moves of a register into itself and the constants which are already held
+    /// by their destination register as removed.
+    fn find_redundant_moves_and_constants(&self, removed: &mut [bool]) {
+        let labelled_positions: HashSet<OpcodeLocation> = self.labels.values().copied().collect();
+
+        // The constant held by each register, by register index, as far as we know
+        // at the current position.
+        let mut constants: HashMap<usize, Value> = HashMap::new();
+
+        for (position, opcode) in self.byte_code.iter().enumerate() {
+            // A labelled opcode may be jumped to from anywhere, so we can't assume anything
+            // about the registers when reaching it.
+            if labelled_positions.contains(&position) {
+                constants.clear();
+            }
+
+            match opcode {
+                BrilligOpcode::Mov { destination, source } => {
+                    if destination == source {
+                        removed[position] = true;
+                    } else if let Some(value) = constants.get(&source.to_usize()).copied() {
+                        constants.insert(destination.to_usize(), value);
+                    } else {
+                        constants.remove(&destination.to_usize());
+                    }
+                }
+                BrilligOpcode::Const { destination, value } => {
+                    if constants.get(&destination.to_usize()) == Some(value) {
+                        removed[position] = true;
+                    } else {
+                        constants.insert(destination.to_usize(), *value);
+                    }
+                }
+                BrilligOpcode::BinaryFieldOp { destination, .. }
+                | BrilligOpcode::BinaryIntOp { destination, .. }
+                | BrilligOpcode::Load { destination, .. } => {
+                    constants.remove(&destination.to_usize());
+                }
+                BrilligOpcode::Store { .. }
+                | BrilligOpcode::JumpIf { .. }
+                | BrilligOpcode::JumpIfNot { .. } => {}
+                // Calls may overwrite any register, and the opcode following the others
+                // can only be reached through a jump.
+                BrilligOpcode::Call { .. }
+                | BrilligOpcode::ForeignCall { .. }
+                | BrilligOpcode::Jump { .. }
+                | BrilligOpcode::Return
+                | BrilligOpcode::Trap
+                | BrilligOpcode::Stop => constants.clear(),
+            }
+        }
+    }
+
+    /// Marks the jumps whose target is the first opcode after them which hasn't been removed
+    /// as removed, returning whether any jump was found.
+    fn find_jumps_to_next_opcode(&self, removed: &mut [bool]) -> bool {
+        let mut found_jump = false;
+        for (position, label) in &self.unresolved_jumps {
+            if removed[*position] {
+                continue;
+            }
+            if let Some(target) = self.labels.get(label) {
+                if *target > *position && (position + 1..*target).all(|skipped| removed[skipped]) {
+                    removed[*position] = true;
+                    found_jump = true;
+                }
+            }
+        }
+        found_jump
+    }
+
+    /// Removes the marked opcodes, moving the labels and jumps to the new positions of their opcodes.
+    fn remove_opcodes(&mut self, removed: &[bool]) {
+        // The new position of an opcode is the number of opcodes kept before it.
+        // Labels can also point right after the last opcode, hence the extra position.
+        let mut new_positions = Vec::with_capacity(removed.len() + 1);
+        let mut kept_opcodes = 0;
+        for is_removed in removed {
+            new_positions.push(kept_opcodes);
+            if !is_removed {
+                kept_opcodes += 1;
+            }
+        }
+        new_positions.push(kept_opcodes);
+
+        let byte_code = std::mem::take(&mut self.byte_code);
+        self.byte_code = byte_code
+            .into_iter()
+            .zip(removed)
+            .filter_map(|(opcode, is_removed)| (!is_removed).then_some(opcode))
+            .collect();
+
+        for position in self.labels.values_mut() {
+            *position = new_positions[*position];
+        }
+
+        self.unresolved_jumps.retain(|(position, _)| !removed[*position]);
+        for (position, _) in
+            self.unresolved_jumps.iter_mut().chain(&mut self.unresolved_external_call_labels)
+        {
+            *position = new_positions[*position];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::brillig_vm::{
+        BinaryIntOp, Opcode as BrilligOpcode, RegisterIndex, Registers, VMStatus, Value, VM,
+    };
+
+    use crate::brillig::brillig_ir::artifact::BrilligArtifact;
+
+    #[test]
+    fn removes_redundant_moves_and_constants() {
+        let r0 = RegisterIndex::from(0);
+        let r1 = RegisterIndex::from(1);
+        let r2 = RegisterIndex::from(2);
+
+        let mut artifact = BrilligArtifact::new(vec![], vec![]);
+        artifact.push_opcode(BrilligOpcode::Const { destination: r0, value: Value::from(1_usize) });
+        artifact.push_opcode(BrilligOpcode::Mov { destination: r1, source: r0 });
+        // r1 already holds 1 through the move
+        artifact.push_opcode(BrilligOpcode::Const { destination: r1, value: Value::from(1_usize) });
+        artifact.push_opcode(BrilligOpcode::Mov { destination: r2, source: r2 });
+        artifact.push_opcode(BrilligOpcode::BinaryIntOp {
+            destination: r0,
+            op: BinaryIntOp::Add,
+            bit_size: 32,
+            lhs: r0,
+            rhs: r1,
+        });
+        // r0 has been overwritten
+        artifact.push_opcode(BrilligOpcode::Const { destination: r0, value: Value::from(1_usize) });
+        // The registers of a labelled opcode are unknown
+        artifact.add_label_at_position("block".to_string(), artifact.index_of_next_opcode());
+        artifact.push_opcode(BrilligOpcode::Const { destination: r1, value: Value::from(1_usize) });
+        artifact.push_opcode(BrilligOpcode::Stop);
+
+        artifact.optimize();
+
+        assert_eq!(
+            artifact.finish(),
+            vec![
+                BrilligOpcode::Const { destination: r0, value: Value::from(1_usize) },
+                BrilligOpcode::Mov { destination: r1, source: r0 },
+                BrilligOpcode::BinaryIntOp {
+                    destination: r0,
+                    op: BinaryIntOp::Add,
+                    bit_size: 32,
+                    lhs: r0,
+                    rhs: r1,
+                },
+                BrilligOpcode::Const { destination: r0, value: Value::from(1_usize) },
+                BrilligOpcode::Const { destination: r1, value: Value::from(1_usize) },
+                BrilligOpcode::Stop,
+            ]
+        );
+    }
+
+    #[test]
+    fn removes_jumps_to_next_opcode() {
+        let r0 = RegisterIndex::from(0);
+
+        let mut artifact = BrilligArtifact::new(vec![], vec![]);
+        artifact.push_opcode(BrilligOpcode::Const { destination: r0, value: Value::from(0_usize) });
+        artifact.add_unresolved_jump(
+            BrilligOpcode::JumpIf { condition: r0, location: 0 },
+            "trap".to_string(),
+        );
+        artifact.add_unresolved_jump(BrilligOpcode::Jump { location: 0 }, "first".to_string());
+        artifact.add_label_at_position("first".to_string(), artifact.index_of_next_opcode());
+        // Only jumps to the next opcode once the move is removed
+        artifact.add_unresolved_jump(BrilligOpcode::Jump { location: 0 }, "second".to_string());
+        artifact.push_opcode(BrilligOpcode::Mov { destination: r0, source: r0 });
+        artifact.add_label_at_position("second".to_string(), artifact.index_of_next_opcode());
+        artifact.add_unresolved_jump(BrilligOpcode::Jump { location: 0 }, "stop".to_string());
+        artifact.add_label_at_position("trap".to_string(), artifact.index_of_next_opcode());
+        artifact.push_opcode(BrilligOpcode::Trap);
+        artifact.add_label_at_position("stop".to_string(), artifact.index_of_next_opcode());
+        artifact.push_opcode(BrilligOpcode::Stop);
+
+        artifact.optimize();
+
+        let bytecode = artifact.finish();
+        assert_eq!(
+            bytecode,
+            vec![
+                BrilligOpcode::Const { destination: r0, value: Value::from(0_usize) },
+                BrilligOpcode::JumpIf { condition: r0, location: 3 },
+                BrilligOpcode::Jump { location: 4 },
+                BrilligOpcode::Trap,
+                BrilligOpcode::Stop,
+            ]
+        );
+
+        let mut vm = VM::new(Registers { inner: vec![] }, vec![], bytecode, vec![]);
+        assert_eq!(vm.process_opcodes(), VMStatus::Finished);
+    }
+}
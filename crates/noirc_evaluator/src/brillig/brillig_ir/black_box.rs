@@ -0,0 +1,1049 @@
+//! Brillig implementations of the black box functions which can be computed by plain Brillig
+//! bytecode: the hash functions and fixed base scalar multiplication over Grumpkin.
+//!
+//! The Brillig VM has no opcodes for black box functions so these are written out in full,
+//! following the reference implementations used by the ACVM to solve the corresponding ACIR
+//! opcodes.
+use acvm::{
+    acir::brillig_vm::{BinaryFieldOp, BinaryIntOp, RegisterIndex, Value},
+    FieldElement,
+};
+
+use super::{BrilligContext, BRILLIG_MEMORY_ADDRESSING_BIT_SIZE};
+
+const SHA256_ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const SHA256_INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const BLAKE2S_IV: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+
+/// The parameter block of a 32 byte digest without a key, xor-ed into the first word of the
+/// initial state.
+const BLAKE2S_PARAMETERS: u32 = 0x01010020;
+
+const BLAKE2S_SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// The number of bytes absorbed by each Keccak-f permutation
+const KECCAK256_RATE: usize = 136;
+
+const KECCAK_ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The rotation applied to each lane `x + 5 * y` of the Keccak state, indexed by `[x][y]`
+const KECCAK_ROTATIONS: [[u32; 5]; 5] = [
+    [0, 36, 3, 41, 18],
+    [1, 44, 10, 45, 2],
+    [62, 6, 43, 15, 61],
+    [28, 55, 25, 21, 56],
+    [27, 20, 39, 8, 14],
+];
+
+/// The y coordinate of the generator of Grumpkin, whose x coordinate is 1
+const GRUMPKIN_GENERATOR_Y: &str =
+    "0000000000000002cf135e7506a45d632d270d45f1181294833fc48d823f272c";
+
+impl BrilligContext {
+    /// Computes the SHA-256 hash of the `input_length` bytes of the array at `input_pointer`,
+    /// writing its 32 bytes into the array at `output_pointer`.
+    pub(crate) fn sha256_instruction(
+        &mut self,
+        input_pointer: RegisterIndex,
+        input_length: usize,
+        output_pointer: RegisterIndex,
+    ) {
+        // The message is padded with a one bit, zeros and its length in bits as a big endian
+        // 64 bit integer, up to a multiple of 64 bytes.
+        let padded_length = (input_length + 8) / 64 * 64 + 64;
+        let message = self.copy_to_padded_array(input_pointer, input_length, padded_length);
+        self.store_constant_at(message, input_length, Value::from(0x80_u128));
+        let bit_length = input_length as u128 * 8;
+        for i in 0..8 {
+            let byte = (bit_length >> (8 * (7 - i))) & 0xff;
+            self.store_constant_at(message, padded_length - 8 + i, Value::from(byte));
+        }
+
+        let round_constants = self.allocate_register();
+        self.allocate_fixed_length_array(round_constants, SHA256_ROUND_CONSTANTS.len());
+        for (i, constant) in SHA256_ROUND_CONSTANTS.iter().enumerate() {
+            self.store_constant_at(round_constants, i, Value::from(*constant as u128));
+        }
+
+        let state = SHA256_INITIAL_STATE.map(|word| self.make_constant(Value::from(word as u128)));
+        let schedule = self.allocate_register();
+        self.allocate_fixed_length_array(schedule, 64);
+
+        let block_count = self.make_constant(Value::from(padded_length / 64));
+        self.loop_instruction(block_count, |ctx, block| {
+            let block_pointer = ctx.block_pointer(message, block, 64);
+
+            // The first 16 words of the schedule are the block itself, the next ones are
+            // derived from the previous words.
+            let word = ctx.allocate_register();
+            for i in 0..16 {
+                ctx.load_word(word, block_pointer, 4 * i, 4, true);
+                ctx.store_at(schedule, i, word);
+            }
+            ctx.deallocate_register(word);
+
+            let derived_word_count = ctx.make_constant(Value::from(48_usize));
+            ctx.loop_instruction(derived_word_count, |ctx, i| {
+                // The schedule from the 16th word before the word derived
+                let window = ctx.allocate_register();
+                ctx.integer_op(
+                    schedule,
+                    BinaryIntOp::Add,
+                    i,
+                    window,
+                    BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+                );
+
+                let word = ctx.allocate_register();
+                let sigma = ctx.allocate_register();
+                ctx.load_at(word, window, 0);
+                ctx.load_at(sigma, window, 9);
+                ctx.integer_op(word, BinaryIntOp::Add, sigma, word, 32);
+                let previous = ctx.allocate_register();
+                ctx.load_at(previous, window, 1);
+                ctx.sha256_sigma(previous, [7, 18, 3], false, sigma);
+                ctx.integer_op(word, BinaryIntOp::Add, sigma, word, 32);
+                ctx.load_at(previous, window, 14);
+                ctx.sha256_sigma(previous, [17, 19, 10], false, sigma);
+                ctx.integer_op(word, BinaryIntOp::Add, sigma, word, 32);
+                ctx.store_at(window, 16, word);
+
+                ctx.deallocate_register(previous);
+                ctx.deallocate_register(sigma);
+                ctx.deallocate_register(word);
+                ctx.deallocate_register(window);
+            });
+            ctx.deallocate_register(derived_word_count);
+
+            let [a, b, c, d, e, f, g, h] = state.map(|word| {
+                let variable = ctx.allocate_register();
+                ctx.mov_instruction(variable, word);
+                variable
+            });
+
+            let round_count = ctx.make_constant(Value::from(64_usize));
+            ctx.loop_instruction(round_count, |ctx, round| {
+                // temp1 = h + Σ1(e) + ch(e, f, g) + k[round] + w[round]
+                let temp1 = ctx.allocate_register();
+                let scratch = ctx.allocate_register();
+                ctx.sha256_sigma(e, [6, 11, 25], true, temp1);
+                ctx.integer_op(temp1, BinaryIntOp::Add, h, temp1, 32);
+                ctx.integer_op(e, BinaryIntOp::And, f, scratch, 32);
+                ctx.integer_op(temp1, BinaryIntOp::Add, scratch, temp1, 32);
+                ctx.not_instruction(e, 32, scratch);
+                ctx.integer_op(scratch, BinaryIntOp::And, g, scratch, 32);
+                ctx.integer_op(temp1, BinaryIntOp::Add, scratch, temp1, 32);
+                ctx.array_get(round_constants, round, scratch);
+                ctx.integer_op(temp1, BinaryIntOp::Add, scratch, temp1, 32);
+                ctx.array_get(schedule, round, scratch);
+                ctx.integer_op(temp1, BinaryIntOp::Add, scratch, temp1, 32);
+
+                // temp2 = Σ0(a) + maj(a, b, c)
+                let temp2 = ctx.allocate_register();
+                ctx.sha256_sigma(a, [2, 13, 22], true, temp2);
+                let majority = ctx.allocate_register();
+                ctx.integer_op(a, BinaryIntOp::And, b, majority, 32);
+                ctx.integer_op(a, BinaryIntOp::And, c, scratch, 32);
+                ctx.integer_op(majority, BinaryIntOp::Xor, scratch, majority, 32);
+                ctx.integer_op(b, BinaryIntOp::And, c, scratch, 32);
+                ctx.integer_op(majority, BinaryIntOp::Xor, scratch, majority, 32);
+                ctx.integer_op(temp2, BinaryIntOp::Add, majority, temp2, 32);
+
+                ctx.mov_instruction(h, g);
+                ctx.mov_instruction(g, f);
+                ctx.mov_instruction(f, e);
+                ctx.integer_op(d, BinaryIntOp::Add, temp1, e, 32);
+                ctx.mov_instruction(d, c);
+                ctx.mov_instruction(c, b);
+                ctx.mov_instruction(b, a);
+                ctx.integer_op(temp1, BinaryIntOp::Add, temp2, a, 32);
+
+                ctx.deallocate_register(majority);
+                ctx.deallocate_register(temp2);
+                ctx.deallocate_register(scratch);
+                ctx.deallocate_register(temp1);
+            });
+            ctx.deallocate_register(round_count);
+
+            for (word, variable) in state.iter().zip([a, b, c, d, e, f, g, h]) {
+                ctx.integer_op(*word, BinaryIntOp::Add, variable, *word, 32);
+                ctx.deallocate_register(variable);
+            }
+            ctx.deallocate_register(block_pointer);
+        });
+
+        for (i, word) in state.iter().enumerate() {
+            self.store_word(output_pointer, 4 * i, *word, 4, true);
+        }
+
+        self.deallocate_register(block_count);
+        self.deallocate_register(schedule);
+        self.deallocate_register(round_constants);
+        self.deallocate_register(message);
+        for word in state {
+            self.deallocate_register(word);
+        }
+    }
+
+    /// Computes one of the σ or Σ functions of SHA-256 into `destination`: the xor of two
+    /// rotations of `word` with either a third rotation or a shift, by the given amounts.
+    fn sha256_sigma(
+        &mut self,
+        word: RegisterIndex,
+        amounts: [u32; 3],
+        rotate_last: bool,
+        destination: RegisterIndex,
+    ) {
+        let scratch = self.allocate_register();
+        let result = self.allocate_register();
+        self.rotate_right(word, amounts[0], result, 32);
+        self.rotate_right(word, amounts[1], scratch, 32);
+        self.integer_op(result, BinaryIntOp::Xor, scratch, result, 32);
+        if rotate_last {
+            self.rotate_right(word, amounts[2], scratch, 32);
+        } else {
+            let shift = self.make_constant(Value::from(amounts[2] as u128));
+            self.integer_op(word, BinaryIntOp::Shr, shift, scratch, 32);
+            self.deallocate_register(shift);
+        }
+        self.integer_op(result, BinaryIntOp::Xor, scratch, destination, 32);
+        self.deallocate_register(result);
+        self.deallocate_register(scratch);
+    }
+
+    /// Computes the Blake2s hash of the `input_length` bytes of the array at `input_pointer`,
+    /// writing its 32 bytes into the array at `output_pointer`.
+    pub(crate) fn blake2s_instruction(
+        &mut self,
+        input_pointer: RegisterIndex,
+        input_length: usize,
+        output_pointer: RegisterIndex,
+    ) {
+        // The message is padded with zeros up to a multiple of 64 bytes, with at least one
+        // block being compressed.
+        let block_count = std::cmp::max(1, (input_length + 63) / 64);
+        let message = self.copy_to_padded_array(input_pointer, input_length, block_count * 64);
+
+        let state = BLAKE2S_IV.map(|word| self.make_constant(Value::from(word as u128)));
+        self.const_instruction(state[0], Value::from((BLAKE2S_IV[0] ^ BLAKE2S_PARAMETERS) as u128));
+
+        let block_count_register = self.make_constant(Value::from(block_count));
+        self.loop_instruction(block_count_register, |ctx, block| {
+            let block_pointer = ctx.block_pointer(message, block, 64);
+            let message_words: Vec<_> = (0..16)
+                .map(|i| {
+                    let word = ctx.allocate_register();
+                    ctx.load_word(word, block_pointer, 4 * i, 4, false);
+                    word
+                })
+                .collect();
+
+            // The number of bytes hashed once this block is compressed, which is the length
+            // of the input for the last block.
+            let is_last_block = ctx.allocate_register();
+            let last_block = ctx.make_constant(Value::from(block_count - 1));
+            ctx.integer_op(
+                block,
+                BinaryIntOp::Equals,
+                last_block,
+                is_last_block,
+                BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+            );
+            let counter = ctx.allocate_register();
+            ctx.mov_instruction(counter, block);
+            ctx.usize_op(counter, BinaryIntOp::Add, 1);
+            ctx.usize_op(counter, BinaryIntOp::Mul, 64);
+            ctx.branch_instruction(is_last_block, |ctx| {
+                ctx.const_instruction(counter, Value::from(input_length));
+            });
+
+            let mut v: Vec<_> = state
+                .iter()
+                .map(|word| {
+                    let variable = ctx.allocate_register();
+                    ctx.mov_instruction(variable, *word);
+                    variable
+                })
+                .collect();
+            v.extend(BLAKE2S_IV.iter().map(|word| ctx.make_constant(Value::from(*word as u128))));
+            ctx.integer_op(v[12], BinaryIntOp::Xor, counter, v[12], 32);
+            // The last block is flagged by inverting all bits of v[14]
+            let zero = ctx.make_constant(0_u128.into());
+            ctx.integer_op(zero, BinaryIntOp::Sub, is_last_block, is_last_block, 32);
+            ctx.integer_op(v[14], BinaryIntOp::Xor, is_last_block, v[14], 32);
+
+            for sigma in BLAKE2S_SIGMA {
+                let m = |i: usize| message_words[sigma[i]];
+                ctx.blake2s_mix(&v, [0, 4, 8, 12], m(0), m(1));
+                ctx.blake2s_mix(&v, [1, 5, 9, 13], m(2), m(3));
+                ctx.blake2s_mix(&v, [2, 6, 10, 14], m(4), m(5));
+                ctx.blake2s_mix(&v, [3, 7, 11, 15], m(6), m(7));
+                ctx.blake2s_mix(&v, [0, 5, 10, 15], m(8), m(9));
+                ctx.blake2s_mix(&v, [1, 6, 11, 12], m(10), m(11));
+                ctx.blake2s_mix(&v, [2, 7, 8, 13], m(12), m(13));
+                ctx.blake2s_mix(&v, [3, 4, 9, 14], m(14), m(15));
+            }
+
+            for (i, word) in state.iter().enumerate() {
+                ctx.integer_op(*word, BinaryIntOp::Xor, v[i], *word, 32);
+                ctx.integer_op(*word, BinaryIntOp::Xor, v[i + 8], *word, 32);
+            }
+
+            ctx.deallocate_register(zero);
+            ctx.deallocate_register(counter);
+            ctx.deallocate_register(last_block);
+            ctx.deallocate_register(is_last_block);
+            ctx.deallocate_register(block_pointer);
+            for register in v.into_iter().chain(message_words) {
+                ctx.deallocate_register(register);
+            }
+        });
+
+        for (i, word) in state.iter().enumerate() {
+            self.store_word(output_pointer, 4 * i, *word, 4, false);
+        }
+
+        self.deallocate_register(block_count_register);
+        self.deallocate_register(message);
+        for word in state {
+            self.deallocate_register(word);
+        }
+    }
+
+    /// The G mixing function of Blake2s, mixing the words `x` and `y` of the message into the
+    /// words of the working vector `v` at the given indices.
+    fn blake2s_mix(
+        &mut self,
+        v: &[RegisterIndex],
+        [a, b, c, d]: [usize; 4],
+        x: RegisterIndex,
+        y: RegisterIndex,
+    ) {
+        for (message_word, rotations) in [(x, [16, 12]), (y, [8, 7])] {
+            self.integer_op(v[a], BinaryIntOp::Add, v[b], v[a], 32);
+            self.integer_op(v[a], BinaryIntOp::Add, message_word, v[a], 32);
+            self.integer_op(v[d], BinaryIntOp::Xor, v[a], v[d], 32);
+            self.rotate_right(v[d], rotations[0], v[d], 32);
+            self.integer_op(v[c], BinaryIntOp::Add, v[d], v[c], 32);
+            self.integer_op(v[b], BinaryIntOp::Xor, v[c], v[b], 32);
+            self.rotate_right(v[b], rotations[1], v[b], 32);
+        }
+    }
+
+    /// Computes the Keccak-256 hash of the first `message_size` bytes of the array of
+    /// `input_length` bytes at `input_pointer`, writing its 32 bytes into the array at
+    /// `output_pointer`.
+    pub(crate) fn keccak256_instruction(
+        &mut self,
+        input_pointer: RegisterIndex,
+        input_length: usize,
+        message_size: RegisterIndex,
+        output_pointer: RegisterIndex,
+    ) {
+        let input_length_register = self.make_constant(Value::from(input_length));
+        let fits = self.allocate_register();
+        self.integer_op(
+            message_size,
+            BinaryIntOp::LessThanEquals,
+            input_length_register,
+            fits,
+            BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+        );
+        self.constrain_instruction(fits);
+        self.deallocate_register(fits);
+        self.deallocate_register(input_length_register);
+
+        // The message is padded with a one bit, zeros and a final one bit, up to a multiple of
+        // the rate. As the size of the message is only known at runtime, so is the number of
+        // blocks, which is at most the number of blocks of the whole input.
+        let block_count = self.allocate_register();
+        self.mov_instruction(block_count, message_size);
+        self.usize_op(block_count, BinaryIntOp::UnsignedDiv, KECCAK256_RATE);
+        self.usize_op(block_count, BinaryIntOp::Add, 1);
+        let padded_length = self.allocate_register();
+        self.mov_instruction(padded_length, block_count);
+        self.usize_op(padded_length, BinaryIntOp::Mul, KECCAK256_RATE);
+
+        let message = self.allocate_register();
+        let max_block_count = input_length / KECCAK256_RATE + 1;
+        self.allocate_fixed_length_array(message, max_block_count * KECCAK256_RATE);
+        self.copy_array_instruction(input_pointer, message, message_size);
+
+        let padding_length = self.allocate_register();
+        self.integer_op(
+            padded_length,
+            BinaryIntOp::Sub,
+            message_size,
+            padding_length,
+            BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+        );
+        self.loop_instruction(padding_length, |ctx, i| {
+            let index = ctx.allocate_register();
+            ctx.integer_op(
+                message_size,
+                BinaryIntOp::Add,
+                i,
+                index,
+                BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+            );
+            let zero = ctx.make_constant(0_u128.into());
+            ctx.array_set(message, index, zero);
+            ctx.deallocate_register(zero);
+            ctx.deallocate_register(index);
+        });
+        self.deallocate_register(padding_length);
+
+        let byte = self.make_constant(1_u128.into());
+        self.array_set(message, message_size, byte);
+        let last_index = self.allocate_register();
+        self.mov_instruction(last_index, padded_length);
+        self.usize_op(last_index, BinaryIntOp::Sub, 1);
+        self.array_get(message, last_index, byte);
+        let last_bit = self.make_constant(0x80_u128.into());
+        self.integer_op(byte, BinaryIntOp::Or, last_bit, byte, 8);
+        self.array_set(message, last_index, byte);
+        self.deallocate_register(last_bit);
+        self.deallocate_register(last_index);
+        self.deallocate_register(byte);
+
+        let round_constants = self.allocate_register();
+        self.allocate_fixed_length_array(round_constants, KECCAK_ROUND_CONSTANTS.len());
+        for (i, constant) in KECCAK_ROUND_CONSTANTS.iter().enumerate() {
+            self.store_constant_at(round_constants, i, Value::from(*constant as u128));
+        }
+
+        let state: Vec<_> = (0..25).map(|_| self.make_constant(0_u128.into())).collect();
+        self.loop_instruction(block_count, |ctx, block| {
+            let block_pointer = ctx.block_pointer(message, block, KECCAK256_RATE);
+            let lane = ctx.allocate_register();
+            for (i, state_lane) in state.iter().enumerate().take(KECCAK256_RATE / 8) {
+                ctx.load_word(lane, block_pointer, 8 * i, 8, false);
+                ctx.integer_op(*state_lane, BinaryIntOp::Xor, lane, *state_lane, 64);
+            }
+            ctx.deallocate_register(lane);
+            ctx.deallocate_register(block_pointer);
+
+            ctx.keccak_f_instruction(&state, round_constants);
+        });
+
+        for (i, lane) in state.iter().take(4).enumerate() {
+            self.store_word(output_pointer, 8 * i, *lane, 8, false);
+        }
+
+        for lane in state {
+            self.deallocate_register(lane);
+        }
+        self.deallocate_register(round_constants);
+        self.deallocate_register(message);
+        self.deallocate_register(padded_length);
+        self.deallocate_register(block_count);
+    }
+
+    /// Applies the Keccak-f[1600] permutation to the 25 lanes of the state, each held in a
+    /// register. `round_constants` points to the array of the constants of each round.
+    fn keccak_f_instruction(&mut self, state: &[RegisterIndex], round_constants: RegisterIndex) {
+        let round_count = self.make_constant(Value::from(KECCAK_ROUND_CONSTANTS.len()));
+        self.loop_instruction(round_count, |ctx, round| {
+            // θ step: xor each lane with the parities of two neighbouring columns
+            let parities: Vec<_> = (0..5)
+                .map(|x| {
+                    let parity = ctx.allocate_register();
+                    ctx.mov_instruction(parity, state[x]);
+                    for y in 1..5 {
+                        ctx.integer_op(parity, BinaryIntOp::Xor, state[x + 5 * y], parity, 64);
+                    }
+                    parity
+                })
+                .collect();
+            let column_mix = ctx.allocate_register();
+            for x in 0..5 {
+                ctx.rotate_right(parities[(x + 1) % 5], 63, column_mix, 64);
+                ctx.integer_op(parities[(x + 4) % 5], BinaryIntOp::Xor, column_mix, column_mix, 64);
+                for y in 0..5 {
+                    ctx.integer_op(
+                        state[x + 5 * y],
+                        BinaryIntOp::Xor,
+                        column_mix,
+                        state[x + 5 * y],
+                        64,
+                    );
+                }
+            }
+            ctx.deallocate_register(column_mix);
+            for parity in parities {
+                ctx.deallocate_register(parity);
+            }
+
+            // ρ and π steps: rotate each lane and move it to its new position
+            let moved: Vec<_> = (0..25).map(|_| ctx.allocate_register()).collect();
+            for x in 0..5 {
+                for y in 0..5 {
+                    let rotation = KECCAK_ROTATIONS[x][y];
+                    let destination = moved[y + 5 * ((2 * x + 3 * y) % 5)];
+                    ctx.rotate_right(state[x + 5 * y], (64 - rotation) % 64, destination, 64);
+                }
+            }
+
+            // χ step: combine each lane with the next two lanes of its row
+            let scratch = ctx.allocate_register();
+            for x in 0..5 {
+                for y in 0..5 {
+                    ctx.not_instruction(moved[(x + 1) % 5 + 5 * y], 64, scratch);
+                    ctx.integer_op(
+                        scratch,
+                        BinaryIntOp::And,
+                        moved[(x + 2) % 5 + 5 * y],
+                        scratch,
+                        64,
+                    );
+                    ctx.integer_op(
+                        moved[x + 5 * y],
+                        BinaryIntOp::Xor,
+                        scratch,
+                        state[x + 5 * y],
+                        64,
+                    );
+                }
+            }
+
+            // ι step: xor the round constant into the first lane
+            ctx.array_get(round_constants, round, scratch);
+            ctx.integer_op(state[0], BinaryIntOp::Xor, scratch, state[0], 64);
+
+            ctx.deallocate_register(scratch);
+            for register in moved {
+                ctx.deallocate_register(register);
+            }
+        });
+        self.deallocate_register(round_count);
+    }
+
+    /// Hashes the `input_length` field elements of the array at `input_pointer` into the field
+    /// element `result`, by hashing the 32 little endian bytes of each with Blake2s and reducing
+    /// the big endian digest into a field element.
+    pub(crate) fn hash_to_field_128_security_instruction(
+        &mut self,
+        input_pointer: RegisterIndex,
+        input_length: usize,
+        result: RegisterIndex,
+    ) {
+        let bytes = self.allocate_register();
+        self.allocate_fixed_length_array(bytes, 32 * input_length);
+        let radix = self.make_constant(256_u128.into());
+        let input_length_register = self.make_constant(Value::from(input_length));
+        self.loop_instruction(input_length_register, |ctx, i| {
+            let value = ctx.allocate_register();
+            ctx.array_get(input_pointer, i, value);
+            let value_bytes = ctx.allocate_register();
+            ctx.mov_instruction(value_bytes, i);
+            ctx.usize_op(value_bytes, BinaryIntOp::Mul, 32);
+            ctx.integer_op(
+                value_bytes,
+                BinaryIntOp::Add,
+                bytes,
+                value_bytes,
+                BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+            );
+            ctx.radix_decompose_instruction(value, radix, value_bytes, 32, false);
+            ctx.deallocate_register(value_bytes);
+            ctx.deallocate_register(value);
+        });
+
+        let digest = self.allocate_register();
+        self.allocate_fixed_length_array(digest, 32);
+        self.blake2s_instruction(bytes, 32 * input_length, digest);
+
+        let byte = self.allocate_register();
+        let field_radix = self.make_constant(Value::from(FieldElement::from(256_u128)));
+        self.const_instruction(result, 0_u128.into());
+        for i in 0..32 {
+            self.load_at(byte, digest, i);
+            self.field_op(result, BinaryFieldOp::Mul, field_radix, result);
+            self.field_op(result, BinaryFieldOp::Add, byte, result);
+        }
+
+        self.deallocate_register(field_radix);
+        self.deallocate_register(byte);
+        self.deallocate_register(digest);
+        self.deallocate_register(input_length_register);
+        self.deallocate_register(radix);
+        self.deallocate_register(bytes);
+    }
+
+    /// Multiplies the generator of Grumpkin by the field element `scalar`, writing the x and y
+    /// coordinates of the result into the array at `output_pointer`. The point at infinity is
+    /// written as (0, 0).
+    pub(crate) fn fixed_base_scalar_mul_instruction(
+        &mut self,
+        scalar: RegisterIndex,
+        output_pointer: RegisterIndex,
+    ) {
+        let bit_count = FieldElement::max_num_bits() as usize;
+        let bits = self.allocate_register();
+        self.allocate_fixed_length_array(bits, bit_count);
+        let radix = self.make_constant(2_u128.into());
+        self.radix_decompose_instruction(scalar, radix, bits, bit_count, true);
+        self.deallocate_register(radix);
+
+        let generator_y = FieldElement::from_hex(GRUMPKIN_GENERATOR_Y)
+            .expect("ICE: the generator of Grumpkin should be a field element");
+        let generator = (
+            self.make_constant(Value::from(FieldElement::one())),
+            self.make_constant(Value::from(generator_y)),
+        );
+        let x = self.make_constant(0_u128.into());
+        let y = self.make_constant(0_u128.into());
+        let is_infinity = self.make_constant(1_u128.into());
+
+        // Double and add, from the most significant bit
+        let bit_count_register = self.make_constant(Value::from(bit_count));
+        self.loop_instruction(bit_count_register, |ctx, i| {
+            let is_finite = ctx.allocate_register();
+            ctx.not_instruction(is_infinity, 1, is_finite);
+            ctx.branch_instruction(is_finite, |ctx| ctx.grumpkin_double(x, y));
+            ctx.deallocate_register(is_finite);
+
+            let bit = ctx.allocate_register();
+            ctx.array_get(bits, i, bit);
+            ctx.branch_instruction(bit, |ctx| ctx.grumpkin_add(x, y, is_infinity, generator));
+            ctx.deallocate_register(bit);
+        });
+        self.deallocate_register(bit_count_register);
+
+        self.branch_instruction(is_infinity, |ctx| {
+            ctx.const_instruction(x, 0_u128.into());
+            ctx.const_instruction(y, 0_u128.into());
+        });
+        self.store_at(output_pointer, 0, x);
+        self.store_at(output_pointer, 1, y);
+
+        for register in [is_infinity, y, x, generator.1, generator.0, bits] {
+            self.deallocate_register(register);
+        }
+    }
+
+    /// Doubles the finite point (x, y) of Grumpkin in place.
+    fn grumpkin_double(&mut self, x: RegisterIndex, y: RegisterIndex) {
+        // λ = 3x² / 2y, x' = λ² - 2x, y' = λ(x - x') - y
+        let lambda = self.allocate_register();
+        let scratch = self.allocate_register();
+        self.field_op(x, BinaryFieldOp::Mul, x, lambda);
+        self.const_instruction(scratch, Value::from(FieldElement::from(3_u128)));
+        self.field_op(lambda, BinaryFieldOp::Mul, scratch, lambda);
+        self.field_op(y, BinaryFieldOp::Add, y, scratch);
+        self.field_op(lambda, BinaryFieldOp::Div, scratch, lambda);
+
+        let new_x = self.allocate_register();
+        self.field_op(lambda, BinaryFieldOp::Mul, lambda, new_x);
+        self.field_op(new_x, BinaryFieldOp::Sub, x, new_x);
+        self.field_op(new_x, BinaryFieldOp::Sub, x, new_x);
+
+        self.field_op(x, BinaryFieldOp::Sub, new_x, scratch);
+        self.field_op(lambda, BinaryFieldOp::Mul, scratch, scratch);
+        self.field_op(scratch, BinaryFieldOp::Sub, y, y);
+        self.mov_instruction(x, new_x);
+
+        self.deallocate_register(new_x);
+        self.deallocate_register(scratch);
+        self.deallocate_register(lambda);
+    }
+
+    /// Adds the finite point `other` to the point (x, y) of Grumpkin in place, which is the
+    /// point at infinity if `is_infinity` is set.
+    fn grumpkin_add(
+        &mut self,
+        x: RegisterIndex,
+        y: RegisterIndex,
+        is_infinity: RegisterIndex,
+        other: (RegisterIndex, RegisterIndex),
+    ) {
+        // Which case applies is decided up front, as the point is updated by the first one
+        let was_infinity = self.allocate_register();
+        let is_finite = self.allocate_register();
+        let same_x = self.allocate_register();
+        let same_y = self.allocate_register();
+        self.mov_instruction(was_infinity, is_infinity);
+        self.not_instruction(is_infinity, 1, is_finite);
+        self.field_op(x, BinaryFieldOp::Equals, other.0, same_x);
+        self.field_op(y, BinaryFieldOp::Equals, other.1, same_y);
+
+        let is_double = self.allocate_register();
+        self.integer_op(is_finite, BinaryIntOp::And, same_x, is_double, 1);
+        let is_opposite = self.allocate_register();
+        self.not_instruction(same_y, 1, is_opposite);
+        self.integer_op(is_double, BinaryIntOp::And, is_opposite, is_opposite, 1);
+        self.integer_op(is_double, BinaryIntOp::And, same_y, is_double, 1);
+        let is_general = self.allocate_register();
+        self.not_instruction(same_x, 1, is_general);
+        self.integer_op(is_finite, BinaryIntOp::And, is_general, is_general, 1);
+
+        self.branch_instruction(was_infinity, |ctx| {
+            ctx.mov_instruction(x, other.0);
+            ctx.mov_instruction(y, other.1);
+            ctx.const_instruction(is_infinity, 0_u128.into());
+        });
+        self.branch_instruction(is_double, |ctx| ctx.grumpkin_double(x, y));
+        self.branch_instruction(is_opposite, |ctx| {
+            ctx.const_instruction(is_infinity, 1_u128.into());
+        });
+        self.branch_instruction(is_general, |ctx| {
+            // λ = (y2 - y1) / (x2 - x1), x3 = λ² - x1 - x2, y3 = λ(x1 - x3) - y1
+            let lambda = ctx.allocate_register();
+            let scratch = ctx.allocate_register();
+            ctx.field_op(other.1, BinaryFieldOp::Sub, y, lambda);
+            ctx.field_op(other.0, BinaryFieldOp::Sub, x, scratch);
+            ctx.field_op(lambda, BinaryFieldOp::Div, scratch, lambda);
+
+            let new_x = ctx.allocate_register();
+            ctx.field_op(lambda, BinaryFieldOp::Mul, lambda, new_x);
+            ctx.field_op(new_x, BinaryFieldOp::Sub, x, new_x);
+            ctx.field_op(new_x, BinaryFieldOp::Sub, other.0, new_x);
+
+            ctx.field_op(x, BinaryFieldOp::Sub, new_x, scratch);
+            ctx.field_op(lambda, BinaryFieldOp::Mul, scratch, scratch);
+            ctx.field_op(scratch, BinaryFieldOp::Sub, y, y);
+            ctx.mov_instruction(x, new_x);
+
+            ctx.deallocate_register(new_x);
+            ctx.deallocate_register(scratch);
+            ctx.deallocate_register(lambda);
+        });
+
+        for register in
+            [is_general, is_opposite, is_double, same_y, same_x, is_finite, was_infinity]
+        {
+            self.deallocate_register(register);
+        }
+    }
+
+    /// Allocates an array of `padded_length` values holding the `input_length` values of the
+    /// array at `input_pointer` followed by zeros.
+    fn copy_to_padded_array(
+        &mut self,
+        input_pointer: RegisterIndex,
+        input_length: usize,
+        padded_length: usize,
+    ) -> RegisterIndex {
+        let array = self.allocate_register();
+        self.allocate_fixed_length_array(array, padded_length);
+        let input_length_register = self.make_constant(Value::from(input_length));
+        self.copy_array_instruction(input_pointer, array, input_length_register);
+        self.deallocate_register(input_length_register);
+        for i in input_length..padded_length {
+            self.store_constant_at(array, i, 0_u128.into());
+        }
+        array
+    }
+
+    /// Returns a register pointing to the `block`th block of `block_size` values of an array.
+    fn block_pointer(
+        &mut self,
+        array: RegisterIndex,
+        block: RegisterIndex,
+        block_size: usize,
+    ) -> RegisterIndex {
+        let block_pointer = self.allocate_register();
+        self.mov_instruction(block_pointer, block);
+        self.usize_op(block_pointer, BinaryIntOp::Mul, block_size);
+        self.integer_op(
+            block_pointer,
+            BinaryIntOp::Add,
+            array,
+            block_pointer,
+            BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+        );
+        block_pointer
+    }
+
+    /// Loads the `byte_count` bytes at `pointer + offset` into a single word.
+    fn load_word(
+        &mut self,
+        destination: RegisterIndex,
+        pointer: RegisterIndex,
+        offset: usize,
+        byte_count: usize,
+        big_endian: bool,
+    ) {
+        let bit_size = 8 * byte_count as u32;
+        let byte = self.allocate_register();
+        let shift = self.allocate_register();
+        self.const_instruction(destination, 0_u128.into());
+        for i in 0..byte_count {
+            let position = if big_endian { byte_count - 1 - i } else { i };
+            self.load_at(byte, pointer, offset + i);
+            self.const_instruction(shift, Value::from(8 * position));
+            self.integer_op(byte, BinaryIntOp::Shl, shift, byte, bit_size);
+            self.integer_op(destination, BinaryIntOp::Or, byte, destination, bit_size);
+        }
+        self.deallocate_register(shift);
+        self.deallocate_register(byte);
+    }
+
+    /// Stores the `byte_count` bytes of the word in `source` at `pointer + offset`.
+    fn store_word(
+        &mut self,
+        pointer: RegisterIndex,
+        offset: usize,
+        source: RegisterIndex,
+        byte_count: usize,
+        big_endian: bool,
+    ) {
+        let bit_size = 8 * byte_count as u32;
+        let byte = self.allocate_register();
+        let shift = self.allocate_register();
+        for i in 0..byte_count {
+            let position = if big_endian { byte_count - 1 - i } else { i };
+            self.const_instruction(shift, Value::from(8 * position));
+            self.integer_op(source, BinaryIntOp::Shr, shift, byte, bit_size);
+            self.cast_instruction(byte, byte, 8);
+            self.store_at(pointer, offset + i, byte);
+        }
+        self.deallocate_register(shift);
+        self.deallocate_register(byte);
+    }
+
+    /// Rotates the `bit_size` bits of `source` to the right by `rotation` bits.
+    fn rotate_right(
+        &mut self,
+        source: RegisterIndex,
+        rotation: u32,
+        destination: RegisterIndex,
+        bit_size: u32,
+    ) {
+        if rotation == 0 {
+            self.mov_instruction(destination, source);
+            return;
+        }
+        let shift = self.make_constant(Value::from(rotation as u128));
+        let low_bits = self.allocate_register();
+        self.integer_op(source, BinaryIntOp::Shr, shift, low_bits, bit_size);
+        self.const_instruction(shift, Value::from((bit_size - rotation) as u128));
+        self.integer_op(source, BinaryIntOp::Shl, shift, destination, bit_size);
+        self.integer_op(destination, BinaryIntOp::Or, low_bits, destination, bit_size);
+        self.deallocate_register(low_bits);
+        self.deallocate_register(shift);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::{
+        acir::brillig_vm::{BinaryFieldOp, RegisterIndex, VMStatus, Value},
+        FieldElement,
+    };
+
+    use crate::brillig::brillig_ir::{
+        tests::{constrain_array, make_array, new_context, run},
+        BrilligContext,
+    };
+
+    /// Constrains the register to hold the field element with the given hexadecimal value
+    fn constrain_field(context: &mut BrilligContext, register: RegisterIndex, expected: &str) {
+        let expected = FieldElement::from_hex(expected).unwrap();
+        let expected = context.make_constant(Value::from(expected));
+        let is_equal = context.allocate_register();
+        context.field_op(register, BinaryFieldOp::Equals, expected, is_equal);
+        context.constrain_instruction(is_equal);
+        context.deallocate_register(is_equal);
+        context.deallocate_register(expected);
+    }
+
+    /// Converts a hexadecimal digest into its bytes
+    fn digest(hex: &str) -> Vec<u128> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u128::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn bytes(message: &[u8]) -> Vec<u128> {
+        message.iter().map(|byte| *byte as u128).collect()
+    }
+
+    /// Checks the digest of each message computed by the given hash instruction
+    fn check_digests(
+        hash: impl Fn(&mut BrilligContext, RegisterIndex, usize, RegisterIndex),
+        cases: &[(&[u8], &str)],
+    ) {
+        for (message, expected) in cases {
+            let mut context = new_context();
+            let input = make_array(&mut context, &bytes(message));
+            let output = context.allocate_register();
+            context.allocate_fixed_length_array(output, 32);
+            hash(&mut context, input, message.len(), output);
+            constrain_array(&mut context, output, &digest(expected));
+            assert_eq!(run(context), VMStatus::Finished, "digest of {message:?}");
+        }
+    }
+
+    /// Bytes 0 to 199, spanning more than one block of each hash function
+    fn long_message() -> Vec<u8> {
+        (0..200).collect()
+    }
+
+    #[test]
+    fn sha256() {
+        let long_message = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+        check_digests(
+            BrilligContext::sha256_instruction,
+            &[
+                (b"", "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"),
+                (b"abc", "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+                (long_message, "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"),
+            ],
+        );
+    }
+
+    #[test]
+    fn blake2s() {
+        check_digests(
+            BrilligContext::blake2s_instruction,
+            &[
+                (b"", "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9"),
+                (b"abc", "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982"),
+                (
+                    &long_message()[..100],
+                    "81dcc3a505eace3f879d8f702776770f9df50e521d1428a85daf04f9ad2150e0",
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn keccak256() {
+        let keccak256 = |context: &mut BrilligContext, input, input_length: usize, output| {
+            let message_size = context.make_constant(Value::from(input_length));
+            context.keccak256_instruction(input, input_length, message_size, output);
+        };
+        check_digests(
+            keccak256,
+            &[
+                (b"", "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"),
+                (b"abc", "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"),
+                (
+                    &long_message(),
+                    "bfb0aa97863e797943cf7c33bb7e880bb4543f3d2703c0923c6901c2af57b890",
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn keccak256_of_message_prefix() {
+        // Only the first 150 bytes of the array are hashed
+        let mut context = new_context();
+        let input = make_array(&mut context, &bytes(&long_message()));
+        let message_size = context.make_constant(Value::from(150_usize));
+        let output = context.allocate_register();
+        context.allocate_fixed_length_array(output, 32);
+        context.keccak256_instruction(input, 200, message_size, output);
+
+        let expected = digest("51d16b7a39cd78b7c7ca02c8430383f0d1e481862e8d84fa3c4319186b04d4d7");
+        constrain_array(&mut context, output, &expected);
+        assert_eq!(run(context), VMStatus::Finished);
+    }
+
+    #[test]
+    fn hash_to_field_128_security() {
+        let cases: [(&[u128], &str); 2] = [
+            (&[], "0858dd4ab72d4041707096633f3299c1ceede5b7392ec3fb936122d53ed0eef7"),
+            (&[1, 2], "1466784a2149964c3bb5af60fb274365a73ced9e96459ea486fe330a3afa4177"),
+        ];
+        for (inputs, expected) in cases {
+            let mut context = new_context();
+            let input = make_array(&mut context, inputs);
+            let result = context.allocate_register();
+            context.hash_to_field_128_security_instruction(input, inputs.len(), result);
+            constrain_field(&mut context, result, expected);
+            assert_eq!(run(context), VMStatus::Finished, "hash of {inputs:?}");
+        }
+    }
+
+    #[test]
+    fn fixed_base_scalar_mul() {
+        let generator_y = super::GRUMPKIN_GENERATOR_Y;
+        let cases = [
+            (0, ("00", "00")),
+            (1, ("01", generator_y)),
+            (
+                2,
+                (
+                    "06ce1b0827aafa85ddeb49cdaa36306d19a74caa311e13d46d8bc688cdbffffe",
+                    "1c122f81a3a14964909ede0ba2a6855fc93faf6fa1a788bf467be7e7a43f80ac",
+                ),
+            ),
+            (
+                7,
+                (
+                    "0e602b9dd6a3e8d039a17f069add3f9c2a187a8f629a1de60a33a8067b9b2842",
+                    "14cc8e83df1b5cbb163bd2c94005cb0707fe570def5a165242b1c1419cb014cb",
+                ),
+            ),
+        ];
+        for (scalar, (x, y)) in cases {
+            let mut context = new_context();
+            let scalar_register = context.make_constant(Value::from(scalar as u128));
+            let output = context.allocate_register();
+            context.allocate_fixed_length_array(output, 2);
+            context.fixed_base_scalar_mul_instruction(scalar_register, output);
+
+            let coordinate = context.allocate_register();
+            context.load_at(coordinate, output, 0);
+            constrain_field(&mut context, coordinate, x);
+            context.load_at(coordinate, output, 1);
+            constrain_field(&mut context, coordinate, y);
+            assert_eq!(run(context), VMStatus::Finished, "multiplication by {scalar}");
+        }
+    }
+}
@@ -27,6 +27,8 @@ impl DebugToString for RegisterIndex {
     fn debug_to_string(&self) -> String {
         if *self == ReservedRegisters::stack_pointer() {
             "Stack".into()
+        } else if *self == ReservedRegisters::saved_registers_pointer() {
+            "SavedRegisters".into()
         } else {
             format!("R{}", self.to_usize())
         }
@@ -0,0 +1,306 @@
+//! Brillig implementations of the intrinsic functions which are not black box functions:
+//! radix decompositions and sorting. Range checks are also found here as they are needed
+//! for field elements which do not fit into Brillig integers.
+use acvm::{
+    acir::brillig_vm::{BinaryFieldOp, BinaryIntOp, RegisterIndex, Value},
+    FieldElement,
+};
+
+use super::{
+    BrilligContext, BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE, BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+};
+
+/// The bit size of the limbs a field element is split into when it is decomposed,
+/// chosen such that a limb multiplied by a radix fits into a Brillig integer.
+const LIMB_BIT_SIZE: u32 = 96;
+
+/// The number of limbs needed to hold a field element
+const NUM_LIMBS: usize = 3;
+
+impl BrilligContext {
+    /// Splits the field element in `source` into limbs of `LIMB_BIT_SIZE` bits, starting
+    /// from the least significant one.
+    ///
+    /// Field elements may not fit into a Brillig integer, so they are split using a cast to
+    /// get the lowest bits and an exact field division to shift the remaining bits down.
+    pub(crate) fn field_to_limbs(&mut self, source: RegisterIndex) -> [RegisterIndex; NUM_LIMBS] {
+        let limbs = [self.allocate_register(), self.allocate_register(), self.allocate_register()];
+        let two_pow_limb_bit_size =
+            self.make_constant(Value::from(FieldElement::from(1_u128 << LIMB_BIT_SIZE)));
+
+        let remaining = self.allocate_register();
+        self.mov_instruction(remaining, source);
+        for (i, limb) in limbs.iter().enumerate() {
+            if i == NUM_LIMBS - 1 {
+                self.mov_instruction(*limb, remaining);
+            } else {
+                self.cast_instruction(*limb, remaining, LIMB_BIT_SIZE);
+                self.field_op(remaining, BinaryFieldOp::Sub, *limb, remaining);
+                self.field_op(remaining, BinaryFieldOp::Div, two_pow_limb_bit_size, remaining);
+            }
+        }
+        self.deallocate_register(remaining);
+        self.deallocate_register(two_pow_limb_bit_size);
+        limbs
+    }
+
+    /// Decomposes the field element in `source` into `limb_count` digits in the radix held in
+    /// `radix`, writing them into the array at `destination_pointer` from the least significant
+    /// one, or from the most significant one if `big_endian` is set.
+    ///
+    /// As in constrained code, the program fails if the value does not fit into `limb_count`
+    /// digits.
+    pub(crate) fn radix_decompose_instruction(
+        &mut self,
+        source: RegisterIndex,
+        radix: RegisterIndex,
+        destination_pointer: RegisterIndex,
+        limb_count: usize,
+        big_endian: bool,
+    ) {
+        let limbs = self.field_to_limbs(source);
+        let two_pow_limb_bit_size = self.make_constant(Value::from(1_u128 << LIMB_BIT_SIZE));
+        let limb_count_register = self.make_constant(Value::from(limb_count));
+
+        self.loop_instruction(limb_count_register, |ctx, iterator| {
+            // Divide the value held in the limbs by the radix, from the most significant limb,
+            // carrying the remainder of each division into the next limb. The final remainder
+            // is the next digit.
+            let remainder = ctx.make_constant(0_u128.into());
+            let dividend = ctx.allocate_register();
+            for limb in limbs.iter().rev() {
+                ctx.integer_op(
+                    remainder,
+                    BinaryIntOp::Mul,
+                    two_pow_limb_bit_size,
+                    dividend,
+                    BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE,
+                );
+                ctx.integer_op(
+                    dividend,
+                    BinaryIntOp::Add,
+                    *limb,
+                    dividend,
+                    BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE,
+                );
+                ctx.integer_op(
+                    dividend,
+                    BinaryIntOp::UnsignedDiv,
+                    radix,
+                    *limb,
+                    BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE,
+                );
+                ctx.integer_op(
+                    *limb,
+                    BinaryIntOp::Mul,
+                    radix,
+                    remainder,
+                    BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE,
+                );
+                ctx.integer_op(
+                    dividend,
+                    BinaryIntOp::Sub,
+                    remainder,
+                    remainder,
+                    BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE,
+                );
+            }
+
+            let index = ctx.allocate_register();
+            if big_endian {
+                ctx.const_instruction(index, Value::from(limb_count - 1));
+                ctx.integer_op(
+                    index,
+                    BinaryIntOp::Sub,
+                    iterator,
+                    index,
+                    BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+                );
+            } else {
+                ctx.mov_instruction(index, iterator);
+            }
+            ctx.array_set(destination_pointer, index, remainder);
+
+            ctx.deallocate_register(index);
+            ctx.deallocate_register(dividend);
+            ctx.deallocate_register(remainder);
+        });
+
+        // Whatever is left of the value must be zero
+        let is_zero = self.allocate_register();
+        self.integer_op(
+            limbs[0],
+            BinaryIntOp::Add,
+            limbs[1],
+            is_zero,
+            BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE,
+        );
+        self.integer_op(
+            is_zero,
+            BinaryIntOp::Add,
+            limbs[2],
+            is_zero,
+            BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE,
+        );
+        let zero = self.make_constant(0_u128.into());
+        self.integer_op(
+            is_zero,
+            BinaryIntOp::Equals,
+            zero,
+            is_zero,
+            BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE,
+        );
+        self.constrain_instruction(is_zero);
+
+        self.deallocate_register(zero);
+        self.deallocate_register(is_zero);
+        self.deallocate_register(limb_count_register);
+        self.deallocate_register(two_pow_limb_bit_size);
+        for limb in limbs {
+            self.deallocate_register(limb);
+        }
+    }
+
+    /// Computes `lhs < rhs` into `result`, for unsigned values of the given bit size.
+    ///
+    /// Values of more than `BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE` bits are compared limb by limb.
+    pub(crate) fn less_than_instruction(
+        &mut self,
+        lhs: RegisterIndex,
+        rhs: RegisterIndex,
+        result: RegisterIndex,
+        bit_size: u32,
+    ) {
+        if bit_size <= BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE {
+            self.integer_op(lhs, BinaryIntOp::LessThan, rhs, result, bit_size);
+            return;
+        }
+
+        let lhs_limbs = self.field_to_limbs(lhs);
+        let rhs_limbs = self.field_to_limbs(rhs);
+        let limb_less_than = self.allocate_register();
+        let limb_equals = self.allocate_register();
+
+        // Starting from the least significant limb:
+        // result = lhs_limb < rhs_limb || (lhs_limb == rhs_limb && result)
+        self.const_instruction(result, 0_u128.into());
+        for (lhs_limb, rhs_limb) in lhs_limbs.iter().zip(rhs_limbs.iter()) {
+            self.integer_op(
+                *lhs_limb,
+                BinaryIntOp::LessThan,
+                *rhs_limb,
+                limb_less_than,
+                BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE,
+            );
+            self.integer_op(
+                *lhs_limb,
+                BinaryIntOp::Equals,
+                *rhs_limb,
+                limb_equals,
+                BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE,
+            );
+            self.integer_op(limb_equals, BinaryIntOp::And, result, result, 1);
+            self.integer_op(limb_less_than, BinaryIntOp::Or, result, result, 1);
+        }
+
+        self.deallocate_register(limb_equals);
+        self.deallocate_register(limb_less_than);
+        for limb in lhs_limbs.into_iter().chain(rhs_limbs) {
+            self.deallocate_register(limb);
+        }
+    }
+
+    /// Makes the program fail if the value in `value` does not fit into `bit_size` bits.
+    pub(crate) fn range_check_instruction(&mut self, value: RegisterIndex, bit_size: u32) {
+        if bit_size >= FieldElement::max_num_bits() {
+            return;
+        }
+
+        let fits = self.allocate_register();
+        if bit_size <= BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE {
+            // The value fits iff truncating it leaves it unchanged
+            self.cast_instruction(fits, value, bit_size);
+            self.field_op(fits, BinaryFieldOp::Equals, value, fits);
+        } else {
+            // Each limb must fit into the bits of the value which fall into it
+            let limbs = self.field_to_limbs(value);
+            let limb_fits = self.allocate_register();
+            self.const_instruction(fits, 1_u128.into());
+            for (i, limb) in limbs.iter().enumerate() {
+                let limb_bit_size = bit_size.saturating_sub(LIMB_BIT_SIZE * i as u32);
+                if limb_bit_size >= LIMB_BIT_SIZE {
+                    continue;
+                }
+                let max = self.make_constant(Value::from(1_u128 << limb_bit_size));
+                self.integer_op(
+                    *limb,
+                    BinaryIntOp::LessThan,
+                    max,
+                    limb_fits,
+                    BRILLIG_INTEGER_ARITHMETIC_BIT_SIZE,
+                );
+                self.integer_op(limb_fits, BinaryIntOp::And, fits, fits, 1);
+                self.deallocate_register(max);
+            }
+            self.deallocate_register(limb_fits);
+            for limb in limbs {
+                self.deallocate_register(limb);
+            }
+        }
+        self.constrain_instruction(fits);
+        self.deallocate_register(fits);
+    }
+
+    /// Writes the `item_count` values of the array at `source_pointer` into the array at
+    /// `destination_pointer`, sorted in increasing order as unsigned values of `bit_size` bits.
+    pub(crate) fn sort_instruction(
+        &mut self,
+        source_pointer: RegisterIndex,
+        destination_pointer: RegisterIndex,
+        item_count: usize,
+        bit_size: u32,
+    ) {
+        let item_count_register = self.make_constant(Value::from(item_count));
+        self.copy_array_instruction(source_pointer, destination_pointer, item_count_register);
+
+        // Bubble sort: after the i'th pass, the last i items are sorted.
+        self.loop_instruction(item_count_register, |ctx, pass| {
+            let unsorted_pairs = ctx.make_constant(Value::from(item_count));
+            ctx.usize_op(unsorted_pairs, BinaryIntOp::Sub, 1);
+            ctx.integer_op(
+                unsorted_pairs,
+                BinaryIntOp::Sub,
+                pass,
+                unsorted_pairs,
+                BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
+            );
+
+            ctx.loop_instruction(unsorted_pairs, |ctx, index| {
+                let next_index = ctx.allocate_register();
+                ctx.mov_instruction(next_index, index);
+                ctx.usize_op(next_index, BinaryIntOp::Add, 1);
+
+                let current = ctx.allocate_register();
+                let next = ctx.allocate_register();
+                ctx.array_get(destination_pointer, index, current);
+                ctx.array_get(destination_pointer, next_index, next);
+
+                let is_out_of_order = ctx.allocate_register();
+                ctx.less_than_instruction(next, current, is_out_of_order, bit_size);
+                ctx.branch_instruction(is_out_of_order, |ctx| {
+                    ctx.array_set(destination_pointer, index, next);
+                    ctx.array_set(destination_pointer, next_index, current);
+                });
+
+                ctx.deallocate_register(is_out_of_order);
+                ctx.deallocate_register(next);
+                ctx.deallocate_register(current);
+                ctx.deallocate_register(next_index);
+            });
+
+            ctx.deallocate_register(unsorted_pairs);
+        });
+
+        self.deallocate_register(item_count_register);
+    }
+}
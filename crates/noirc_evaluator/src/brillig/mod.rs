@@ -5,10 +5,20 @@ use self::{
     brillig_gen::{brillig_fn::FunctionContext, convert_ssa_function},
     brillig_ir::artifact::{BrilligArtifact, Label},
 };
-use crate::ssa_refactor::{
-    ir::function::{Function, FunctionId, RuntimeType},
-    ssa_gen::Ssa,
+use crate::{
+    errors::{RuntimeError, RuntimeErrorKind},
+    ssa_refactor::{
+        ir::{
+            dfg::{CallStack, DataFlowGraph},
+            function::{Function, FunctionId, RuntimeType},
+            instruction::{Instruction, Intrinsic},
+            types::Type,
+            value::{Value, ValueId},
+        },
+        ssa_gen::Ssa,
+    },
 };
+use acvm::acir::BlackBoxFunc;
 use std::collections::HashMap;
 
 /// Context structure for the brillig pass.
@@ -17,23 +27,32 @@ use std::collections::HashMap;
 pub struct Brillig {
     /// Maps SSA function labels to their brillig artifact
     ssa_function_to_brillig: HashMap<FunctionId, BrilligArtifact>,
+    /// Maps SSA functions to the names of the source functions they were compiled from
+    ssa_function_names: HashMap<FunctionId, String>,
 }
 
 impl Brillig {
     /// Compiles a function into brillig and store the compilation artifacts
-    pub(crate) fn compile(&mut self, func: &Function) {
-        let obj = convert_ssa_function(func);
+    pub(crate) fn compile(&mut self, func: &Function, allow_log_ops: bool) {
+        let obj = convert_ssa_function(func, allow_log_ops);
         self.ssa_function_to_brillig.insert(func.id(), obj);
+        self.ssa_function_names.insert(func.id(), func.name().to_string());
     }
 
     /// Finds a brillig function artifact by its function label
     pub(crate) fn find_by_function_label(&self, function_label: Label) -> Option<&BrilligArtifact> {
-        self.ssa_function_to_brillig.iter().find_map(|(function_id, obj)| {
-            if FunctionContext::function_id_to_function_label(*function_id) == function_label {
-                Some(obj)
-            } else {
-                None
-            }
+        self.find_function_id(&function_label).map(|function_id| &self[function_id])
+    }
+
+    /// Finds the name of a brillig function by its function label
+    pub(crate) fn find_name_by_function_label(&self, function_label: &Label) -> Option<&str> {
+        let function_id = self.find_function_id(function_label)?;
+        self.ssa_function_names.get(&function_id).map(String::as_str)
+    }
+
+    fn find_function_id(&self, function_label: &Label) -> Option<FunctionId> {
+        self.ssa_function_to_brillig.keys().copied().find(|function_id| {
+            &FunctionContext::function_id_to_function_label(*function_id) == function_label
         })
     }
 }
@@ -47,16 +66,103 @@ impl std::ops::Index<FunctionId> for Brillig {
 
 impl Ssa {
     /// Generate compilation artifacts for brillig functions
-    pub(crate) fn to_brillig(&self) -> Brillig {
+    ///
+    /// Calls to `println` are only compiled if `allow_log_ops` is set.
+    pub(crate) fn to_brillig(&self, allow_log_ops: bool) -> Brillig {
         // Collect all of the brillig functions
         let brillig_functions =
             self.functions.values().filter(|func| func.runtime() == RuntimeType::Brillig);
 
         let mut brillig = Brillig::default();
         for brillig_function in brillig_functions {
-            brillig.compile(brillig_function);
+            brillig.compile(brillig_function, allow_log_ops);
         }
 
         brillig
     }
+    /// Returns an error if an unconstrained function uses a feature which cannot be compiled
+    /// into Brillig:
+    /// - Recursive aggregation, which can only be computed while generating a proof.
+    /// - Function values other than the function called by a call instruction, as Brillig
+    ///   functions can only be called directly.
+    pub(crate) fn check_for_unsupported_brillig(&self) -> Result<(), RuntimeError> {
+        let brillig_functions =
+            self.functions.values().filter(|function| function.runtime() == RuntimeType::Brillig);
+        for function in brillig_functions {
+            let dfg = &function.dfg;
+            for block in function.reachable_blocks() {
+                let call_stack = match dfg[block].instructions().first() {
+                    Some(instruction) => dfg.get_call_stack(*instruction),
+                    None => CallStack::new(),
+                };
+                for parameter in dfg.block_parameters(block) {
+                    check_not_function_value(dfg, *parameter, &call_stack)?;
+                }
+
+                for instruction_id in dfg[block].instructions() {
+                    let call_stack = dfg.get_call_stack(*instruction_id);
+                    for result in dfg.instruction_results(*instruction_id) {
+                        check_not_function_value(dfg, *result, &call_stack)?;
+                    }
+
+                    match &dfg[*instruction_id] {
+                        Instruction::Call { func, arguments } => {
+                            check_callee(dfg, *func, &call_stack)?;
+                            for argument in arguments {
+                                check_not_function_value(dfg, *argument, &call_stack)?;
+                            }
+                        }
+                        instruction => {
+                            let mut result = Ok(());
+                            instruction.for_each_value(|value| {
+                                if result.is_ok() {
+                                    result = check_not_function_value(dfg, value, &call_stack);
+                                }
+                            });
+                            result?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns an error if the called function is not known at compile time, or is the recursive
+/// aggregation black box function.
+fn check_callee(
+    dfg: &DataFlowGraph,
+    func: ValueId,
+    call_stack: &CallStack,
+) -> Result<(), RuntimeError> {
+    match &dfg[func] {
+        Value::Intrinsic(Intrinsic::BlackBox(black_box @ BlackBoxFunc::RecursiveAggregation)) => {
+            let message = format!(
+                "The black box function `{black_box}` is not supported in unconstrained functions"
+            );
+            Err(unsupported_brillig_error(message, call_stack))
+        }
+        Value::Function(_) | Value::Intrinsic(_) | Value::ForeignFunction(_) => Ok(()),
+        _ => check_not_function_value(dfg, func, call_stack),
+    }
+}
+
+/// Returns an error if the value is a function value.
+fn check_not_function_value(
+    dfg: &DataFlowGraph,
+    value: ValueId,
+    call_stack: &CallStack,
+) -> Result<(), RuntimeError> {
+    if dfg.type_of_value(value) == Type::Function {
+        let message = "Function values are not supported in unconstrained functions, \
+            functions may only be called directly"
+            .to_string();
+        return Err(unsupported_brillig_error(message, call_stack));
+    }
+    Ok(())
+}
+
+fn unsupported_brillig_error(message: String, call_stack: &CallStack) -> RuntimeError {
+    RuntimeError::new(RuntimeErrorKind::UnstructuredError { message }, call_stack.last().copied())
 }
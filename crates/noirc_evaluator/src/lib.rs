@@ -3,7 +3,7 @@
 #![warn(unreachable_pub)]
 #![warn(clippy::semicolon_if_nothing_returned)]
 
-mod errors;
+pub mod errors;
 mod ssa;
 
 // SSA code to create the SSA based IR
@@ -146,7 +146,7 @@ impl Evaluator {
         self.return_is_distinct =
             program.return_distinctness == noirc_abi::AbiDistinctness::Distinct;
         let mut ir_gen = IrGenerator::new(program);
-        self.parse_abi_alt(&mut ir_gen);
+        self.parse_abi_alt(&mut ir_gen)?;
 
         // Now call the main function
         ir_gen.ssa_gen_main()?;
@@ -189,6 +189,9 @@ impl Evaluator {
                 vec![witness]
             }
             AbiType::Array { length, typ } => {
+                if !is_scalar(typ) {
+                    return Err(nested_abi_type_error(param_type));
+                }
                 let witnesses = self.generate_array_witnesses(length, typ)?;
 
                 ir_gen.abi_array(name, Some(def), typ.as_ref(), *length, &witnesses);
@@ -265,6 +268,7 @@ impl Evaluator {
                 ir_gen.abi_array(name, Some(def), &typ, *length, &witnesses);
                 witnesses
             }
+            AbiType::Tuple { .. } => return Err(nested_abi_type_error(param_type)),
         };
 
         if param_visibility == &AbiVisibility::Public {
@@ -297,6 +301,9 @@ impl Evaluator {
                     struct_witnesses.insert(name.clone(), vec![witness]);
                 }
                 AbiType::Array { length, typ } => {
+                    if !is_scalar(typ) {
+                        return Err(nested_abi_type_error(typ));
+                    }
                     let internal_arr_witnesses = self.generate_array_witnesses(length, typ)?;
                     struct_witnesses.insert(name.clone(), internal_arr_witnesses);
                 }
@@ -312,6 +319,7 @@ impl Evaluator {
                     let internal_str_witnesses = self.generate_array_witnesses(length, &typ)?;
                     struct_witnesses.insert(name.clone(), internal_str_witnesses);
                 }
+                AbiType::Tuple { .. } => return Err(nested_abi_type_error(typ)),
             }
         }
         Ok(())
@@ -341,7 +349,7 @@ impl Evaluator {
     /// Noted in the noirc_abi, it is possible to convert Toml -> NoirTypes
     /// However, this intermediate representation is useful as it allows us to have
     /// intermediate Types which the core type system does not know about like Strings.
-    fn parse_abi_alt(&mut self, ir_gen: &mut IrGenerator) {
+    fn parse_abi_alt(&mut self, ir_gen: &mut IrGenerator) -> Result<(), RuntimeErrorKind> {
         let main = ir_gen.program.main_mut();
         let main_params = std::mem::take(&mut main.parameters);
         let abi_params = std::mem::take(&mut ir_gen.program.main_function_signature.0);
@@ -351,12 +359,25 @@ impl Evaluator {
         for ((param_id, _, param_name, _), abi_param) in main_params.iter().zip(abi_params) {
             assert_eq!(param_name, &abi_param.name);
             let def = Definition::Local(*param_id);
-            self.param_to_var(param_name, def, &abi_param.typ, &abi_param.visibility, ir_gen)
-                .unwrap();
+            self.param_to_var(param_name, def, &abi_param.typ, &abi_param.visibility, ir_gen)?;
         }
 
         // Store the number of witnesses used to represent the types
         // in the ABI
         self.num_witnesses_abi_len = self.current_witness_index as usize;
+        Ok(())
     }
 }
+
+/// Returns true for the types represented by a single witness
+fn is_scalar(typ: &AbiType) -> bool {
+    matches!(typ, AbiType::Field | AbiType::Integer { .. } | AbiType::Boolean)
+}
+
+/// Tuples and arrays of non-scalar elements are only supported as parameters to main by the
+/// experimental SSA.
+fn nested_abi_type_error(typ: &AbiType) -> RuntimeErrorKind {
+    RuntimeErrorKind::Unimplemented(format!(
+        "Parameters of type {typ:?} are only supported by the experimental SSA"
+    ))
+}
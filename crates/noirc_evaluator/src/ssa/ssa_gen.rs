@@ -89,6 +89,9 @@ impl IrGenerator {
             noirc_abi::AbiType::String { .. } => {
                 unreachable!("array of strings are not supported for now")
             }
+            noirc_abi::AbiType::Tuple { .. } => {
+                unreachable!("array of tuples are not supported for now")
+            }
         }
     }
 
@@ -581,6 +584,16 @@ impl IrGenerator {
                 self.ssa_gen_expression(expr.as_ref())?;
                 Ok(Value::dummy())
             }
+            Expression::While(while_expr) => Err(RuntimeError::new(
+                RuntimeErrorKind::Unimplemented(
+                    "While loops are only supported by the experimental SSA".to_string(),
+                ),
+                Some(while_expr.location),
+            )),
+            Expression::Break | Expression::Continue => Err(RuntimeErrorKind::Unimplemented(
+                "`break` and `continue` are only supported by the experimental SSA".to_string(),
+            )
+            .into()),
         }
     }
 
@@ -7,64 +7,84 @@
 //! This module heavily borrows from Cranelift
 #![allow(dead_code)]
 
+use std::path::{Path, PathBuf};
+
 use crate::errors::RuntimeError;
 use acvm::acir::circuit::{Circuit, PublicInputs};
 use noirc_abi::Abi;
+use noirc_errors::DebugInfo;
+use thiserror::Error;
 
 use noirc_frontend::monomorphization::ast::Program;
 
-use self::{abi_gen::gen_abi, acir_gen::GeneratedAcir, ir::function::RuntimeType, ssa_gen::Ssa};
+use self::{
+    abi_gen::gen_abi, acir_gen::GeneratedAcir, ir::function::RuntimeType, pipeline::PassRunner,
+};
 
 mod abi_gen;
 mod acir_gen;
 pub mod ir;
 mod opt;
+mod parser;
+mod pipeline;
 mod ssa_builder;
 pub mod ssa_gen;
+mod validation;
+
+pub use parser::SsaParseError;
+pub use pipeline::SsaOptions;
+pub use validation::SsaValidationError;
 
 /// Optimize the given program by converting it into SSA
 /// form and performing optimizations there. When finished,
 /// convert the final SSA into ACIR and return it.
+///
+/// The passes run, and whether the SSA is printed, validated or dumped after each of them, are
+/// configured by the `SsaOptions`.
 pub(crate) fn optimize_into_acir(
     program: Program,
     allow_log_ops: bool,
-    print_ssa_passes: bool,
-) -> GeneratedAcir {
+    options: &SsaOptions,
+) -> Result<GeneratedAcir, RuntimeError> {
     let abi_distinctness = program.return_distinctness;
-    let mut ssa = ssa_gen::generate_ssa(program).print(print_ssa_passes, "Initial SSA:");
-    let brillig = ssa.to_brillig();
+    let function_name = program.main().name.clone();
+    let passes = options.pipeline()?;
+
+    let mut ssa = ssa_gen::generate_ssa(program);
+    ssa.check_for_vecs_in_acir()?;
+    ssa.check_for_unsupported_brillig()?;
+    let mut runner = PassRunner::new(options, &function_name, &ssa)?;
+    let brillig = ssa.to_brillig(allow_log_ops);
     if let RuntimeType::Acir = ssa.main().runtime() {
-        ssa = ssa
-            .inline_functions()
-            .print(print_ssa_passes, "After Inlining:")
-            .unroll_loops()
-            .print(print_ssa_passes, "After Unrolling:")
-            .simplify_cfg()
-            .print(print_ssa_passes, "After Simplifying:")
-            .flatten_cfg()
-            .print(print_ssa_passes, "After Flattening:")
-            .mem2reg()
-            .print(print_ssa_passes, "After Mem2Reg:")
-            .fold_constants()
-            .print(print_ssa_passes, "After Constant Folding:")
-            .dead_instruction_elimination()
-            .print(print_ssa_passes, "After Dead Instruction Elimination:");
+        for pass in passes {
+            ssa = runner.run_pass(ssa, pass)?;
+        }
     }
+    runner.finish()?;
     ssa.into_acir(brillig, abi_distinctness, allow_log_ops)
 }
 
 /// Compiles the Program into ACIR and applies optimizations to the arithmetic gates
 /// This is analogous to `ssa:create_circuit` and this method is called when one wants
 /// to use the new ssa module to process Noir code.
-// TODO: This no longer needs to return a result, but it is kept to match the signature of `create_circuit`
+///
+/// Alongside the circuit, this returns the source locations each of its opcodes were generated from
+/// and the names of the functions linked into its brillig opcodes.
 pub fn experimental_create_circuit(
     program: Program,
-    enable_logging: bool,
     show_output: bool,
-) -> Result<(Circuit, Abi), RuntimeError> {
+    options: &SsaOptions,
+) -> Result<(Circuit, DebugInfo, Abi), RuntimeError> {
     let func_sig = program.main_function_signature.clone();
-    let GeneratedAcir { current_witness_index, opcodes, return_witnesses } =
-        optimize_into_acir(program, show_output, enable_logging);
+    let function_name = program.main().name.clone();
+    let GeneratedAcir {
+        current_witness_index,
+        opcodes,
+        return_witnesses,
+        locations,
+        brillig_function_names,
+        ..
+    } = optimize_into_acir(program, show_output, options)?;
 
     let abi = gen_abi(func_sig, return_witnesses.clone());
     let public_abi = abi.clone().public_abi();
@@ -74,15 +94,54 @@ pub fn experimental_create_circuit(
     let return_values = PublicInputs(return_witnesses.into_iter().collect());
 
     let circuit = Circuit { current_witness_index, opcodes, public_parameters, return_values };
+    if let Some(path) = options.dump_path(&function_name, "acir.txt") {
+        pipeline::write_dump(&path, &circuit.to_string())?;
+    }
+
+    let locations = locations
+        .into_iter()
+        .map(|(opcode_index, call_stack)| (opcode_index, call_stack.into_iter().collect()))
+        .collect();
+    let debug_info = DebugInfo::new(locations, brillig_function_names);
 
-    Ok((circuit, abi))
+    Ok((circuit, debug_info, abi))
 }
 
-impl Ssa {
-    fn print(self, print_ssa_passes: bool, msg: &str) -> Ssa {
-        if print_ssa_passes {
-            println!("{msg}\n{self}");
-        }
-        self
+/// An error encountered by `run_ssa_passes`.
+#[derive(Debug, Error)]
+pub enum SsaPassesError {
+    #[error("Could not read {}: {error}", path.display())]
+    Io { path: PathBuf, error: std::io::Error },
+    #[error("Could not parse {}: {error}", path.display())]
+    Parse { path: PathBuf, error: SsaParseError },
+    #[error("Unknown SSA pass `{0}`, expected one of: {}", pipeline::pass_names())]
+    UnknownPass(String),
+    #[error("The SSA is invalid after {pass}: {error}")]
+    Invalid { pass: String, error: SsaValidationError },
+}
+
+/// Parses the SSA in the `.ssa` file at the given path, runs the named passes over it in order
+/// and returns the resulting SSA as text.
+///
+/// The file is expected to be in the format printed by `--show-ssa`. The SSA is validated after
+/// parsing it and after each pass.
+pub fn run_ssa_passes(path: &Path, passes: &[&str]) -> Result<String, SsaPassesError> {
+    if let Some(unknown_pass) = passes.iter().find(|pass| pipeline::find_pass(pass).is_none()) {
+        return Err(SsaPassesError::UnknownPass(unknown_pass.to_string()));
+    }
+
+    let source = std::fs::read_to_string(path)
+        .map_err(|error| SsaPassesError::Io { path: path.to_path_buf(), error })?;
+    let mut ssa = parser::parse_ssa(&source)
+        .map_err(|error| SsaPassesError::Parse { path: path.to_path_buf(), error })?;
+
+    ssa.validate()
+        .map_err(|error| SsaPassesError::Invalid { pass: "parsing".to_string(), error })?;
+
+    for pass in passes {
+        ssa = ssa.run_pass(pass, false);
+        ssa.validate()
+            .map_err(|error| SsaPassesError::Invalid { pass: pass.to_string(), error })?;
     }
+    Ok(ssa.to_string())
 }
@@ -1,4 +1,5 @@
 pub(crate) mod acir_variable;
 pub(crate) mod errors;
 pub(crate) mod generated_acir;
+pub(crate) mod memory;
 pub(crate) mod sort;
@@ -1,6 +1,7 @@
 use super::{errors::AcirGenError, generated_acir::GeneratedAcir};
 use crate::brillig::brillig_gen::brillig_directive;
 use crate::ssa_refactor::acir_gen::AcirValue;
+use crate::ssa_refactor::ir::dfg::CallStack;
 use crate::ssa_refactor::ir::types::Type as SsaType;
 use crate::ssa_refactor::ir::{instruction::Endian, types::NumericType};
 use acvm::acir::{
@@ -10,14 +11,14 @@ use acvm::acir::{
 
 use acvm::{
     acir::{
-        circuit::opcodes::FunctionInput,
+        circuit::opcodes::{FunctionInput, MemOp},
         native_types::{Expression, Witness},
         BlackBoxFunc,
     },
     FieldElement,
 };
 use iter_extended::vecmap;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::{borrow::Cow, hash::Hash};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -162,6 +163,7 @@ impl AcirContext {
         let results = self.brillig(
             None,
             inverse_code,
+            BTreeMap::new(),
             vec![AcirValue::Var(var, field_type.clone())],
             vec![field_type],
         );
@@ -389,28 +391,65 @@ impl AcirContext {
     /// Returns an `AcirVar` that is constrained to be `lhs << rhs`.
     ///
     /// We convert left shifts to multiplications, so this is equivalent to
-    /// `lhs * 2^rhs`.
-    ///
-    /// We currently require `rhs` to be a constant
-    /// however this can be extended, see #1478.
+    /// `lhs * 2^rhs`. Shifting by `bit_size` or more bits moves every bit out of
+    /// the integer, so the shift amount is clamped to `bit_size` and the
+    /// truncation following the shift then results in zero.
     pub(crate) fn shift_left_var(
         &mut self,
         lhs: AcirVar,
         rhs: AcirVar,
-        _typ: AcirType,
+        typ: AcirType,
     ) -> Result<AcirVar, AcirGenError> {
-        let rhs_data = &self.vars[&rhs];
-
-        // Compute 2^{rhs}
-        let two_pow_rhs = match rhs_data.as_constant() {
-            Some(exponent) => FieldElement::from(2_i128).pow(&exponent),
-            None => unimplemented!("rhs must be a constant when doing a right shift"),
-        };
-        let two_pow_rhs_var = self.add_constant(two_pow_rhs);
+        let bit_size = typ.bit_size();
+        let two_pow_rhs_var = self.clamped_two_pow_var(rhs, bit_size, bit_size)?;
 
         self.mul_var(lhs, two_pow_rhs_var)
     }
 
+    /// Returns an `AcirVar` that is constrained to be `2^min(exponent, max_exponent)`.
+    ///
+    /// `exponent` is expected to fit in `exponent_bit_size` bits. When it is not a constant,
+    /// it is clamped to `max_exponent` and decomposed into bits `b_i`, so that the power is
+    /// the product of `1 + b_i * (2^{2^i} - 1)`.
+    fn clamped_two_pow_var(
+        &mut self,
+        exponent: AcirVar,
+        exponent_bit_size: u32,
+        max_exponent: u32,
+    ) -> Result<AcirVar, AcirGenError> {
+        if let Some(exponent) = self.vars[&exponent].as_constant() {
+            let exponent = std::cmp::min(exponent.to_u128(), max_exponent as u128);
+            let two_pow_exponent = FieldElement::from(2_i128).pow(&FieldElement::from(exponent));
+            return Ok(self.add_constant(two_pow_exponent));
+        }
+
+        // clamped_exponent = exponent + (exponent >= max_exponent) * (max_exponent - exponent)
+        let max_exponent_var = self.add_constant(FieldElement::from(max_exponent as u128));
+        let exceeds_max =
+            self.more_than_eq_var(exponent, max_exponent_var, exponent_bit_size, None)?;
+        let excess = self.sub_var(max_exponent_var, exponent)?;
+        let clamp = self.mul_var(exceeds_max, excess)?;
+        let clamped_exponent = self.add_var(exponent, clamp)?;
+
+        let exponent_bits_count = u32::BITS - max_exponent.leading_zeros();
+        let clamped_exponent_expr = self.vars[&clamped_exponent].to_expression();
+        let exponent_bits =
+            self.acir_ir.radix_le_decompose(&clamped_exponent_expr, 2, exponent_bits_count, 1)?;
+
+        let one = self.add_constant(FieldElement::one());
+        let mut two_pow_exponent = one;
+        let mut two_pow_two_pow_i = FieldElement::from(2_i128);
+        for bit in exponent_bits {
+            let bit_var = self.add_data(AcirVarData::Witness(bit));
+            let factor_minus_one = self.add_constant(two_pow_two_pow_i - FieldElement::one());
+            let factor_minus_one = self.mul_var(bit_var, factor_minus_one)?;
+            let factor = self.add_var(factor_minus_one, one)?;
+            two_pow_exponent = self.mul_var(two_pow_exponent, factor)?;
+            two_pow_two_pow_i = two_pow_two_pow_i * two_pow_two_pow_i;
+        }
+        Ok(two_pow_exponent)
+    }
+
     /// Returns the quotient and remainder such that lhs = rhs * quotient + remainder
     fn euclidean_division_var(
         &mut self,
@@ -480,29 +519,83 @@ impl AcirContext {
     /// Returns an `AcirVar` that is constrained to be `lhs >> rhs`.
     ///
     /// We convert right shifts to divisions, so this is equivalent to
-    /// `lhs / 2^rhs`.
+    /// `lhs / 2^rhs` rounded down. As for left shifts, the shift amount is clamped
+    /// to `bit_size`.
     ///
-    /// We currently require `rhs` to be a constant
-    /// however this can be extended, see #1478.
-    ///
-    /// This code is doing a field division instead of an integer division,
-    /// see #1479 about how this is expected to change.
+    /// Signed integers are shifted arithmetically: a negative `lhs` is shifted as
+    /// `!((!lhs) >> rhs)` so that the sign bit is copied into the vacated bits.
     pub(crate) fn shift_right_var(
         &mut self,
         lhs: AcirVar,
         rhs: AcirVar,
         typ: AcirType,
     ) -> Result<AcirVar, AcirGenError> {
-        let rhs_data = &self.vars[&rhs];
+        let bit_size = typ.bit_size();
+        let two_pow_rhs_var = self.clamped_two_pow_var(rhs, bit_size, bit_size)?;
+
+        if !typ.is_signed() {
+            let (quotient_var, _remainder_var) =
+                self.euclidean_division_var(lhs, two_pow_rhs_var, bit_size)?;
+            return Ok(quotient_var);
+        }
+
+        // Conditionally flip the bits of lhs before and after the division:
+        // x + is_negative * (!x - x)
+        let is_negative = self.is_negative_var(lhs, bit_size)?;
+        let not_lhs = self.not_var(lhs, typ.clone())?;
+        let lhs_flip = self.sub_var(not_lhs, lhs)?;
+        let lhs_flip = self.mul_var(is_negative, lhs_flip)?;
+        let unsigned_lhs = self.add_var(lhs, lhs_flip)?;
+
+        let (quotient_var, _remainder_var) =
+            self.euclidean_division_var(unsigned_lhs, two_pow_rhs_var, bit_size)?;
+
+        let not_quotient = self.not_var(quotient_var, typ)?;
+        let quotient_flip = self.sub_var(not_quotient, quotient_var)?;
+        let quotient_flip = self.mul_var(is_negative, quotient_flip)?;
+        self.add_var(quotient_var, quotient_flip)
+    }
 
-        // Compute 2^{rhs}
-        let two_pow_rhs = match rhs_data.as_constant() {
-            Some(exponent) => FieldElement::from(2_i128).pow(&exponent),
-            None => unimplemented!("rhs must be a constant when doing a right shift"),
+    /// Returns an `AcirVar` which will be `1` if the signed `bit_size`-bit integer `lhs`
+    /// is negative and `0` otherwise.
+    ///
+    /// Signed integers are stored in two's complement, so `lhs` is negative when its
+    /// most significant bit is set, i.e. when `lhs >= 2^{bit_size - 1}`.
+    fn is_negative_var(&mut self, lhs: AcirVar, bit_size: u32) -> Result<AcirVar, AcirGenError> {
+        let min_negative =
+            FieldElement::from(2_i128).pow(&FieldElement::from(bit_size as i128 - 1));
+        let min_negative_var = self.add_constant(min_negative);
+        self.more_than_eq_var(lhs, min_negative_var, bit_size, None)
+    }
+
+    /// Returns an `AcirVar` that is constrained to be the signed `bit_size`-bit integer `lhs`
+    /// cast to `target_type`, which must be a `Field` or an integer of at least `bit_size` bits.
+    ///
+    /// Negative values have their sign extended over the wider two's complement representation,
+    /// or are mapped to `p - |lhs|` when casting to a `Field`.
+    pub(crate) fn sign_extend_var(
+        &mut self,
+        lhs: AcirVar,
+        bit_size: u32,
+        target_type: &NumericType,
+    ) -> Result<AcirVar, AcirGenError> {
+        let two_pow_bit_size =
+            FieldElement::from(2_i128).pow(&FieldElement::from(bit_size as i128));
+        let extension = match target_type {
+            NumericType::Signed { bit_size: target_bit_size }
+            | NumericType::Unsigned { bit_size: target_bit_size } => {
+                assert!(*target_bit_size >= bit_size, "ICE: cannot sign extend to a smaller type");
+                let two_pow_target_bit_size =
+                    FieldElement::from(2_i128).pow(&FieldElement::from(*target_bit_size as i128));
+                two_pow_target_bit_size - two_pow_bit_size
+            }
+            NumericType::NativeField => -two_pow_bit_size,
         };
-        let two_pow_rhs_var = self.add_constant(two_pow_rhs);
 
-        self.div_var(lhs, two_pow_rhs_var, typ)
+        let is_negative = self.is_negative_var(lhs, bit_size)?;
+        let extension_var = self.add_constant(extension);
+        let extension_var = self.mul_var(is_negative, extension_var)?;
+        self.add_var(lhs, extension_var)
     }
 
     /// Converts the `AcirVar` to a `Witness` if it hasn't been already, and appends it to the
@@ -764,6 +857,11 @@ impl AcirContext {
         self.acir_ir
     }
 
+    /// Sets the source locations to attach to the opcodes generated after this call.
+    pub(crate) fn set_call_stack(&mut self, call_stack: CallStack) {
+        self.acir_ir.call_stack = call_stack;
+    }
+
     /// Adds `Data` into the context and assigns it a Variable.
     ///
     /// Variable can be seen as an index into the context.
@@ -779,6 +877,7 @@ impl AcirContext {
         &mut self,
         predicate: Option<AcirVar>,
         code: Vec<BrilligOpcode>,
+        function_names: BTreeMap<usize, String>,
         inputs: Vec<AcirValue>,
         outputs: Vec<AcirType>,
     ) -> Vec<AcirValue> {
@@ -803,27 +902,41 @@ impl AcirContext {
                 let var = self.add_data(AcirVarData::Witness(witness_index));
                 AcirValue::Var(var, output.clone())
             }
-            AcirType::Array(element_types, size) => {
+            AcirType::Array(..) => {
                 let mut witnesses = Vec::new();
-                let mut array_values = im::Vector::new();
-                for _ in 0..size {
-                    for element_type in &element_types {
-                        let witness_index = self.acir_ir.next_witness_index();
-                        witnesses.push(witness_index);
-                        let var = self.add_data(AcirVarData::Witness(witness_index));
-                        array_values.push_back(AcirValue::Var(var, element_type.clone()));
-                    }
-                }
+                let array_value = self.brillig_array_output(&mut witnesses, &output);
                 b_outputs.push(BrilligOutputs::Array(witnesses));
-                AcirValue::Array(array_values)
+                array_value
             }
         });
         let predicate = predicate.map(|var| self.vars[&var].to_expression().into_owned());
-        self.acir_ir.brillig(predicate, code, b_inputs, b_outputs);
+        self.acir_ir.brillig(predicate, code, function_names, b_inputs, b_outputs);
 
         outputs_var
     }
 
+    /// Creates the value of an output of the given type of a brillig call, pushing the witness
+    /// of each of its elements. Nested arrays are flattened into the witnesses as for inputs.
+    fn brillig_array_output(&mut self, witnesses: &mut Vec<Witness>, typ: &AcirType) -> AcirValue {
+        match typ {
+            AcirType::NumericType(_) => {
+                let witness_index = self.acir_ir.next_witness_index();
+                witnesses.push(witness_index);
+                let var = self.add_data(AcirVarData::Witness(witness_index));
+                AcirValue::Var(var, typ.clone())
+            }
+            AcirType::Array(element_types, size) => {
+                let mut array_values = im::Vector::new();
+                for _ in 0..*size {
+                    for element_type in element_types {
+                        array_values.push_back(self.brillig_array_output(witnesses, element_type));
+                    }
+                }
+                AcirValue::Array(array_values)
+            }
+        }
+    }
+
     fn brillig_array_input(&self, var_expressions: &mut Vec<Expression>, input: AcirValue) {
         match input {
             AcirValue::Var(var, _) => {
@@ -866,6 +979,67 @@ impl AcirContext {
         Ok(outputs_var)
     }
 
+    /// Returns variables constrained to be the elements of `array` at each of the given indices,
+    /// where the indices may only be known at runtime.
+    ///
+    /// Each index is constrained to be within the array.
+    pub(crate) fn read_from_memory(
+        &mut self,
+        array: &[AcirVar],
+        indices: &[AcirVar],
+    ) -> Result<Vec<AcirVar>, AcirGenError> {
+        let mut trace = self.initialize_memory(array);
+
+        let values = vecmap(indices, |index| {
+            let value = self.acir_ir.next_witness_index();
+            let index = self.vars[index].to_expression().into_owned();
+            trace.push(MemOp { operation: Expression::zero(), index, value: value.into() });
+            self.add_data(AcirVarData::Witness(value))
+        });
+
+        self.acir_ir.memory_block(array.len(), trace)?;
+        Ok(values)
+    }
+
+    /// Returns variables constrained to be the elements of `array` after writing each value
+    /// to its corresponding index, where the indices may only be known at runtime.
+    ///
+    /// Each index is constrained to be within the array.
+    pub(crate) fn write_to_memory(
+        &mut self,
+        array: &[AcirVar],
+        indices: &[AcirVar],
+        values: &[AcirVar],
+    ) -> Result<Vec<AcirVar>, AcirGenError> {
+        let mut trace = self.initialize_memory(array);
+
+        for (index, value) in indices.iter().zip(values) {
+            let index = self.vars[index].to_expression().into_owned();
+            let value = self.vars[value].to_expression().into_owned();
+            trace.push(MemOp { operation: Expression::one(), index, value });
+        }
+
+        // Read every element back out of memory to find the updated array
+        let new_array = vecmap(0..array.len(), |index| {
+            let value = self.acir_ir.next_witness_index();
+            let index = Expression::from_field(FieldElement::from(index as u128));
+            trace.push(MemOp { operation: Expression::zero(), index, value: value.into() });
+            self.add_data(AcirVarData::Witness(value))
+        });
+
+        self.acir_ir.memory_block(array.len(), trace)?;
+        Ok(new_array)
+    }
+
+    /// Returns the start of a memory trace which writes each element of `array` to its index.
+    fn initialize_memory(&self, array: &[AcirVar]) -> Vec<MemOp> {
+        vecmap(array.iter().enumerate(), |(index, element)| MemOp {
+            operation: Expression::one(),
+            index: Expression::from_field(FieldElement::from(index as u128)),
+            value: self.vars[element].to_expression().into_owned(),
+        })
+    }
+
     /// Constrain lhs to be less than rhs
     fn less_than_constrain(
         &mut self,
@@ -1,6 +1,8 @@
 //! `GeneratedAcir` is constructed as part of the `acir_gen` pass to accumulate all of the ACIR
 //! program as it is being converted from SSA form.
-use crate::brillig::brillig_gen::brillig_directive;
+use std::collections::BTreeMap;
+
+use crate::{brillig::brillig_gen::brillig_directive, ssa_refactor::ir::dfg::CallStack};
 
 use super::errors::AcirGenError;
 use acvm::acir::{
@@ -36,6 +38,20 @@ pub(crate) struct GeneratedAcir {
     /// Note: This may contain repeated indices, which is necessary for later mapping into the
     /// abi's return type.
     pub(crate) return_witnesses: Vec<Witness>,
+
+    /// The number of memory blocks declared so far, which is used to give each block a unique id.
+    pub(crate) memory_blocks: u32,
+
+    /// The source locations of each opcode, keyed by the opcode's index.
+    pub(crate) locations: BTreeMap<usize, CallStack>,
+
+    /// The source locations of the SSA instruction currently being converted, which are
+    /// attached to each opcode pushed while converting it.
+    pub(crate) call_stack: CallStack,
+
+    /// The names of the functions linked into each brillig opcode, keyed by the opcode's index
+    /// and then by the position of the function's first opcode within the brillig bytecode.
+    pub(crate) brillig_function_names: BTreeMap<usize, BTreeMap<usize, String>>,
 }
 
 impl GeneratedAcir {
@@ -45,8 +61,11 @@ impl GeneratedAcir {
     }
 
     /// Adds a new opcode into ACIR.
-    fn push_opcode(&mut self, opcode: AcirOpcode) {
+    pub(crate) fn push_opcode(&mut self, opcode: AcirOpcode) {
         self.opcodes.push(opcode);
+        if !self.call_stack.is_empty() {
+            self.locations.insert(self.opcodes.len() - 1, self.call_stack.clone());
+        }
     }
 
     /// Updates the witness index counter and returns
@@ -257,7 +276,7 @@ impl GeneratedAcir {
             }
         };
 
-        self.opcodes.push(AcirOpcode::BlackBoxFuncCall(black_box_func_call));
+        self.push_opcode(AcirOpcode::BlackBoxFuncCall(black_box_func_call));
 
         outputs_clone
     }
@@ -539,7 +558,7 @@ impl GeneratedAcir {
         let inverse_code = brillig_directive::directive_invert();
         let inputs = vec![BrilligInputs::Single(expr.clone())];
         let outputs = vec![BrilligOutputs::Simple(inverted_witness)];
-        self.brillig(Some(Expression::one()), inverse_code, inputs, outputs);
+        self.brillig(Some(Expression::one()), inverse_code, BTreeMap::new(), inputs, outputs);
 
         inverted_witness
     }
@@ -748,6 +767,7 @@ impl GeneratedAcir {
         &mut self,
         predicate: Option<Expression>,
         code: Vec<BrilligOpcode>,
+        function_names: BTreeMap<usize, String>,
         inputs: Vec<BrilligInputs>,
         outputs: Vec<BrilligOutputs>,
     ) {
@@ -759,6 +779,9 @@ impl GeneratedAcir {
             predicate,
         });
         self.push_opcode(opcode);
+        if !function_names.is_empty() {
+            self.brillig_function_names.insert(self.opcodes.len() - 1, function_names);
+        }
     }
 
     /// Generate gates and control bits witnesses which ensure that out_expr is a permutation of in_expr
@@ -0,0 +1,107 @@
+use acvm::{
+    acir::{
+        circuit::{
+            directives::Directive,
+            opcodes::{BlockId, MemOp, MemoryBlock, Opcode as AcirOpcode},
+        },
+        native_types::Expression,
+    },
+    FieldElement,
+};
+use iter_extended::vecmap;
+
+use super::{errors::AcirGenError, generated_acir::GeneratedAcir};
+
+impl GeneratedAcir {
+    /// Adds a memory block of `len` cells which is accessed by the given trace of operations.
+    ///
+    /// The trace must start by writing to every cell of the block, in order. Each read
+    /// in the trace should have a fresh witness as its value, which the ACVM solves through
+    /// the block opcode. As the block opcode does not constrain anything by itself, the trace
+    /// is then constrained to be consistent, see `constrain_memory_trace`.
+    pub(crate) fn memory_block(
+        &mut self,
+        len: usize,
+        trace: Vec<MemOp>,
+    ) -> Result<(), AcirGenError> {
+        let id = BlockId(self.memory_blocks);
+        self.memory_blocks += 1;
+
+        let block = MemoryBlock { id, len: len as u32, trace: trace.clone() };
+        self.push_opcode(AcirOpcode::Block(block));
+        self.constrain_memory_trace(len, &trace)
+    }
+
+    /// Constrains each read in the trace to return the value last written to the same cell,
+    /// and each operation to be on a cell within the block.
+    ///
+    /// To do so, the operations are sorted by their index, then by their position in the trace,
+    /// using a permutation network. In the sorted trace:
+    /// - The indices start at `0`, end at `len - 1` and increase by at most one at each step.
+    /// - Two operations on the same index are in the same order as in the original trace.
+    /// - The first operation on each index is a write.
+    /// - A read returns the same value as the operation before it.
+    fn constrain_memory_trace(&mut self, len: usize, trace: &[MemOp]) -> Result<(), AcirGenError> {
+        let counters = vecmap(0..trace.len(), |counter| {
+            Expression::from_field(FieldElement::from(counter as u128))
+        });
+        let indices = vecmap(trace, |operation| operation.index.clone());
+        let values = vecmap(trace, |operation| operation.value.clone());
+        let operations = vecmap(trace, |operation| operation.operation.clone());
+
+        // Every column is sorted by the same network, which the ACVM configures
+        // through the PermutationSort directive.
+        let (bits, counters_sorted) = self.permutation_layer(&counters, &[], true);
+        let (_, indices_sorted) = self.permutation_layer(&indices, &bits, false);
+        let (_, values_sorted) = self.permutation_layer(&values, &bits, false);
+        let (_, operations_sorted) = self.permutation_layer(&operations, &bits, false);
+
+        let inputs =
+            vecmap(indices.into_iter().zip(counters), |(index, counter)| vec![index, counter]);
+        self.push_opcode(AcirOpcode::Directive(Directive::PermutationSort {
+            inputs,
+            tuple: 2,
+            bits,
+            sort_by: vec![0, 1],
+        }));
+
+        let last_index = Expression::from_field(FieldElement::from((len - 1) as u128));
+        self.assert_is_zero(indices_sorted[0].clone());
+        self.assert_is_zero(&indices_sorted[trace.len() - 1] - &last_index);
+        self.assert_is_zero(&operations_sorted[0] - &Expression::one());
+
+        let counter_bits = usize::BITS - trace.len().leading_zeros();
+        for i in 0..trace.len() - 1 {
+            let index_step = &indices_sorted[i + 1] - &indices_sorted[i];
+            let same_index = &Expression::one() - &index_step;
+
+            // index_step * (index_step - 1) == 0
+            let step_is_boolean =
+                self.mul_with_witness(&index_step, &(&index_step - &Expression::one()));
+            self.assert_is_zero(step_is_boolean);
+
+            // Operations on the same index must keep their order from the trace,
+            // so (1 - (counter[i + 1] >= counter[i])) * (1 - index_step) == 0
+            let counter_increases = self.more_than_eq_comparison(
+                &counters_sorted[i + 1],
+                &counters_sorted[i],
+                counter_bits,
+                None,
+            )?;
+            let counter_decreases = &Expression::one() - &Expression::from(counter_increases);
+            let out_of_order = self.mul_with_witness(&counter_decreases, &same_index);
+            self.assert_is_zero(out_of_order);
+
+            // A read cannot be the first operation on an index: index_step * (1 - operation) == 0
+            let is_read = &Expression::one() - &operations_sorted[i + 1];
+            let read_from_unwritten_index = self.mul_with_witness(&index_step, &is_read);
+            self.assert_is_zero(read_from_unwritten_index);
+
+            // A read returns the previous value of its index: (1 - operation) * (value - previous) == 0
+            let value_change = &values_sorted[i + 1] - &values_sorted[i];
+            let read_changes_value = self.mul_with_witness(&is_read, &value_change);
+            self.assert_is_zero(read_changes_value);
+        }
+        Ok(())
+    }
+}
@@ -1,11 +1,12 @@
 //! This file holds the pass to convert from Noir's SSA IR to ACIR.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::brillig::{
     brillig_gen::brillig_fn::FunctionContext as BrilligFunctionContext,
     brillig_ir::artifact::BrilligArtifact, Brillig,
 };
+use crate::errors::{RuntimeError, RuntimeErrorKind};
 
 use self::acir_ir::{
     acir_variable::{AcirContext, AcirType, AcirVar},
@@ -13,7 +14,8 @@ use self::acir_ir::{
 };
 use super::{
     ir::{
-        dfg::DataFlowGraph,
+        bit_size::BitSizeAnalysis,
+        dfg::{CallStack, DataFlowGraph},
         function::{Function, RuntimeType},
         instruction::{
             Binary, BinaryOp, Instruction, InstructionId, Intrinsic, TerminatorInstruction,
@@ -53,6 +55,10 @@ struct Context {
 
     /// Manages and builds the `AcirVar`s to which the converted SSA values refer.
     acir_context: AcirContext,
+
+    /// The maximum bit sizes of the values of the function being converted, used to skip
+    /// truncations which are redundant.
+    bit_sizes: BitSizeAnalysis,
 }
 
 #[derive(Debug, Clone)]
@@ -75,6 +81,20 @@ impl AcirValue {
             AcirValue::Array(array) => array.into_iter().flat_map(AcirValue::flatten).collect(),
         }
     }
+
+    /// Returns a value with the same structure and types as this one, taking its
+    /// variables from the given iterator in the order they appear when flattened.
+    fn replace_vars(&self, vars: &mut impl Iterator<Item = AcirVar>) -> AcirValue {
+        match self {
+            AcirValue::Var(_, typ) => {
+                let var = vars.next().expect("ICE: not enough variables to replace those in value");
+                AcirValue::Var(var, typ.clone())
+            }
+            AcirValue::Array(array) => {
+                AcirValue::Array(array.iter().map(|element| element.replace_vars(vars)).collect())
+            }
+        }
+    }
 }
 
 impl Ssa {
@@ -83,9 +103,9 @@ impl Ssa {
         brillig: Brillig,
         abi_distinctness: AbiDistinctness,
         allow_log_ops: bool,
-    ) -> GeneratedAcir {
+    ) -> Result<GeneratedAcir, RuntimeError> {
         let context = Context::default();
-        let mut generated_acir = context.convert_ssa(self, brillig, allow_log_ops);
+        let mut generated_acir = context.convert_ssa(self, brillig, allow_log_ops)?;
 
         match abi_distinctness {
             AbiDistinctness::Distinct => {
@@ -102,20 +122,56 @@ impl Ssa {
                     .collect();
 
                 generated_acir.return_witnesses = distinct_return_witness;
-                generated_acir
+                Ok(generated_acir)
             }
-            AbiDistinctness::DuplicationAllowed => generated_acir,
+            AbiDistinctness::DuplicationAllowed => Ok(generated_acir),
         }
     }
+
+    /// Returns an error if a Vec is created in a constrained function.
+    ///
+    /// Vecs are dynamically sized, so they cannot be represented in ACIR and may only be used in
+    /// unconstrained functions.
+    pub(crate) fn check_for_vecs_in_acir(&self) -> Result<(), RuntimeError> {
+        let acir_functions =
+            self.functions.values().filter(|function| function.runtime() == RuntimeType::Acir);
+        for function in acir_functions {
+            let dfg = &function.dfg;
+            for block in function.reachable_blocks() {
+                for instruction in dfg[block].instructions() {
+                    let is_vec = dfg
+                        .instruction_results(*instruction)
+                        .iter()
+                        .any(|result| matches!(dfg.type_of_value(*result), Type::Slice(_)));
+                    if is_vec {
+                        let message = "Vec is only supported in unconstrained functions, \
+                            BoundedVec can be used instead"
+                            .to_string();
+                        let location = dfg.get_call_stack(*instruction).last().copied();
+                        return Err(RuntimeError::new(
+                            RuntimeErrorKind::UnstructuredError { message },
+                            location,
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Context {
     /// Converts SSA into ACIR
-    fn convert_ssa(self, ssa: Ssa, brillig: Brillig, allow_log_ops: bool) -> GeneratedAcir {
+    fn convert_ssa(
+        self,
+        ssa: Ssa,
+        brillig: Brillig,
+        allow_log_ops: bool,
+    ) -> Result<GeneratedAcir, RuntimeError> {
         let main_func = ssa.main();
         match main_func.runtime() {
             RuntimeType::Acir => self.convert_acir_main(main_func, &ssa, brillig, allow_log_ops),
-            RuntimeType::Brillig => self.convert_brillig_main(main_func, brillig),
+            RuntimeType::Brillig => Ok(self.convert_brillig_main(main_func, brillig)),
         }
     }
 
@@ -125,19 +181,22 @@ impl Context {
         ssa: &Ssa,
         brillig: Brillig,
         allow_log_ops: bool,
-    ) -> GeneratedAcir {
+    ) -> Result<GeneratedAcir, RuntimeError> {
         let dfg = &main_func.dfg;
         let entry_block = &dfg[main_func.entry_block()];
 
+        self.bit_sizes = BitSizeAnalysis::with_function(main_func);
         self.convert_ssa_block_params(entry_block.parameters(), dfg);
 
         for instruction_id in entry_block.instructions() {
-            self.convert_ssa_instruction(*instruction_id, dfg, ssa, &brillig, allow_log_ops);
+            self.acir_context.set_call_stack(dfg.get_call_stack(*instruction_id));
+            self.convert_ssa_instruction(*instruction_id, dfg, ssa, &brillig, allow_log_ops)?;
         }
+        self.acir_context.set_call_stack(CallStack::new());
 
         self.convert_ssa_return(entry_block.terminator().unwrap(), dfg);
 
-        self.acir_context.finish()
+        Ok(self.acir_context.finish())
     }
 
     fn convert_brillig_main(mut self, main_func: &Function, brillig: Brillig) -> GeneratedAcir {
@@ -152,9 +211,9 @@ impl Context {
             dfg.type_of_value(result_id).into()
         });
 
-        let code = self.gen_brillig_for(main_func, &brillig);
+        let (code, function_names) = self.gen_brillig_for(main_func, &brillig);
 
-        let output_values = self.acir_context.brillig(None, code, inputs, outputs);
+        let output_values = self.acir_context.brillig(None, code, function_names, inputs, outputs);
         let output_vars: Vec<_> = output_values
             .iter()
             .flat_map(|value| value.clone().flatten())
@@ -229,7 +288,7 @@ impl Context {
         ssa: &Ssa,
         brillig: &Brillig,
         allow_log_ops: bool,
-    ) {
+    ) -> Result<(), RuntimeError> {
         let instruction = &dfg[instruction_id];
 
         match instruction {
@@ -263,11 +322,11 @@ impl Context {
                             RuntimeType::Brillig => {
                                 let inputs = vecmap(arguments, |arg| self.convert_value(*arg, dfg));
 
-                                let code = self.gen_brillig_for(func, brillig);
+                                let (code, function_names) = self.gen_brillig_for(func, brillig);
 
                                 let outputs: Vec<AcirType> = vecmap(result_ids, |result_id| dfg.type_of_value(*result_id).into());
 
-                                let output_values = self.acir_context.brillig(self.current_side_effects_enabled_var,code, inputs, outputs);
+                                let output_values = self.acir_context.brillig(self.current_side_effects_enabled_var, code, function_names, inputs, outputs);
                                 // Compiler sanity check
                                 assert_eq!(result_ids.len(), output_values.len(), "ICE: The number of Brillig output values should match the result ids in SSA");
 
@@ -322,10 +381,10 @@ impl Context {
                 self.current_side_effects_enabled_var = Some(acir_var);
             }
             Instruction::ArrayGet { array, index } => {
-                self.handle_array_operation(instruction_id, *array, *index, None, dfg);
+                self.handle_array_operation(instruction_id, *array, *index, None, dfg)?;
             }
             Instruction::ArraySet { array, index, value } => {
-                self.handle_array_operation(instruction_id, *array, *index, Some(*value), dfg);
+                self.handle_array_operation(instruction_id, *array, *index, Some(*value), dfg)?;
             }
             Instruction::Allocate => {
                 unreachable!("Expected all allocate instructions to be removed before acir_gen")
@@ -337,24 +396,37 @@ impl Context {
                 unreachable!("Expected all load instructions to be removed before acir_gen")
             }
         }
+        Ok(())
     }
 
-    fn gen_brillig_for(&self, func: &Function, brillig: &Brillig) -> Vec<Opcode> {
+    /// Links the brillig code of `func` with the functions it calls.
+    ///
+    /// Alongside the bytecode, this returns the name of each linked function keyed by the
+    /// position of its first opcode.
+    fn gen_brillig_for(
+        &self,
+        func: &Function,
+        brillig: &Brillig,
+    ) -> (Vec<Opcode>, BTreeMap<usize, String>) {
         // Create the entry point artifact
         let mut entry_point = BrilligArtifact::new_entry_point_artifact(
             BrilligFunctionContext::parameters(func),
             BrilligFunctionContext::return_values(func),
             BrilligFunctionContext::function_id_to_function_label(func.id()),
         );
+        let mut function_names = BTreeMap::new();
         // Link the entry point with all dependencies
         while let Some(unresolved_fn_label) = entry_point.first_unresolved_function_call() {
             let artifact = &brillig
                 .find_by_function_label(unresolved_fn_label.clone())
                 .expect("Cannot find linked fn {unresolved_fn_label}");
+            if let Some(name) = brillig.find_name_by_function_label(&unresolved_fn_label) {
+                function_names.insert(entry_point.index_of_next_opcode(), name.to_string());
+            }
             entry_point.link_with(unresolved_fn_label, artifact);
         }
         // Generate the final bytecode
-        entry_point.finish()
+        (entry_point.finish(), function_names)
     }
 
     /// Handles an ArrayGet or ArraySet instruction.
@@ -367,26 +439,39 @@ impl Context {
         index: ValueId,
         store_value: Option<ValueId>,
         dfg: &DataFlowGraph,
-    ) {
+    ) -> Result<(), RuntimeError> {
         let array = self.convert_array_value(array, dfg);
-        let index = dfg
-            .get_numeric_constant(index)
-            .expect("Expected array index to be a known constant")
-            .try_to_u64()
-            .expect("Expected array index to fit into a u64") as usize;
+        let index = match dfg.get_numeric_constant(index) {
+            Some(index) => {
+                index.try_to_u64().expect("Expected array index to fit into a u64") as usize
+            }
+            // Any index is out of bounds of an empty array
+            None if array.is_empty() => 0,
+            None => {
+                let value = self
+                    .handle_dynamic_array_operation(array, index, store_value, dfg)
+                    .expect("add Result types to all methods so errors bubble up");
+                self.define_result(dfg, instruction, value);
+                return Ok(());
+            }
+        };
 
         if index >= array.len() {
             // Ignore the error if side effects are disabled.
             if let Some(var) = self.current_side_effects_enabled_var {
                 if self.acir_context.is_constant_one(&var) {
-                    // TODO: Can we save a source Location for this error?
-                    panic!("Index {} is out of bounds for array of length {}", index, array.len());
+                    let kind = RuntimeErrorKind::ArrayOutOfBounds {
+                        index: index as u128,
+                        bound: array.len() as u128,
+                    };
+                    let location = dfg.get_call_stack(instruction).last().copied();
+                    return Err(RuntimeError::new(kind, location));
                 }
             }
             let result_type = dfg.type_of_value(dfg.instruction_results(instruction)[0]);
             let value = self.create_default_value(&result_type);
             self.define_result(dfg, instruction, value);
-            return;
+            return Ok(());
         }
 
         let value = match store_value {
@@ -398,6 +483,65 @@ impl Context {
         };
 
         self.define_result(dfg, instruction, value);
+        Ok(())
+    }
+
+    /// Handles an ArrayGet or ArraySet instruction whose index is only known at runtime.
+    ///
+    /// The flattened elements of the array are written into a memory block, from which the
+    /// element at the index is read, or to which the new value is written before reading back
+    /// the updated array.
+    fn handle_dynamic_array_operation(
+        &mut self,
+        array: im::Vector<AcirValue>,
+        index: ValueId,
+        store_value: Option<ValueId>,
+        dfg: &DataFlowGraph,
+    ) -> Result<AcirValue, AcirGenError> {
+        let mut index = self.convert_numeric_value(index, dfg);
+
+        // When side effects are disabled the index may be out of bounds, as this
+        // operation's result is unused, so we read from or write to index 0 instead.
+        if let Some(var) = self.current_side_effects_enabled_var {
+            if !self.acir_context.is_constant_one(&var) {
+                index = self.acir_context.mul_var(index, var)?;
+            }
+        }
+
+        // Each element may itself be a tuple or array, in which case it takes up
+        // several consecutive cells of the flattened array.
+        let element_size = array[0].clone().flatten().len();
+        let flattened_array: Vec<_> = array
+            .iter()
+            .flat_map(|element| element.clone().flatten())
+            .map(|(var, _)| var)
+            .collect();
+
+        let element_size_var =
+            self.acir_context.add_constant(FieldElement::from(element_size as u128));
+        let first_cell = self.acir_context.mul_var(index, element_size_var)?;
+        let mut cells = Vec::with_capacity(element_size);
+        for offset in 0..element_size {
+            let offset = self.acir_context.add_constant(FieldElement::from(offset as u128));
+            cells.push(self.acir_context.add_var(first_cell, offset)?);
+        }
+
+        match store_value {
+            Some(store_value) => {
+                let store_value = self.convert_value(store_value, dfg).flatten();
+                let store_value = vecmap(store_value, |(var, _)| var);
+                let new_array =
+                    self.acir_context.write_to_memory(&flattened_array, &cells, &store_value)?;
+
+                let mut new_array = new_array.into_iter();
+                let elements = array.iter().map(|element| element.replace_vars(&mut new_array));
+                Ok(AcirValue::Array(elements.collect()))
+            }
+            None => {
+                let element = self.acir_context.read_from_memory(&flattened_array, &cells)?;
+                Ok(array[0].replace_vars(&mut element.into_iter()))
+            }
+        }
     }
 
     /// Remember the result of an instruction returning a single value
@@ -595,6 +739,9 @@ impl Context {
             (_, Type::Array(..)) | (Type::Array(..), _) => {
                 unreachable!("Arrays are invalid in binary operations")
             }
+            (_, Type::Slice(_)) | (Type::Slice(_), _) => {
+                unreachable!("Slices are invalid in binary operations")
+            }
             // If either side is a Field constant then, we coerce into the type
             // of the other operand
             (Type::Numeric(NumericType::NativeField), typ)
@@ -614,7 +761,10 @@ impl Context {
 
     /// Returns an `AcirVar` that is constrained to fit in the target type by truncating the input.
     /// If the target cast is to a `NativeField`, no truncation is required so the cast becomes a
-    /// no-op.
+    /// no-op, unless the input is a signed integer.
+    ///
+    /// Signed integers are represented in two's complement, so casting a signed integer into
+    /// a wider type requires its sign bit to be extended.
     fn convert_ssa_cast(
         &mut self,
         value_id: &ValueId,
@@ -629,23 +779,35 @@ impl Context {
             Type::Numeric(numeric) => numeric,
             _ => unreachable!("Can only cast to a numeric"),
         };
+        let incoming_bit_size = incoming_type.bit_size();
         match target_numeric {
             NumericType::NativeField => {
+                if incoming_type.is_signed() {
+                    return self.acir_context.sign_extend_var(
+                        variable,
+                        incoming_bit_size,
+                        target_numeric,
+                    );
+                }
                 // Casting into a Field as a no-op
                 Ok(variable)
             }
-            NumericType::Unsigned { bit_size } => {
-                if incoming_type.is_signed() {
-                    todo!("Cast from unsigned to signed")
+            NumericType::Unsigned { bit_size } | NumericType::Signed { bit_size } => {
+                if incoming_type.is_signed() && incoming_bit_size < *bit_size {
+                    return self.acir_context.sign_extend_var(
+                        variable,
+                        incoming_bit_size,
+                        target_numeric,
+                    );
                 }
-                let max_bit_size = incoming_type.bit_size();
-                if max_bit_size <= *bit_size {
+                if incoming_bit_size <= *bit_size
+                    || self.bit_sizes.is_redundant_cast_truncation(dfg, *value_id, typ)
+                {
                     // Incoming variable already fits into target bit size -  this is a no-op
                     return Ok(variable);
                 }
-                self.acir_context.truncate_var(variable, *bit_size, max_bit_size)
+                self.acir_context.truncate_var(variable, *bit_size, incoming_bit_size)
             }
-            NumericType::Signed { .. } => todo!("Cast into signed"),
         }
     }
 
@@ -749,6 +911,15 @@ impl Context {
 
                 Self::convert_vars_to_values(out_vars, dfg, result_ids)
             }
+            Intrinsic::SliceNew
+            | Intrinsic::SliceLen
+            | Intrinsic::SliceGet
+            | Intrinsic::SlicePush
+            | Intrinsic::SlicePop => {
+                unreachable!(
+                    "ICE: Vecs in constrained functions are rejected before ACIR generation"
+                )
+            }
         }
     }
 
@@ -845,6 +1016,7 @@ mod tests {
         },
         FieldElement,
     };
+    use iter_extended::vecmap;
 
     use crate::{
         brillig::Brillig,
@@ -875,11 +1047,52 @@ mod tests {
         let ssa = builder.finish();
 
         let context = Context::default();
-        let acir = context.convert_ssa(ssa, Brillig::default(), false);
+        let acir = context.convert_ssa(ssa, Brillig::default(), false).unwrap();
 
         let expected_opcodes =
             vec![Opcode::Arithmetic(&Expression::one() - &Expression::from(Witness(1)))];
         assert_eq!(acir.opcodes, expected_opcodes);
         assert_eq!(acir.return_witnesses, vec![Witness(1)]);
     }
+
+    #[test]
+    fn reads_arrays_at_runtime_indices() {
+        // fn main {
+        //   b0(v0: [Field; 3], v1: Field):
+        //     v2 = array_get v0, index v1
+        //     return v2
+        // }
+        let func_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("func".into(), func_id, RuntimeType::Acir);
+
+        let array_type = Type::Array(Rc::new(vec![Type::field()]), 3);
+        let array = builder.add_parameter(array_type);
+        let index = builder.add_parameter(Type::field());
+        let element = builder.insert_array_get(array, index, Type::field());
+        builder.terminate_with_return(vec![element]);
+
+        let ssa = builder.finish();
+
+        let context = Context::default();
+        let acir = context.convert_ssa(ssa, Brillig::default(), false).unwrap();
+
+        let blocks: Vec<_> = acir
+            .opcodes
+            .iter()
+            .filter_map(|opcode| match opcode {
+                Opcode::Block(block) => Some(block),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].len, 3);
+
+        // The three elements are written to the block before the element is read from it
+        let operations = vecmap(&blocks[0].trace, |operation| operation.operation.clone());
+        let expected_operations =
+            vec![Expression::one(), Expression::one(), Expression::one(), Expression::zero()];
+        assert_eq!(operations, expected_operations);
+        assert_eq!(blocks[0].trace[3].index, Expression::from(Witness(4)));
+        assert_eq!(acir.return_witnesses, vec![blocks[0].trace[3].value.to_witness().unwrap()]);
+    }
 }
@@ -1,4 +1,5 @@
 pub(crate) mod basic_block;
+pub(crate) mod bit_size;
 pub(crate) mod cfg;
 pub(crate) mod dfg;
 pub(crate) mod dom;
@@ -0,0 +1,150 @@
+//! An analysis of the maximum number of bits needed to represent each numeric value of a
+//! function, used to find truncations and range constraints which are redundant.
+//!
+//! Every integer value of a function is expected to fit within the bit size of its type, except
+//! for the results of binary operations which are yet to be truncated. The bit sizes of these
+//! results are inferred from their operands: e.g. the sum of two values which fit in 8 bits fits
+//! in 9 bits. Values whose bit size cannot be bounded below that of the native field are given
+//! `FieldElement::max_num_bits()`, which is never considered to be a proven bound.
+use std::collections::HashMap;
+
+use acvm::FieldElement;
+
+use super::{
+    dfg::DataFlowGraph,
+    function::Function,
+    instruction::{BinaryOp, Instruction, InstructionId},
+    post_order::PostOrder,
+    types::{NumericType, Type},
+    value::{Value, ValueId},
+};
+
+/// The maximum bit size of each value defined by an instruction of a function.
+#[derive(Default)]
+pub(crate) struct BitSizeAnalysis {
+    max_bit_sizes: HashMap<ValueId, u32>,
+}
+
+impl BitSizeAnalysis {
+    /// Computes the maximum bit size of the results of each reachable instruction of the function.
+    pub(crate) fn with_function(function: &Function) -> Self {
+        let mut analysis = BitSizeAnalysis::default();
+
+        // Blocks are visited in reverse post-order, such that the operands of each instruction
+        // are visited before the instruction itself, unless they are block parameters.
+        let post_order = PostOrder::with_function(function);
+        for block in post_order.as_slice().iter().rev() {
+            for instruction in function.dfg[*block].instructions() {
+                analysis.analyze_instruction(&function.dfg, *instruction);
+            }
+        }
+        analysis
+    }
+
+    /// Returns the maximum number of bits needed to represent the given value.
+    pub(crate) fn max_bit_size(&self, dfg: &DataFlowGraph, value: ValueId) -> u32 {
+        let value = dfg.resolve(value);
+        if let Some(max_bit_size) = self.max_bit_sizes.get(&value) {
+            return *max_bit_size;
+        }
+
+        match &dfg[value] {
+            Value::NumericConstant { constant, .. } => constant.num_bits(),
+            _ => type_bit_size(&dfg.type_of_value(value)),
+        }
+    }
+
+    /// Computes and records the maximum bit sizes of the results of the given instruction.
+    ///
+    /// The bit sizes of its operands are expected to have been computed already.
+    pub(crate) fn analyze_instruction(&mut self, dfg: &DataFlowGraph, instruction: InstructionId) {
+        let results = dfg.instruction_results(instruction);
+        let max_bit_size = match &dfg[instruction] {
+            Instruction::Binary(binary) => {
+                let lhs = self.max_bit_size(dfg, binary.lhs);
+                let rhs = self.max_bit_size(dfg, binary.rhs);
+                let is_unsigned = matches!(
+                    dfg.type_of_value(binary.lhs),
+                    Type::Numeric(NumericType::Unsigned { .. })
+                );
+                let type_bit_size = type_bit_size(&dfg.type_of_value(results[0]));
+
+                match binary.operator {
+                    BinaryOp::Add => lhs.max(rhs) + 1,
+                    BinaryOp::Mul => lhs + rhs,
+                    BinaryOp::Sub => FieldElement::max_num_bits(),
+                    BinaryOp::Div | BinaryOp::Shr if is_unsigned => lhs,
+                    BinaryOp::Mod if is_unsigned => lhs.min(rhs),
+                    BinaryOp::Div | BinaryOp::Mod | BinaryOp::Shr => type_bit_size,
+                    BinaryOp::Eq | BinaryOp::Lt => 1,
+                    BinaryOp::And => lhs.min(rhs),
+                    BinaryOp::Or | BinaryOp::Xor => lhs.max(rhs),
+                    BinaryOp::Shl => match dfg.get_numeric_constant(binary.rhs) {
+                        Some(shift) if shift.num_bits() <= 8 => lhs + shift.to_u128() as u32,
+                        _ => FieldElement::max_num_bits(),
+                    },
+                }
+            }
+            Instruction::Cast(value, typ) => {
+                let max_bit_size = self.max_bit_size(dfg, *value);
+                let incoming_type = dfg.type_of_value(*value);
+                let is_signed = matches!(incoming_type, Type::Numeric(NumericType::Signed { .. }));
+
+                match typ {
+                    // Signed values are sign-extended when they are cast to a larger type
+                    Type::Numeric(NumericType::NativeField) if is_signed => {
+                        FieldElement::max_num_bits()
+                    }
+                    Type::Numeric(NumericType::Signed { bit_size })
+                    | Type::Numeric(NumericType::Unsigned { bit_size })
+                        if is_signed && type_bit_size(&incoming_type) < *bit_size =>
+                    {
+                        *bit_size
+                    }
+                    _ => max_bit_size.min(type_bit_size(typ)),
+                }
+            }
+            Instruction::Truncate { value, bit_size, .. } => {
+                self.max_bit_size(dfg, *value).min(*bit_size)
+            }
+            _ => return,
+        };
+
+        // Beyond the field's bit size, values may wrap around the field modulus
+        let max_bit_size = max_bit_size.min(FieldElement::max_num_bits());
+        self.max_bit_sizes.insert(results[0], max_bit_size);
+    }
+
+    /// Returns true if the given value is proven to fit within `bit_size` bits.
+    pub(crate) fn fits_in(&self, dfg: &DataFlowGraph, value: ValueId, bit_size: u32) -> bool {
+        let max_bit_size = self.max_bit_size(dfg, value);
+        max_bit_size < FieldElement::max_num_bits() && max_bit_size <= bit_size
+    }
+
+    /// Returns true if casting the given value to `typ` would truncate it, but the value is
+    /// proven to already fit within the bit size of `typ`.
+    pub(crate) fn is_redundant_cast_truncation(
+        &self,
+        dfg: &DataFlowGraph,
+        value: ValueId,
+        typ: &Type,
+    ) -> bool {
+        match typ {
+            Type::Numeric(NumericType::Signed { bit_size })
+            | Type::Numeric(NumericType::Unsigned { bit_size }) => {
+                let incoming_bit_size = type_bit_size(&dfg.type_of_value(value));
+                incoming_bit_size > *bit_size && self.fits_in(dfg, value, *bit_size)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns the number of bits values of the given type are expected to fit in.
+fn type_bit_size(typ: &Type) -> u32 {
+    match typ {
+        Type::Numeric(NumericType::Signed { bit_size })
+        | Type::Numeric(NumericType::Unsigned { bit_size }) => *bit_size,
+        _ => FieldElement::max_num_bits(),
+    }
+}
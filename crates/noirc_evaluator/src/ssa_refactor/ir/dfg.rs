@@ -15,6 +15,11 @@ use super::{
 
 use acvm::FieldElement;
 use iter_extended::vecmap;
+use noirc_errors::Location;
+
+/// The source locations an instruction was generated from, from the outermost
+/// call site down to the expression the instruction itself implements.
+pub(crate) type CallStack = im::Vector<Location>;
 
 /// The DataFlowGraph contains most of the actual data in a function including
 /// its blocks, instructions, and values. This struct is largely responsible for
@@ -69,6 +74,10 @@ pub(crate) struct DataFlowGraph {
     /// for that of another. This information is purely used for printing the SSA, and has no
     /// material effect on the SSA itself.
     replaced_value_ids: HashMap<ValueId, ValueId>,
+
+    /// Source locations of each instruction, along with the call sites it was inlined through.
+    /// Instructions which were not generated from a known location are absent from this map.
+    locations: HashMap<InstructionId, CallStack>,
 }
 
 impl DataFlowGraph {
@@ -137,6 +146,7 @@ impl DataFlowGraph {
         instruction: Instruction,
         block: BasicBlockId,
         ctrl_typevars: Option<Vec<Type>>,
+        call_stack: CallStack,
     ) -> InsertInstructionResult {
         use InsertInstructionResult::*;
         match instruction.simplify(self, block) {
@@ -145,11 +155,20 @@ impl DataFlowGraph {
             SimplifyResult::None => {
                 let id = self.make_instruction(instruction, ctrl_typevars);
                 self.blocks[block].insert_instruction(id);
+                if !call_stack.is_empty() {
+                    self.locations.insert(id, call_stack);
+                }
                 InsertInstructionResult::Results(self.instruction_results(id))
             }
         }
     }
 
+    /// Returns the source locations the given instruction was generated from, or an empty
+    /// call stack if they are unknown.
+    pub(crate) fn get_call_stack(&self, instruction: InstructionId) -> CallStack {
+        self.locations.get(&instruction).cloned().unwrap_or_default()
+    }
+
     /// Insert a value into the dfg's storage and return an id to reference it.
     /// Until the value is used in an instruction it is unreachable.
     pub(crate) fn make_value(&mut self, value: Value) -> ValueId {
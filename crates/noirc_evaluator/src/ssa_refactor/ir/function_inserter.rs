@@ -77,8 +77,13 @@ impl<'f> FunctionInserter<'f> {
             .requires_ctrl_typevars()
             .then(|| vecmap(&results, |result| self.function.dfg.type_of_value(*result)));
 
-        let new_results =
-            self.function.dfg.insert_instruction_and_results(instruction, block, ctrl_typevars);
+        let call_stack = self.function.dfg.get_call_stack(id);
+        let new_results = self.function.dfg.insert_instruction_and_results(
+            instruction,
+            block,
+            ctrl_typevars,
+            call_stack,
+        );
 
         Self::insert_new_instruction_results(&mut self.values, &results, &new_results);
         new_results
@@ -36,6 +36,11 @@ pub(crate) enum Intrinsic {
     ToBits(Endian),
     ToRadix(Endian),
     BlackBox(BlackBoxFunc),
+    SliceNew,
+    SliceLen,
+    SliceGet,
+    SlicePush,
+    SlicePop,
 }
 
 impl std::fmt::Display for Intrinsic {
@@ -48,6 +53,11 @@ impl std::fmt::Display for Intrinsic {
             Intrinsic::ToRadix(Endian::Big) => write!(f, "to_be_radix"),
             Intrinsic::ToRadix(Endian::Little) => write!(f, "to_le_radix"),
             Intrinsic::BlackBox(function) => write!(f, "{function}"),
+            Intrinsic::SliceNew => write!(f, "vec_new"),
+            Intrinsic::SliceLen => write!(f, "vec_len"),
+            Intrinsic::SliceGet => write!(f, "vec_get"),
+            Intrinsic::SlicePush => write!(f, "vec_push"),
+            Intrinsic::SlicePop => write!(f, "vec_pop"),
         }
     }
 }
@@ -63,6 +73,11 @@ impl Intrinsic {
             "to_be_radix" => Some(Intrinsic::ToRadix(Endian::Big)),
             "to_le_bits" => Some(Intrinsic::ToBits(Endian::Little)),
             "to_be_bits" => Some(Intrinsic::ToBits(Endian::Big)),
+            "vec_new" => Some(Intrinsic::SliceNew),
+            "vec_len" => Some(Intrinsic::SliceLen),
+            "vec_get" => Some(Intrinsic::SliceGet),
+            "vec_push" => Some(Intrinsic::SlicePush),
+            "vec_pop" => Some(Intrinsic::SlicePop),
             other => BlackBoxFunc::lookup(other).map(Intrinsic::BlackBox),
         }
     }
@@ -393,7 +408,14 @@ fn simplify_call(func: ValueId, arguments: &[ValueId], dfg: &mut DataFlowGraph)
             let limb_count = constant_args[2].to_u128() as u32;
             SimplifiedTo(constant_to_radix(endian, field, radix, limb_count, dfg))
         }
-        Intrinsic::BlackBox(_) | Intrinsic::Println | Intrinsic::Sort => None,
+        Intrinsic::BlackBox(_)
+        | Intrinsic::Println
+        | Intrinsic::Sort
+        | Intrinsic::SliceNew
+        | Intrinsic::SliceLen
+        | Intrinsic::SliceGet
+        | Intrinsic::SlicePush
+        | Intrinsic::SlicePop => None,
     }
 }
 
@@ -4,18 +4,20 @@ use std::{
     fmt::{Formatter, Result},
 };
 
+use acvm::FieldElement;
 use iter_extended::vecmap;
 
 use super::{
     basic_block::BasicBlockId,
     function::Function,
     instruction::{Instruction, InstructionId, TerminatorInstruction},
+    types::CompositeType,
     value::ValueId,
 };
 
 /// Helper function for Function's Display impl to pretty-print the function with the given formatter.
 pub(crate) fn display_function(function: &Function, f: &mut Formatter) -> Result {
-    writeln!(f, "fn {} {} {{", function.name(), function.id())?;
+    writeln!(f, "{} fn {} {} {{", function.runtime(), function.name(), function.id())?;
     display_block_with_successors(function, function.entry_block(), &mut HashSet::new(), f)?;
     write!(f, "}}")
 }
@@ -64,18 +66,48 @@ fn value(function: &Function, id: ValueId) -> String {
     let id = function.dfg.resolve(id);
     match &function.dfg[id] {
         Value::NumericConstant { constant, typ } => {
-            format!("{typ} {constant}")
+            format!("{typ} {}", numeric_constant(*constant))
         }
         Value::Function(id) => id.to_string(),
         Value::Intrinsic(intrinsic) => intrinsic.to_string(),
-        Value::Array { array, .. } => {
+        Value::ForeignFunction(name) => name.clone(),
+        Value::Array { array, element_type } => {
             let elements = vecmap(array, |element| value(function, *element));
-            format!("[{}]", elements.join(", "))
-        }
-        Value::Param { .. } | Value::Instruction { .. } | Value::ForeignFunction(_) => {
-            id.to_string()
+            format!("[{}] of {}", elements.join(", "), composite_type(element_type))
         }
+        Value::Param { .. } | Value::Instruction { .. } => id.to_string(),
+    }
+}
+
+/// Display a numeric constant such that it can be parsed back: in decimal if either it or its
+/// negation fits in a u128, and in hexadecimal otherwise.
+fn numeric_constant(constant: FieldElement) -> String {
+    if constant.num_bits() <= 128 {
+        constant.to_u128().to_string()
+    } else if (-constant).num_bits() <= 128 {
+        format!("-{}", (-constant).to_u128())
+    } else {
+        format!("0x{}", constant.to_hex())
+    }
+}
+
+/// Display the type of each element of an array, e.g. `Field` or `(Field, u8)`
+fn composite_type(element_type: &CompositeType) -> String {
+    match element_type.as_slice() {
+        [typ] => typ.to_string(),
+        types => format!("({})", vecmap(types, ToString::to_string).join(", ")),
+    }
+}
+
+/// Display the types of the given results if they are not implied by their instruction,
+/// e.g. ` -> Field, u8`
+fn result_types(function: &Function, instruction: InstructionId) -> String {
+    let results = function.dfg.instruction_results(instruction);
+    if results.is_empty() || !function.dfg[instruction].requires_ctrl_typevars() {
+        return String::new();
     }
+    let types = vecmap(results, |result| function.dfg.type_of_value(*result).to_string());
+    format!(" -> {}", types.join(", "))
 }
 
 /// Display each value along with its type. E.g. `v0: Field, v1: u64, v2: u1`
@@ -149,10 +181,13 @@ pub(crate) fn display_instruction(
             writeln!(f, "constrain {}", show(*value))
         }
         Instruction::Call { func, arguments } => {
-            writeln!(f, "call {}({})", show(*func), value_list(function, arguments))
+            let result_types = result_types(function, instruction);
+            writeln!(f, "call {}({}){result_types}", show(*func), value_list(function, arguments))
         }
         Instruction::Allocate => writeln!(f, "allocate"),
-        Instruction::Load { address } => writeln!(f, "load {}", show(*address)),
+        Instruction::Load { address } => {
+            writeln!(f, "load {}{}", show(*address), result_types(function, instruction))
+        }
         Instruction::Store { address, value } => {
             writeln!(f, "store {} at {}", show(*value), show(*address))
         }
@@ -160,7 +195,8 @@ pub(crate) fn display_instruction(
             writeln!(f, "enable_side_effects {}", show(*condition))
         }
         Instruction::ArrayGet { array, index } => {
-            writeln!(f, "array_get {}, index {}", show(*array), show(*index))
+            let result_types = result_types(function, instruction);
+            writeln!(f, "array_get {}, index {}{result_types}", show(*array), show(*index))
         }
         Instruction::ArraySet { array, index, value } => {
             writeln!(
@@ -29,6 +29,10 @@ pub(crate) enum Type {
     /// An immutable array value with the given element type and length
     Array(Rc<CompositeType>, usize),
 
+    /// A dynamically sized array with the given element type.
+    /// Slices may only be used in unconstrained (Brillig) functions.
+    Slice(Rc<CompositeType>),
+
     /// A function that may be called directly
     Function,
 }
@@ -74,6 +78,10 @@ impl std::fmt::Display for Type {
                 let elements = vecmap(element.iter(), |element| element.to_string());
                 write!(f, "[{}; {length}]", elements.join(", "))
             }
+            Type::Slice(element) => {
+                let elements = vecmap(element.iter(), |element| element.to_string());
+                write!(f, "[{}]", elements.join(", "))
+            }
             Type::Function => write!(f, "function"),
         }
     }
@@ -64,7 +64,11 @@ impl Value {
             Value::Instruction { typ, .. } => typ.clone(),
             Value::Param { typ, .. } => typ.clone(),
             Value::NumericConstant { typ, .. } => typ.clone(),
-            Value::Array { element_type, array } => Type::Array(element_type.clone(), array.len()),
+            Value::Array { element_type, array } => {
+                // The fields of each item of the array are flattened into its elements
+                let length = array.len().checked_div(element_type.len()).unwrap_or(0);
+                Type::Array(element_type.clone(), length)
+            }
             Value::Function { .. } => Type::Function,
             Value::Intrinsic { .. } => Type::Function,
             Value::ForeignFunction { .. } => Type::Function,
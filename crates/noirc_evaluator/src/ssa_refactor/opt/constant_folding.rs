@@ -71,12 +71,17 @@ impl Context {
             .requires_ctrl_typevars()
             .then(|| vecmap(&old_results, |result| function.dfg.type_of_value(*result)));
 
-        let new_results =
-            match function.dfg.insert_instruction_and_results(instruction, block, ctrl_typevars) {
-                InsertInstructionResult::SimplifiedTo(new_result) => vec![new_result],
-                InsertInstructionResult::Results(new_results) => new_results.to_vec(),
-                InsertInstructionResult::InstructionRemoved => vec![],
-            };
+        let call_stack = function.dfg.get_call_stack(id);
+        let new_results = match function.dfg.insert_instruction_and_results(
+            instruction,
+            block,
+            ctrl_typevars,
+            call_stack,
+        ) {
+            InsertInstructionResult::SimplifiedTo(new_result) => vec![new_result],
+            InsertInstructionResult::Results(new_results) => new_results.to_vec(),
+            InsertInstructionResult::InstructionRemoved => vec![],
+        };
         assert_eq!(old_results.len(), new_results.len());
         for (old_result, new_result) in old_results.iter().zip(new_results) {
             function.dfg.set_value_from_id(*old_result, new_result);
@@ -0,0 +1,351 @@
+//! Common Subexpression Elimination (CSE) pass: Replaces each instruction which is identical to
+//! an instruction in a dominating position with the results of that earlier instruction.
+//!
+//! Loop unrolling and inlining tend to produce many copies of the same computation, e.g. the
+//! same `Binary`, `Cast` or `Truncate` applied to the same values. Only instructions without
+//! side-effects are deduplicated, along with constraints and range checks: once a value has been
+//! constrained, constraining it again within a dominated position is redundant.
+//!
+//! After flattening, the ACIR generated for some instructions depends on the side-effects
+//! condition they are under (see `Instruction::EnableSideEffects`). These instructions are only
+//! deduplicated if they are under the same condition.
+use std::collections::HashMap;
+
+use acvm::acir::BlackBoxFunc;
+
+use crate::ssa_refactor::{
+    ir::{
+        basic_block::BasicBlockId,
+        dfg::DataFlowGraph,
+        dom::DominatorTree,
+        function::Function,
+        instruction::{BinaryOp, Instruction, InstructionId, Intrinsic},
+        post_order::PostOrder,
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+impl Ssa {
+    /// Performs Common Subexpression Elimination (CSE) on each function, removing any
+    /// instruction which recomputes the results of an identical dominating instruction.
+    pub(crate) fn common_subexpression_elimination(mut self) -> Ssa {
+        for function in self.functions.values_mut() {
+            common_subexpression_elimination(function);
+        }
+        self
+    }
+}
+
+/// The blocks of the function are visited in reverse post-order, such that each block is
+/// visited after all of the blocks dominating it.
+fn common_subexpression_elimination(function: &mut Function) {
+    let mut dom_tree = DominatorTree::with_function(function);
+    let post_order = PostOrder::with_function(function);
+    let mut context = Context::default();
+
+    for block in post_order.as_slice().iter().rev() {
+        context.eliminate_in_block(function, *block, &mut dom_tree);
+    }
+}
+
+/// The side-effects condition an instruction is under, if its ACIR depends on it.
+type SideEffectsCondition = Option<ValueId>;
+
+#[derive(Default)]
+struct Context {
+    /// Maps each deduplicable instruction, with its values resolved, to the instructions
+    /// which have been kept for it along with the block containing them.
+    available_instructions:
+        HashMap<(Instruction, SideEffectsCondition), Vec<(BasicBlockId, InstructionId)>>,
+}
+
+impl Context {
+    fn eliminate_in_block(
+        &mut self,
+        function: &mut Function,
+        block: BasicBlockId,
+        dom_tree: &mut DominatorTree,
+    ) {
+        let instructions = std::mem::take(function.dfg[block].instructions_mut());
+
+        // Side-effects are enabled at the start of each block
+        let mut side_effects_condition = None;
+
+        for instruction_id in instructions {
+            let instruction =
+                function.dfg[instruction_id].map_values(|value| function.dfg.resolve(value));
+
+            if let Instruction::EnableSideEffects { condition } = instruction {
+                side_effects_condition = Some(condition);
+            }
+
+            if can_be_deduplicated(&instruction, &function.dfg) {
+                let condition = if depends_on_side_effects(&instruction) {
+                    side_effects_condition
+                } else {
+                    None
+                };
+                let key = (instruction, condition);
+
+                let available = self.available_instructions.entry(key).or_default();
+                let existing = available
+                    .iter()
+                    .find(|(existing_block, _)| dom_tree.dominates(*existing_block, block));
+
+                if let Some((_, existing_id)) = existing {
+                    let old_results = function.dfg.instruction_results(instruction_id).to_vec();
+                    let new_results = function.dfg.instruction_results(*existing_id).to_vec();
+                    for (old_result, new_result) in old_results.into_iter().zip(new_results) {
+                        function.dfg.set_value_from_id(old_result, new_result);
+                    }
+                    continue;
+                }
+                available.push((block, instruction_id));
+            }
+
+            function.dfg[block].insert_instruction(instruction_id);
+        }
+    }
+}
+
+/// Returns true if the given instruction may be replaced by an identical instruction which is
+/// executed before it.
+fn can_be_deduplicated(instruction: &Instruction, dfg: &DataFlowGraph) -> bool {
+    use Instruction::*;
+
+    match instruction {
+        Binary(_)
+        | Cast(_, _)
+        | Not(_)
+        | Truncate { .. }
+        | Constrain(_)
+        | ArrayGet { .. }
+        | ArraySet { .. } => true,
+
+        // Range checks are the only calls without results which can be removed
+        Call { func, .. } => {
+            matches!(dfg[*func], Value::Intrinsic(Intrinsic::BlackBox(BlackBoxFunc::RANGE)))
+        }
+
+        Allocate | Load { .. } | Store { .. } | EnableSideEffects { .. } => false,
+    }
+}
+
+/// Returns true if the ACIR generated for the given instruction depends on the side-effects
+/// condition it is under.
+fn depends_on_side_effects(instruction: &Instruction) -> bool {
+    match instruction {
+        Instruction::Binary(binary) => matches!(binary.operator, BinaryOp::Lt),
+        Instruction::ArrayGet { .. } | Instruction::ArraySet { .. } => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa_refactor::{
+        ir::{
+            function::RuntimeType,
+            instruction::{BinaryOp, Instruction, TerminatorInstruction},
+            map::Id,
+            types::Type,
+        },
+        parser::{assert_normalized_ssa_equals, parse_ssa},
+        ssa_builder::FunctionBuilder,
+    };
+
+    #[test]
+    fn deduplicates_pure_instructions() {
+        // fn main f0 {
+        //   b0(v0: Field, v1: Field):
+        //     v2 = add v0, v1
+        //     v3 = add v0, v1
+        //     v4 = cast v2 as u32
+        //     v5 = cast v3 as u32
+        //     v6 = truncate v4 to 8 bits, max_bit_size: 32
+        //     v7 = truncate v5 to 8 bits, max_bit_size: 32
+        //     return v6, v7
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+        let v1 = builder.add_parameter(Type::field());
+
+        let v2 = builder.insert_binary(v0, BinaryOp::Add, v1);
+        let v3 = builder.insert_binary(v0, BinaryOp::Add, v1);
+        let v4 = builder.insert_cast(v2, Type::unsigned(32));
+        let v5 = builder.insert_cast(v3, Type::unsigned(32));
+        let v6 = builder.insert_truncate(v4, 8, 32);
+        let v7 = builder.insert_truncate(v5, 8, 32);
+        builder.terminate_with_return(vec![v6, v7]);
+
+        // Expected output:
+        //
+        // fn main f0 {
+        //   b0(v0: Field, v1: Field):
+        //     v2 = add v0, v1
+        //     v4 = cast v2 as u32
+        //     v6 = truncate v4 to 8 bits, max_bit_size: 32
+        //     return v6, v6
+        // }
+        let ssa = builder.finish().common_subexpression_elimination();
+        let main = ssa.main();
+        let block = &main.dfg[main.entry_block()];
+        assert_eq!(block.instructions().len(), 3);
+
+        match block.unwrap_terminator() {
+            TerminatorInstruction::Return { return_values } => {
+                assert_eq!(main.dfg.resolve(return_values[0]), v6);
+                assert_eq!(main.dfg.resolve(return_values[1]), v6);
+            }
+            _ => unreachable!("b0 should have a return terminator"),
+        }
+    }
+
+    #[test]
+    fn deduplicates_constraints() {
+        // fn main f0 {
+        //   b0(v0: Field, v1: Field):
+        //     v2 = eq v0, v1
+        //     constrain v2
+        //     v3 = eq v0, v1
+        //     constrain v3
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+        let v1 = builder.add_parameter(Type::field());
+
+        let v2 = builder.insert_binary(v0, BinaryOp::Eq, v1);
+        builder.insert_constrain(v2);
+        let v3 = builder.insert_binary(v0, BinaryOp::Eq, v1);
+        builder.insert_constrain(v3);
+        builder.terminate_with_return(vec![]);
+
+        let ssa = builder.finish().common_subexpression_elimination();
+        let main = ssa.main();
+        let instructions = main.dfg[main.entry_block()].instructions();
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(main.dfg[instructions[1]], Instruction::Constrain(v2));
+    }
+
+    #[test]
+    fn only_deduplicates_dominated_instructions() {
+        // fn main f0 {
+        //   b0(v0: Field, v1: u1):
+        //     v2 = mul v0, v0
+        //     jmpif v1 then: b1, else: b2
+        //   b1():
+        //     v3 = mul v0, v0
+        //     v4 = add v0, Field 1
+        //     jmp b3()
+        //   b2():
+        //     v5 = add v0, Field 1
+        //     jmp b3()
+        //   b3():
+        //     return v3, v5
+        // }
+        //
+        // v3 should be replaced by v2 since b0 dominates b1, but v5 should be kept since
+        // b1 does not dominate b2.
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+        let v1 = builder.add_parameter(Type::bool());
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+
+        let v2 = builder.insert_binary(v0, BinaryOp::Mul, v0);
+        builder.terminate_with_jmpif(v1, b1, b2);
+
+        builder.switch_to_block(b1);
+        let one = builder.field_constant(1u128);
+        let v3 = builder.insert_binary(v0, BinaryOp::Mul, v0);
+        builder.insert_binary(v0, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b3, vec![]);
+
+        builder.switch_to_block(b2);
+        let v5 = builder.insert_binary(v0, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b3, vec![]);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_return(vec![v3, v5]);
+
+        let ssa = builder.finish().common_subexpression_elimination();
+        let main = ssa.main();
+        assert_eq!(main.dfg[b1].instructions().len(), 1);
+        assert_eq!(main.dfg[b2].instructions().len(), 1);
+        assert_eq!(main.dfg.resolve(v3), v2);
+        assert_eq!(main.dfg.resolve(v5), v5);
+    }
+
+    #[test]
+    fn keeps_comparisons_under_different_conditions() {
+        // fn main f0 {
+        //   b0(v0: u32, v1: u32, v2: u1):
+        //     enable_side_effects v2
+        //     v3 = lt v0, v1
+        //     enable_side_effects u1 1
+        //     v4 = lt v0, v1
+        //     return v3, v4
+        // }
+        //
+        // The range constraints of v3 are disabled when v2 is false,
+        // so v4 cannot reuse its result.
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::unsigned(32));
+        let v1 = builder.add_parameter(Type::unsigned(32));
+        let v2 = builder.add_parameter(Type::bool());
+
+        builder.insert_instruction(Instruction::EnableSideEffects { condition: v2 }, None);
+        let v3 = builder.insert_binary(v0, BinaryOp::Lt, v1);
+        let one = builder.numeric_constant(1u128, Type::bool());
+        builder.insert_instruction(Instruction::EnableSideEffects { condition: one }, None);
+        let v4 = builder.insert_binary(v0, BinaryOp::Lt, v1);
+        builder.terminate_with_return(vec![v3, v4]);
+
+        let ssa = builder.finish().common_subexpression_elimination();
+        let main = ssa.main();
+        assert_eq!(main.dfg[main.entry_block()].instructions().len(), 4);
+        assert_ne!(main.dfg.resolve(v3), main.dfg.resolve(v4));
+    }
+
+    #[test]
+    fn deduplicates_instructions_of_unrolled_loops() {
+        let src = "
+acir fn main f0 {
+  b0(v0: u32, v1: u32):
+    v2 = mul v0, v1
+    v3 = truncate v2 to 32 bits, max_bit_size: 64
+    v4 = add v3, u32 1
+    v5 = truncate v4 to 32 bits, max_bit_size: 33
+    v6 = mul v0, v1
+    v7 = truncate v6 to 32 bits, max_bit_size: 64
+    v8 = add v7, u32 1
+    v9 = truncate v8 to 32 bits, max_bit_size: 33
+    v10 = eq v5, v9
+    constrain v10
+    return v9
+}
+";
+        let expected = "
+acir fn main f0 {
+  b0(v0: u32, v1: u32):
+    v2 = mul v0, v1
+    v3 = truncate v2 to 32 bits, max_bit_size: 64
+    v4 = add v3, u32 1
+    v5 = truncate v4 to 32 bits, max_bit_size: 33
+    v6 = eq v5, v5
+    constrain v6
+    return v5
+}
+";
+        let ssa = parse_ssa(src).unwrap().common_subexpression_elimination();
+        assert_normalized_ssa_equals(ssa, expected);
+    }
+}
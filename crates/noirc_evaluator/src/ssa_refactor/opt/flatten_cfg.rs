@@ -143,7 +143,7 @@ use crate::ssa_refactor::{
     ir::{
         basic_block::BasicBlockId,
         cfg::ControlFlowGraph,
-        dfg::InsertInstructionResult,
+        dfg::{CallStack, InsertInstructionResult},
         function::Function,
         function_inserter::FunctionInserter,
         instruction::{BinaryOp, Instruction, InstructionId, TerminatorInstruction},
@@ -196,6 +196,11 @@ struct Context<'f> {
     /// condition. If we are under multiple conditions (a nested if), the topmost condition is
     /// the most recent condition combined with all previous conditions via `And` instructions.
     conditions: Vec<(BasicBlockId, ValueId)>,
+
+    /// The call stack of the instruction currently being inlined, which is given to any
+    /// instructions inserted on its behalf, such as those applying the branch condition
+    /// to a constrain. Instructions inserted while merging branches have no call stack.
+    call_stack: CallStack,
 }
 
 struct Store {
@@ -230,6 +235,7 @@ fn flatten_function_cfg(function: &mut Function) {
         local_allocations: HashSet::new(),
         branch_ends,
         conditions: Vec::new(),
+        call_stack: CallStack::new(),
     };
     context.flatten();
 }
@@ -329,7 +335,11 @@ impl<'f> Context<'f> {
     /// within the given instruction, nor will it modify self.values in any way.
     fn insert_instruction(&mut self, instruction: Instruction) -> ValueId {
         let block = self.inserter.function.entry_block();
-        self.inserter.function.dfg.insert_instruction_and_results(instruction, block, None).first()
+        self.inserter
+            .function
+            .dfg
+            .insert_instruction_and_results(instruction, block, None, self.call_stack.clone())
+            .first()
     }
 
     /// Inserts a new instruction into the function's entry block, using the given
@@ -342,7 +352,12 @@ impl<'f> Context<'f> {
         ctrl_typevars: Option<Vec<Type>>,
     ) -> InsertInstructionResult {
         let block = self.inserter.function.entry_block();
-        self.inserter.function.dfg.insert_instruction_and_results(instruction, block, ctrl_typevars)
+        self.inserter.function.dfg.insert_instruction_and_results(
+            instruction,
+            block,
+            ctrl_typevars,
+            self.call_stack.clone(),
+        )
     }
 
     /// Checks the branch condition on the top of the stack and uses it to build and insert an
@@ -390,6 +405,9 @@ impl<'f> Context<'f> {
             ),
             Type::Reference => panic!("Cannot return references from an if expression"),
             Type::Function => panic!("Cannot return functions from an if expression"),
+            Type::Slice(_) => {
+                unreachable!("Slices only exist in Brillig functions, which are not flattened")
+            }
         }
     }
 
@@ -443,7 +461,6 @@ impl<'f> Context<'f> {
         then_value: ValueId,
         else_value: ValueId,
     ) -> ValueId {
-        let block = self.inserter.function.entry_block();
         let then_type = self.inserter.function.dfg.type_of_value(then_value);
         let else_type = self.inserter.function.dfg.type_of_value(else_value);
         assert_eq!(
@@ -456,15 +473,13 @@ impl<'f> Context<'f> {
         let else_condition = self.insert_instruction(Instruction::Cast(else_condition, else_type));
 
         let mul = Instruction::binary(BinaryOp::Mul, then_condition, then_value);
-        let then_value =
-            self.inserter.function.dfg.insert_instruction_and_results(mul, block, None).first();
+        let then_value = self.insert_instruction(mul);
 
         let mul = Instruction::binary(BinaryOp::Mul, else_condition, else_value);
-        let else_value =
-            self.inserter.function.dfg.insert_instruction_and_results(mul, block, None).first();
+        let else_value = self.insert_instruction(mul);
 
         let add = Instruction::binary(BinaryOp::Add, then_value, else_value);
-        self.inserter.function.dfg.insert_instruction_and_results(add, block, None).first()
+        self.insert_instruction(add)
     }
 
     /// Inline one branch of a jmpif instruction.
@@ -649,12 +664,15 @@ impl<'f> Context<'f> {
     /// with a different InstructionId from the original. The results of the given instruction
     /// will also be mapped to the results of the new instruction.
     fn push_instruction(&mut self, id: InstructionId) {
+        self.call_stack = self.inserter.function.dfg.get_call_stack(id);
+
         let instruction = self.inserter.map_instruction(id);
         let instruction = self.handle_instruction_side_effects(instruction);
         let is_allocate = matches!(instruction, Instruction::Allocate);
 
         let entry = self.inserter.function.entry_block();
         let results = self.inserter.push_instruction_value(instruction, id, entry);
+        self.call_stack = CallStack::new();
 
         // Remember an allocate was created local to this branch so that we do not try to merge store
         // values across branches for it later.
@@ -702,6 +720,8 @@ impl<'f> Context<'f> {
 mod test {
     use std::rc::Rc;
 
+    use noirc_errors::{Location, Span};
+
     use crate::ssa_refactor::{
         ir::{
             dfg::DataFlowGraph,
@@ -1348,6 +1368,50 @@ mod test {
         assert_eq!(constrain_count, 1);
     }
 
+    #[test]
+    fn constrain_in_branch_keeps_its_call_stack() {
+        // fn main f0 {
+        //   b0(v0: u1, v1: u1):
+        //     jmpif v0, then: b1, else: b2
+        //   b1():
+        //     constrain v1         // at constrain_location
+        //     jmp b2()
+        //   b2():
+        //     return
+        // }
+        let constrain_location = Location::new(Span::single_char(1), Default::default());
+
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+
+        let v0 = builder.add_parameter(Type::bool());
+        let v1 = builder.add_parameter(Type::bool());
+        builder.terminate_with_jmpif(v0, b1, b2);
+
+        builder.switch_to_block(b1);
+        builder.set_location(constrain_location).insert_constrain(v1);
+        builder.terminate_with_jmp(b2, vec![]);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_return(vec![]);
+
+        let ssa = builder.finish().flatten_cfg();
+        let main = ssa.main();
+
+        // The condition is applied to the constrain by instructions which share its location,
+        // while the instructions enabling side effects don't belong to any source location
+        for instruction in main.dfg[main.entry_block()].instructions() {
+            let call_stack = main.dfg.get_call_stack(*instruction);
+            match &main.dfg[*instruction] {
+                Instruction::EnableSideEffects { .. } => assert!(call_stack.is_empty()),
+                _ => assert_eq!(call_stack, im::vector![constrain_location]),
+            }
+        }
+    }
+
     #[test]
     fn undo_stores() {
         // Regression test for #1826. Ensures the `else` branch does not see the stores of the
@@ -9,7 +9,7 @@ use iter_extended::vecmap;
 use crate::ssa_refactor::{
     ir::{
         basic_block::BasicBlockId,
-        dfg::InsertInstructionResult,
+        dfg::{CallStack, InsertInstructionResult},
         function::{Function, FunctionId, RuntimeType},
         instruction::{Instruction, InstructionId, TerminatorInstruction},
         value::{Value, ValueId},
@@ -49,6 +49,10 @@ struct InlineContext {
     recursion_level: u32,
     builder: FunctionBuilder,
 
+    /// The locations of each call currently being inlined, from the outermost call in main
+    /// down to the call of the function currently being inlined.
+    call_stack: CallStack,
+
     /// True if we failed to inline at least one call. If this is still false when finishing
     /// inlining we can remove all other functions from the resulting Ssa struct and keep only
     /// the function that was inlined into.
@@ -98,7 +102,12 @@ impl InlineContext {
     fn new(ssa: &Ssa) -> InlineContext {
         let main_name = ssa.main().name().to_owned();
         let builder = FunctionBuilder::new(main_name, ssa.next_id.next(), RuntimeType::Acir);
-        Self { builder, recursion_level: 0, failed_to_inline_a_call: false }
+        Self {
+            builder,
+            recursion_level: 0,
+            call_stack: CallStack::new(),
+            failed_to_inline_a_call: false,
+        }
     }
 
     /// Start inlining the main function and all functions reachable from it.
@@ -370,7 +379,14 @@ impl<'function> PerFunctionContext<'function> {
     ) {
         let old_results = self.source_function.dfg.instruction_results(call_id);
         let arguments = vecmap(arguments, |arg| self.translate_value(*arg));
+
+        let call_stack = self.source_function.dfg.get_call_stack(call_id);
+        let call_stack_len = call_stack.len();
+        self.context.call_stack.append(call_stack);
+
         let new_results = self.context.inline_function(ssa, function, &arguments);
+
+        self.context.call_stack.truncate(self.context.call_stack.len() - call_stack_len);
         let new_results = InsertInstructionResult::Results(&new_results);
         Self::insert_new_instruction_results(&mut self.values, old_results, new_results);
     }
@@ -386,6 +402,10 @@ impl<'function> PerFunctionContext<'function> {
             .requires_ctrl_typevars()
             .then(|| vecmap(&results, |result| self.source_function.dfg.type_of_value(*result)));
 
+        let mut call_stack = self.context.call_stack.clone();
+        call_stack.append(self.source_function.dfg.get_call_stack(id));
+
+        self.context.builder.set_call_stack(call_stack);
         let new_results = self.context.builder.insert_instruction(instruction, ctrl_typevars);
         Self::insert_new_instruction_results(&mut self.values, &results, new_results);
     }
@@ -472,6 +492,7 @@ impl<'function> PerFunctionContext<'function> {
 #[cfg(test)]
 mod test {
     use acvm::FieldElement;
+    use noirc_errors::{Location, Span};
 
     use crate::ssa_refactor::{
         ir::{
@@ -750,4 +771,43 @@ mod test {
         let main = ssa.main();
         assert_eq!(main.reachable_blocks().len(), 4);
     }
+
+    #[test]
+    fn inlined_instructions_keep_their_call_stack() {
+        // fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = call f1(v0)     // at call_location
+        //     return v1
+        // }
+        // fn square f1 {
+        //   b0(v0: Field):
+        //     v1 = mul v0, v0      // at mul_location
+        //     return v1
+        // }
+        let call_location = Location::new(Span::single_char(1), Default::default());
+        let mul_location = Location::new(Span::single_char(2), Default::default());
+
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let main_v0 = builder.add_parameter(Type::field());
+        let square_id = Id::test_new(1);
+        let square = builder.import_function(square_id);
+        builder.set_location(call_location);
+        let main_v1 = builder.insert_call(square, vec![main_v0], vec![Type::field()])[0];
+        builder.terminate_with_return(vec![main_v1]);
+
+        builder.new_function("square".into(), square_id);
+        let square_v0 = builder.add_parameter(Type::field());
+        builder.set_location(mul_location);
+        let square_v1 = builder.insert_binary(square_v0, BinaryOp::Mul, square_v0);
+        builder.terminate_with_return(vec![square_v1]);
+
+        let ssa = builder.finish().inline_functions();
+        let main = ssa.main();
+        let instructions = main.dfg[main.entry_block()].instructions();
+        assert_eq!(instructions.len(), 1);
+
+        let call_stack = main.dfg.get_call_stack(instructions[0]);
+        assert_eq!(call_stack, im::vector![call_location, mul_location]);
+    }
 }
@@ -4,9 +4,11 @@
 //! simpler form until the IR only has a single function remaining with 1 block within it.
 //! Generally, these passes are also expected to minimize the final amount of instructions.
 mod constant_folding;
+mod cse;
 mod die;
 mod flatten_cfg;
 mod inlining;
 mod mem2reg;
+mod range_checks;
 mod simplify_cfg;
 mod unrolling;
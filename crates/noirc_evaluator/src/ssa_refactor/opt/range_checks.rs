@@ -0,0 +1,203 @@
+//! This pass removes truncations which are redundant because the value they apply to is already
+//! proven to fit within the required number of bits, using the `BitSizeAnalysis` of each function.
+//!
+//! Truncations which cannot be removed are narrowed instead: their `max_bit_size` is lowered to
+//! the bit size proven for their value, which reduces the width of the range constraint placed on
+//! the truncation's quotient.
+//!
+//! Casts are truncated during ACIR generation rather than in the SSA, which uses the same
+//! analysis to skip their truncation when it is redundant. They are counted here so that the
+//! summary printed by this pass reflects all of the savings.
+use crate::ssa_refactor::{
+    ir::{
+        bit_size::BitSizeAnalysis, function::Function, instruction::Instruction,
+        post_order::PostOrder,
+    },
+    ssa_gen::Ssa,
+};
+
+/// The number of ACIR opcodes generated for a truncation: a quotient directive, two range
+/// constraints and the arithmetic constraint relating the value to its quotient and remainder.
+const TRUNCATION_OPCODES: usize = 4;
+
+impl Ssa {
+    /// Removes any truncations which are redundant, and narrows the remaining truncations
+    /// where possible.
+    ///
+    /// If `print_stats` is true, the number of ACIR opcodes saved is printed.
+    pub(crate) fn remove_redundant_range_checks(mut self, print_stats: bool) -> Ssa {
+        let mut stats = RangeCheckStats::default();
+        for function in self.functions.values_mut() {
+            remove_redundant_range_checks(function, &mut stats);
+        }
+        if print_stats {
+            println!("{stats}");
+        }
+        self
+    }
+}
+
+/// The range checks removed or narrowed across all functions.
+#[derive(Default)]
+struct RangeCheckStats {
+    removed_truncations: usize,
+    narrowed_truncations: usize,
+    removed_cast_truncations: usize,
+}
+
+impl std::fmt::Display for RangeCheckStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let saved_opcodes =
+            (self.removed_truncations + self.removed_cast_truncations) * TRUNCATION_OPCODES;
+
+        writeln!(f, "Redundant range checks:")?;
+        writeln!(f, "  removed truncations: {}", self.removed_truncations)?;
+        writeln!(f, "  narrowed truncations: {}", self.narrowed_truncations)?;
+        writeln!(f, "  removed cast truncations: {}", self.removed_cast_truncations)?;
+        write!(f, "  ACIR opcodes saved: {saved_opcodes}")
+    }
+}
+
+/// The blocks are visited in reverse post-order, so that the bit sizes of the values used by an
+/// instruction are known before the instruction is visited, unless they are block parameters.
+fn remove_redundant_range_checks(function: &mut Function, stats: &mut RangeCheckStats) {
+    let mut analysis = BitSizeAnalysis::default();
+    let post_order = PostOrder::with_function(function);
+
+    for block in post_order.as_slice().iter().rev() {
+        let instructions = function.dfg[*block].instructions().to_vec();
+
+        for instruction_id in instructions {
+            match function.dfg[instruction_id].clone() {
+                Instruction::Truncate { value, bit_size, max_bit_size } => {
+                    if analysis.fits_in(&function.dfg, value, bit_size) {
+                        let result = function.dfg.instruction_results(instruction_id)[0];
+                        function.dfg.set_value_from_id(result, value);
+                        function.dfg[*block].remove_instruction(instruction_id);
+                        stats.removed_truncations += 1;
+                        continue;
+                    }
+
+                    let value_bit_size = analysis.max_bit_size(&function.dfg, value);
+                    if value_bit_size < max_bit_size {
+                        function.dfg[instruction_id] =
+                            Instruction::Truncate { value, bit_size, max_bit_size: value_bit_size };
+                        stats.narrowed_truncations += 1;
+                    }
+                }
+                Instruction::Cast(value, typ) => {
+                    if analysis.is_redundant_cast_truncation(&function.dfg, value, &typ) {
+                        stats.removed_cast_truncations += 1;
+                    }
+                }
+                _ => (),
+            }
+
+            analysis.analyze_instruction(&function.dfg, instruction_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa_refactor::{
+        ir::{
+            function::RuntimeType,
+            instruction::{BinaryOp, Instruction, TerminatorInstruction},
+            map::Id,
+            types::Type,
+        },
+        ssa_builder::FunctionBuilder,
+    };
+
+    #[test]
+    fn removes_and_narrows_truncations() {
+        // fn main f0 {
+        //   b0(v0: u8, v1: u8):
+        //     v2 = truncate v0 to 8 bits, max_bit_size: 9
+        //     v3 = add v0, v1
+        //     v4 = truncate v3 to 8 bits, max_bit_size: 16
+        //     v5 = sub v0, v1
+        //     v6 = truncate v5 to 8 bits, max_bit_size: 9
+        //     return v2, v4, v6
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::unsigned(8));
+        let v1 = builder.add_parameter(Type::unsigned(8));
+
+        let v2 = builder.insert_truncate(v0, 8, 9);
+        let v3 = builder.insert_binary(v0, BinaryOp::Add, v1);
+        let v4 = builder.insert_truncate(v3, 8, 16);
+        let v5 = builder.insert_binary(v0, BinaryOp::Sub, v1);
+        let v6 = builder.insert_truncate(v5, 8, 9);
+        builder.terminate_with_return(vec![v2, v4, v6]);
+
+        // Expected output:
+        //
+        // fn main f0 {
+        //   b0(v0: u8, v1: u8):
+        //     v3 = add v0, v1
+        //     v4 = truncate v3 to 8 bits, max_bit_size: 9
+        //     v5 = sub v0, v1
+        //     v6 = truncate v5 to 8 bits, max_bit_size: 9
+        //     return v0, v4, v6
+        // }
+        let ssa = builder.finish().remove_redundant_range_checks(false);
+        let main = ssa.main();
+        let block = &main.dfg[main.entry_block()];
+        let instructions = block.instructions();
+        assert_eq!(instructions.len(), 4);
+        assert_eq!(
+            main.dfg[instructions[1]],
+            Instruction::Truncate { value: v3, bit_size: 8, max_bit_size: 9 }
+        );
+        // The result of a subtraction may underflow, so its truncation is kept as is
+        assert_eq!(
+            main.dfg[instructions[3]],
+            Instruction::Truncate { value: v5, bit_size: 8, max_bit_size: 9 }
+        );
+
+        match block.unwrap_terminator() {
+            TerminatorInstruction::Return { return_values } => {
+                assert_eq!(main.dfg.resolve(return_values[0]), v0);
+                assert_eq!(main.dfg.resolve(return_values[1]), v4);
+            }
+            _ => unreachable!("b0 should have a return terminator"),
+        }
+    }
+
+    #[test]
+    fn keeps_truncations_of_unbounded_values() {
+        // fn main f0 {
+        //   b0(v0: Field):
+        //     v1 = truncate v0 to 8 bits, max_bit_size: 254
+        //     v2 = mul v1, v1
+        //     v3 = truncate v2 to 8 bits, max_bit_size: 254
+        //     return v3
+        // }
+        //
+        // v1 is unbounded, while v2 fits in 16 bits.
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+
+        let v1 = builder.insert_truncate(v0, 8, 254);
+        let v2 = builder.insert_binary(v1, BinaryOp::Mul, v1);
+        let v3 = builder.insert_truncate(v2, 8, 254);
+        builder.terminate_with_return(vec![v3]);
+
+        let ssa = builder.finish().remove_redundant_range_checks(false);
+        let main = ssa.main();
+        let instructions = main.dfg[main.entry_block()].instructions();
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(
+            main.dfg[instructions[0]],
+            Instruction::Truncate { value: v0, bit_size: 8, max_bit_size: 254 }
+        );
+        assert_eq!(
+            main.dfg[instructions[2]],
+            Instruction::Truncate { value: v2, bit_size: 8, max_bit_size: 16 }
+        );
+    }
+}
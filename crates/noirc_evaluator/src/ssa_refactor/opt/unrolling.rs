@@ -16,9 +16,15 @@ use std::collections::{HashMap, HashSet};
 
 use crate::ssa_refactor::{
     ir::{
-        basic_block::BasicBlockId, cfg::ControlFlowGraph, dfg::DataFlowGraph, dom::DominatorTree,
-        function::Function, function_inserter::FunctionInserter,
-        instruction::TerminatorInstruction, post_order::PostOrder, value::ValueId,
+        basic_block::BasicBlockId,
+        cfg::ControlFlowGraph,
+        dfg::DataFlowGraph,
+        dom::DominatorTree,
+        function::{Function, RuntimeType},
+        function_inserter::FunctionInserter,
+        instruction::TerminatorInstruction,
+        post_order::PostOrder,
+        value::ValueId,
     },
     ssa_gen::Ssa,
 };
@@ -26,8 +32,14 @@ use crate::ssa_refactor::{
 impl Ssa {
     /// Unroll all loops in each SSA function.
     /// If any loop cannot be unrolled, it is left as-is or in a partially unrolled state.
+    ///
+    /// Brillig functions are skipped: their loops are executed as-is, and `while` loops or
+    /// loops exiting early with `break` may not have a known number of iterations.
     pub(crate) fn unroll_loops(mut self) -> Ssa {
         for function in self.functions.values_mut() {
+            if function.runtime() == RuntimeType::Brillig {
+                continue;
+            }
             find_all_loops(function).unroll_each_loop(function);
         }
         self
@@ -0,0 +1,87 @@
+//! Splits a single line of SSA text into tokens.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Token {
+    /// A keyword, value, block, function or type name, e.g. `add`, `v0`, `b1`, `f2` or `u32`
+    Ident(String),
+    /// A decimal or `0x`-prefixed hexadecimal integer
+    Int(String),
+    Minus,
+    Arrow,
+    Comma,
+    Colon,
+    Semicolon,
+    Equal,
+    LeftParen,
+    RightParen,
+    LeftBracket,
+    RightBracket,
+    LeftBrace,
+    RightBrace,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "{ident}"),
+            Token::Int(int) => write!(f, "{int}"),
+            Token::Minus => write!(f, "-"),
+            Token::Arrow => write!(f, "->"),
+            Token::Comma => write!(f, ","),
+            Token::Colon => write!(f, ":"),
+            Token::Semicolon => write!(f, ";"),
+            Token::Equal => write!(f, "="),
+            Token::LeftParen => write!(f, "("),
+            Token::RightParen => write!(f, ")"),
+            Token::LeftBracket => write!(f, "["),
+            Token::RightBracket => write!(f, "]"),
+            Token::LeftBrace => write!(f, "{{"),
+            Token::RightBrace => write!(f, "}}"),
+        }
+    }
+}
+
+/// Returns the tokens of the given line, or an error describing the first unexpected character.
+pub(super) fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        let token = match char {
+            ' ' | '\t' => continue,
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                Token::Arrow
+            }
+            '-' => Token::Minus,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            ';' => Token::Semicolon,
+            '=' => Token::Equal,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '[' => Token::LeftBracket,
+            ']' => Token::RightBracket,
+            '{' => Token::LeftBrace,
+            '}' => Token::RightBrace,
+            _ if char.is_ascii_digit() => {
+                let mut int = char.to_string();
+                while let Some(next) = chars.next_if(|next| next.is_ascii_alphanumeric()) {
+                    int.push(next);
+                }
+                Token::Int(int)
+            }
+            _ if char.is_alphabetic() || char == '_' => {
+                let mut ident = char.to_string();
+                while let Some(next) = chars.next_if(|next| next.is_alphanumeric() || *next == '_')
+                {
+                    ident.push(next);
+                }
+                Token::Ident(ident)
+            }
+            _ => return Err(format!("unexpected character `{char}`")),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
@@ -0,0 +1,754 @@
+//! A parser for the textual form of the SSA, as printed by `ir/printer.rs`.
+//!
+//! This allows SSA passes to be tested by running them on SSA written as text and comparing the
+//! result to the expected SSA text, rather than building the input IR through the
+//! `FunctionBuilder` and inspecting the output by hand.
+//!
+//! The text is parsed line by line: each line holds either a function header, the closing brace
+//! of a function, a block header, an instruction or a terminator. Functions, blocks and values are
+//! referred to by the names they were printed with, e.g. `f1`, `b2` or `v3`, but are given fresh
+//! ids when parsed. The instructions are inserted as they are written, without being simplified.
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use acvm::FieldElement;
+use thiserror::Error;
+
+use crate::ssa_refactor::{
+    ir::{
+        basic_block::BasicBlockId,
+        function::{Function, FunctionId, RuntimeType},
+        instruction::{BinaryOp, Instruction, Intrinsic, TerminatorInstruction},
+        map::AtomicCounter,
+        types::Type,
+        value::ValueId,
+    },
+    ssa_gen::Ssa,
+};
+
+use self::lexer::{tokenize, Token};
+
+mod lexer;
+
+/// An error found while parsing SSA text, along with the line it was found on.
+#[derive(Debug, Error)]
+#[error("line {line}: {message}")]
+pub struct SsaParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Parses SSA text in the format printed by the `Display` implementation of `Ssa`.
+///
+/// The first function of the text is the program's main function.
+pub(crate) fn parse_ssa(source: &str) -> Result<Ssa, SsaParseError> {
+    let mut lines = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let tokens =
+            tokenize(line).map_err(|message| SsaParseError { line: index + 1, message })?;
+        if !tokens.is_empty() {
+            lines.push((index + 1, tokens));
+        }
+    }
+
+    // Functions may be called before they are defined, so their ids are assigned up front
+    let function_ids = AtomicCounter::default();
+    let mut function_names = HashMap::new();
+    for (_, tokens) in &lines {
+        if let [Token::Ident(_), Token::Ident(keyword), Token::Ident(_), Token::Ident(name), ..] =
+            tokens.as_slice()
+        {
+            if keyword == "fn" {
+                function_names.insert(name.clone(), function_ids.next());
+            }
+        }
+    }
+
+    let mut parser = Parser { function_names, functions: Vec::new(), current_function: None };
+    for (line, tokens) in &lines {
+        let tokens = Tokens { tokens: tokens.clone(), position: 0 };
+        parser.parse_line(tokens).map_err(|message| SsaParseError { line: *line, message })?;
+    }
+
+    let last_line = lines.last().map_or(0, |(line, _)| *line);
+    if parser.current_function.is_some() {
+        let message = "expected `}` at the end of the function".to_string();
+        return Err(SsaParseError { line: last_line, message });
+    }
+    if parser.functions.is_empty() {
+        let message = "expected at least one function".to_string();
+        return Err(SsaParseError { line: last_line, message });
+    }
+    Ok(Ssa::new(parser.functions))
+}
+
+/// Asserts that the given SSA is equal to the expected SSA text, up to the naming of values,
+/// blocks and functions and the formatting of the text.
+///
+/// Both are parsed, which numbers their values and blocks in the order they are written, before
+/// being printed again for comparison.
+#[cfg(test)]
+pub(crate) fn assert_normalized_ssa_equals(ssa: Ssa, expected: &str) {
+    let normalize = |source: &str| match parse_ssa(source) {
+        Ok(ssa) => ssa.to_string(),
+        Err(error) => panic!("Failed to parse SSA: {error}\n{source}"),
+    };
+    assert_eq!(normalize(&ssa.to_string()), normalize(expected));
+}
+
+/// The kinds of lines of SSA text, told apart by their first tokens.
+enum LineKind {
+    /// e.g. `acir fn main f0 {`
+    FunctionHeader,
+    /// `}`
+    FunctionEnd,
+    /// e.g. `b1(v0: Field):`
+    BlockHeader,
+    /// `(no terminator instruction)`, printed for a block still under construction
+    MissingTerminator,
+    /// e.g. `jmp b1(v0)`
+    Terminator,
+    /// e.g. `v2 = add v0, v1`
+    Instruction,
+}
+
+impl LineKind {
+    fn of(tokens: &[Token]) -> LineKind {
+        match tokens {
+            [Token::Ident(_), Token::Ident(keyword), ..] if keyword == "fn" => {
+                LineKind::FunctionHeader
+            }
+            [Token::RightBrace] => LineKind::FunctionEnd,
+            [Token::Ident(name), Token::LeftParen, ..] if name.starts_with('b') => {
+                LineKind::BlockHeader
+            }
+            [Token::LeftParen, Token::Ident(no), ..] if no == "no" => LineKind::MissingTerminator,
+            [Token::Ident(keyword), ..]
+                if matches!(keyword.as_str(), "jmp" | "jmpif" | "return") =>
+            {
+                LineKind::Terminator
+            }
+            _ => LineKind::Instruction,
+        }
+    }
+}
+
+struct Parser {
+    /// The ids of each function, keyed by the name they are referred to with, e.g. `f1`
+    function_names: HashMap<String, FunctionId>,
+    functions: Vec<Function>,
+    current_function: Option<FunctionContext>,
+}
+
+/// The function being parsed, along with the blocks and values named within it so far.
+struct FunctionContext {
+    function: Function,
+    blocks: HashMap<String, BasicBlockId>,
+    defined_blocks: HashSet<BasicBlockId>,
+    current_block: Option<BasicBlockId>,
+    values: HashMap<String, ValueId>,
+}
+
+impl Parser {
+    fn parse_line(&mut self, mut tokens: Tokens) -> Result<(), String> {
+        let kind = LineKind::of(&tokens.tokens);
+        match kind {
+            LineKind::FunctionHeader => return self.parse_function_header(tokens),
+            LineKind::FunctionEnd => return self.finish_function(),
+            _ => (),
+        }
+
+        let context = self
+            .current_function
+            .as_mut()
+            .ok_or_else(|| "expected a function header".to_string())?;
+        match kind {
+            LineKind::BlockHeader => context.parse_block_header(tokens),
+            LineKind::Terminator => context.parse_terminator(&mut tokens, &self.function_names),
+            LineKind::Instruction => context.parse_instruction(&mut tokens, &self.function_names),
+            LineKind::MissingTerminator => Ok(()),
+            LineKind::FunctionHeader | LineKind::FunctionEnd => unreachable!(),
+        }
+    }
+
+    /// Parses a function header, e.g. `acir fn main f0 {`
+    fn parse_function_header(&mut self, mut tokens: Tokens) -> Result<(), String> {
+        if self.current_function.is_some() {
+            return Err("expected `}` before the next function".to_string());
+        }
+
+        let runtime = match tokens.expect_ident()?.as_str() {
+            "acir" => RuntimeType::Acir,
+            "brillig" => RuntimeType::Brillig,
+            other => return Err(format!("expected `acir` or `brillig`, found `{other}`")),
+        };
+        tokens.expect_keyword("fn")?;
+        let name = tokens.expect_ident()?;
+        let id = tokens.expect_ident()?;
+        let id = self.function_names[&id];
+        tokens.expect(Token::LeftBrace)?;
+        tokens.expect_end()?;
+
+        let mut function = Function::new(name, id);
+        function.set_runtime(runtime);
+        self.current_function = Some(FunctionContext {
+            function,
+            blocks: HashMap::new(),
+            defined_blocks: HashSet::new(),
+            current_block: None,
+            values: HashMap::new(),
+        });
+        Ok(())
+    }
+
+    fn finish_function(&mut self) -> Result<(), String> {
+        let context = self.current_function.take().ok_or_else(|| "unexpected `}`".to_string())?;
+
+        for (name, block) in &context.blocks {
+            if !context.defined_blocks.contains(block) {
+                return Err(format!("block `{name}` is used but never defined"));
+            }
+        }
+        self.functions.push(context.function);
+        Ok(())
+    }
+}
+
+impl FunctionContext {
+    /// Parses a block header along with its parameters, e.g. `b1(v0: Field, v1: u8):`
+    fn parse_block_header(&mut self, mut tokens: Tokens) -> Result<(), String> {
+        let name = tokens.expect_ident()?;
+
+        // The first block of a function is its entry block
+        let block = if self.blocks.is_empty() {
+            let entry_block = self.function.entry_block();
+            self.blocks.insert(name.clone(), entry_block);
+            entry_block
+        } else {
+            self.block(name.clone())
+        };
+        if !self.defined_blocks.insert(block) {
+            return Err(format!("block `{name}` is defined twice"));
+        }
+
+        tokens.expect(Token::LeftParen)?;
+        if !tokens.eat(&Token::RightParen) {
+            loop {
+                let name = tokens.expect_ident()?;
+                tokens.expect(Token::Colon)?;
+                let typ = tokens.parse_type()?;
+                let parameter = self.function.dfg.add_block_parameter(block, typ);
+                self.define_value(name, parameter)?;
+
+                if !tokens.eat(&Token::Comma) {
+                    tokens.expect(Token::RightParen)?;
+                    break;
+                }
+            }
+        }
+        tokens.expect(Token::Colon)?;
+        tokens.expect_end()?;
+
+        self.current_block = Some(block);
+        Ok(())
+    }
+
+    /// Parses an instruction along with the names of its results, e.g. `v2 = add v0, v1`
+    fn parse_instruction(
+        &mut self,
+        tokens: &mut Tokens,
+        function_names: &HashMap<String, FunctionId>,
+    ) -> Result<(), String> {
+        let block = self.current_block()?;
+
+        let mut result_names = Vec::new();
+        if matches!(tokens.tokens.get(1), Some(Token::Comma | Token::Equal)) {
+            loop {
+                result_names.push(tokens.expect_ident()?);
+                if !tokens.eat(&Token::Comma) {
+                    tokens.expect(Token::Equal)?;
+                    break;
+                }
+            }
+        }
+
+        let instruction = match tokens.expect_ident()?.as_str() {
+            "cast" => {
+                let value = self.parse_value(tokens, function_names)?;
+                tokens.expect_keyword("as")?;
+                Instruction::Cast(value, tokens.parse_type()?)
+            }
+            "not" => Instruction::Not(self.parse_value(tokens, function_names)?),
+            "truncate" => {
+                let value = self.parse_value(tokens, function_names)?;
+                tokens.expect_keyword("to")?;
+                let bit_size = tokens.expect_u32()?;
+                tokens.expect_keyword("bits")?;
+                tokens.expect(Token::Comma)?;
+                tokens.expect_keyword("max_bit_size")?;
+                tokens.expect(Token::Colon)?;
+                let max_bit_size = tokens.expect_u32()?;
+                Instruction::Truncate { value, bit_size, max_bit_size }
+            }
+            "constrain" => Instruction::Constrain(self.parse_value(tokens, function_names)?),
+            "call" => {
+                let func = self.parse_value(tokens, function_names)?;
+                tokens.expect(Token::LeftParen)?;
+                let arguments =
+                    self.parse_values(tokens, function_names, Some(Token::RightParen))?;
+                Instruction::Call { func, arguments }
+            }
+            "allocate" => Instruction::Allocate,
+            "load" => Instruction::Load { address: self.parse_value(tokens, function_names)? },
+            "store" => {
+                let value = self.parse_value(tokens, function_names)?;
+                tokens.expect_keyword("at")?;
+                let address = self.parse_value(tokens, function_names)?;
+                Instruction::Store { address, value }
+            }
+            "enable_side_effects" => {
+                let condition = self.parse_value(tokens, function_names)?;
+                Instruction::EnableSideEffects { condition }
+            }
+            "array_get" => {
+                let array = self.parse_value(tokens, function_names)?;
+                tokens.expect(Token::Comma)?;
+                tokens.expect_keyword("index")?;
+                let index = self.parse_value(tokens, function_names)?;
+                Instruction::ArrayGet { array, index }
+            }
+            "array_set" => {
+                let array = self.parse_value(tokens, function_names)?;
+                tokens.expect(Token::Comma)?;
+                tokens.expect_keyword("index")?;
+                let index = self.parse_value(tokens, function_names)?;
+                tokens.expect(Token::Comma)?;
+                tokens.expect_keyword("value")?;
+                let value = self.parse_value(tokens, function_names)?;
+                Instruction::ArraySet { array, index, value }
+            }
+            other => {
+                let operator = binary_operator(other)
+                    .ok_or_else(|| format!("expected an instruction, found `{other}`"))?;
+                let lhs = self.parse_value(tokens, function_names)?;
+                tokens.expect(Token::Comma)?;
+                let rhs = self.parse_value(tokens, function_names)?;
+                Instruction::binary(operator, lhs, rhs)
+            }
+        };
+
+        // The types of the results are only written when they are not implied by the instruction
+        let mut result_types = Vec::new();
+        if tokens.eat(&Token::Arrow) {
+            loop {
+                result_types.push(tokens.parse_type()?);
+                if !tokens.eat(&Token::Comma) {
+                    break;
+                }
+            }
+        }
+        tokens.expect_end()?;
+
+        let ctrl_typevars = instruction.requires_ctrl_typevars().then_some(result_types);
+        let instruction = self.function.dfg.make_instruction(instruction, ctrl_typevars);
+        self.function.dfg[block].insert_instruction(instruction);
+
+        let results = self.function.dfg.instruction_results(instruction).to_vec();
+        if results.len() != result_names.len() {
+            return Err(format!(
+                "expected {} results for this instruction, found {}",
+                results.len(),
+                result_names.len()
+            ));
+        }
+        for (name, result) in result_names.into_iter().zip(results) {
+            self.define_value(name, result)?;
+        }
+        Ok(())
+    }
+
+    /// Parses the terminator of the current block, e.g. `jmp b1(v0)` or `return v1`
+    fn parse_terminator(
+        &mut self,
+        tokens: &mut Tokens,
+        function_names: &HashMap<String, FunctionId>,
+    ) -> Result<(), String> {
+        let block = self.current_block()?;
+
+        let terminator = match tokens.expect_ident()?.as_str() {
+            "jmp" => {
+                let destination = self.block(tokens.expect_ident()?);
+                tokens.expect(Token::LeftParen)?;
+                let arguments =
+                    self.parse_values(tokens, function_names, Some(Token::RightParen))?;
+                TerminatorInstruction::Jmp { destination, arguments }
+            }
+            "jmpif" => {
+                let condition = self.parse_value(tokens, function_names)?;
+                tokens.expect_keyword("then")?;
+                tokens.expect(Token::Colon)?;
+                let then_destination = self.block(tokens.expect_ident()?);
+                tokens.expect(Token::Comma)?;
+                tokens.expect_keyword("else")?;
+                tokens.expect(Token::Colon)?;
+                let else_destination = self.block(tokens.expect_ident()?);
+                TerminatorInstruction::JmpIf { condition, then_destination, else_destination }
+            }
+            _ => {
+                let return_values = self.parse_values(tokens, function_names, None)?;
+                TerminatorInstruction::Return { return_values }
+            }
+        };
+        tokens.expect_end()?;
+
+        self.function.dfg[block].set_terminator(terminator);
+        self.current_block = None;
+        Ok(())
+    }
+
+    /// Parses a comma-separated list of values, which ends either with the given closing token or
+    /// at the end of the line.
+    fn parse_values(
+        &mut self,
+        tokens: &mut Tokens,
+        function_names: &HashMap<String, FunctionId>,
+        closing_token: Option<Token>,
+    ) -> Result<Vec<ValueId>, String> {
+        let mut values = Vec::new();
+        let is_closed = |tokens: &mut Tokens| match &closing_token {
+            Some(closing_token) => tokens.eat(closing_token),
+            None => tokens.is_at_end(),
+        };
+
+        if is_closed(tokens) {
+            return Ok(values);
+        }
+        loop {
+            values.push(self.parse_value(tokens, function_names)?);
+            if !tokens.eat(&Token::Comma) {
+                if let Some(closing_token) = &closing_token {
+                    tokens.expect(closing_token.clone())?;
+                }
+                return Ok(values);
+            }
+        }
+    }
+
+    /// Parses a value, which is either:
+    /// - A value defined earlier in the function, e.g. `v0`
+    /// - A numeric constant, e.g. `u8 1` or `Field -1`
+    /// - A constant array, e.g. `[Field 1, v0] of Field`
+    /// - A function, e.g. `f1`, an intrinsic, e.g. `to_le_bits`, or a foreign function
+    fn parse_value(
+        &mut self,
+        tokens: &mut Tokens,
+        function_names: &HashMap<String, FunctionId>,
+    ) -> Result<ValueId, String> {
+        match tokens.next() {
+            Some(Token::Ident(name)) => {
+                if let Some(typ) = numeric_type(&name) {
+                    let constant = tokens.expect_constant()?;
+                    return Ok(self.function.dfg.make_constant(constant, typ));
+                }
+                if let Some(value) = self.values.get(&name) {
+                    return Ok(*value);
+                }
+                if let Some(function) = function_names.get(&name) {
+                    return Ok(self.function.dfg.import_function(*function));
+                }
+                if let Some(intrinsic) = Intrinsic::lookup(&name) {
+                    return Ok(self.function.dfg.import_intrinsic(intrinsic));
+                }
+                if is_value_name(&name) {
+                    return Err(format!("use of undefined value `{name}`"));
+                }
+                Ok(self.function.dfg.import_foreign_function(&name))
+            }
+            Some(Token::LeftBracket) => {
+                let elements =
+                    self.parse_values(tokens, function_names, Some(Token::RightBracket))?;
+                tokens.expect_keyword("of")?;
+                let element_type = if tokens.eat(&Token::LeftParen) {
+                    tokens.parse_types(Token::RightParen)?
+                } else {
+                    vec![tokens.parse_type()?]
+                };
+                Ok(self.function.dfg.make_array(elements.into(), Rc::new(element_type)))
+            }
+            Some(token) => Err(format!("expected a value, found `{token}`")),
+            None => Err("expected a value, found the end of the line".to_string()),
+        }
+    }
+
+    fn current_block(&self) -> Result<BasicBlockId, String> {
+        self.current_block.ok_or_else(|| "expected a block header".to_string())
+    }
+
+    /// Returns the block with the given name, creating it if it has not been used yet.
+    fn block(&mut self, name: String) -> BasicBlockId {
+        match self.blocks.get(&name) {
+            Some(block) => *block,
+            None => {
+                let block = self.function.dfg.make_block();
+                self.blocks.insert(name, block);
+                block
+            }
+        }
+    }
+
+    fn define_value(&mut self, name: String, value: ValueId) -> Result<(), String> {
+        if self.values.contains_key(&name) {
+            return Err(format!("value `{name}` is defined twice"));
+        }
+        self.values.insert(name, value);
+        Ok(())
+    }
+}
+
+/// The tokens of a single line, consumed from left to right.
+struct Tokens {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Tokens {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    /// Consumes the next token if it is equal to the given one.
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.tokens.get(self.position) == Some(token) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!("expected `{expected}`, found `{token}`")),
+            None => Err(format!("expected `{expected}`, found the end of the line")),
+        }
+    }
+
+    fn expect_end(&self) -> Result<(), String> {
+        match self.tokens.get(self.position) {
+            Some(token) => Err(format!("expected the end of the line, found `{token}`")),
+            None => Ok(()),
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Ident(ident)) => Ok(ident),
+            Some(token) => Err(format!("expected a name, found `{token}`")),
+            None => Err("expected a name, found the end of the line".to_string()),
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Ident(ident)) if ident == keyword => Ok(()),
+            Some(token) => Err(format!("expected `{keyword}`, found `{token}`")),
+            None => Err(format!("expected `{keyword}`, found the end of the line")),
+        }
+    }
+
+    fn expect_u32(&mut self) -> Result<u32, String> {
+        match self.next() {
+            Some(Token::Int(int)) => int.parse().map_err(|_| format!("invalid integer `{int}`")),
+            Some(token) => Err(format!("expected an integer, found `{token}`")),
+            None => Err("expected an integer, found the end of the line".to_string()),
+        }
+    }
+
+    /// Parses the value of a numeric constant, in decimal or `0x`-prefixed hexadecimal, optionally
+    /// preceded by a minus sign.
+    fn expect_constant(&mut self) -> Result<FieldElement, String> {
+        let is_negative = self.eat(&Token::Minus);
+        let constant = match self.next() {
+            Some(Token::Int(int)) => {
+                let constant = match int.strip_prefix("0x") {
+                    Some(hex) => FieldElement::from_hex(hex),
+                    None => int.parse::<u128>().ok().map(FieldElement::from),
+                };
+                constant.ok_or_else(|| format!("invalid constant `{int}`"))?
+            }
+            Some(token) => return Err(format!("expected a constant, found `{token}`")),
+            None => return Err("expected a constant, found the end of the line".to_string()),
+        };
+        Ok(if is_negative { -constant } else { constant })
+    }
+
+    /// Parses a type, e.g. `Field`, `u8`, `reference`, `[Field, u8; 4]` or `[Field, u8]`
+    fn parse_type(&mut self) -> Result<Type, String> {
+        match self.next() {
+            Some(Token::Ident(name)) => match name.as_str() {
+                "reference" => Ok(Type::Reference),
+                "function" => Ok(Type::Function),
+                _ => numeric_type(&name).ok_or_else(|| format!("expected a type, found `{name}`")),
+            },
+            Some(Token::LeftBracket) => {
+                let mut element_types = vec![self.parse_type()?];
+                while self.eat(&Token::Comma) {
+                    element_types.push(self.parse_type()?);
+                }
+                if self.eat(&Token::RightBracket) {
+                    return Ok(Type::Slice(Rc::new(element_types)));
+                }
+                self.expect(Token::Semicolon)?;
+                let length = self.expect_u32()? as usize;
+                self.expect(Token::RightBracket)?;
+                Ok(Type::Array(Rc::new(element_types), length))
+            }
+            Some(token) => Err(format!("expected a type, found `{token}`")),
+            None => Err("expected a type, found the end of the line".to_string()),
+        }
+    }
+
+    /// Parses a comma-separated list of types, up to the given closing token.
+    fn parse_types(&mut self, closing_token: Token) -> Result<Vec<Type>, String> {
+        let mut types = Vec::new();
+        loop {
+            types.push(self.parse_type()?);
+            if !self.eat(&Token::Comma) {
+                self.expect(closing_token)?;
+                return Ok(types);
+            }
+        }
+    }
+}
+
+/// Returns the numeric type with the given name, e.g. `Field`, `u8` or `i32`
+fn numeric_type(name: &str) -> Option<Type> {
+    if name == "Field" {
+        return Some(Type::field());
+    }
+    let bit_size = name.get(1..)?.parse().ok()?;
+    match name.chars().next()? {
+        'u' => Some(Type::unsigned(bit_size)),
+        'i' => Some(Type::signed(bit_size)),
+        _ => None,
+    }
+}
+
+/// Returns true if the given name refers to a value, e.g. `v0`
+fn is_value_name(name: &str) -> bool {
+    name.strip_prefix('v').map_or(false, |index| index.parse::<usize>().is_ok())
+}
+
+fn binary_operator(name: &str) -> Option<BinaryOp> {
+    match name {
+        "add" => Some(BinaryOp::Add),
+        "sub" => Some(BinaryOp::Sub),
+        "mul" => Some(BinaryOp::Mul),
+        "div" => Some(BinaryOp::Div),
+        "eq" => Some(BinaryOp::Eq),
+        "mod" => Some(BinaryOp::Mod),
+        "lt" => Some(BinaryOp::Lt),
+        "and" => Some(BinaryOp::And),
+        "or" => Some(BinaryOp::Or),
+        "xor" => Some(BinaryOp::Xor),
+        "shl" => Some(BinaryOp::Shl),
+        "shr" => Some(BinaryOp::Shr),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{assert_normalized_ssa_equals, parse_ssa};
+
+    /// Asserts that the given SSA text is printed back as it is written once parsed, up to the
+    /// naming of its values and blocks.
+    fn assert_round_trip(source: &str) {
+        let ssa = parse_ssa(source).unwrap_or_else(|error| panic!("{error}"));
+        assert_normalized_ssa_equals(ssa, source);
+    }
+
+    #[test]
+    fn round_trips_instructions() {
+        let source = "
+acir fn main f0 {
+  b0(v0: Field, v1: u8, v2: [Field; 2]):
+    v3 = add v0, Field 1
+    v4 = cast v1 as u32
+    v5 = truncate v4 to 8 bits, max_bit_size: 32
+    v6 = not v1
+    v7 = lt v1, u8 3
+    constrain v7
+    v8 = allocate
+    store Field -1 at v8
+    v9 = load v8 -> Field
+    enable_side_effects v7
+    v10 = array_get v2, index u32 1 -> Field
+    v11 = array_set v2, index u32 0, value v9
+    v12 = call f1(v3, [Field 2, v0] of Field) -> u8
+    v13 = call to_le_bits(v0, u32 8) -> [u1; 8]
+    call println(v13)
+    return v12, v11
+}
+brillig fn foo f1 {
+  b0(v0: Field, v1: [Field; 2]):
+    v2 = call get_number() -> Field
+    v3 = call vec_new() -> [Field, [u8; 2]]
+    v4 = call vec_push(v3, v0, [u8 1, u8 2] of u8) -> [Field, [u8; 2]]
+    v5, v6, v7 = call vec_pop(v4) -> [Field, [u8; 2]], Field, [u8; 2]
+    v8 = call vec_len(v5) -> Field
+    return Field 0x0100000000000000000000000000000000000000000000000000000000000000
+}
+";
+        assert_round_trip(source);
+    }
+
+    #[test]
+    fn round_trips_blocks() {
+        let source = "
+acir fn main f0 {
+  b0(v0: u1):
+    jmpif v0 then: b1, else: b2
+  b1():
+    jmp b3(u32 1)
+  b3(v1: u32):
+    return v1
+  b2():
+    jmp b3(u32 2)
+}
+";
+        assert_round_trip(source);
+    }
+
+    #[test]
+    fn reports_errors_with_their_line() {
+        let source = "
+acir fn main f0 {
+  b0(v0: Field):
+    v1 = add v0, v2
+    return v1
+}
+";
+        let error = parse_ssa(source).err().expect("Expected a parse error");
+        assert_eq!(error.line, 4);
+        assert_eq!(error.message, "use of undefined value `v2`");
+
+        let source = "
+acir fn main f0 {
+  b0(v0: Field):
+    jmp b1()
+}
+";
+        let error = parse_ssa(source).err().expect("Expected a parse error");
+        assert_eq!(error.message, "block `b1` is used but never defined");
+    }
+}
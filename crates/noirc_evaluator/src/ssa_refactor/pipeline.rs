@@ -0,0 +1,320 @@
+//! The SSA passes run by `optimize_into_acir`, configured by `SsaOptions`: which passes are run
+//! and in which order, and whether the SSA is printed, validated and dumped into a directory
+//! after each of them.
+use std::path::{Path, PathBuf};
+
+use iter_extended::vecmap;
+
+use crate::errors::{RuntimeError, RuntimeErrorKind};
+
+use super::ssa_gen::Ssa;
+
+/// The name of each pass which can be run over the SSA, in the order of the default pipeline,
+/// along with the description printed after it is run.
+pub(super) const SSA_PASSES: &[(&str, &str)] = &[
+    ("inline", "Inlining"),
+    ("unroll", "Unrolling"),
+    ("simplify_cfg", "Simplifying"),
+    ("flatten_cfg", "Flattening"),
+    ("mem2reg", "Mem2Reg"),
+    ("fold_constants", "Constant Folding"),
+    ("cse", "Common Subexpression Elimination"),
+    ("remove_range_checks", "Removing Redundant Range Checks"),
+    ("die", "Dead Instruction Elimination"),
+];
+
+/// The passes which ACIR generation relies upon: it expects a single function without any loops,
+/// branches or references. These cannot be skipped, and their last runs must be in this order as
+/// each pass relies on the ones before it, e.g. inlining introduces references which are only
+/// removed by mem2reg.
+const MANDATORY_PASSES: &[&str] = &["inline", "unroll", "flatten_cfg", "mem2reg"];
+
+/// Returns the names of all SSA passes, separated by commas.
+pub(super) fn pass_names() -> String {
+    vecmap(SSA_PASSES, |(name, _)| *name).join(", ")
+}
+
+/// Returns the name of the given pass as listed in `SSA_PASSES`, if it is one.
+pub(super) fn find_pass(pass: &str) -> Option<&'static str> {
+    SSA_PASSES.iter().find(|(name, _)| *name == pass).map(|(name, _)| *name)
+}
+
+/// Options controlling the SSA passes run when compiling a program with the experimental SSA.
+#[derive(Debug, Clone, Default)]
+pub struct SsaOptions {
+    /// Print the SSA after each pass, along with the number of blocks and instructions before
+    /// and after it.
+    pub print_ssa_passes: bool,
+
+    /// Check that the SSA is well formed after each pass. This is always done in debug builds.
+    pub validate_ssa: bool,
+
+    /// The names of the passes to run, in order, instead of the default pipeline.
+    pub passes: Option<Vec<String>>,
+
+    /// The names of passes which are not run.
+    pub skipped_passes: Vec<String>,
+
+    /// A directory into which the SSA after each pass, the final ACIR and the statistics of
+    /// each pass are written, each into a separate file prefixed with the name of the compiled
+    /// function.
+    pub dump_dir: Option<PathBuf>,
+}
+
+impl SsaOptions {
+    /// Returns the names of the passes to run in order, or an error if any pass named by these
+    /// options does not exist, a mandatory pass would not be run or the mandatory passes would be
+    /// run out of order.
+    pub(super) fn pipeline(&self) -> Result<Vec<&'static str>, RuntimeError> {
+        let unknown_pass = |pass: &String| {
+            let message = format!("Unknown SSA pass `{pass}`, expected one of: {}", pass_names());
+            RuntimeError::from(RuntimeErrorKind::Spanless(message))
+        };
+
+        for pass in &self.skipped_passes {
+            find_pass(pass).ok_or_else(|| unknown_pass(pass))?;
+        }
+
+        let passes = match &self.passes {
+            Some(passes) => passes
+                .iter()
+                .map(|pass| find_pass(pass).ok_or_else(|| unknown_pass(pass)))
+                .collect::<Result<Vec<_>, _>>()?,
+            None => vecmap(SSA_PASSES, |(name, _)| *name),
+        };
+
+        let passes: Vec<_> = passes
+            .into_iter()
+            .filter(|pass| !self.skipped_passes.iter().any(|skipped| skipped == pass))
+            .collect();
+
+        let mut previous_run: Option<(&str, usize)> = None;
+        for pass in MANDATORY_PASSES {
+            let last_run = match passes.iter().rposition(|run| run == pass) {
+                Some(last_run) => last_run,
+                None => {
+                    let message = format!(
+                        "The SSA pass `{pass}` is required to generate ACIR and cannot be skipped"
+                    );
+                    return Err(RuntimeError::from(RuntimeErrorKind::Spanless(message)));
+                }
+            };
+            if let Some((previous_pass, previous_last_run)) = previous_run {
+                if last_run < previous_last_run {
+                    let message = format!(
+                        "The SSA pass `{pass}` must be run after the last run of `{previous_pass}`"
+                    );
+                    return Err(RuntimeError::from(RuntimeErrorKind::Spanless(message)));
+                }
+            }
+            previous_run = Some((*pass, last_run));
+        }
+
+        Ok(passes)
+    }
+
+    /// Returns the path of the given file in the dump directory, prefixed with the name of the
+    /// compiled function so that the dumps of several functions do not overwrite each other.
+    pub(super) fn dump_path(&self, function_name: &str, file_name: &str) -> Option<PathBuf> {
+        let dump_dir = self.dump_dir.as_ref()?;
+        Some(dump_dir.join(format!("{function_name}_{file_name}")))
+    }
+}
+
+/// The number of reachable blocks and instructions across all functions of the SSA.
+#[derive(Clone, Copy)]
+struct SsaStats {
+    blocks: usize,
+    instructions: usize,
+}
+
+impl SsaStats {
+    fn new(ssa: &Ssa) -> Self {
+        let mut stats = SsaStats { blocks: 0, instructions: 0 };
+        for function in ssa.functions.values() {
+            for block in function.reachable_blocks() {
+                stats.blocks += 1;
+                stats.instructions += function.dfg[block].instructions().len();
+            }
+        }
+        stats
+    }
+}
+
+/// Runs the passes over the SSA, reporting on the SSA after each of them as configured by the
+/// `SsaOptions`.
+pub(super) struct PassRunner<'o> {
+    options: &'o SsaOptions,
+
+    /// The name of the compiled function, which prefixes the name of each dump
+    function_name: &'o str,
+
+    /// True if the SSA is validated after each pass
+    validate: bool,
+
+    /// The number of SSA dumps written so far, which prefixes the name of the next dump so that
+    /// the dumps are listed in the order of the pipeline.
+    dumps: usize,
+
+    /// The statistics line of each pass run so far
+    stats: Vec<String>,
+}
+
+impl<'o> PassRunner<'o> {
+    /// Creates the dump directory, if any, and reports on the SSA generated from the program.
+    pub(super) fn new(
+        options: &'o SsaOptions,
+        function_name: &'o str,
+        ssa: &Ssa,
+    ) -> Result<Self, RuntimeError> {
+        if let Some(dump_dir) = &options.dump_dir {
+            std::fs::create_dir_all(dump_dir).map_err(|error| {
+                let message = format!("Could not create {}: {error}", dump_dir.display());
+                RuntimeError::from(RuntimeErrorKind::Spanless(message))
+            })?;
+        }
+
+        let validate = options.validate_ssa || cfg!(debug_assertions);
+        let mut runner =
+            PassRunner { options, function_name, validate, dumps: 0, stats: Vec::new() };
+
+        if options.print_ssa_passes {
+            println!("Initial SSA:\n{ssa}");
+        }
+        runner.validate(ssa, "SSA generation");
+        runner.dump_ssa(ssa, "initial")?;
+        Ok(runner)
+    }
+
+    /// Runs the given pass, which must be one of `SSA_PASSES`, and reports on the SSA it
+    /// produces.
+    pub(super) fn run_pass(&mut self, ssa: Ssa, pass: &str) -> Result<Ssa, RuntimeError> {
+        let before = SsaStats::new(&ssa);
+        let ssa = ssa.run_pass(pass, self.options.print_ssa_passes);
+        let after = SsaStats::new(&ssa);
+
+        let stats = format!(
+            "{pass}: blocks {} -> {}, instructions {} -> {}",
+            before.blocks, after.blocks, before.instructions, after.instructions
+        );
+        if self.options.print_ssa_passes {
+            let (_, description) = SSA_PASSES.iter().find(|(name, _)| *name == pass).unwrap();
+            println!("After {description}:\n{ssa}\n{stats}");
+        }
+        self.stats.push(stats);
+
+        self.validate(&ssa, pass);
+        self.dump_ssa(&ssa, pass)?;
+        Ok(ssa)
+    }
+
+    /// Writes the statistics of each pass run into the dump directory, if any.
+    pub(super) fn finish(self) -> Result<(), RuntimeError> {
+        match self.options.dump_path(self.function_name, "stats.txt") {
+            Some(path) => {
+                let mut stats = self.stats.join("\n");
+                stats.push('\n');
+                write_dump(&path, &stats)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Panics if the SSA is to be validated and the SSA produced by the given pass is invalid.
+    fn validate(&self, ssa: &Ssa, pass: &str) {
+        if self.validate {
+            if let Err(error) = ssa.validate() {
+                panic!("ICE: The SSA is invalid after {pass}: {error}");
+            }
+        }
+    }
+
+    fn dump_ssa(&mut self, ssa: &Ssa, stage: &str) -> Result<(), RuntimeError> {
+        let file_name = format!("{:02}_{stage}.ssa", self.dumps);
+        if let Some(path) = self.options.dump_path(self.function_name, &file_name) {
+            write_dump(&path, &ssa.to_string())?;
+            self.dumps += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the given contents into a file of the dump directory.
+pub(super) fn write_dump(path: &Path, contents: &str) -> Result<(), RuntimeError> {
+    std::fs::write(path, contents).map_err(|error| {
+        let message = format!("Could not write {}: {error}", path.display());
+        RuntimeError::from(RuntimeErrorKind::Spanless(message))
+    })
+}
+
+impl Ssa {
+    /// Runs the pass with the given name, which must be one of `SSA_PASSES`.
+    ///
+    /// If `print_stats` is true, passes which collect statistics print them.
+    pub(super) fn run_pass(self, pass: &str, print_stats: bool) -> Ssa {
+        match pass {
+            "inline" => self.inline_functions(),
+            "unroll" => self.unroll_loops(),
+            "simplify_cfg" => self.simplify_cfg(),
+            "flatten_cfg" => self.flatten_cfg(),
+            "mem2reg" => self.mem2reg(),
+            "fold_constants" => self.fold_constants(),
+            "cse" => self.common_subexpression_elimination(),
+            "remove_range_checks" => self.remove_redundant_range_checks(print_stats),
+            "die" => self.dead_instruction_elimination(),
+            _ => unreachable!("ICE: unknown SSA pass {pass}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SsaOptions;
+
+    #[test]
+    fn selects_passes_to_run() {
+        let options = SsaOptions::default();
+        assert_eq!(options.pipeline().unwrap().len(), super::SSA_PASSES.len());
+
+        let passes = ["inline", "unroll", "flatten_cfg", "mem2reg", "cse", "die", "cse"];
+        let options = SsaOptions {
+            passes: Some(passes.iter().map(|pass| pass.to_string()).collect()),
+            skipped_passes: vec!["die".into()],
+            ..SsaOptions::default()
+        };
+        assert_eq!(
+            options.pipeline().unwrap(),
+            vec!["inline", "unroll", "flatten_cfg", "mem2reg", "cse", "cse"]
+        );
+
+        let options = SsaOptions { skipped_passes: vec!["cse".into()], ..SsaOptions::default() };
+        assert!(!options.pipeline().unwrap().contains(&"cse"));
+
+        let options = SsaOptions { passes: Some(vec!["sccp".into()]), ..SsaOptions::default() };
+        assert!(options.pipeline().is_err());
+    }
+
+    #[test]
+    fn rejects_skipping_mandatory_passes() {
+        let options = SsaOptions { skipped_passes: vec!["inline".into()], ..SsaOptions::default() };
+        assert!(options.pipeline().is_err());
+
+        let options = SsaOptions { passes: Some(vec!["unroll".into()]), ..SsaOptions::default() };
+        assert!(options.pipeline().is_err());
+    }
+
+    #[test]
+    fn rejects_mandatory_passes_out_of_order() {
+        let passes = |passes: &[&str]| SsaOptions {
+            passes: Some(passes.iter().map(|pass| pass.to_string()).collect()),
+            ..SsaOptions::default()
+        };
+
+        let options = passes(&["mem2reg", "flatten_cfg", "unroll", "inline"]);
+        assert!(options.pipeline().is_err());
+
+        // Only the last run of each pass matters
+        let options = passes(&["mem2reg", "inline", "unroll", "flatten_cfg", "mem2reg"]);
+        assert!(options.pipeline().is_ok());
+    }
+}
@@ -1,6 +1,7 @@
 use std::{borrow::Cow, rc::Rc};
 
 use acvm::FieldElement;
+use noirc_errors::Location;
 
 use crate::ssa_refactor::ir::{
     basic_block::BasicBlockId,
@@ -13,7 +14,7 @@ use crate::ssa_refactor::ir::{
 use super::{
     ir::{
         basic_block::BasicBlock,
-        dfg::InsertInstructionResult,
+        dfg::{CallStack, InsertInstructionResult},
         function::RuntimeType,
         instruction::{InstructionId, Intrinsic},
         types::CompositeType,
@@ -32,6 +33,7 @@ pub(crate) struct FunctionBuilder {
     pub(super) current_function: Function,
     current_block: BasicBlockId,
     finished_functions: Vec<Function>,
+    call_stack: CallStack,
 }
 
 impl FunctionBuilder {
@@ -48,7 +50,12 @@ impl FunctionBuilder {
         new_function.set_runtime(runtime);
         let current_block = new_function.entry_block();
 
-        Self { current_function: new_function, current_block, finished_functions: Vec::new() }
+        Self {
+            current_function: new_function,
+            current_block,
+            finished_functions: Vec::new(),
+            call_stack: CallStack::new(),
+        }
     }
 
     /// Finish the current function and create a new function.
@@ -65,6 +72,7 @@ impl FunctionBuilder {
         let mut new_function = Function::new(name, function_id);
         new_function.set_runtime(runtime_type);
         self.current_block = new_function.entry_block();
+        self.call_stack = CallStack::new();
 
         let old_function = std::mem::replace(&mut self.current_function, new_function);
         self.finished_functions.push(old_function);
@@ -148,9 +156,28 @@ impl FunctionBuilder {
             instruction,
             self.current_block,
             ctrl_typevars,
+            self.call_stack.clone(),
         )
     }
 
+    /// Returns the source locations given to the instructions inserted from now on.
+    pub(crate) fn get_call_stack(&self) -> CallStack {
+        self.call_stack.clone()
+    }
+
+    /// Sets the source location of the instructions inserted after this call.
+    pub(crate) fn set_location(&mut self, location: Location) -> &mut FunctionBuilder {
+        self.call_stack = im::Vector::unit(location);
+        self
+    }
+
+    /// Sets the source locations, including the call sites they were inlined through,
+    /// of the instructions inserted after this call.
+    pub(crate) fn set_call_stack(&mut self, call_stack: CallStack) -> &mut FunctionBuilder {
+        self.call_stack = call_stack;
+        self
+    }
+
     /// Switch to inserting instructions in the given block.
     /// Expects the given block to be within the same function. If you want to insert
     /// instructions into a new function, call new_function instead.
@@ -163,6 +190,11 @@ impl FunctionBuilder {
         self.current_block
     }
 
+    /// Returns the runtime of the function currently being built
+    pub(crate) fn current_runtime(&self) -> RuntimeType {
+        self.current_function.runtime()
+    }
+
     /// Insert an allocate instruction at the end of the current block, allocating the
     /// given amount of field elements. Returns the result of the allocate instruction,
     /// which is always a Reference to the allocated data.
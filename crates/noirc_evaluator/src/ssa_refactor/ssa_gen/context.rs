@@ -4,16 +4,19 @@ use std::sync::{Mutex, RwLock};
 
 use acvm::FieldElement;
 use iter_extended::vecmap;
+use noirc_abi::{AbiType, Sign};
+use noirc_errors::Location;
 use noirc_frontend::monomorphization::ast::{self, LocalId, Parameters};
 use noirc_frontend::monomorphization::ast::{FuncId, Program};
 use noirc_frontend::Signedness;
 
+use crate::ssa_refactor::ir::basic_block::BasicBlockId;
 use crate::ssa_refactor::ir::dfg::DataFlowGraph;
 use crate::ssa_refactor::ir::function::FunctionId as IrFunctionId;
 use crate::ssa_refactor::ir::function::{Function, RuntimeType};
-use crate::ssa_refactor::ir::instruction::BinaryOp;
+use crate::ssa_refactor::ir::instruction::{BinaryOp, Intrinsic};
 use crate::ssa_refactor::ir::map::AtomicCounter;
-use crate::ssa_refactor::ir::types::{NumericType, Type};
+use crate::ssa_refactor::ir::types::{CompositeType, NumericType, Type};
 use crate::ssa_refactor::ir::value::ValueId;
 use crate::ssa_refactor::ssa_builder::FunctionBuilder;
 
@@ -35,6 +38,27 @@ pub(super) struct FunctionContext<'a> {
 
     pub(super) builder: FunctionBuilder,
     shared_context: &'a SharedContext,
+
+    /// The exits of each loop enclosing the code currently being compiled, innermost last.
+    /// For loops in ACIR functions, this only holds the loops containing a `break` or `continue`.
+    loops: Vec<LoopExits>,
+}
+
+/// How `break` and `continue` leave the loop they are in.
+#[derive(Clone, Copy)]
+pub(super) enum LoopExits {
+    /// In Brillig functions, jumps go straight to the end of the loop, or to the block
+    /// moving on to the next iteration.
+    Jumps { break_block: BasicBlockId, continue_block: BasicBlockId },
+
+    /// ACIR functions need their loops to be unrolled and their branches to be flattened, which
+    /// expects each branch to rejoin the block it started from. Jumps are thus made by setting
+    /// flags instead, which guard the code following them:
+    /// - `broken` is set on `break` and skips any further iteration of the loop,
+    /// - `skip` is set on both `break` and `continue` and skips the rest of the current one.
+    ///
+    /// Both are references to a boolean.
+    Flags { broken: ValueId, skip: ValueId },
 }
 
 /// Shared context for all functions during ssa codegen. This is the only
@@ -79,8 +103,8 @@ impl<'a> FunctionContext<'a> {
     /// queue is non-empty at the time of calling this function. This can be ensured by calling
     /// `shared_context.get_or_queue_function(function_to_queue)` before calling this constructor.
     ///
-    /// `function_name` and `parameters` are expected to be the name and parameters of the function
-    /// this constructor will pop from the function queue.
+    /// `function_name` and `parameters` are expected to be the name and parameters of the main
+    /// function, which this constructor will pop from the function queue.
     pub(super) fn new(
         function_name: String,
         parameters: &Parameters,
@@ -93,8 +117,9 @@ impl<'a> FunctionContext<'a> {
             .1;
 
         let builder = FunctionBuilder::new(function_name, function_id, runtime);
-        let mut this = Self { definitions: HashMap::new(), builder, shared_context };
-        this.add_parameters_to_scope(parameters);
+        let mut this =
+            Self { definitions: HashMap::new(), builder, shared_context, loops: Vec::new() };
+        this.add_main_parameters_to_scope(parameters);
         this
     }
 
@@ -123,6 +148,142 @@ impl<'a> FunctionContext<'a> {
         }
     }
 
+    /// Adds the parameters of the main function to scope.
+    ///
+    /// The witnesses of main's parameters are laid out as in the ABI, where the fields of each
+    /// item of an array of structs or tuples are next to each other. Such arrays are received
+    /// as a single parameter holding every field of each item, which is then split into the
+    /// struct of arrays their monomorphized type expects.
+    fn add_main_parameters_to_scope(&mut self, parameters: &Parameters) {
+        let shared_context = self.shared_context;
+        let mut values = Vec::new();
+        for param in &shared_context.program.main_function_signature.0 {
+            let value =
+                self.unpack_abi_value(&param.typ, &mut |this, typ| this.builder.add_parameter(typ));
+            values.extend(value.flatten());
+        }
+
+        // Patterns in main's signature are expanded into several parameters whose values are
+        // the fields of the pattern's value, in the same order.
+        let mut values = values.into_iter();
+        for (id, mutable, _, typ) in parameters {
+            let parameter_value = Self::map_type(typ, |_| {
+                let value = values.next().expect("ICE: Missing value for a parameter of main");
+                let value = value.eval(self);
+                if *mutable {
+                    self.new_mutable_variable(value)
+                } else {
+                    value.into()
+                }
+            });
+            self.definitions.insert(*id, parameter_value);
+        }
+    }
+
+    /// Builds a value of the given ABI type, laid out as its monomorphized type, from the values
+    /// of its fields laid out as in the ABI. `next_field` returns the value of the next such
+    /// field, given its type.
+    fn unpack_abi_value(
+        &mut self,
+        typ: &AbiType,
+        next_field: &mut dyn FnMut(&mut Self, Type) -> ValueId,
+    ) -> Values {
+        match typ {
+            AbiType::Struct { fields } => {
+                Tree::Branch(vecmap(fields, |(_, field)| self.unpack_abi_value(field, next_field)))
+            }
+            AbiType::Tuple { fields } => {
+                Tree::Branch(vecmap(fields, |field| self.unpack_abi_value(field, next_field)))
+            }
+            AbiType::Array { length, typ: element } if is_composite_abi_type(element) => {
+                let array = next_field(self, Self::convert_abi_type(typ));
+                let mut index = 0u128;
+                let items = vecmap(0..*length, |_| {
+                    self.unpack_abi_value(element, &mut |this, typ| {
+                        let field_index = this.builder.field_constant(index);
+                        index += 1;
+                        this.builder.insert_array_get(array, field_index, typ)
+                    })
+                });
+                self.transpose_items(items, Self::abi_layout_type(element))
+            }
+            _ => next_field(self, Self::convert_abi_type(typ)).into(),
+        }
+    }
+
+    /// Converts the items of an array of structs or tuples, each laid out as `layout`, into the
+    /// struct of arrays holding each of their fields.
+    fn transpose_items(&mut self, items: Vec<Values>, layout: Tree<Type>) -> Values {
+        match layout {
+            Tree::Branch(field_layouts) => {
+                let mut item_fields = vecmap(items, |item| match item {
+                    Tree::Branch(fields) => fields.into_iter(),
+                    Tree::Leaf(_) => unreachable!("ICE: Expected the item to be a struct or tuple"),
+                });
+                Tree::Branch(vecmap(field_layouts, |field_layout| {
+                    let fields = vecmap(&mut item_fields, |fields| {
+                        fields.next().expect("ICE: Missing field of an array item")
+                    });
+                    self.transpose_items(fields, field_layout)
+                }))
+            }
+            Tree::Leaf(element_type) => {
+                let mut elements = im::Vector::new();
+                for item in items {
+                    let element = item.into_leaf().eval(self);
+                    elements.push_back(element);
+                }
+                self.builder.array_constant(elements, Rc::new(vec![element_type])).into()
+            }
+        }
+    }
+
+    /// Returns the SSA type of each field of the monomorphized type of the given ABI type.
+    fn abi_layout_type(typ: &AbiType) -> Tree<Type> {
+        match typ {
+            AbiType::Struct { fields } => {
+                Tree::Branch(vecmap(fields, |(_, field)| Self::abi_layout_type(field)))
+            }
+            AbiType::Tuple { fields } => Tree::Branch(vecmap(fields, Self::abi_layout_type)),
+            AbiType::Array { length, typ: element } if is_composite_abi_type(element) => {
+                Self::abi_layout_type(element)
+                    .map(|field| Tree::Leaf(Type::Array(Rc::new(vec![field]), *length as usize)))
+            }
+            _ => Tree::Leaf(Self::convert_abi_type(typ)),
+        }
+    }
+
+    /// Converts an ABI type into the SSA type holding its fields laid out as in the ABI.
+    /// Panics if a struct or tuple type is passed.
+    fn convert_abi_type(typ: &AbiType) -> Type {
+        match typ {
+            AbiType::Field => Type::field(),
+            AbiType::Integer { sign: Sign::Signed, width } => Type::signed(*width),
+            AbiType::Integer { sign: Sign::Unsigned, width } => Type::unsigned(*width),
+            AbiType::Boolean => Type::bool(),
+            AbiType::String { length } => {
+                Type::Array(Rc::new(vec![Type::char()]), *length as usize)
+            }
+            AbiType::Array { length, typ } => {
+                Type::Array(Rc::new(Self::flatten_abi_type(typ)), *length as usize)
+            }
+            AbiType::Struct { .. } | AbiType::Tuple { .. } => {
+                panic!("convert_abi_type called on a struct or tuple: {typ:?}")
+            }
+        }
+    }
+
+    /// Returns the SSA type of each field of the given ABI type, laid out as in the ABI.
+    fn flatten_abi_type(typ: &AbiType) -> CompositeType {
+        match typ {
+            AbiType::Struct { fields } => {
+                fields.iter().flat_map(|(_, field)| Self::flatten_abi_type(field)).collect()
+            }
+            AbiType::Tuple { fields } => fields.iter().flat_map(Self::flatten_abi_type).collect(),
+            _ => vec![Self::convert_abi_type(typ)],
+        }
+    }
+
     /// Adds a "single" parameter to scope.
     ///
     /// Single is in quotes here because in the case of tuple parameters, the tuple is flattened
@@ -155,6 +316,47 @@ impl<'a> FunctionContext<'a> {
         Value::Mutable(alloc, typ)
     }
 
+    /// Compiles the body of a loop with the given exits for the `break` and `continue`
+    /// expressions within it.
+    pub(super) fn in_loop<T>(&mut self, exits: LoopExits, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.loops.push(exits);
+        let result = f(self);
+        self.loops.pop();
+        result
+    }
+
+    /// Returns the exits of the innermost loop, if any
+    pub(super) fn current_loop(&self) -> Option<LoopExits> {
+        self.loops.last().copied()
+    }
+
+    /// Creates a value of the given type with each of its fields zeroed. This is used where
+    /// a value is needed for code which was skipped and whose result is therefore never used.
+    pub(super) fn zeroed_value(&mut self, typ: Type) -> ValueId {
+        match typ {
+            Type::Numeric(_) => self.builder.numeric_constant(0u128, typ),
+            Type::Array(element_types, length) => {
+                let mut elements = im::Vector::new();
+                for _ in 0..length {
+                    for element_type in element_types.iter() {
+                        elements.push_back(self.zeroed_value(element_type.clone()));
+                    }
+                }
+                self.builder.array_constant(elements, element_types)
+            }
+            Type::Slice(_) => {
+                let vec_new = self.builder.import_intrinsic_id(Intrinsic::SliceNew);
+                self.builder.insert_call(vec_new, Vec::new(), vec![typ])[0]
+            }
+            Type::Reference => {
+                panic!("Cannot produce a reference from code skipped after a break or continue")
+            }
+            Type::Function => {
+                panic!("Cannot produce a function from code skipped after a break or continue")
+            }
+        }
+    }
+
     /// Maps the given type to a Tree of the result type.
     ///
     /// This can be used to (for example) flatten a tuple type, creating
@@ -211,11 +413,10 @@ impl<'a> FunctionContext<'a> {
                 Self::convert_non_tuple_type(element);
                 Type::Reference
             }
-
-            // How should we represent Vecs?
-            // Are they a struct of array + length + capacity?
-            // Or are they just references?
-            ast::Type::Vec(_) => Type::Reference,
+            ast::Type::Vec(element) => {
+                let element_types = Self::convert_type(element).flatten();
+                Type::Slice(Rc::new(element_types))
+            }
         }
     }
 
@@ -380,10 +581,12 @@ impl<'a> FunctionContext<'a> {
         reshaped_return_values
     }
 
-    /// Create a const offset of an address for an array load or store
+    /// Create a const offset of an address for an array load or store.
+    /// The offset has the same type as the address.
     pub(super) fn make_offset(&mut self, mut address: ValueId, offset: u128) -> ValueId {
         if offset != 0 {
-            let offset = self.builder.field_constant(offset);
+            let address_type = self.builder.type_of_value(address);
+            let offset = self.builder.numeric_constant(offset, address_type);
             address = self.builder.insert_binary(address, BinaryOp::Add, offset);
         }
         address
@@ -477,7 +680,9 @@ impl<'a> FunctionContext<'a> {
     pub(super) fn extract_current_value(&mut self, lvalue: &ast::LValue) -> LValue {
         match lvalue {
             ast::LValue::Ident(ident) => LValue::Ident(self.ident_lvalue(ident)),
-            ast::LValue::Index { array, index, .. } => self.index_lvalue(array, index).2,
+            ast::LValue::Index { array, index, location, .. } => {
+                self.index_lvalue(array, index, *location).2
+            }
             ast::LValue::MemberAccess { object, field_index } => {
                 let (old_object, object_lvalue) = self.extract_current_value_recursive(object);
                 let object_lvalue = Box::new(object_lvalue);
@@ -513,11 +718,13 @@ impl<'a> FunctionContext<'a> {
         &mut self,
         array: &ast::LValue,
         index: &ast::Expression,
+        location: Location,
     ) -> (ValueId, ValueId, LValue) {
         let (old_array, array_lvalue) = self.extract_current_value_recursive(array);
         let old_array = old_array.into_leaf().eval(self);
         let array_lvalue = Box::new(array_lvalue);
         let index = self.codegen_non_tuple_expression(index);
+        self.builder.set_location(location);
         (old_array, index, LValue::Index { old_array, index, array_lvalue })
     }
 
@@ -527,8 +734,8 @@ impl<'a> FunctionContext<'a> {
                 let variable = self.ident_lvalue(ident);
                 (variable.clone(), LValue::Ident(variable))
             }
-            ast::LValue::Index { array, index, element_type, location: _ } => {
-                let (old_array, index, index_lvalue) = self.index_lvalue(array, index);
+            ast::LValue::Index { array, index, element_type, location } => {
+                let (old_array, index, index_lvalue) = self.index_lvalue(array, index, *location);
                 let element = self.codegen_array_index(old_array, index, element_type);
                 (element, index_lvalue)
             }
@@ -593,6 +800,11 @@ impl<'a> FunctionContext<'a> {
     }
 }
 
+/// True if values of the given ABI type are made of several fields, like structs and tuples.
+fn is_composite_abi_type(typ: &AbiType) -> bool {
+    matches!(typ, AbiType::Struct { .. } | AbiType::Tuple { .. })
+}
+
 /// True if the given operator cannot be encoded directly and needs
 /// to be represented as !(some other operator)
 fn operator_requires_not(op: noirc_frontend::BinaryOpKind) -> bool {
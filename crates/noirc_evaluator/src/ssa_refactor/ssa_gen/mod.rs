@@ -8,15 +8,15 @@ pub(crate) use program::Ssa;
 
 use context::SharedContext;
 use iter_extended::vecmap;
-use noirc_errors::Location;
 use noirc_frontend::monomorphization::ast::{self, Expression, Program};
 
 use self::{
-    context::FunctionContext,
+    context::{FunctionContext, LoopExits},
     value::{Tree, Values},
 };
 
 use super::ir::{
+    basic_block::BasicBlockId,
     function::RuntimeType,
     instruction::BinaryOp,
     types::{CompositeType, Type},
@@ -65,8 +65,15 @@ impl<'a> FunctionContext<'a> {
         self.builder.terminate_with_return(results);
     }
 
+    /// Codegens an expression, attributing the instructions it generates to its own location if it
+    /// has one, or else to the location of the innermost enclosing expression which has one.
     fn codegen_expression(&mut self, expr: &Expression) -> Values {
-        match expr {
+        let enclosing_call_stack = self.builder.get_call_stack();
+        if let Some(location) = expr.location() {
+            self.builder.set_location(location);
+        }
+
+        let result = match expr {
             Expression::Ident(ident) => self.codegen_ident(ident),
             Expression::Literal(literal) => self.codegen_literal(literal),
             Expression::Block(block) => self.codegen_block(block),
@@ -75,6 +82,7 @@ impl<'a> FunctionContext<'a> {
             Expression::Index(index) => self.codegen_index(index),
             Expression::Cast(cast) => self.codegen_cast(cast),
             Expression::For(for_expr) => self.codegen_for(for_expr),
+            Expression::While(while_expr) => self.codegen_while(while_expr),
             Expression::If(if_expr) => self.codegen_if(if_expr),
             Expression::Tuple(tuple) => self.codegen_tuple(tuple),
             Expression::ExtractTupleField(tuple, index) => {
@@ -82,12 +90,15 @@ impl<'a> FunctionContext<'a> {
             }
             Expression::Call(call) => self.codegen_call(call),
             Expression::Let(let_expr) => self.codegen_let(let_expr),
-            Expression::Constrain(constrain, location) => {
-                self.codegen_constrain(constrain, *location)
-            }
+            Expression::Constrain(constrain, _) => self.codegen_constrain(constrain),
             Expression::Assign(assign) => self.codegen_assign(assign),
             Expression::Semi(semi) => self.codegen_semi(semi),
-        }
+            Expression::Break => self.codegen_break(),
+            Expression::Continue => self.codegen_continue(),
+        };
+
+        self.builder.set_call_stack(enclosing_call_stack);
+        result
     }
 
     /// Codegen any non-tuple expression so that we can unwrap the Values
@@ -156,10 +167,21 @@ impl<'a> FunctionContext<'a> {
         self.builder.array_constant(array, Rc::new(element_types)).into()
     }
 
+    /// Codegens each expression of the block, returning the value of the last one.
+    ///
+    /// Within a loop of an ACIR function, the expressions following one which may `break` or
+    /// `continue` are only run if it didn't, see `LoopExits::Flags`.
     fn codegen_block(&mut self, block: &[Expression]) -> Values {
         let mut result = Self::unit_value();
-        for expr in block {
+        for (i, expr) in block.iter().enumerate() {
             result = self.codegen_expression(expr);
+
+            if let Some(LoopExits::Flags { skip, .. }) = self.current_loop() {
+                let rest = &block[i + 1..];
+                if !rest.is_empty() && contains_jump(expr) {
+                    return self.codegen_unless(skip, |this| this.codegen_block(rest));
+                }
+            }
         }
         result
     }
@@ -221,7 +243,8 @@ impl<'a> FunctionContext<'a> {
     ) -> Values {
         // base_index = index * type_size
         let type_size = Self::convert_type(element_type).size_of_type();
-        let type_size = self.builder.field_constant(type_size as u128);
+        let index_type = self.builder.type_of_value(index);
+        let type_size = self.builder.numeric_constant(type_size as u128, index_type);
         let base_index = self.builder.insert_binary(index, BinaryOp::Mul, type_size);
 
         let mut field_index = 0u128;
@@ -238,54 +261,292 @@ impl<'a> FunctionContext<'a> {
         self.builder.insert_cast(lhs, typ).into()
     }
 
-    /// Codegens a for loop, creating three new blocks in the process.
+    /// Codegens a for loop, see `codegen_loop`.
     /// The return value of a for loop is always a unit literal.
+    fn codegen_for(&mut self, for_expr: &ast::For) -> Values {
+        // this is the 'i' in `for i in start .. end { block }`
+        let index_type = Self::convert_non_tuple_type(&for_expr.index_type);
+
+        let start_index = self.codegen_non_tuple_expression(&for_expr.start_range);
+        let end_index = self.codegen_non_tuple_expression(&for_expr.end_range);
+
+        let needs_flags =
+            self.builder.current_runtime() == RuntimeType::Acir && contains_jump(&for_expr.block);
+        let flags = if needs_flags { Some(self.new_loop_flags()) } else { None };
+
+        self.codegen_loop(start_index, end_index, index_type, flags, |this, loop_index| {
+            this.define(for_expr.index_variable, loop_index.into());
+            this.codegen_expression(&for_expr.block);
+        });
+        Self::unit_value()
+    }
+
+    /// Codegens a loop running `body` with each index from `start` to `end` (exclusive).
+    /// `body` is given the index, of the given type, for the current iteration.
     ///
-    /// For example, the loop `for i in start .. end { body }` is codegen'd as:
+    /// The loop `for i in start .. end { body }` is codegen'd as:
     ///
     ///   v0 = ... codegen start ...
     ///   v1 = ... codegen end ...
     ///   br loop_entry(v0)
     /// loop_entry(i: Field):
-    ///   v2 = lt i v1
+    ///   v2 = lt i, v1
     ///   brif v2, then: loop_body, else: loop_end
     /// loop_body():
     ///   v3 = ... codegen body ...
+    ///   br loop_latch()
+    /// loop_latch():
     ///   v4 = add 1, i
     ///   br loop_entry(v4)
     /// loop_end():
-    ///   ... This is the current insert point after codegen_for finishes ...
-    fn codegen_for(&mut self, for_expr: &ast::For) -> Values {
+    ///   ... This is the current insert point after codegen_loop finishes ...
+    ///
+    /// In Brillig functions, `break` and `continue` jump to `loop_end` and `loop_latch`.
+    /// In ACIR functions, they set the given flags if any, and the body is codegen'd
+    /// as `if !broken { skip = false; body }` instead.
+    fn codegen_loop(
+        &mut self,
+        start: ValueId,
+        end: ValueId,
+        index_type: Type,
+        flags: Option<LoopExits>,
+        body: impl FnOnce(&mut Self, ValueId),
+    ) {
         let loop_entry = self.builder.insert_block();
         let loop_body = self.builder.insert_block();
+        let loop_latch = self.builder.insert_block();
         let loop_end = self.builder.insert_block();
 
-        // this is the 'i' in `for i in start .. end { block }`
-        let index_type = Self::convert_non_tuple_type(&for_expr.index_type);
         let loop_index = self.builder.add_block_parameter(loop_entry, index_type);
-
-        let start_index = self.codegen_non_tuple_expression(&for_expr.start_range);
-        let end_index = self.codegen_non_tuple_expression(&for_expr.end_range);
-
-        self.builder.terminate_with_jmp(loop_entry, vec![start_index]);
+        self.builder.terminate_with_jmp(loop_entry, vec![start]);
 
         // Compile the loop entry block
         self.builder.switch_to_block(loop_entry);
-        let jump_condition = self.builder.insert_binary(loop_index, BinaryOp::Lt, end_index);
+        let jump_condition = self.builder.insert_binary(loop_index, BinaryOp::Lt, end);
         self.builder.terminate_with_jmpif(jump_condition, loop_body, loop_end);
 
         // Compile the loop body
         self.builder.switch_to_block(loop_body);
-        self.define(for_expr.index_variable, loop_index.into());
-        self.codegen_expression(&for_expr.block);
+        match (self.builder.current_runtime(), flags) {
+            (RuntimeType::Brillig, _) => {
+                let exits = LoopExits::Jumps { break_block: loop_end, continue_block: loop_latch };
+                self.in_loop(exits, |this| body(this, loop_index));
+            }
+            (RuntimeType::Acir, Some(exits @ LoopExits::Flags { broken, skip })) => {
+                self.in_loop(exits, |this| {
+                    this.codegen_unless(broken, |this| {
+                        let false_value = this.builder.numeric_constant(0u128, Type::bool());
+                        this.builder.insert_store(skip, false_value);
+                        body(this, loop_index);
+                        Self::unit_value()
+                    });
+                });
+            }
+            (RuntimeType::Acir, _) => body(self, loop_index),
+        }
+        self.builder.terminate_with_jmp(loop_latch, vec![]);
+
+        // Compile the loop latch, moving on to the next iteration
+        self.builder.switch_to_block(loop_latch);
         let new_loop_index = self.make_offset(loop_index, 1);
         self.builder.terminate_with_jmp(loop_entry, vec![new_loop_index]);
 
         // Finish by switching back to the end of the loop
         self.builder.switch_to_block(loop_end);
+    }
+
+    /// Codegens a while loop.
+    ///
+    /// In Brillig functions, `while cond max N { body }` is codegen'd as:
+    ///
+    ///   br loop_entry(0)
+    /// loop_entry(count: u64):
+    ///   v0 = ... codegen cond ...
+    ///   brif v0, then: loop_body, else: loop_end
+    /// loop_body():
+    ///   v1 = lt count, N
+    ///   constrain v1
+    ///   v2 = ... codegen body ...
+    ///   br loop_latch()
+    /// loop_latch():
+    ///   v3 = add count, 1
+    ///   br loop_entry(v3)
+    /// loop_end():
+    ///   ... This is the current insert point after codegen_while finishes ...
+    ///
+    /// where the constraint is left out if there is no bound.
+    ///
+    /// ACIR functions can only have loops with a known number of iterations, so the loop
+    /// is instead codegen'd as N iterations of a `for` loop which stop once the condition fails:
+    ///
+    ///   for _ in 0 .. N {
+    ///       if !broken { skip = false; if cond { body } else { broken = true } }
+    ///   }
+    ///   if !broken { constrain !cond }
+    ///
+    /// The final constraint fails if the loop would have needed more than N iterations.
+    fn codegen_while(&mut self, while_expr: &ast::While) -> Values {
+        match self.builder.current_runtime() {
+            RuntimeType::Brillig => self.codegen_brillig_while(while_expr),
+            RuntimeType::Acir => self.codegen_acir_while(while_expr),
+        }
+        Self::unit_value()
+    }
+
+    fn codegen_brillig_while(&mut self, while_expr: &ast::While) {
+        let loop_entry = self.builder.insert_block();
+        let loop_body = self.builder.insert_block();
+        let loop_latch = self.builder.insert_block();
+        let loop_end = self.builder.insert_block();
+
+        let counter_type = Type::unsigned(64);
+        let counter = self.builder.add_block_parameter(loop_entry, counter_type.clone());
+        let zero = self.builder.numeric_constant(0u128, counter_type.clone());
+        self.builder.terminate_with_jmp(loop_entry, vec![zero]);
+
+        self.builder.switch_to_block(loop_entry);
+        let condition = self.codegen_non_tuple_expression(&while_expr.condition);
+        self.builder.terminate_with_jmpif(condition, loop_body, loop_end);
+
+        self.builder.switch_to_block(loop_body);
+        if let Some(max_iterations) = while_expr.max_iterations {
+            let max_iterations =
+                self.builder.numeric_constant(max_iterations as u128, counter_type);
+            let in_bounds = self.builder.insert_binary(counter, BinaryOp::Lt, max_iterations);
+            self.builder.insert_constrain(in_bounds);
+        }
+        let exits = LoopExits::Jumps { break_block: loop_end, continue_block: loop_latch };
+        self.in_loop(exits, |this| this.codegen_expression(&while_expr.block));
+        self.builder.terminate_with_jmp(loop_latch, vec![]);
+
+        self.builder.switch_to_block(loop_latch);
+        let new_counter = self.make_offset(counter, 1);
+        self.builder.terminate_with_jmp(loop_entry, vec![new_counter]);
+
+        self.builder.switch_to_block(loop_end);
+    }
+
+    fn codegen_acir_while(&mut self, while_expr: &ast::While) {
+        let max_iterations = while_expr
+            .max_iterations
+            .expect("Expected while loops in constrained functions to be bounded");
+
+        let flags = self.new_loop_flags();
+        let broken = match flags {
+            LoopExits::Flags { broken, .. } => broken,
+            LoopExits::Jumps { .. } => unreachable!("Expected flags for an ACIR loop"),
+        };
+
+        let start = self.builder.field_constant(0u128);
+        let end = self.builder.field_constant(max_iterations as u128);
+        self.codegen_loop(start, end, Type::field(), Some(flags), |this, _| {
+            let condition = this.codegen_non_tuple_expression(&while_expr.condition);
+            this.codegen_branch(
+                condition,
+                |this| {
+                    this.codegen_expression(&while_expr.block);
+                    Self::unit_value()
+                },
+                |this, _| {
+                    let true_value = this.builder.numeric_constant(1u128, Type::bool());
+                    this.builder.insert_store(broken, true_value);
+                    Vec::new()
+                },
+            );
+        });
+
+        // If the loop wasn't exited by now, the condition must have become false
+        self.codegen_unless(broken, |this| {
+            let condition = this.codegen_non_tuple_expression(&while_expr.condition);
+            let finished = this.builder.insert_not(condition);
+            this.builder.insert_constrain(finished);
+            Self::unit_value()
+        });
+    }
+
+    /// Allocates the flags used by `break` and `continue` in a loop of an ACIR function,
+    /// which both start out unset.
+    fn new_loop_flags(&mut self) -> LoopExits {
+        let false_value = self.builder.numeric_constant(0u128, Type::bool());
+        let broken = self.builder.insert_allocate();
+        self.builder.insert_store(broken, false_value);
+        let skip = self.builder.insert_allocate();
+        self.builder.insert_store(skip, false_value);
+        LoopExits::Flags { broken, skip }
+    }
+
+    fn codegen_break(&mut self) -> Values {
+        match self.current_loop().expect("Expected `break` to be within a loop") {
+            LoopExits::Jumps { break_block, .. } => self.codegen_jump(break_block),
+            LoopExits::Flags { broken, skip } => {
+                let true_value = self.builder.numeric_constant(1u128, Type::bool());
+                self.builder.insert_store(broken, true_value);
+                self.builder.insert_store(skip, true_value);
+            }
+        }
+        Self::unit_value()
+    }
+
+    fn codegen_continue(&mut self) -> Values {
+        match self.current_loop().expect("Expected `continue` to be within a loop") {
+            LoopExits::Jumps { continue_block, .. } => self.codegen_jump(continue_block),
+            LoopExits::Flags { skip, .. } => {
+                let true_value = self.builder.numeric_constant(1u128, Type::bool());
+                self.builder.insert_store(skip, true_value);
+            }
+        }
         Self::unit_value()
     }
 
+    /// Jumps to the given block. Any code following the jump is unreachable,
+    /// so it is codegen'd into a new block without any predecessors.
+    fn codegen_jump(&mut self, destination: BasicBlockId) {
+        self.builder.terminate_with_jmp(destination, vec![]);
+        let unreachable_block = self.builder.insert_block();
+        self.builder.switch_to_block(unreachable_block);
+    }
+
+    /// Codegens `if condition { then } else { otherwise }` for a branch made by the compiler
+    /// rather than written in the program. As both branches must result in values of the same
+    /// types, `otherwise` is given the types of the values `then` results in.
+    fn codegen_branch(
+        &mut self,
+        condition: ValueId,
+        then: impl FnOnce(&mut Self) -> Values,
+        otherwise: impl FnOnce(&mut Self, Vec<Type>) -> Vec<ValueId>,
+    ) -> Values {
+        let then_block = self.builder.insert_block();
+        let else_block = self.builder.insert_block();
+        let end_block = self.builder.insert_block();
+
+        self.builder.terminate_with_jmpif(condition, then_block, else_block);
+
+        self.builder.switch_to_block(then_block);
+        let then_value = then(self);
+        let then_values = then_value.clone().into_value_list(self);
+        let types = vecmap(&then_values, |value| self.builder.type_of_value(*value));
+        self.builder.terminate_with_jmp(end_block, then_values);
+
+        self.builder.switch_to_block(else_block);
+        let else_values = otherwise(self, types.clone());
+        self.builder.terminate_with_jmp(end_block, else_values);
+
+        self.builder.switch_to_block(end_block);
+        let results = vecmap(types, |typ| self.builder.add_block_parameter(end_block, typ));
+        let mut results = results.into_iter();
+        then_value.map(|_| Values::from(results.next().unwrap()))
+    }
+
+    /// Codegens `if !*flag { then }`, where `flag` is a reference to a boolean.
+    /// If `then` results in any value, its fields are zeroed when it is skipped.
+    fn codegen_unless(&mut self, flag: ValueId, then: impl FnOnce(&mut Self) -> Values) -> Values {
+        let flag = self.builder.insert_load(flag, Type::bool());
+        let condition = self.builder.insert_not(flag);
+        self.codegen_branch(condition, then, |this, types| {
+            vecmap(types, |typ| this.zeroed_value(typ))
+        })
+    }
+
     /// Codegens an if expression, handling the case of what to do if there is no 'else'.
     ///
     /// For example, the expression `if cond { a } else { b }` is codegen'd as:
@@ -390,7 +651,7 @@ impl<'a> FunctionContext<'a> {
         Self::unit_value()
     }
 
-    fn codegen_constrain(&mut self, expr: &Expression, _location: Location) -> Values {
+    fn codegen_constrain(&mut self, expr: &Expression) -> Values {
         let boolean = self.codegen_non_tuple_expression(expr);
         self.builder.insert_constrain(boolean);
         Self::unit_value()
@@ -409,3 +670,36 @@ impl<'a> FunctionContext<'a> {
         Self::unit_value()
     }
 }
+
+/// Returns true if the given expression contains a `break` or `continue` exiting the loop
+/// around it. The bodies of nested loops aren't searched since any jump within them exits
+/// these loops instead.
+fn contains_jump(expr: &Expression) -> bool {
+    match expr {
+        Expression::Break | Expression::Continue => true,
+        Expression::Ident(_) => false,
+        Expression::Literal(ast::Literal::Array(array)) => array.contents.iter().any(contains_jump),
+        Expression::Literal(_) => false,
+        Expression::Block(exprs) | Expression::Tuple(exprs) => exprs.iter().any(contains_jump),
+        Expression::Unary(unary) => contains_jump(&unary.rhs),
+        Expression::Binary(binary) => contains_jump(&binary.lhs) || contains_jump(&binary.rhs),
+        Expression::Index(index) => contains_jump(&index.collection) || contains_jump(&index.index),
+        Expression::Cast(cast) => contains_jump(&cast.lhs),
+        Expression::For(for_expr) => {
+            contains_jump(&for_expr.start_range) || contains_jump(&for_expr.end_range)
+        }
+        Expression::While(while_expr) => contains_jump(&while_expr.condition),
+        Expression::If(if_expr) => {
+            contains_jump(&if_expr.condition)
+                || contains_jump(&if_expr.consequence)
+                || if_expr.alternative.as_deref().map_or(false, contains_jump)
+        }
+        Expression::ExtractTupleField(tuple, _) => contains_jump(tuple),
+        Expression::Call(call) => {
+            contains_jump(&call.func) || call.arguments.iter().any(contains_jump)
+        }
+        Expression::Let(let_expr) => contains_jump(&let_expr.expression),
+        Expression::Constrain(expr, _) | Expression::Semi(expr) => contains_jump(expr),
+        Expression::Assign(assign) => contains_jump(&assign.expression),
+    }
+}
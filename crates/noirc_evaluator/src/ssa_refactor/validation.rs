@@ -0,0 +1,352 @@
+//! A verifier checking that the SSA is well formed, which is run after each SSA pass in debug
+//! builds and when `--validate-ssa` is set, so that a pass producing invalid SSA is caught where
+//! the SSA is broken rather than when it is later converted into ACIR.
+//!
+//! Only the reachable blocks of each function are checked. These must:
+//! - end with a terminator,
+//! - only use values which are defined before their use, in the same block or in a block
+//!   dominating it,
+//! - pass as many arguments to the blocks they jump to as those blocks have parameters, and
+//!   never conditionally jump to a block with parameters,
+//! - only contain binary instructions whose operands are of the same type, with the exception
+//!   of shifts.
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use super::{
+    ir::{
+        basic_block::BasicBlockId,
+        cfg::ControlFlowGraph,
+        dom::DominatorTree,
+        function::Function,
+        instruction::{BinaryOp, Instruction, InstructionId, TerminatorInstruction},
+        post_order::PostOrder,
+        printer::{display_instruction, display_terminator},
+        value::{Value, ValueId},
+    },
+    ssa_gen::Ssa,
+};
+
+/// An error found while validating the SSA, along with the instruction it was found at.
+#[derive(Debug, Error)]
+#[error("{message} in function {function}, at:\n{instruction}")]
+pub struct SsaValidationError {
+    function: String,
+    instruction: String,
+    message: String,
+}
+
+impl Ssa {
+    /// Checks that each function of the SSA is well formed, returning the first error found.
+    pub(crate) fn validate(&self) -> Result<(), SsaValidationError> {
+        for function in self.functions.values() {
+            FunctionValidator::new(function).validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// The instruction or terminator at which an error is found.
+#[derive(Clone, Copy)]
+enum Location {
+    Instruction(InstructionId),
+    Terminator(BasicBlockId),
+}
+
+struct FunctionValidator<'f> {
+    function: &'f Function,
+    cfg: ControlFlowGraph,
+    dom_tree: DominatorTree,
+
+    /// The reachable blocks of the function, in reverse post-order.
+    blocks: Vec<BasicBlockId>,
+
+    /// The block each instruction of a reachable block is in, along with its position there.
+    instruction_positions: HashMap<InstructionId, (BasicBlockId, usize)>,
+}
+
+impl<'f> FunctionValidator<'f> {
+    fn new(function: &'f Function) -> Self {
+        let cfg = ControlFlowGraph::with_function(function);
+        let post_order = PostOrder::with_function(function);
+        let dom_tree = DominatorTree::with_cfg_and_post_order(&cfg, &post_order);
+        let blocks: Vec<_> = post_order.as_slice().iter().rev().copied().collect();
+
+        let mut instruction_positions = HashMap::new();
+        for block in &blocks {
+            for (position, instruction) in function.dfg[*block].instructions().iter().enumerate() {
+                instruction_positions.insert(*instruction, (*block, position));
+            }
+        }
+
+        Self { function, cfg, dom_tree, blocks, instruction_positions }
+    }
+
+    fn validate(mut self) -> Result<(), SsaValidationError> {
+        let function = self.function;
+        for block in self.blocks.clone() {
+            let instructions = function.dfg[block].instructions();
+            for (position, instruction) in instructions.iter().enumerate() {
+                let location = Location::Instruction(*instruction);
+
+                let mut values = Vec::new();
+                function.dfg[*instruction].for_each_value(|value| values.push(value));
+                for value in values {
+                    self.validate_use(value, block, position, location)?;
+                }
+                self.validate_instruction(*instruction)?;
+            }
+
+            self.validate_terminator(block)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that the operands of a binary instruction are of the same type.
+    ///
+    /// The amount of bits to shift by may be of a different type than the value shifted.
+    fn validate_instruction(&self, instruction: InstructionId) -> Result<(), SsaValidationError> {
+        let dfg = &self.function.dfg;
+        match &dfg[instruction] {
+            Instruction::Binary(binary)
+                if !matches!(binary.operator, BinaryOp::Shl | BinaryOp::Shr) =>
+            {
+                let lhs_type = dfg.type_of_value(binary.lhs);
+                let rhs_type = dfg.type_of_value(binary.rhs);
+                if lhs_type != rhs_type {
+                    let message = format!(
+                        "The operands of a binary instruction have different types {lhs_type} and {rhs_type}"
+                    );
+                    return Err(self.error(Location::Instruction(instruction), message));
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that the block has a terminator, that the values it uses are defined, and that
+    /// the blocks it jumps to receive one argument for each of their parameters.
+    fn validate_terminator(&mut self, block: BasicBlockId) -> Result<(), SsaValidationError> {
+        let function = self.function;
+        let location = Location::Terminator(block);
+        let terminator = match function.dfg[block].terminator() {
+            Some(terminator) => terminator,
+            None => return Err(self.error(location, format!("Block {block} has no terminator"))),
+        };
+
+        let mut values = Vec::new();
+        terminator.for_each_value(|value| values.push(value));
+        let position = function.dfg[block].instructions().len();
+        for value in values {
+            self.validate_use(value, block, position, location)?;
+        }
+
+        if let TerminatorInstruction::Jmp { destination, arguments } = terminator {
+            let parameters = function.dfg[*destination].parameters();
+            if arguments.len() != parameters.len() {
+                let message = format!(
+                    "Block {destination} expects {} arguments but is given {}",
+                    parameters.len(),
+                    arguments.len()
+                );
+                return Err(self.error(location, message));
+            }
+        }
+
+        // Blocks with parameters may only be reached through a jmp, which provides their arguments
+        for successor in self.cfg.successors(block) {
+            let has_parameters = !function.dfg[successor].parameters().is_empty();
+            if has_parameters && !matches!(terminator, TerminatorInstruction::Jmp { .. }) {
+                let message = format!(
+                    "Block {successor} has parameters but is reached without arguments from {block}"
+                );
+                return Err(self.error(location, message));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the given value is defined before it is used at the given position of the
+    /// given block. Terminators are at the position following the last instruction of a block.
+    fn validate_use(
+        &mut self,
+        value: ValueId,
+        block: BasicBlockId,
+        position: usize,
+        location: Location,
+    ) -> Result<(), SsaValidationError> {
+        let function = self.function;
+        let value = function.dfg.resolve(value);
+        match &function.dfg[value] {
+            Value::Instruction { instruction, .. } => {
+                let is_defined = match self.instruction_positions.get(instruction) {
+                    Some((definition_block, definition_position)) if *definition_block == block => {
+                        *definition_position < position
+                    }
+                    Some((definition_block, _)) => {
+                        self.dom_tree.dominates(*definition_block, block)
+                    }
+                    None => false,
+                };
+                if !is_defined {
+                    let message = format!("{value} is used before it is defined");
+                    return Err(self.error(location, message));
+                }
+            }
+            Value::Param { block: definition_block, .. } => {
+                let is_defined = self.dom_tree.is_reachable(*definition_block)
+                    && self.dom_tree.dominates(*definition_block, block);
+                if !is_defined {
+                    let message = format!(
+                        "{value} is a parameter of block {definition_block}, which does not dominate its use"
+                    );
+                    return Err(self.error(location, message));
+                }
+            }
+            Value::Array { array, .. } => {
+                for element in array.clone() {
+                    self.validate_use(element, block, position, location)?;
+                }
+            }
+            Value::NumericConstant { .. }
+            | Value::Function(_)
+            | Value::Intrinsic(_)
+            | Value::ForeignFunction(_) => (),
+        }
+        Ok(())
+    }
+
+    fn error(&self, location: Location, message: String) -> SsaValidationError {
+        let function = format!("{} {}", self.function.name(), self.function.id());
+        let instruction = DisplayLocation(self.function, location).to_string();
+        SsaValidationError { function, instruction, message }
+    }
+}
+
+/// Displays the instruction or terminator at a location as it is printed within its function.
+struct DisplayLocation<'f>(&'f Function, Location);
+
+impl std::fmt::Display for DisplayLocation<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.1 {
+            Location::Instruction(instruction) => display_instruction(self.0, instruction, f),
+            Location::Terminator(block) => {
+                display_terminator(self.0, self.0.dfg[block].terminator(), f)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa_refactor::parser::parse_ssa;
+
+    fn validation_error(source: &str) -> String {
+        let ssa = parse_ssa(source).unwrap_or_else(|error| panic!("{error}"));
+        ssa.validate().err().expect("Expected the SSA to be invalid").to_string()
+    }
+
+    #[test]
+    fn accepts_valid_ssa() {
+        let source = "
+acir fn main f0 {
+  b0(v0: u32):
+    jmp b1(u32 0)
+  b1(v1: u32):
+    v2 = lt v1, v0
+    jmpif v2 then: b3, else: b2
+  b3():
+    v3 = add v1, u32 1
+    jmp b1(v3)
+  b2():
+    return v1
+}
+";
+        let ssa = parse_ssa(source).unwrap_or_else(|error| panic!("{error}"));
+        assert!(ssa.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_uses_before_definitions() {
+        let source = "
+acir fn main f0 {
+  b0(v0: Field):
+    v1 = add v0, v0
+    v2 = mul v1, v1
+    return v2
+}
+";
+        let mut ssa = parse_ssa(source).unwrap_or_else(|error| panic!("{error}"));
+        let main = ssa.main_mut();
+        let entry = main.entry_block();
+        main.dfg[entry].instructions_mut().swap(0, 1);
+
+        let error = ssa.validate().err().expect("Expected the SSA to be invalid").to_string();
+        assert!(error.starts_with("v1 is used before it is defined in function main f0"));
+        assert!(error.ends_with("v2 = mul v1, v1\n"));
+    }
+
+    #[test]
+    fn rejects_uses_in_blocks_which_are_not_dominated() {
+        let source = "
+acir fn main f0 {
+  b0(v0: u1, v1: Field):
+    jmpif v0 then: b1, else: b2
+  b1():
+    v2 = add v1, v1
+    jmp b3()
+  b2():
+    jmp b3()
+  b3():
+    return v2
+}
+";
+        let error = validation_error(source);
+        assert!(error.starts_with("v2 is used before it is defined"));
+        assert!(error.ends_with("return v2\n"));
+    }
+
+    #[test]
+    fn rejects_mismatched_block_arguments() {
+        let source = "
+acir fn main f0 {
+  b0(v0: Field):
+    jmp b1(v0, v0)
+  b1(v1: Field):
+    return v1
+}
+";
+        assert!(validation_error(source).starts_with("Block b1 expects 1 arguments but is given 2"));
+
+        let source = "
+acir fn main f0 {
+  b0(v0: u1):
+    jmpif v0 then: b1, else: b2
+  b1(v1: Field):
+    return v1
+  b2():
+    return Field 0
+}
+";
+        assert!(validation_error(source)
+            .starts_with("Block b1 has parameters but is reached without arguments from b0"));
+    }
+
+    #[test]
+    fn rejects_binary_operands_of_different_types() {
+        let source = "
+acir fn main f0 {
+  b0(v0: u32):
+    v1 = mul v0, Field 2
+    v2 = shl v0, u8 2
+    return v1, v2
+}
+";
+        let error = validation_error(source);
+        assert!(error.starts_with(
+            "The operands of a binary instruction have different types u32 and Field"
+        ));
+    }
+}
@@ -1,7 +1,10 @@
 use std::fmt::Display;
 
 use crate::token::{Attribute, Token};
-use crate::{Ident, Path, Pattern, Recoverable, Statement, UnresolvedType};
+use crate::{
+    Ident, Path, Pattern, Recoverable, Statement, TraitConstraint, UnresolvedType,
+    UnresolvedTypeExpression,
+};
 use acvm::FieldElement;
 use iter_extended::vecmap;
 use noirc_errors::{Span, Spanned};
@@ -19,7 +22,9 @@ pub enum ExpressionKind {
     Cast(Box<CastExpression>),
     Infix(Box<InfixExpression>),
     For(Box<ForExpression>),
+    While(Box<WhileExpression>),
     If(Box<IfExpression>),
+    Match(Box<MatchExpression>),
     Variable(Path),
     Tuple(Vec<Expression>),
     Lambda(Box<Lambda>),
@@ -172,6 +177,15 @@ pub struct ForExpression {
     pub block: Expression,
 }
 
+/// A `while condition max N { block }` loop. The maximum number of iterations must be
+/// known at compile-time and may only be omitted in unconstrained functions.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct WhileExpression {
+    pub condition: Expression,
+    pub max_iterations: Option<UnresolvedTypeExpression>,
+    pub block: Expression,
+}
+
 pub type BinaryOp = Spanned<BinaryOpKind>;
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Hash, Debug, Copy, Clone)]
@@ -311,6 +325,23 @@ pub struct IfExpression {
     pub alternative: Option<Expression>,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MatchExpression {
+    pub expression: Expression,
+    pub rules: Vec<(MatchPattern, Expression)>,
+}
+
+/// The left hand side of a single rule within a `match` expression.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MatchPattern {
+    /// A variant of an enum, with a sub-pattern for each of the variant's fields:
+    /// `Shape::Rectangle(width, height)`. Unit variants have no sub-patterns.
+    Variant(Path, Vec<Pattern>, Span),
+
+    /// Matches any value, binding it to the given pattern: `_` or `other`.
+    Catchall(Pattern),
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Lambda {
     pub parameters: Vec<(Pattern, UnresolvedType)>,
@@ -338,6 +369,10 @@ pub struct FunctionDefinition {
     pub return_type: UnresolvedType,
     pub return_visibility: noirc_abi::AbiVisibility,
     pub return_distinctness: noirc_abi::AbiDistinctness,
+
+    /// Trait bounds on this function's generics, from either `<T: Trait>`
+    /// or an explicit `where T: Trait` clause.
+    pub where_clause: Vec<TraitConstraint>,
 }
 
 /// Describes the types of smart contract functions that are allowed.
@@ -426,7 +461,9 @@ impl Display for ExpressionKind {
             Cast(cast) => cast.fmt(f),
             Infix(infix) => infix.fmt(f),
             For(for_loop) => for_loop.fmt(f),
+            While(while_loop) => while_loop.fmt(f),
             If(if_expr) => if_expr.fmt(f),
+            Match(match_expr) => match_expr.fmt(f),
             Variable(path) => path.fmt(f),
             Constructor(constructor) => constructor.fmt(f),
             MemberAccess(access) => access.fmt(f),
@@ -567,6 +604,16 @@ impl Display for ForExpression {
     }
 }
 
+impl Display for WhileExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "while {}", self.condition)?;
+        if let Some(max_iterations) = &self.max_iterations {
+            write!(f, " max {max_iterations}")?;
+        }
+        write!(f, " {}", self.block)
+    }
+}
+
 impl Display for IfExpression {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "if {} {}", self.condition, self.consequence)?;
@@ -577,6 +624,29 @@ impl Display for IfExpression {
     }
 }
 
+impl Display for MatchExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "match {} {{", self.expression)?;
+        for (pattern, branch) in &self.rules {
+            writeln!(f, "    {pattern} => {branch},")?;
+        }
+        write!(f, "}}")
+    }
+}
+
+impl Display for MatchPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchPattern::Variant(path, fields, _) if fields.is_empty() => path.fmt(f),
+            MatchPattern::Variant(path, fields, _) => {
+                let fields = vecmap(fields, ToString::to_string);
+                write!(f, "{path}({})", fields.join(", "))
+            }
+            MatchPattern::Catchall(pattern) => pattern.fmt(f),
+        }
+    }
+}
+
 impl Display for Lambda {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let parameters = vecmap(&self.parameters, |(name, r#type)| format!("{name}: {type}"));
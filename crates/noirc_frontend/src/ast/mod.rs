@@ -4,23 +4,25 @@
 //!
 //! Noir's Ast is produced by the parser and taken as input to name resolution,
 //! where it is converted into the Hir (defined in the hir_def module).
+mod enumeration;
 mod expression;
 mod function;
 mod statement;
 mod structure;
+mod traits;
 
+pub use enumeration::*;
 pub use expression::*;
 pub use function::*;
 
 use noirc_errors::Span;
 pub use statement::*;
 pub use structure::*;
+pub use traits::*;
 
-use crate::{
-    parser::{ParserError, ParserErrorReason},
-    token::IntType,
-    BinaryTypeOperator, CompTime,
-};
+use std::hash::{Hash, Hasher};
+
+use crate::{token::IntType, BinaryTypeOperator, CompTime};
 use iter_extended::vecmap;
 
 /// The parser parses types as 'UnresolvedType's which
@@ -53,16 +55,21 @@ pub enum UnresolvedType {
     // Note: Tuples have no visibility, instead each of their elements may have one.
     Tuple(Vec<UnresolvedType>),
 
-    Function(/*args:*/ Vec<UnresolvedType>, /*ret:*/ Box<UnresolvedType>),
+    Function(
+        /*args:*/ Vec<UnresolvedType>,
+        /*ret:*/ Box<UnresolvedType>,
+        /*env:*/ Box<UnresolvedType>,
+    ),
 
     Unspecified, // This is for when the user declares a variable without specifying it's type
     Error,
 }
 
 /// The precursor to TypeExpression, this is the type that the parser allows
-/// to be used in the length position of an array type. Only constants, variables,
-/// and numeric binary operators are allowed here.
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+/// to be used in the length position of an array type. Constants, variables,
+/// and numeric binary operators are kept as is, while any other expression
+/// is evaluated at compile-time once it has been resolved.
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum UnresolvedTypeExpression {
     Variable(Path),
     Constant(u64, Span),
@@ -72,6 +79,29 @@ pub enum UnresolvedTypeExpression {
         Box<UnresolvedTypeExpression>,
         Span,
     ),
+    Expression(Box<Expression>),
+}
+
+impl Hash for UnresolvedTypeExpression {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            UnresolvedTypeExpression::Variable(path) => path.hash(state),
+            UnresolvedTypeExpression::Constant(value, span) => {
+                value.hash(state);
+                span.hash(state);
+            }
+            UnresolvedTypeExpression::BinaryOperation(lhs, op, rhs, span) => {
+                lhs.hash(state);
+                op.hash(state);
+                rhs.hash(state);
+                span.hash(state);
+            }
+            // Expressions are equal when their kinds are, regardless of their spans,
+            // which is also the case for their printed form.
+            UnresolvedTypeExpression::Expression(expr) => expr.to_string().hash(state),
+        }
+    }
 }
 
 impl Recoverable for UnresolvedType {
@@ -111,9 +141,12 @@ impl std::fmt::Display for UnresolvedType {
                 None => write!(f, "str[]"),
                 Some(len) => write!(f, "str[{len}]"),
             },
-            Function(args, ret) => {
+            Function(args, ret, env) => {
                 let args = vecmap(args, ToString::to_string);
-                write!(f, "fn({}) -> {ret}", args.join(", "))
+                match env.as_ref() {
+                    UnresolvedType::Unit => write!(f, "fn({}) -> {ret}", args.join(", ")),
+                    env => write!(f, "fn[{env}]({}) -> {ret}", args.join(", ")),
+                }
             }
             Vec(args, _span) => {
                 let args = vecmap(args, ToString::to_string);
@@ -135,6 +168,7 @@ impl std::fmt::Display for UnresolvedTypeExpression {
             UnresolvedTypeExpression::BinaryOperation(lhs, op, rhs, _) => {
                 write!(f, "({lhs} {op} {rhs})")
             }
+            UnresolvedTypeExpression::Expression(expr) => expr.fmt(f),
         }
     }
 }
@@ -156,19 +190,12 @@ pub enum Signedness {
 }
 
 impl UnresolvedTypeExpression {
-    // This large error size is justified because it improves parsing speeds by around 40% in
-    // release mode. See `ParserError` definition for further explanation.
-    #[allow(clippy::result_large_err)]
-    pub fn from_expr(
-        expr: Expression,
-        span: Span,
-    ) -> Result<UnresolvedTypeExpression, ParserError> {
-        Self::from_expr_helper(expr).map_err(|err_expr| {
-            ParserError::with_reason(
-                ParserErrorReason::InvalidArrayLengthExpression(err_expr),
-                span,
-            )
-        })
+    /// Converts an expression used as a type, falling back to an `Expression` to be
+    /// evaluated at compile-time when it isn't made only of constants, variables,
+    /// and numeric binary operators.
+    pub fn from_expr(expr: Expression) -> UnresolvedTypeExpression {
+        Self::from_expr_helper(expr.clone())
+            .unwrap_or_else(|_| UnresolvedTypeExpression::Expression(Box::new(expr)))
     }
 
     pub fn span(&self) -> Span {
@@ -176,6 +203,7 @@ impl UnresolvedTypeExpression {
             UnresolvedTypeExpression::Variable(path) => path.span(),
             UnresolvedTypeExpression::Constant(_, span) => *span,
             UnresolvedTypeExpression::BinaryOperation(_, _, _, span) => *span,
+            UnresolvedTypeExpression::Expression(expr) => expr.span,
         }
     }
 
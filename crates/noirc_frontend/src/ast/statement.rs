@@ -25,6 +25,8 @@ pub enum Statement {
     Assign(AssignStatement),
     // This is an expression with a trailing semi-colon
     Semi(Expression),
+    Break(Span),
+    Continue(Span),
     // This statement is the result of a recovered parse error.
     // To avoid issuing multiple errors in later steps, it should
     // be skipped in any future analysis if possible.
@@ -66,12 +68,22 @@ impl Statement {
                 self
             }
 
+            // `break` and `continue` may omit their semicolon at the end of a block
+            Statement::Break(_) | Statement::Continue(_) => {
+                if semi.is_none() && !last_statement_in_block {
+                    emit_error(missing_semicolon);
+                }
+                self
+            }
+
             Statement::Expression(expr) => {
                 match (&expr.kind, semi, last_statement_in_block) {
                     // Semicolons are optional for these expressions
                     (ExpressionKind::Block(_), semi, _)
                     | (ExpressionKind::For(_), semi, _)
-                    | (ExpressionKind::If(_), semi, _) => {
+                    | (ExpressionKind::While(_), semi, _)
+                    | (ExpressionKind::If(_), semi, _)
+                    | (ExpressionKind::Match(_), semi, _) => {
                         if semi.is_some() {
                             Statement::Semi(expr)
                         } else {
@@ -252,7 +264,7 @@ impl Display for UseTree {
             UseTreeKind::Path(name, alias) => {
                 write!(f, "{name}")?;
 
-                while let Some(alias) = alias {
+                if let Some(alias) = alias {
                     write!(f, " as {}", alias)?;
                 }
 
@@ -465,6 +477,8 @@ impl Display for Statement {
             Statement::Expression(expression) => expression.fmt(f),
             Statement::Assign(assign) => assign.fmt(f),
             Statement::Semi(semi) => write!(f, "{semi};"),
+            Statement::Break(_) => write!(f, "break"),
+            Statement::Continue(_) => write!(f, "continue"),
             Statement::Error => write!(f, "Error"),
         }
     }
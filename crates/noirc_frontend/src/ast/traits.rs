@@ -0,0 +1,121 @@
+use std::fmt::Display;
+
+use iter_extended::vecmap;
+use noirc_abi::AbiVisibility;
+use noirc_errors::Span;
+
+use crate::{
+    BlockExpression, Ident, NoirFunction, Path, Pattern, UnresolvedGenerics, UnresolvedType,
+};
+
+/// AST node for trait definitions:
+/// `trait name { ... items ... }`
+#[derive(Clone, Debug)]
+pub struct NoirTrait {
+    pub name: Ident,
+    pub items: Vec<TraitItem>,
+    pub span: Span,
+}
+
+/// Any declaration inside the body of a trait that a user is required to
+/// specify when implementing the trait.
+#[derive(Clone, Debug)]
+pub enum TraitItem {
+    Function {
+        name: Ident,
+        generics: UnresolvedGenerics,
+        parameters: Vec<(Pattern, UnresolvedType, AbiVisibility)>,
+        return_type: UnresolvedType,
+        where_clause: Vec<TraitConstraint>,
+        /// A trait method may optionally provide a default implementation
+        /// which is used by any impl that does not override it.
+        body: Option<BlockExpression>,
+    },
+}
+
+/// Ast node for an impl of a trait for a particular type
+/// `impl trait_name for object_type { ... methods ... }`
+#[derive(Clone, Debug)]
+pub struct NoirTraitImpl {
+    pub trait_name: Path,
+    pub object_type: UnresolvedType,
+    pub type_span: Span,
+    pub generics: UnresolvedGenerics,
+    pub methods: Vec<NoirFunction>,
+}
+
+/// Represents a trait constraint such as `where Foo: Display`.
+/// `T: A + B` is desugared into one constraint per bound.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraitConstraint {
+    pub typ: UnresolvedType,
+    pub trait_name: Path,
+}
+
+impl Display for NoirTrait {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "trait {} {{", self.name)?;
+
+        for item in self.items.iter() {
+            let item = item.to_string();
+            for line in item.lines() {
+                writeln!(f, "    {line}")?;
+            }
+        }
+
+        write!(f, "}}")
+    }
+}
+
+impl Display for TraitItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TraitItem::Function { name, generics, parameters, return_type, where_clause, body } => {
+                let generics = vecmap(generics, |generic| generic.to_string());
+                let parameters = vecmap(parameters, |(name, typ, visibility)| {
+                    format!("{name}: {visibility} {typ}")
+                });
+                let where_clause = vecmap(where_clause, ToString::to_string);
+
+                let generics = generics.join(", ");
+                let parameters = parameters.join(", ");
+                let where_clause = where_clause.join(", ");
+
+                write!(
+                    f,
+                    "fn {name}<{generics}>({parameters}) -> {return_type} where {where_clause}"
+                )?;
+
+                if let Some(body) = body {
+                    write!(f, " {body}")
+                } else {
+                    write!(f, ";")
+                }
+            }
+        }
+    }
+}
+
+impl Display for NoirTraitImpl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let generics = vecmap(&self.generics, |generic| generic.to_string());
+        let generics = if generics.is_empty() { "".into() } else { generics.join(", ") };
+
+        writeln!(f, "impl{} {} for {} {{", generics, self.trait_name, self.object_type)?;
+
+        for method in self.methods.iter() {
+            let method = method.to_string();
+            for line in method.lines() {
+                writeln!(f, "    {line}")?;
+            }
+        }
+
+        write!(f, "}}")
+    }
+}
+
+impl Display for TraitConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.typ, self.trait_name)
+    }
+}
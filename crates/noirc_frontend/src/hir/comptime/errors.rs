@@ -0,0 +1,126 @@
+use noirc_errors::{CustomDiagnostic as Diagnostic, FileDiagnostic, Location};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InterpreterError {
+    #[error("Expression cannot be evaluated at compile-time")]
+    Unsupported { location: Location },
+    #[error("Variable is not known at compile-time")]
+    NonComptimeVariable { name: String, location: Location },
+    #[error("Function is not yet resolved")]
+    NotYetResolved { location: Location },
+    #[error("Division by zero")]
+    DivisionByZero { location: Location },
+    #[error("Index out of bounds")]
+    IndexOutOfBounds { index: u128, length: usize, location: Location },
+    #[error("Failed constraint")]
+    FailingConstraint { location: Location },
+    #[error("Type mismatch")]
+    TypeMismatch { expected: &'static str, location: Location },
+    #[error("Invalid array length")]
+    InvalidArrayLength { location: Location },
+    #[error("Integer overflow")]
+    Overflow { location: Location },
+    #[error("Calls are nested too deeply")]
+    CallDepthExceeded { location: Location },
+    #[error("Global depends on its own value")]
+    GlobalCycle { name: String, location: Location },
+    /// The expression failed to resolve or type check, which was already reported.
+    #[error("Error expression")]
+    ErrorExpression { location: Location },
+    /// Not an error: unwinds the evaluation of a loop's body up to the loop on `break`.
+    #[error("Break")]
+    Break { location: Location },
+    /// Not an error: unwinds the evaluation of a loop's body up to the loop on `continue`.
+    #[error("Continue")]
+    Continue { location: Location },
+}
+
+impl InterpreterError {
+    pub fn location(&self) -> Location {
+        match self {
+            InterpreterError::Unsupported { location }
+            | InterpreterError::NonComptimeVariable { location, .. }
+            | InterpreterError::NotYetResolved { location }
+            | InterpreterError::DivisionByZero { location }
+            | InterpreterError::IndexOutOfBounds { location, .. }
+            | InterpreterError::FailingConstraint { location }
+            | InterpreterError::TypeMismatch { location, .. }
+            | InterpreterError::InvalidArrayLength { location }
+            | InterpreterError::Overflow { location }
+            | InterpreterError::CallDepthExceeded { location }
+            | InterpreterError::GlobalCycle { location, .. }
+            | InterpreterError::ErrorExpression { location }
+            | InterpreterError::Break { location }
+            | InterpreterError::Continue { location } => *location,
+        }
+    }
+
+    /// Converts this error into a diagnostic, unless it has already been reported.
+    pub fn into_file_diagnostic(self) -> Option<FileDiagnostic> {
+        let location = self.location();
+        let span = location.span;
+        let diagnostic = match self {
+            InterpreterError::Unsupported { .. } => Diagnostic::simple_error(
+                "Expression cannot be evaluated at compile-time".into(),
+                "Globals and array lengths may only use literals, operators, globals and calls to pure functions".into(),
+                span,
+            ),
+            InterpreterError::NonComptimeVariable { name, .. } => Diagnostic::simple_error(
+                format!("The value of `{name}` is not known at compile-time"),
+                "Only globals and generic parameters can be used here".into(),
+                span,
+            ),
+            InterpreterError::NotYetResolved { .. } => Diagnostic::simple_error(
+                "Function cannot be called at compile-time here".into(),
+                "This function is not resolved yet".into(),
+                span,
+            ),
+            InterpreterError::DivisionByZero { .. } => Diagnostic::simple_error(
+                "Division by zero in a compile-time expression".into(),
+                String::new(),
+                span,
+            ),
+            InterpreterError::IndexOutOfBounds { index, length, .. } => Diagnostic::simple_error(
+                format!("Index {index} is out of bounds for an array of length {length}"),
+                String::new(),
+                span,
+            ),
+            InterpreterError::FailingConstraint { .. } => Diagnostic::simple_error(
+                "Failed constraint in a compile-time expression".into(),
+                String::new(),
+                span,
+            ),
+            InterpreterError::TypeMismatch { expected, .. } => Diagnostic::simple_error(
+                format!("Expected {expected} in a compile-time expression"),
+                String::new(),
+                span,
+            ),
+            InterpreterError::InvalidArrayLength { .. } => Diagnostic::simple_error(
+                "Expression invalid in an array-length context".into(),
+                "Array lengths must evaluate to a non-negative integer which fits in a u64".into(),
+                span,
+            ),
+            InterpreterError::Overflow { .. } => Diagnostic::simple_error(
+                "Integer overflow in a compile-time expression".into(),
+                String::new(),
+                span,
+            ),
+            InterpreterError::CallDepthExceeded { .. } => Diagnostic::simple_error(
+                "Calls are nested too deeply in a compile-time expression".into(),
+                "This call may never terminate".into(),
+                span,
+            ),
+            InterpreterError::GlobalCycle { name, .. } => Diagnostic::simple_error(
+                format!("The value of global `{name}` depends on itself"),
+                String::new(),
+                span,
+            ),
+            // A `break` or `continue` outside of a loop was already reported by name resolution
+            InterpreterError::ErrorExpression { .. }
+            | InterpreterError::Break { .. }
+            | InterpreterError::Continue { .. } => return None,
+        };
+        Some(diagnostic.in_file(location.file))
+    }
+}
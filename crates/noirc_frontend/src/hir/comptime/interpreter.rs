@@ -0,0 +1,758 @@
+use std::collections::HashMap;
+
+use acvm::FieldElement;
+use iter_extended::try_vecmap;
+use noirc_errors::Location;
+
+use crate::hir_def::expr::{
+    HirArrayLiteral, HirBlockExpression, HirCallExpression, HirCastExpression,
+    HirConstructorExpression, HirExpression, HirForExpression, HirIdent, HirIfExpression,
+    HirIndexExpression, HirInfixExpression, HirLiteral, HirMemberAccess, HirPrefixExpression,
+    HirWhileExpression,
+};
+use crate::hir_def::function::Param;
+use crate::hir_def::stmt::{HirLValue, HirPattern, HirStatement};
+use crate::node_interner::{DefinitionId, DefinitionKind, ExprId, FuncId, NodeInterner, StmtId};
+use crate::{BinaryOpKind, FunctionKind, Signedness, Type, TypeBinding, UnaryOp};
+
+use super::errors::InterpreterError;
+use super::value::{sign_extend, truncate, Value};
+
+/// Calls nested deeper than this are assumed to never terminate.
+const MAX_CALL_DEPTH: usize = 1000;
+
+/// Evaluates HIR expressions at compile-time.
+///
+/// Only the expressions which can be evaluated without knowing the inputs of the program
+/// are supported, which excludes reading any variable which isn't a global or local
+/// to the evaluation, as well as calling low level, oracle or unconstrained-only functions.
+pub struct Interpreter<'interner> {
+    interner: &'interner NodeInterner,
+
+    /// The values of the local variables, with a new frame for each function call
+    /// and global being evaluated.
+    call_stack: Vec<HashMap<DefinitionId, Value>>,
+
+    /// The globals being evaluated, used to detect globals depending on their own value.
+    evaluating_globals: Vec<DefinitionId>,
+}
+
+type IResult<T> = Result<T, InterpreterError>;
+
+impl<'interner> Interpreter<'interner> {
+    pub fn new(interner: &'interner NodeInterner) -> Self {
+        Self { interner, call_stack: vec![HashMap::new()], evaluating_globals: Vec::new() }
+    }
+
+    pub fn evaluate(&mut self, id: ExprId) -> IResult<Value> {
+        let location = self.interner.expr_location(&id);
+        match self.interner.expression(&id) {
+            HirExpression::Ident(ident) => self.evaluate_ident(ident, id),
+            HirExpression::Literal(literal) => self.evaluate_literal(literal, id, location),
+            HirExpression::Block(block) => self.evaluate_block(block, location),
+            HirExpression::Prefix(prefix) => self.evaluate_prefix(prefix, location),
+            HirExpression::Infix(infix) => self.evaluate_infix(infix),
+            HirExpression::Index(index) => self.evaluate_index(index, location),
+            HirExpression::Constructor(constructor) => {
+                self.evaluate_constructor(constructor, location)
+            }
+            HirExpression::MemberAccess(access) => self.evaluate_member_access(access, location),
+            HirExpression::Call(call) => self.evaluate_call(call, id),
+            HirExpression::Cast(cast) => self.evaluate_cast(cast, location),
+            HirExpression::For(for_expr) => self.evaluate_for(for_expr, location),
+            HirExpression::While(while_expr) => self.evaluate_while(while_expr, location),
+            HirExpression::If(if_expr) => self.evaluate_if(if_expr, location),
+            HirExpression::Tuple(fields) => {
+                Ok(Value::Tuple(try_vecmap(fields, |field| self.evaluate(field))?))
+            }
+            HirExpression::EnumConstructor(_)
+            | HirExpression::Match(_)
+            | HirExpression::MethodCall(_)
+            | HirExpression::Lambda(_) => Err(InterpreterError::Unsupported { location }),
+            HirExpression::Error => Err(InterpreterError::ErrorExpression { location }),
+        }
+    }
+
+    fn current_frame(&mut self) -> &mut HashMap<DefinitionId, Value> {
+        self.call_stack.last_mut().expect("ice: the call stack should never be empty")
+    }
+
+    fn evaluate_ident(&mut self, ident: HirIdent, id: ExprId) -> IResult<Value> {
+        let location = ident.location;
+        if ident.id == DefinitionId::dummy_id() {
+            return Err(InterpreterError::ErrorExpression { location });
+        }
+
+        let interner = self.interner;
+        let definition = interner.definition(ident.id);
+        let non_comptime = || {
+            let name = definition.name.clone();
+            InterpreterError::NonComptimeVariable { name, location }
+        };
+
+        match &definition.kind {
+            DefinitionKind::Function(func) => Ok(Value::Function(*func)),
+            DefinitionKind::Global(expr) => {
+                if self.evaluating_globals.contains(&ident.id) {
+                    let name = definition.name.clone();
+                    return Err(InterpreterError::GlobalCycle { name, location });
+                }
+                let expr = *expr;
+                self.evaluating_globals.push(ident.id);
+                self.call_stack.push(HashMap::new());
+                let result = self.evaluate(expr);
+                self.call_stack.pop();
+                self.evaluating_globals.pop();
+                result
+            }
+            DefinitionKind::Local(_) => self.lookup_local(&ident),
+            DefinitionKind::GenericType(binding) => match &*binding.borrow() {
+                TypeBinding::Bound(typ) => {
+                    let value = typ.evaluate_to_u64().ok_or_else(non_comptime)?;
+                    let value = FieldElement::from(value as u128);
+                    Ok(Value::integer_literal(value, &interner.id_type(id)))
+                }
+                TypeBinding::Unbound(_) => Err(non_comptime()),
+            },
+        }
+    }
+
+    /// Returns the value of a variable local to the evaluation.
+    fn lookup_local(&self, ident: &HirIdent) -> IResult<Value> {
+        let value = self.call_stack.last().and_then(|frame| frame.get(&ident.id));
+        value.cloned().ok_or_else(|| {
+            let name = self.interner.definition_name(ident.id).to_owned();
+            InterpreterError::NonComptimeVariable { name, location: ident.location }
+        })
+    }
+
+    fn evaluate_literal(
+        &mut self,
+        literal: HirLiteral,
+        id: ExprId,
+        location: Location,
+    ) -> IResult<Value> {
+        match literal {
+            HirLiteral::Bool(value) => Ok(Value::Bool(value)),
+            HirLiteral::Integer(value) => {
+                Ok(Value::integer_literal(value, &self.interner.id_type(id)))
+            }
+            HirLiteral::Str(value) => Ok(Value::String(value)),
+            HirLiteral::Array(HirArrayLiteral::Standard(elements)) => {
+                Ok(Value::Array(try_vecmap(elements, |element| self.evaluate(element))?))
+            }
+            HirLiteral::Array(HirArrayLiteral::Repeated { repeated_element, length }) => {
+                let length = length
+                    .evaluate_to_u64()
+                    .ok_or(InterpreterError::InvalidArrayLength { location })?;
+                let element = self.evaluate(repeated_element)?;
+                Ok(Value::Array(vec![element; length as usize]))
+            }
+        }
+    }
+
+    fn evaluate_block(&mut self, block: HirBlockExpression, location: Location) -> IResult<Value> {
+        let mut result = Value::Unit;
+        for statement in block.statements() {
+            result = self.evaluate_statement(*statement, location)?;
+        }
+        Ok(result)
+    }
+
+    /// Evaluates a statement of the block at `location`, returning its value.
+    fn evaluate_statement(&mut self, id: StmtId, location: Location) -> IResult<Value> {
+        match self.interner.statement(&id) {
+            HirStatement::Let(let_statement) => {
+                let value = self.evaluate(let_statement.expression)?;
+                let location = self.interner.expr_location(&let_statement.expression);
+                self.define_pattern(&let_statement.pattern, value, location)?;
+                Ok(Value::Unit)
+            }
+            HirStatement::Constrain(constrain) => {
+                let location = self.interner.expr_location(&constrain.0);
+                match self.evaluate(constrain.0)? {
+                    Value::Bool(true) => Ok(Value::Unit),
+                    Value::Bool(false) => Err(InterpreterError::FailingConstraint { location }),
+                    _ => Err(InterpreterError::TypeMismatch { expected: "a bool", location }),
+                }
+            }
+            HirStatement::Assign(assign) => {
+                let value = self.evaluate(assign.expression)?;
+                let location = self.interner.expr_location(&assign.expression);
+                self.assign(assign.lvalue, value, location)?;
+                Ok(Value::Unit)
+            }
+            HirStatement::Expression(expression) => self.evaluate(expression),
+            HirStatement::Semi(expression) => {
+                self.evaluate(expression)?;
+                Ok(Value::Unit)
+            }
+            HirStatement::Break => Err(InterpreterError::Break { location }),
+            HirStatement::Continue => Err(InterpreterError::Continue { location }),
+            HirStatement::Error => Err(InterpreterError::ErrorExpression { location }),
+        }
+    }
+
+    fn define_pattern(
+        &mut self,
+        pattern: &HirPattern,
+        value: Value,
+        location: Location,
+    ) -> IResult<()> {
+        match (pattern, value) {
+            (HirPattern::Identifier(ident), value) => {
+                self.current_frame().insert(ident.id, value);
+                Ok(())
+            }
+            (HirPattern::Mutable(pattern, _), value) => {
+                self.define_pattern(pattern, value, location)
+            }
+            (HirPattern::Tuple(patterns, _), Value::Tuple(fields))
+                if patterns.len() == fields.len() =>
+            {
+                for (pattern, field) in patterns.iter().zip(fields) {
+                    self.define_pattern(pattern, field, location)?;
+                }
+                Ok(())
+            }
+            (HirPattern::Struct(_, patterns, _), mut value @ Value::Struct(..)) => {
+                for (name, pattern) in patterns {
+                    let field = field_mut(&mut value, &name.0.contents, location)?.clone();
+                    self.define_pattern(pattern, field, location)?;
+                }
+                Ok(())
+            }
+            (HirPattern::Tuple(..), _) => {
+                Err(InterpreterError::TypeMismatch { expected: "a tuple", location })
+            }
+            (HirPattern::Struct(..), _) => {
+                Err(InterpreterError::TypeMismatch { expected: "a struct", location })
+            }
+        }
+    }
+
+    fn assign(&mut self, lvalue: HirLValue, value: Value, location: Location) -> IResult<()> {
+        match lvalue {
+            HirLValue::Ident(ident, _) => {
+                // Variables which aren't local to the evaluation can't be assigned to
+                self.lookup_local(&ident)?;
+                self.current_frame().insert(ident.id, value);
+                Ok(())
+            }
+            HirLValue::MemberAccess { object, field_name, .. } => {
+                let mut object_value = self.evaluate_lvalue(&object, location)?;
+                *field_mut(&mut object_value, &field_name.0.contents, location)? = value;
+                self.assign(*object, object_value, location)
+            }
+            HirLValue::Index { array, index, .. } => {
+                let index = self.evaluate_index_value(index)?;
+                let mut array_value = self.evaluate_lvalue(&array, location)?;
+                *element_mut(&mut array_value, index, location)? = value;
+                self.assign(*array, array_value, location)
+            }
+            HirLValue::Dereference { .. } => Err(InterpreterError::Unsupported { location }),
+        }
+    }
+
+    fn evaluate_lvalue(&mut self, lvalue: &HirLValue, location: Location) -> IResult<Value> {
+        match lvalue {
+            HirLValue::Ident(ident, _) => self.lookup_local(ident),
+            HirLValue::MemberAccess { object, field_name, .. } => {
+                let mut object = self.evaluate_lvalue(object, location)?;
+                Ok(field_mut(&mut object, &field_name.0.contents, location)?.clone())
+            }
+            HirLValue::Index { array, index, .. } => {
+                let index = self.evaluate_index_value(*index)?;
+                let mut array = self.evaluate_lvalue(array, location)?;
+                Ok(element_mut(&mut array, index, location)?.clone())
+            }
+            HirLValue::Dereference { .. } => Err(InterpreterError::Unsupported { location }),
+        }
+    }
+
+    fn evaluate_prefix(
+        &mut self,
+        prefix: HirPrefixExpression,
+        location: Location,
+    ) -> IResult<Value> {
+        let rhs = self.evaluate(prefix.rhs)?;
+        match (prefix.operator, rhs) {
+            (UnaryOp::Minus, Value::Field(value)) => Ok(Value::Field(-value)),
+            (UnaryOp::Minus, Value::Integer(value, signedness, bit_size)) => {
+                Ok(Value::Integer(truncate(value.wrapping_neg(), bit_size), signedness, bit_size))
+            }
+            (UnaryOp::Minus, Value::Untyped(0)) => Ok(Value::Untyped(0)),
+            (UnaryOp::Minus, Value::Untyped(_)) => Err(InterpreterError::Overflow { location }),
+            (UnaryOp::Minus, _) => {
+                Err(InterpreterError::TypeMismatch { expected: "a number", location })
+            }
+            (UnaryOp::Not, Value::Bool(value)) => Ok(Value::Bool(!value)),
+            (UnaryOp::Not, Value::Integer(value, signedness, bit_size)) => {
+                Ok(Value::Integer(truncate(!value, bit_size), signedness, bit_size))
+            }
+            (UnaryOp::Not, _) => {
+                Err(InterpreterError::TypeMismatch { expected: "a bool or an integer", location })
+            }
+            (UnaryOp::MutableReference | UnaryOp::Dereference, _) => {
+                Err(InterpreterError::Unsupported { location })
+            }
+        }
+    }
+
+    fn evaluate_infix(&mut self, infix: HirInfixExpression) -> IResult<Value> {
+        let lhs = self.evaluate(infix.lhs)?;
+        let rhs = self.evaluate(infix.rhs)?;
+        evaluate_binary(lhs, infix.operator.kind, rhs, infix.operator.location)
+    }
+
+    fn evaluate_index_value(&mut self, index: ExprId) -> IResult<u128> {
+        let location = self.interner.expr_location(&index);
+        let value = self.evaluate(index)?;
+        value.to_u128().ok_or(InterpreterError::TypeMismatch { expected: "an index", location })
+    }
+
+    fn evaluate_index(&mut self, index: HirIndexExpression, location: Location) -> IResult<Value> {
+        let mut collection = self.evaluate(index.collection)?;
+        let index = self.evaluate_index_value(index.index)?;
+        Ok(element_mut(&mut collection, index, location)?.clone())
+    }
+
+    fn evaluate_constructor(
+        &mut self,
+        constructor: HirConstructorExpression,
+        location: Location,
+    ) -> IResult<Value> {
+        let mut values = Vec::with_capacity(constructor.fields.len());
+        for (name, field) in constructor.fields {
+            values.push((name.0.contents, self.evaluate(field)?));
+        }
+
+        // Store the fields in declaration order so that equal structs have equal values
+        let declared_fields = constructor.r#type.borrow().get_fields(&constructor.struct_generics);
+        let fields = try_vecmap(declared_fields, |(name, _)| {
+            let index = values.iter().position(|(field, _)| *field == name);
+            let index = index.ok_or(InterpreterError::ErrorExpression { location })?;
+            Ok(values.swap_remove(index))
+        })?;
+
+        Ok(Value::Struct(constructor.r#type, constructor.struct_generics, fields))
+    }
+
+    fn evaluate_member_access(
+        &mut self,
+        access: HirMemberAccess,
+        location: Location,
+    ) -> IResult<Value> {
+        let mut object = self.evaluate(access.lhs)?;
+        Ok(field_mut(&mut object, &access.rhs.0.contents, location)?.clone())
+    }
+
+    fn evaluate_call(&mut self, call: HirCallExpression, id: ExprId) -> IResult<Value> {
+        let function = self.evaluate(call.func)?;
+        let arguments = try_vecmap(call.arguments, |argument| self.evaluate(argument))?;
+        let location = call.location;
+
+        let function = match function {
+            Value::Function(function) => function,
+            _ => return Err(InterpreterError::TypeMismatch { expected: "a function", location }),
+        };
+
+        let meta = self
+            .interner
+            .try_function_meta(&function)
+            .ok_or(InterpreterError::NotYetResolved { location })?;
+
+        match meta.kind {
+            FunctionKind::Normal => {
+                self.call_function(function, meta.parameters.0, arguments, location)
+            }
+            FunctionKind::Builtin => {
+                let builtin = meta.attributes.and_then(|attribute| attribute.builtin());
+                match (builtin.as_deref(), arguments.first()) {
+                    (Some("array_len"), Some(Value::Array(elements))) => {
+                        let length = FieldElement::from(elements.len() as u128);
+                        Ok(Value::integer_literal(length, &self.interner.id_type(id)))
+                    }
+                    _ => Err(InterpreterError::Unsupported { location }),
+                }
+            }
+            FunctionKind::LowLevel | FunctionKind::Oracle => {
+                Err(InterpreterError::Unsupported { location })
+            }
+        }
+    }
+
+    fn call_function(
+        &mut self,
+        function: FuncId,
+        parameters: Vec<Param>,
+        arguments: Vec<Value>,
+        location: Location,
+    ) -> IResult<Value> {
+        // A wrong number of arguments is reported by the type checker
+        if parameters.len() != arguments.len() {
+            return Err(InterpreterError::ErrorExpression { location });
+        }
+        if self.call_stack.len() > MAX_CALL_DEPTH {
+            return Err(InterpreterError::CallDepthExceeded { location });
+        }
+
+        let body = *self.interner.function(&function).as_expr();
+        self.call_stack.push(HashMap::new());
+        let result = self.call_function_in_frame(&parameters, arguments, body, location);
+        self.call_stack.pop();
+        result
+    }
+
+    fn call_function_in_frame(
+        &mut self,
+        parameters: &[Param],
+        arguments: Vec<Value>,
+        body: ExprId,
+        location: Location,
+    ) -> IResult<Value> {
+        for (parameter, argument) in parameters.iter().zip(arguments) {
+            self.define_pattern(&parameter.0, argument, location)?;
+        }
+        self.evaluate(body)
+    }
+
+    fn evaluate_cast(&mut self, cast: HirCastExpression, location: Location) -> IResult<Value> {
+        let value = self.evaluate(cast.lhs)?;
+        let bits = match &value {
+            Value::Field(value) => value.to_u128(),
+            Value::Integer(value, Signedness::Signed, bit_size) => {
+                sign_extend(*value, *bit_size) as u128
+            }
+            Value::Integer(value, Signedness::Unsigned, _) | Value::Untyped(value) => *value,
+            Value::Bool(value) => *value as u128,
+            _ => return Err(InterpreterError::TypeMismatch { expected: "a number", location }),
+        };
+
+        match cast.r#type.follow_bindings() {
+            Type::Integer(_, signedness, bit_size) => {
+                Ok(Value::Integer(truncate(bits, bit_size), signedness, bit_size))
+            }
+            Type::FieldElement(_) => match value {
+                Value::Field(value) => Ok(Value::Field(value)),
+                Value::Integer(value, _, _) => Ok(Value::Field(FieldElement::from(value))),
+                _ => Ok(Value::Field(FieldElement::from(bits))),
+            },
+            Type::Bool(_) => Ok(Value::Bool(bits != 0)),
+            Type::Error => Err(InterpreterError::ErrorExpression { location }),
+            _ => Err(InterpreterError::Unsupported { location }),
+        }
+    }
+
+    fn evaluate_for(&mut self, for_expr: HirForExpression, location: Location) -> IResult<Value> {
+        let start = self.evaluate(for_expr.start_range)?;
+        let end = self.evaluate(for_expr.end_range)?;
+
+        let expected = "an unsigned integer range";
+        let (start_index, end_index) = match (start.to_u128(), end.to_u128()) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return Err(InterpreterError::TypeMismatch { expected, location }),
+        };
+
+        // The index has the type of the range's bounds, which is only known from
+        // a literal bound once type checked
+        let kind = if matches!(start, Value::Untyped(_)) { end } else { start };
+        for index in start_index..end_index {
+            self.current_frame().insert(for_expr.identifier.id, kind.with_same_kind(index));
+            if !self.evaluate_loop_body(for_expr.block)? {
+                break;
+            }
+        }
+        Ok(Value::Unit)
+    }
+
+    fn evaluate_while(
+        &mut self,
+        while_expr: HirWhileExpression,
+        location: Location,
+    ) -> IResult<Value> {
+        let max_iterations = match &while_expr.max_iterations {
+            Some(max) => {
+                Some(max.evaluate_to_u64().ok_or(InterpreterError::Unsupported { location })?)
+            }
+            None => None,
+        };
+
+        let mut iterations = 0;
+        loop {
+            match self.evaluate(while_expr.condition)? {
+                Value::Bool(true) => (),
+                Value::Bool(false) => return Ok(Value::Unit),
+                _ => return Err(InterpreterError::TypeMismatch { expected: "a bool", location }),
+            }
+
+            // Running more iterations than the bound is a failed constraint once compiled
+            if max_iterations == Some(iterations) {
+                return Err(InterpreterError::FailingConstraint { location });
+            }
+            iterations += 1;
+
+            if !self.evaluate_loop_body(while_expr.block)? {
+                return Ok(Value::Unit);
+            }
+        }
+    }
+
+    /// Evaluates the body of a loop, returning false if the loop was exited by a `break`.
+    fn evaluate_loop_body(&mut self, block: ExprId) -> IResult<bool> {
+        match self.evaluate(block) {
+            Ok(_) | Err(InterpreterError::Continue { .. }) => Ok(true),
+            Err(InterpreterError::Break { .. }) => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn evaluate_if(&mut self, if_expr: HirIfExpression, location: Location) -> IResult<Value> {
+        match self.evaluate(if_expr.condition)? {
+            Value::Bool(true) => self.evaluate(if_expr.consequence),
+            Value::Bool(false) => match if_expr.alternative {
+                Some(alternative) => self.evaluate(alternative),
+                None => Ok(Value::Unit),
+            },
+            _ => Err(InterpreterError::TypeMismatch { expected: "a bool", location }),
+        }
+    }
+}
+
+/// Returns the field of a struct, or of a tuple when `name` is an index.
+fn field_mut<'a>(value: &'a mut Value, name: &str, location: Location) -> IResult<&'a mut Value> {
+    let field = match value {
+        Value::Struct(_, _, fields) => {
+            fields.iter_mut().find(|(field, _)| field == name).map(|(_, value)| value)
+        }
+        Value::Tuple(fields) => name.parse::<usize>().ok().and_then(|index| fields.get_mut(index)),
+        _ => None,
+    };
+    field.ok_or(InterpreterError::TypeMismatch { expected: "a struct or tuple", location })
+}
+
+fn element_mut(value: &mut Value, index: u128, location: Location) -> IResult<&mut Value> {
+    match value {
+        Value::Array(elements) => {
+            let length = elements.len();
+            usize::try_from(index)
+                .ok()
+                .and_then(|index| elements.get_mut(index))
+                .ok_or(InterpreterError::IndexOutOfBounds { index, length, location })
+        }
+        _ => Err(InterpreterError::TypeMismatch { expected: "an array", location }),
+    }
+}
+
+fn evaluate_binary(lhs: Value, op: BinaryOpKind, rhs: Value, location: Location) -> IResult<Value> {
+    use BinaryOpKind::*;
+
+    // Literals which haven't been type checked take the type of the other operand
+    let (lhs, rhs) = match (lhs, rhs) {
+        (Value::Untyped(lhs), rhs @ (Value::Integer(..) | Value::Field(_))) => {
+            (rhs.with_same_kind(lhs), rhs)
+        }
+        (lhs @ (Value::Integer(..) | Value::Field(_)), Value::Untyped(rhs)) => {
+            let rhs = lhs.with_same_kind(rhs);
+            (lhs, rhs)
+        }
+        operands => operands,
+    };
+
+    if matches!(op, Equal | NotEqual) {
+        return Ok(Value::Bool((lhs == rhs) == (op == Equal)));
+    }
+
+    let mismatch = InterpreterError::TypeMismatch { expected: "numeric operands", location };
+    match (lhs, rhs) {
+        (Value::Field(lhs), Value::Field(rhs)) => evaluate_field_binary(lhs, op, rhs, location),
+        (Value::Integer(lhs, signedness, bit_size), Value::Integer(rhs, ..)) => {
+            evaluate_integer_binary(lhs, op, rhs, signedness, bit_size, location)
+        }
+        (Value::Untyped(lhs), Value::Untyped(rhs)) => {
+            evaluate_untyped_binary(lhs, op, rhs, location)
+        }
+        (Value::Bool(lhs), Value::Bool(rhs)) => match op {
+            And => Ok(Value::Bool(lhs & rhs)),
+            Or => Ok(Value::Bool(lhs | rhs)),
+            Xor => Ok(Value::Bool(lhs ^ rhs)),
+            _ => Err(mismatch),
+        },
+        _ => Err(mismatch),
+    }
+}
+
+fn evaluate_field_binary(
+    lhs: FieldElement,
+    op: BinaryOpKind,
+    rhs: FieldElement,
+    location: Location,
+) -> IResult<Value> {
+    use BinaryOpKind::*;
+    match op {
+        Add => Ok(Value::Field(lhs + rhs)),
+        Subtract => Ok(Value::Field(lhs - rhs)),
+        Multiply => Ok(Value::Field(lhs * rhs)),
+        Divide if rhs.is_zero() => Err(InterpreterError::DivisionByZero { location }),
+        Divide => Ok(Value::Field(lhs / rhs)),
+        Less => Ok(Value::Bool(lhs < rhs)),
+        LessEqual => Ok(Value::Bool(lhs <= rhs)),
+        Greater => Ok(Value::Bool(lhs > rhs)),
+        GreaterEqual => Ok(Value::Bool(lhs >= rhs)),
+        Equal | NotEqual => unreachable!("equality is checked for all values"),
+        And | Or | Xor | ShiftRight | ShiftLeft | Modulo => {
+            // These are only defined for fields holding integers
+            let expected = "a field fitting in 128 bits";
+            let (lhs, rhs) = match (lhs.try_into_u128(), rhs.try_into_u128()) {
+                (Some(lhs), Some(rhs)) => (lhs, rhs),
+                _ => return Err(InterpreterError::TypeMismatch { expected, location }),
+            };
+            match evaluate_untyped_binary(lhs, op, rhs, location)? {
+                Value::Untyped(value) => Ok(Value::Field(FieldElement::from(value))),
+                other => Ok(other),
+            }
+        }
+    }
+}
+
+fn evaluate_integer_binary(
+    lhs: u128,
+    op: BinaryOpKind,
+    rhs: u128,
+    signedness: Signedness,
+    bit_size: u32,
+    location: Location,
+) -> IResult<Value> {
+    use BinaryOpKind::*;
+    let integer = |value: u128| Value::Integer(truncate(value, bit_size), signedness, bit_size);
+
+    if matches!(op, Divide | Modulo) && rhs == 0 {
+        return Err(InterpreterError::DivisionByZero { location });
+    }
+
+    match signedness {
+        Signedness::Unsigned => Ok(match op {
+            Add => integer(lhs.wrapping_add(rhs)),
+            Subtract => integer(lhs.wrapping_sub(rhs)),
+            Multiply => integer(lhs.wrapping_mul(rhs)),
+            Divide => integer(lhs / rhs),
+            Modulo => integer(lhs % rhs),
+            Less => Value::Bool(lhs < rhs),
+            LessEqual => Value::Bool(lhs <= rhs),
+            Greater => Value::Bool(lhs > rhs),
+            GreaterEqual => Value::Bool(lhs >= rhs),
+            And => integer(lhs & rhs),
+            Or => integer(lhs | rhs),
+            Xor => integer(lhs ^ rhs),
+            ShiftLeft => integer(if rhs < 128 { lhs << rhs } else { 0 }),
+            ShiftRight => integer(if rhs < 128 { lhs >> rhs } else { 0 }),
+            Equal | NotEqual => unreachable!("equality is checked for all values"),
+        }),
+        Signedness::Signed => {
+            let (signed_lhs, signed_rhs) = (sign_extend(lhs, bit_size), sign_extend(rhs, bit_size));
+            let signed = |value: i128| integer(value as u128);
+            Ok(match op {
+                Add => signed(signed_lhs.wrapping_add(signed_rhs)),
+                Subtract => signed(signed_lhs.wrapping_sub(signed_rhs)),
+                Multiply => signed(signed_lhs.wrapping_mul(signed_rhs)),
+                Divide => signed(signed_lhs.wrapping_div(signed_rhs)),
+                Modulo => signed(signed_lhs.wrapping_rem(signed_rhs)),
+                Less => Value::Bool(signed_lhs < signed_rhs),
+                LessEqual => Value::Bool(signed_lhs <= signed_rhs),
+                Greater => Value::Bool(signed_lhs > signed_rhs),
+                GreaterEqual => Value::Bool(signed_lhs >= signed_rhs),
+                And => integer(lhs & rhs),
+                Or => integer(lhs | rhs),
+                Xor => integer(lhs ^ rhs),
+                ShiftLeft => integer(if rhs < 128 { lhs << rhs } else { 0 }),
+                ShiftRight => signed(signed_lhs >> rhs.min(127)),
+                Equal | NotEqual => unreachable!("equality is checked for all values"),
+            })
+        }
+    }
+}
+
+/// Operations over literals which haven't been type checked fail instead of wrapping around,
+/// as is the case for array lengths.
+fn evaluate_untyped_binary(
+    lhs: u128,
+    op: BinaryOpKind,
+    rhs: u128,
+    location: Location,
+) -> IResult<Value> {
+    use BinaryOpKind::*;
+    let overflow = InterpreterError::Overflow { location };
+
+    if matches!(op, Divide | Modulo) && rhs == 0 {
+        return Err(InterpreterError::DivisionByZero { location });
+    }
+
+    let value = match op {
+        Add => lhs.checked_add(rhs).ok_or(overflow)?,
+        Subtract => lhs.checked_sub(rhs).ok_or(overflow)?,
+        Multiply => lhs.checked_mul(rhs).ok_or(overflow)?,
+        Divide => lhs / rhs,
+        Modulo => lhs % rhs,
+        Less => return Ok(Value::Bool(lhs < rhs)),
+        LessEqual => return Ok(Value::Bool(lhs <= rhs)),
+        Greater => return Ok(Value::Bool(lhs > rhs)),
+        GreaterEqual => return Ok(Value::Bool(lhs >= rhs)),
+        And => lhs & rhs,
+        Or => lhs | rhs,
+        Xor => lhs ^ rhs,
+        ShiftLeft if rhs >= 128 || (lhs << rhs) >> rhs != lhs => return Err(overflow),
+        ShiftLeft => lhs << rhs,
+        ShiftRight if rhs >= 128 => 0,
+        ShiftRight => lhs >> rhs,
+        Equal | NotEqual => unreachable!("equality is checked for all values"),
+    };
+    Ok(Value::Untyped(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use fm::FileId;
+    use noirc_errors::{Location, Span};
+
+    use super::{evaluate_binary, InterpreterError, Value};
+    use crate::{BinaryOpKind, Signedness};
+
+    fn binary(lhs: Value, op: BinaryOpKind, rhs: Value) -> Result<Value, InterpreterError> {
+        evaluate_binary(lhs, op, rhs, Location::new(Span::default(), FileId::dummy()))
+    }
+
+    #[test]
+    fn integers_wrap_around_their_bit_size() {
+        let u8 = |value| Value::Integer(value, Signedness::Unsigned, 8);
+        let i8 = |value: i128| Value::Integer(value as u128 & 0xff, Signedness::Signed, 8);
+
+        assert_eq!(binary(u8(255), BinaryOpKind::Add, u8(1)), Ok(u8(0)));
+        assert_eq!(binary(u8(0), BinaryOpKind::Subtract, u8(1)), Ok(u8(255)));
+        assert_eq!(binary(i8(-7), BinaryOpKind::Divide, i8(2)), Ok(i8(-3)));
+        assert_eq!(binary(i8(-8), BinaryOpKind::ShiftRight, i8(1)), Ok(i8(-4)));
+        assert_eq!(binary(i8(-1), BinaryOpKind::Less, i8(0)), Ok(Value::Bool(true)));
+    }
+
+    #[test]
+    fn untyped_literals_take_the_type_of_the_other_operand() {
+        let u32 = |value| Value::Integer(value, Signedness::Unsigned, 32);
+
+        assert_eq!(binary(Value::Untyped(1), BinaryOpKind::ShiftLeft, u32(3)), Ok(u32(8)));
+        assert_eq!(
+            binary(u32(0), BinaryOpKind::Subtract, Value::Untyped(1)),
+            Ok(u32(u32::MAX as u128))
+        );
+        assert_eq!(
+            binary(Value::Untyped(1), BinaryOpKind::ShiftLeft, Value::Untyped(3)),
+            Ok(Value::Untyped(8))
+        );
+        assert!(matches!(
+            binary(Value::Untyped(0), BinaryOpKind::Subtract, Value::Untyped(1)),
+            Err(InterpreterError::Overflow { .. })
+        ));
+        assert!(matches!(
+            binary(u32(1), BinaryOpKind::Divide, Value::Untyped(0)),
+            Err(InterpreterError::DivisionByZero { .. })
+        ));
+    }
+}
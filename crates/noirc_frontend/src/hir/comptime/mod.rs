@@ -0,0 +1,100 @@
+//! Compile-time evaluation of HIR expressions.
+//!
+//! Globals and array lengths may be given by any expression which only depends on other
+//! globals, such as `1 << DEPTH` or a call to a function computing a table. These are
+//! evaluated by interpreting their HIR: array lengths as soon as they are resolved, or once
+//! everything they depend on is resolved, and globals once the crate is type checked,
+//! so that monomorphization only ever sees their values.
+mod errors;
+mod interpreter;
+mod value;
+
+pub use errors::InterpreterError;
+pub use interpreter::Interpreter;
+pub use value::Value;
+
+use noirc_errors::{FileDiagnostic, Location};
+
+use crate::node_interner::{ExprId, NodeInterner};
+use crate::{BinaryTypeOperator, Type, TypeBinding, TypeVariable};
+
+/// The length of an array type which couldn't be evaluated when it was resolved,
+/// since it depends on a global or function which wasn't resolved yet.
+#[derive(Debug, Clone)]
+pub struct DeferredArrayLength {
+    pub length: ArrayLength,
+
+    /// The type variable standing for the length, bound once it is evaluated.
+    pub type_variable: TypeVariable,
+
+    pub location: Location,
+}
+
+#[derive(Debug, Clone)]
+pub enum ArrayLength {
+    Expression(ExprId),
+    BinaryOperation(Type, BinaryTypeOperator, Type),
+}
+
+impl DeferredArrayLength {
+    fn evaluate(&self, interner: &NodeInterner) -> Result<u64, InterpreterError> {
+        let location = self.location;
+        match &self.length {
+            ArrayLength::Expression(expr) => {
+                let value = Interpreter::new(interner).evaluate(*expr)?;
+                value.to_array_length().ok_or(InterpreterError::InvalidArrayLength { location })
+            }
+            ArrayLength::BinaryOperation(lhs, op, rhs) => {
+                let operand = |typ: &Type| match typ.follow_bindings() {
+                    // The operand was itself a length which failed to evaluate
+                    Type::Error => Err(InterpreterError::ErrorExpression { location }),
+                    typ => typ
+                        .evaluate_to_u64()
+                        .ok_or(InterpreterError::InvalidArrayLength { location }),
+                };
+                let (lhs, rhs) = (operand(lhs)?, operand(rhs)?);
+                if rhs == 0
+                    && matches!(op, BinaryTypeOperator::Division | BinaryTypeOperator::Modulo)
+                {
+                    return Err(InterpreterError::DivisionByZero { location });
+                }
+                op.function()(lhs, rhs).ok_or(InterpreterError::Overflow { location })
+            }
+        }
+    }
+}
+
+/// Evaluates the array lengths deferred during name resolution, binding each one's
+/// type variable to its value. This must happen once all globals and functions are
+/// resolved but before type checking.
+///
+/// The lengths which can't be evaluated are bound to `Type::Error`.
+pub fn evaluate_deferred_array_lengths(interner: &mut NodeInterner) -> Vec<FileDiagnostic> {
+    let mut deferred = interner.take_deferred_array_lengths();
+
+    // A length may depend on another deferred length, so we keep evaluating the remaining
+    // lengths for as long as any of them can be evaluated.
+    loop {
+        let mut remaining = Vec::new();
+        let mut errors = Vec::new();
+        for length in &deferred {
+            match length.evaluate(interner) {
+                Ok(value) => {
+                    *length.type_variable.borrow_mut() = TypeBinding::Bound(Type::Constant(value));
+                }
+                Err(error) => {
+                    remaining.push(length.clone());
+                    errors.push(error);
+                }
+            }
+        }
+
+        if remaining.len() == deferred.len() {
+            for length in remaining {
+                *length.type_variable.borrow_mut() = TypeBinding::Bound(Type::Error);
+            }
+            return errors.into_iter().filter_map(InterpreterError::into_file_diagnostic).collect();
+        }
+        deferred = remaining;
+    }
+}
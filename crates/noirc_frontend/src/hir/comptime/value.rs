@@ -0,0 +1,167 @@
+use acvm::FieldElement;
+use iter_extended::try_vecmap;
+use noirc_errors::{Location, Spanned};
+
+use crate::hir_def::expr::{HirArrayLiteral, HirConstructorExpression, HirExpression, HirLiteral};
+use crate::node_interner::{ExprId, FuncId, NodeInterner};
+use crate::{Ident, Shared, Signedness, StructType, Type};
+
+use super::errors::InterpreterError;
+
+/// A value computed at compile-time.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Unit,
+    Bool(bool),
+    Field(FieldElement),
+    /// An integer of the given signedness and bit size, in two's complement.
+    Integer(u128, Signedness, u32),
+    /// An integer literal whose type isn't known yet since it hasn't been type checked.
+    /// Arithmetic over these is checked, as is the case for array lengths.
+    Untyped(u128),
+    String(String),
+    Array(Vec<Value>),
+    Tuple(Vec<Value>),
+    /// A struct value, with its fields in declaration order.
+    Struct(Shared<StructType>, Vec<Type>, Vec<(String, Value)>),
+    Function(FuncId),
+}
+
+impl Value {
+    /// Creates the value of an integer literal of the given type.
+    pub(crate) fn integer_literal(value: FieldElement, typ: &Type) -> Value {
+        match typ.follow_bindings() {
+            Type::Integer(_, signedness, bit_size) => {
+                Value::Integer(truncate(value.to_u128(), bit_size), signedness, bit_size)
+            }
+            Type::Error => match value.try_into_u128() {
+                Some(value) => Value::Untyped(value),
+                None => Value::Field(value),
+            },
+            _ => Value::Field(value),
+        }
+    }
+
+    /// Creates an integer of the same kind as this one, used for the indices of for loops.
+    pub(crate) fn with_same_kind(&self, value: u128) -> Value {
+        match self {
+            Value::Integer(_, signedness, bit_size) => {
+                Value::Integer(truncate(value, *bit_size), *signedness, *bit_size)
+            }
+            Value::Untyped(_) => Value::Untyped(value),
+            _ => Value::Field(FieldElement::from(value)),
+        }
+    }
+
+    /// Returns the value of this integer if it fits in a u128.
+    pub(crate) fn to_u128(&self) -> Option<u128> {
+        match self {
+            Value::Field(value) => value.try_into_u128(),
+            Value::Integer(value, Signedness::Unsigned, _) | Value::Untyped(value) => Some(*value),
+            Value::Integer(value, Signedness::Signed, bit_size) => {
+                (sign_extend(*value, *bit_size) >= 0).then_some(*value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns true if this value is a non-negative integer which doesn't fit in a u64.
+    pub fn is_too_large_for_array_length(&self) -> bool {
+        match self {
+            Value::Field(_) => self.to_array_length().is_none(),
+            _ => self.to_u128().map_or(false, |value| value > u64::MAX as u128),
+        }
+    }
+
+    /// Returns this value as an array length, if it is a non-negative integer fitting in a u64.
+    pub fn to_array_length(&self) -> Option<u64> {
+        self.to_u128().and_then(|length| length.try_into().ok())
+    }
+
+    /// Converts this value back into an expression of the given type,
+    /// so that it may replace the expression it was computed from.
+    pub fn into_hir_expression(
+        self,
+        interner: &mut NodeInterner,
+        typ: &Type,
+        location: Location,
+    ) -> Result<HirExpression, InterpreterError> {
+        let typ = typ.follow_bindings();
+        let literal = match self {
+            Value::Unit => return Ok(HirExpression::empty_block()),
+            Value::Bool(value) => HirLiteral::Bool(value),
+            Value::Field(value) => HirLiteral::Integer(value),
+            Value::Integer(value, _, _) | Value::Untyped(value) => {
+                HirLiteral::Integer(FieldElement::from(value))
+            }
+            Value::String(value) => HirLiteral::Str(value),
+            Value::Array(elements) => {
+                let element_type = match &typ {
+                    Type::Array(_, element_type) => element_type.as_ref().clone(),
+                    _ => Type::Error,
+                };
+                let elements = try_vecmap(elements, |element| {
+                    element.into_expr_id(interner, &element_type, location)
+                })?;
+                HirLiteral::Array(HirArrayLiteral::Standard(elements))
+            }
+            Value::Tuple(fields) => {
+                let field_types = match typ {
+                    Type::Tuple(field_types) => field_types,
+                    _ => vec![Type::Error; fields.len()],
+                };
+                let fields = try_vecmap(fields.into_iter().zip(field_types), |(field, typ)| {
+                    field.into_expr_id(interner, &typ, location)
+                })?;
+                return Ok(HirExpression::Tuple(fields));
+            }
+            Value::Struct(struct_type, struct_generics, fields) => {
+                let field_types = struct_type.borrow().get_fields(&struct_generics);
+                let fields = try_vecmap(fields.into_iter().zip(field_types), |(field, typ)| {
+                    let (name, value) = field;
+                    let name = Ident(Spanned::from(location.span, name));
+                    Ok((name, value.into_expr_id(interner, &typ.1, location)?))
+                })?;
+                return Ok(HirExpression::Constructor(HirConstructorExpression {
+                    r#type: struct_type,
+                    struct_generics,
+                    fields,
+                }));
+            }
+            Value::Function(_) => return Err(InterpreterError::Unsupported { location }),
+        };
+        Ok(HirExpression::Literal(literal))
+    }
+
+    fn into_expr_id(
+        self,
+        interner: &mut NodeInterner,
+        typ: &Type,
+        location: Location,
+    ) -> Result<ExprId, InterpreterError> {
+        let expr = self.into_hir_expression(interner, typ, location)?;
+        let id = interner.push_expr(expr);
+        interner.push_expr_type(&id, typ.clone());
+        interner.push_expr_location(id, location.span, location.file);
+        Ok(id)
+    }
+}
+
+/// Keeps only the lowest `bit_size` bits of `value`.
+pub(crate) fn truncate(value: u128, bit_size: u32) -> u128 {
+    if bit_size >= 128 {
+        value
+    } else {
+        value & ((1 << bit_size) - 1)
+    }
+}
+
+/// Interprets the lowest `bit_size` bits of `value` as a two's complement integer.
+pub(crate) fn sign_extend(value: u128, bit_size: u32) -> i128 {
+    if bit_size == 0 || bit_size >= 128 {
+        value as i128
+    } else {
+        let shift = 128 - bit_size;
+        ((value << shift) as i128) >> shift
+    }
+}
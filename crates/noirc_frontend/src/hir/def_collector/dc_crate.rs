@@ -1,6 +1,7 @@
 use super::dc_mod::collect_defs;
 use super::errors::DefCollectorErrorKind;
 use crate::graph::{CrateId, LOCAL_CRATE};
+use crate::hir::comptime::{evaluate_deferred_array_lengths, Interpreter};
 use crate::hir::def_map::{CrateDefMap, LocalModuleId, ModuleId};
 use crate::hir::resolution::errors::ResolverError;
 use crate::hir::resolution::resolver::Resolver;
@@ -10,15 +11,20 @@ use crate::hir::resolution::{
 };
 use crate::hir::type_check::{type_check_func, TypeChecker};
 use crate::hir::Context;
-use crate::node_interner::{FuncId, NodeInterner, StmtId, StructId};
+use crate::hir_def::expr::{HirExpression, HirIdent};
+use crate::hir_def::stmt::{HirLetStatement, HirPattern, HirStatement};
+use crate::hir_def::traits::{Trait, TraitFunction, TraitImpl};
+use crate::node_interner::{
+    DefinitionKind, EnumId, FuncId, NodeInterner, StmtId, StructId, TraitId,
+};
 use crate::{
-    ExpressionKind, Generics, Ident, LetStatement, NoirFunction, NoirStruct, ParsedModule, Shared,
-    Type, TypeBinding, UnresolvedGenerics, UnresolvedType,
+    ExpressionKind, Generics, Ident, LetStatement, NoirEnum, NoirFunction, NoirStruct, Path,
+    Shared, SortedModule, Type, TypeBinding, UnresolvedGenerics, UnresolvedType,
 };
 use fm::FileId;
 use iter_extended::vecmap;
-use noirc_errors::Span;
 use noirc_errors::{CustomDiagnostic, FileDiagnostic};
+use noirc_errors::{Location, Span};
 use std::collections::HashMap;
 use std::rc::Rc;
 
@@ -40,6 +46,31 @@ pub struct UnresolvedStruct {
     pub struct_def: NoirStruct,
 }
 
+pub struct UnresolvedEnum {
+    pub file_id: FileId,
+    pub module_id: LocalModuleId,
+    pub enum_def: NoirEnum,
+}
+
+pub struct UnresolvedTrait {
+    pub file_id: FileId,
+    pub module_id: LocalModuleId,
+    pub name: Ident,
+    pub span: Span,
+    pub methods: UnresolvedFunctions,
+    pub method_info: Vec<TraitFunction>,
+}
+
+pub struct UnresolvedTraitImpl {
+    pub file_id: FileId,
+    pub module_id: LocalModuleId,
+    pub trait_path: Path,
+    pub object_type: UnresolvedType,
+    pub type_span: Span,
+    pub generics: UnresolvedGenerics,
+    pub methods: UnresolvedFunctions,
+}
+
 #[derive(Clone)]
 pub struct UnresolvedGlobal {
     pub file_id: FileId,
@@ -54,8 +85,11 @@ pub struct DefCollector {
     pub(crate) collected_imports: Vec<ImportDirective>,
     pub(crate) collected_functions: Vec<UnresolvedFunctions>,
     pub(crate) collected_types: HashMap<StructId, UnresolvedStruct>,
+    pub(crate) collected_enums: HashMap<EnumId, UnresolvedEnum>,
     pub(crate) collected_globals: Vec<UnresolvedGlobal>,
     pub(crate) collected_impls: ImplMap,
+    pub(crate) collected_traits: HashMap<TraitId, UnresolvedTrait>,
+    pub(crate) collected_trait_impls: Vec<UnresolvedTraitImpl>,
 }
 
 /// Maps the type and the module id in which the impl is defined to the functions contained in that
@@ -71,8 +105,11 @@ impl DefCollector {
             collected_imports: vec![],
             collected_functions: vec![],
             collected_types: HashMap::new(),
+            collected_enums: HashMap::new(),
             collected_impls: HashMap::new(),
             collected_globals: vec![],
+            collected_traits: HashMap::new(),
+            collected_trait_impls: vec![],
         }
     }
 
@@ -82,7 +119,7 @@ impl DefCollector {
     pub fn collect(
         mut def_map: CrateDefMap,
         context: &mut Context,
-        ast: ParsedModule,
+        ast: SortedModule,
         root_file_id: FileId,
         errors: &mut Vec<FileDiagnostic>,
     ) {
@@ -155,10 +192,21 @@ impl DefCollector {
         let (integer_globals, other_globals) =
             filter_integer_globals(def_collector.collected_globals);
 
+        // Every global is declared before any is resolved, since a global may refer to any other.
+        declare_globals(context, &integer_globals, crate_id);
+        declare_globals(context, &other_globals, crate_id);
+
         let mut file_global_ids = resolve_globals(context, integer_globals, crate_id, errors);
 
-        // Must resolve structs before we resolve globals.
+        // Enums are pushed to the interner before any structs are resolved so that
+        // struct fields may refer to enums and vice-versa.
+        for (enum_id, typ) in &def_collector.collected_enums {
+            context.def_interner.push_empty_enum(*enum_id, typ);
+        }
+
+        // Must resolve structs and enums before we resolve globals.
         resolve_structs(context, def_collector.collected_types, crate_id, errors);
+        resolve_enums(context, def_collector.collected_enums, crate_id, errors);
 
         // We must wait to resolve non-integer globals until after we resolve structs since structs
         // globals will need to reference the struct type they're initialized to to ensure they are valid.
@@ -166,6 +214,16 @@ impl DefCollector {
 
         file_global_ids.append(&mut more_global_ids);
 
+        // Traits must be resolved before any functions since functions may be bounded by them,
+        // and the methods of trait impls are checked against the trait's declarations.
+        let file_trait_method_ids = resolve_traits(
+            &mut context.def_interner,
+            crate_id,
+            &context.def_maps,
+            def_collector.collected_traits,
+            errors,
+        );
+
         // Before we resolve any function symbols we must go through our impls and
         // re-collect the methods within into their proper module. This cannot be
         // done before resolution since we need to be able to resolve the type of the
@@ -190,11 +248,28 @@ impl DefCollector {
             errors,
         );
 
-        type_check_globals(&mut context.def_interner, file_global_ids, errors);
+        let file_trait_impl_method_ids = resolve_trait_impls(
+            &mut context.def_interner,
+            crate_id,
+            &context.def_maps,
+            def_collector.collected_trait_impls,
+            errors,
+        );
+
+        // Every global and function an array length may depend on is now resolved,
+        // so the lengths which couldn't be evaluated when they were resolved can be.
+        errors.extend(evaluate_deferred_array_lengths(&mut context.def_interner));
+
+        type_check_globals(&mut context.def_interner, &file_global_ids, errors);
 
         // Type check all of the functions in the crate
         type_check_functions(&mut context.def_interner, file_func_ids, errors);
         type_check_functions(&mut context.def_interner, file_method_ids, errors);
+        type_check_functions(&mut context.def_interner, file_trait_method_ids, errors);
+        type_check_functions(&mut context.def_interner, file_trait_impl_method_ids, errors);
+
+        // Globals are replaced by their values so that they aren't computed at each use.
+        evaluate_globals(&mut context.def_interner, &file_global_ids, errors);
     }
 }
 
@@ -252,6 +327,7 @@ fn collect_impls(
 fn get_local_id_from_type(typ: &Type) -> Option<LocalModuleId> {
     match typ {
         Type::Struct(definition, _) => Some(definition.borrow().id.0.local_id),
+        Type::Enum(definition, _) => Some(definition.borrow().id.0.local_id),
         _ => None,
     }
 }
@@ -274,6 +350,30 @@ fn filter_integer_globals(
         .partition(|global| matches!(&global.stmt_def.expression.kind, ExpressionKind::Literal(_)))
 }
 
+/// Defines each global with a placeholder expression, replaced once the global is resolved.
+fn declare_globals(context: &mut Context, globals: &[UnresolvedGlobal], crate_id: CrateId) {
+    for global in globals {
+        let module_id = ModuleId { local_id: global.module_id, krate: crate_id };
+        let storage_slot = context.next_storage_slot(module_id);
+
+        let name = global.stmt_def.pattern.name_ident().clone();
+        let location = Location::new(name.span(), global.file_id);
+
+        let interner = &mut context.def_interner;
+        let expression = interner.push_expr(HirExpression::Error);
+        interner.push_expr_location(expression, global.stmt_def.expression.span, global.file_id);
+
+        let definition = DefinitionKind::Global(expression);
+        let id =
+            interner.push_definition(name.0.contents.clone(), false, definition, Some(location));
+        let pattern = HirPattern::Identifier(HirIdent { location, id });
+
+        let let_statement = HirLetStatement { pattern, r#type: Type::Error, expression };
+        interner.update_global(global.stmt_id, HirStatement::Let(let_statement));
+        interner.push_global(global.stmt_id, name, global.module_id, storage_slot);
+    }
+}
+
 fn resolve_globals(
     context: &mut Context,
     globals: Vec<UnresolvedGlobal>,
@@ -283,7 +383,6 @@ fn resolve_globals(
     vecmap(globals, |global| {
         let module_id = ModuleId { local_id: global.module_id, krate: crate_id };
         let path_resolver = StandardPathResolver::new(module_id);
-        let storage_slot = context.next_storage_slot(module_id);
 
         let mut resolver = Resolver::new(
             &mut context.def_interner,
@@ -292,27 +391,47 @@ fn resolve_globals(
             global.file_id,
         );
 
-        let name = global.stmt_def.pattern.name_ident().clone();
-
-        let hir_stmt = resolver.resolve_global_let(global.stmt_def);
+        let hir_stmt = resolver.resolve_global_let(global.stmt_def, global.stmt_id);
         extend_errors(errors, global.file_id, resolver.take_errors());
 
         context.def_interner.update_global(global.stmt_id, hir_stmt);
 
-        context.def_interner.push_global(global.stmt_id, name, global.module_id, storage_slot);
-
         (global.file_id, global.stmt_id)
     })
 }
 
 fn type_check_globals(
     interner: &mut NodeInterner,
-    global_ids: Vec<(FileId, StmtId)>,
+    global_ids: &[(FileId, StmtId)],
     all_errors: &mut Vec<FileDiagnostic>,
 ) {
     for (file_id, stmt_id) in global_ids {
-        let errors = TypeChecker::check_global(&stmt_id, interner);
-        extend_errors(all_errors, file_id, errors);
+        let errors = TypeChecker::check_global(stmt_id, interner);
+        extend_errors(all_errors, *file_id, errors);
+    }
+}
+
+/// Evaluates the value of each global, replacing its expression with the value.
+/// Globals are only evaluated when there are no errors, since they must be type checked.
+fn evaluate_globals(
+    interner: &mut NodeInterner,
+    global_ids: &[(FileId, StmtId)],
+    errors: &mut Vec<FileDiagnostic>,
+) {
+    if errors.iter().any(|error| error.diagnostic.is_error()) {
+        return;
+    }
+
+    for (_, stmt_id) in global_ids {
+        let expression = interner.let_statement(stmt_id).expression;
+        let location = interner.expr_location(&expression);
+        let typ = interner.id_type(expression);
+
+        let value = Interpreter::new(interner).evaluate(expression);
+        match value.and_then(|value| value.into_hir_expression(interner, &typ, location)) {
+            Ok(value) => interner.replace_expr(&expression, value),
+            Err(error) => errors.extend(error.into_file_diagnostic()),
+        }
     }
 }
 
@@ -360,6 +479,35 @@ fn resolve_struct_fields(
     (generics, fields)
 }
 
+/// Resolve the variants of each enum. Each enum must already have been
+/// pushed to the def_interner via push_empty_enum.
+fn resolve_enums(
+    context: &mut Context,
+    enums: HashMap<EnumId, UnresolvedEnum>,
+    krate: CrateId,
+    all_errors: &mut Vec<FileDiagnostic>,
+) {
+    for (enum_id, unresolved) in enums {
+        let path_resolver =
+            StandardPathResolver::new(ModuleId { local_id: unresolved.module_id, krate });
+
+        let (generics, variants, errors) = Resolver::new(
+            &mut context.def_interner,
+            &path_resolver,
+            &context.def_maps,
+            unresolved.file_id,
+        )
+        .resolve_enum_variants(unresolved.enum_def);
+
+        extend_errors(all_errors, unresolved.file_id, errors);
+
+        context.def_interner.update_enum(enum_id, |enum_def| {
+            enum_def.set_variants(variants);
+            enum_def.generics = generics;
+        });
+    }
+}
+
 fn resolve_impls(
     interner: &mut NodeInterner,
     crate_id: CrateId,
@@ -415,6 +563,239 @@ fn resolve_impls(
     file_method_ids
 }
 
+/// Resolve each trait along with the methods declared within it. Each method is generic
+/// over the trait's `Self` type. Returns only the methods which have a default body
+/// since those are the only ones that need to be type checked.
+fn resolve_traits(
+    interner: &mut NodeInterner,
+    crate_id: CrateId,
+    def_maps: &HashMap<CrateId, CrateDefMap>,
+    traits: HashMap<TraitId, UnresolvedTrait>,
+    errors: &mut Vec<FileDiagnostic>,
+) -> Vec<(FileId, FuncId)> {
+    let mut file_method_ids = Vec::new();
+
+    for (trait_id, unresolved_trait) in traits {
+        let self_type_typevar_id = interner.next_type_variable_id();
+        let self_type_typevar = Shared::new(TypeBinding::Unbound(self_type_typevar_id));
+        let self_type_name = Rc::new("Self".to_owned());
+        let self_type = Type::NamedGeneric(self_type_typevar.clone(), self_type_name.clone());
+
+        interner.push_trait(
+            trait_id,
+            Trait {
+                id: trait_id,
+                name: unresolved_trait.name,
+                methods: unresolved_trait.method_info,
+                self_type_typevar_id,
+                self_type_typevar: self_type_typevar.clone(),
+                span: unresolved_trait.span,
+            },
+        );
+
+        let file_id = unresolved_trait.methods.file_id;
+        let trait_generics = vec![(self_type_name, self_type_typevar, unresolved_trait.span)];
+
+        for (mod_id, func_id, func) in unresolved_trait.methods.functions {
+            let path_resolver =
+                StandardPathResolver::new(ModuleId { local_id: mod_id, krate: crate_id });
+
+            let mut resolver = Resolver::new(interner, &path_resolver, def_maps, file_id);
+            resolver.set_generics(trait_generics.clone());
+            resolver.set_self_type(Some(self_type.clone()));
+            resolver.set_trait_id(Some(trait_id));
+
+            let (hir_func, func_meta, errs) = resolver.resolve_function(func, func_id);
+            let has_default = interner
+                .get_trait(trait_id)
+                .methods
+                .iter()
+                .any(|method| method.id == func_id && method.has_default);
+
+            interner.push_fn_meta(func_meta, func_id);
+            interner.update_fn(func_id, hir_func);
+            extend_errors(errors, file_id, errs);
+
+            if has_default {
+                file_method_ids.push((file_id, func_id));
+            }
+        }
+    }
+
+    file_method_ids
+}
+
+/// Resolve each `impl Trait for Type` block, checking that the methods defined match those
+/// declared by the trait, and register the impl so that trait methods can be dispatched to it.
+fn resolve_trait_impls(
+    interner: &mut NodeInterner,
+    crate_id: CrateId,
+    def_maps: &HashMap<CrateId, CrateDefMap>,
+    trait_impls: Vec<UnresolvedTraitImpl>,
+    errors: &mut Vec<FileDiagnostic>,
+) -> Vec<(FileId, FuncId)> {
+    let mut file_method_ids = Vec::new();
+
+    for trait_impl in trait_impls {
+        let path_resolver =
+            StandardPathResolver::new(ModuleId { local_id: trait_impl.module_id, krate: crate_id });
+
+        let file = trait_impl.file_id;
+
+        let mut resolver = Resolver::new(interner, &path_resolver, def_maps, file);
+        resolver.add_generics(&trait_impl.generics);
+        let generics = resolver.get_generics().to_vec();
+        let self_type = resolver.resolve_type(trait_impl.object_type);
+        let trait_id = resolver.lookup_trait_or_error(trait_impl.trait_path);
+        extend_errors(errors, file, resolver.take_errors());
+
+        let mut file_func_ids = resolve_function_set(
+            interner,
+            crate_id,
+            def_maps,
+            trait_impl.methods,
+            Some(self_type.clone()),
+            generics,
+            errors,
+        );
+
+        if let Some(trait_id) = trait_id {
+            if self_type != Type::Error {
+                let span = trait_impl.type_span;
+                let (methods, method_errors) =
+                    check_trait_impl_methods(interner, trait_id, &self_type, &file_func_ids, span);
+
+                extend_errors(errors, file, method_errors);
+
+                let new_impl = TraitImpl { trait_id, typ: self_type.clone(), methods, span };
+
+                if let Err(first_span) = interner.add_trait_implementation(new_impl) {
+                    let error = DefCollectorErrorKind::DuplicateTraitImpl {
+                        trait_name: interner.get_trait(trait_id).name.to_string(),
+                        typ: self_type.to_string(),
+                        first_span,
+                        second_span: span,
+                    };
+                    errors.push(error.into_file_diagnostic(file));
+                }
+            }
+        }
+
+        file_method_ids.append(&mut file_func_ids);
+    }
+
+    file_method_ids
+}
+
+/// Check the methods of a trait impl against those declared by the trait itself, returning
+/// the impl's methods keyed by name along with any errors found.
+fn check_trait_impl_methods(
+    interner: &mut NodeInterner,
+    trait_id: TraitId,
+    self_type: &Type,
+    impl_methods: &[(FileId, FuncId)],
+    impl_span: Span,
+) -> (HashMap<String, FuncId>, Vec<DefCollectorErrorKind>) {
+    let the_trait = interner.get_trait(trait_id).clone();
+    let trait_name = the_trait.name.to_string();
+
+    let mut methods = HashMap::new();
+    let mut errors = Vec::new();
+
+    for (_, method_id) in impl_methods {
+        let method_name = interner.function_ident(method_id);
+
+        match the_trait.find_method(&method_name.0.contents) {
+            Some(trait_method) => {
+                let expected = interner.function_meta(&trait_method.id).parameters.len();
+                let actual = interner.function_meta(method_id).parameters.len();
+
+                if expected != actual {
+                    errors.push(DefCollectorErrorKind::MismatchTraitImplNumParameters {
+                        trait_name: trait_name.clone(),
+                        method_name: method_name.clone(),
+                        expected,
+                        actual,
+                    });
+                } else if let Err((expected, actual)) = check_trait_impl_method_signature(
+                    interner,
+                    &the_trait,
+                    trait_method.id,
+                    *method_id,
+                    self_type,
+                ) {
+                    errors.push(DefCollectorErrorKind::MismatchTraitImplMethodSignature {
+                        trait_name: trait_name.clone(),
+                        method_name: method_name.clone(),
+                        expected: expected.to_string(),
+                        actual: actual.to_string(),
+                    });
+                }
+
+                methods.insert(method_name.0.contents, *method_id);
+            }
+            None => errors.push(DefCollectorErrorKind::MethodNotInTrait {
+                trait_name: trait_name.clone(),
+                impl_method: method_name,
+            }),
+        }
+    }
+
+    for trait_method in &the_trait.methods {
+        if !trait_method.has_default && !methods.contains_key(&trait_method.name.0.contents) {
+            errors.push(DefCollectorErrorKind::MissingTraitMethod {
+                trait_name: trait_name.clone(),
+                method_name: trait_method.name.to_string(),
+                span: impl_span,
+            });
+        }
+    }
+
+    (methods, errors)
+}
+
+/// Check that the signature of a method defined in a trait impl matches the one declared by the
+/// trait, once `Self` is replaced by the type the trait is implemented for. On a mismatch, the
+/// expected and actual function types are returned.
+fn check_trait_impl_method_signature(
+    interner: &mut NodeInterner,
+    the_trait: &Trait,
+    trait_method: FuncId,
+    impl_method: FuncId,
+    self_type: &Type,
+) -> Result<(), (Type, Type)> {
+    // The trait method is generic over `Self` and its own generics. `Self` is replaced by the
+    // impl's type, while the method's generics may be instantiated to those of the impl method.
+    let expected = match interner.function_meta(&trait_method).typ {
+        Type::Forall(generics, typ) => {
+            let bindings = generics
+                .into_iter()
+                .map(|(id, var)| {
+                    let replacement = if id == the_trait.self_type_typevar_id {
+                        self_type.clone()
+                    } else {
+                        interner.next_type_variable()
+                    };
+                    (id, (var, replacement))
+                })
+                .collect();
+            typ.substitute(&bindings)
+        }
+        typ => typ,
+    };
+
+    // The generics of the impl method are kept as they are, so that they only match themselves.
+    let actual = match interner.function_meta(&impl_method).typ {
+        Type::Forall(_, typ) => *typ,
+        typ => typ,
+    };
+
+    match actual.try_unify(&expected, the_trait.span) {
+        Ok(()) => Ok(()),
+        Err(_) => Err((expected, actual)),
+    }
+}
+
 fn resolve_free_functions(
     interner: &mut NodeInterner,
     crate_id: CrateId,
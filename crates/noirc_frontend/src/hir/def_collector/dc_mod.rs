@@ -1,13 +1,22 @@
 use fm::FileId;
 use noirc_errors::FileDiagnostic;
 
+use noirc_abi::{AbiDistinctness, AbiVisibility};
+
 use crate::{
-    graph::CrateId, hir::def_collector::dc_crate::UnresolvedStruct, node_interner::StructId,
-    parser::SubModule, Ident, LetStatement, NoirFunction, NoirImpl, NoirStruct, ParsedModule,
+    graph::CrateId,
+    hir::def_collector::dc_crate::{UnresolvedEnum, UnresolvedStruct},
+    hir_def::traits::TraitFunction,
+    node_interner::{EnumId, StructId, TraitId},
+    parser::SortedSubModule,
+    BlockExpression, FunctionDefinition, Ident, LetStatement, NoirEnum, NoirFunction, NoirImpl,
+    NoirStruct, NoirTrait, NoirTraitImpl, SortedModule, TraitItem,
 };
 
 use super::{
-    dc_crate::{DefCollector, UnresolvedFunctions, UnresolvedGlobal},
+    dc_crate::{
+        DefCollector, UnresolvedFunctions, UnresolvedGlobal, UnresolvedTrait, UnresolvedTraitImpl,
+    },
     errors::DefCollectorErrorKind,
 };
 use crate::hir::def_map::{parse_file, LocalModuleId, ModuleData, ModuleId, ModuleOrigin};
@@ -26,7 +35,7 @@ struct ModCollector<'a> {
 /// This performs the entirety of the definition collection phase of the name resolution pass.
 pub fn collect_defs(
     def_collector: &mut DefCollector,
-    ast: ParsedModule,
+    ast: SortedModule,
     file_id: FileId,
     module_id: LocalModuleId,
     crate_id: CrateId,
@@ -55,9 +64,15 @@ pub fn collect_defs(
 
     collector.collect_structs(ast.types, crate_id, errors);
 
+    collector.collect_enums(ast.enums, crate_id, errors);
+
+    collector.collect_traits(context, ast.traits, crate_id, errors);
+
     collector.collect_functions(context, ast.functions, errors);
 
     collector.collect_impls(context, ast.impls);
+
+    collector.collect_trait_impls(context, ast.trait_impls);
 }
 
 impl<'a> ModCollector<'a> {
@@ -109,6 +124,29 @@ impl<'a> ModCollector<'a> {
         }
     }
 
+    fn collect_trait_impls(&mut self, context: &mut Context, impls: Vec<NoirTraitImpl>) {
+        for trait_impl in impls {
+            let mut unresolved_functions =
+                UnresolvedFunctions { file_id: self.file_id, functions: Vec::new() };
+
+            for method in trait_impl.methods {
+                let func_id = context.def_interner.push_empty_fn();
+                context.def_interner.push_function_definition(method.name().to_owned(), func_id);
+                unresolved_functions.push_fn(self.module_id, func_id, method);
+            }
+
+            self.def_collector.collected_trait_impls.push(UnresolvedTraitImpl {
+                file_id: self.file_id,
+                module_id: self.module_id,
+                trait_path: trait_impl.trait_name,
+                object_type: trait_impl.object_type,
+                type_span: trait_impl.type_span,
+                generics: trait_impl.generics,
+                methods: unresolved_functions,
+            });
+        }
+    }
+
     fn collect_functions(
         &mut self,
         context: &mut Context,
@@ -183,11 +221,133 @@ impl<'a> ModCollector<'a> {
         }
     }
 
+    /// Collect any enum definitions declared within the ast.
+    /// Like structs, each enum is given its own module so that methods may be added to it.
+    fn collect_enums(
+        &mut self,
+        enums: Vec<NoirEnum>,
+        krate: CrateId,
+        errors: &mut Vec<FileDiagnostic>,
+    ) {
+        for enum_definition in enums {
+            let name = enum_definition.name.clone();
+
+            // Create the corresponding module for the enum namespace
+            let id = match self.push_child_module(&name, self.file_id, false, false, errors) {
+                Some(local_id) => EnumId(ModuleId { krate, local_id }),
+                None => continue,
+            };
+
+            // Add the enum to scope so its path can be looked up later
+            let result =
+                self.def_collector.def_map.modules[self.module_id.0].declare_enum(name, id);
+
+            if let Err((first_def, second_def)) = result {
+                let err = DefCollectorErrorKind::DuplicateFunction { first_def, second_def };
+                errors.push(err.into_file_diagnostic(self.file_id));
+            }
+
+            let unresolved = UnresolvedEnum {
+                file_id: self.file_id,
+                module_id: self.module_id,
+                enum_def: enum_definition,
+            };
+            self.def_collector.collected_enums.insert(id, unresolved);
+        }
+    }
+
+    /// Collect any trait definitions declared within the ast.
+    /// Each method declared by a trait is added to the trait's own module so that
+    /// it may be referred to by path, e.g. `Default::default()`.
+    fn collect_traits(
+        &mut self,
+        context: &mut Context,
+        traits: Vec<NoirTrait>,
+        krate: CrateId,
+        errors: &mut Vec<FileDiagnostic>,
+    ) {
+        for trait_definition in traits {
+            let name = trait_definition.name.clone();
+
+            // Create the corresponding module for the trait namespace
+            let id = match self.push_child_module(&name, self.file_id, false, false, errors) {
+                Some(local_id) => TraitId(ModuleId { krate, local_id }),
+                None => continue,
+            };
+
+            // Add the trait to scope so its path can be looked up later
+            let result =
+                self.def_collector.def_map.modules[self.module_id.0].declare_trait(name, id);
+
+            if let Err((first_def, second_def)) = result {
+                let err = DefCollectorErrorKind::DuplicateFunction { first_def, second_def };
+                errors.push(err.into_file_diagnostic(self.file_id));
+            }
+
+            let mut methods = UnresolvedFunctions { file_id: self.file_id, functions: Vec::new() };
+            let mut method_info = Vec::new();
+
+            for item in trait_definition.items {
+                let TraitItem::Function {
+                    name,
+                    generics,
+                    parameters,
+                    return_type,
+                    where_clause,
+                    body,
+                } = item;
+
+                let func_id = context.def_interner.push_empty_fn();
+                context.def_interner.push_function_definition(name.0.contents.clone(), func_id);
+
+                let trait_module = &mut self.def_collector.def_map.modules[id.0.local_id.0];
+                if let Err((first_def, second_def)) =
+                    trait_module.declare_function(name.clone(), func_id)
+                {
+                    let error = DefCollectorErrorKind::DuplicateFunction { first_def, second_def };
+                    errors.push(error.into_file_diagnostic(self.file_id));
+                }
+
+                method_info.push(TraitFunction {
+                    name: name.clone(),
+                    id: func_id,
+                    has_default: body.is_some(),
+                });
+
+                let def = FunctionDefinition {
+                    span: name.span(),
+                    name,
+                    attribute: None,
+                    is_open: false,
+                    is_unconstrained: false,
+                    generics,
+                    parameters,
+                    body: body.unwrap_or_else(|| BlockExpression(Vec::new())),
+                    return_type,
+                    return_visibility: AbiVisibility::Private,
+                    return_distinctness: AbiDistinctness::DuplicationAllowed,
+                    where_clause,
+                };
+                methods.push_fn(self.module_id, func_id, NoirFunction::normal(def));
+            }
+
+            let unresolved = UnresolvedTrait {
+                file_id: self.file_id,
+                module_id: self.module_id,
+                name: trait_definition.name,
+                span: trait_definition.span,
+                methods,
+                method_info,
+            };
+            self.def_collector.collected_traits.insert(id, unresolved);
+        }
+    }
+
     fn collect_submodules(
         &mut self,
         context: &mut Context,
         crate_id: CrateId,
-        submodules: Vec<SubModule>,
+        submodules: Vec<SortedSubModule>,
         file_id: FileId,
         errors: &mut Vec<FileDiagnostic>,
     ) {
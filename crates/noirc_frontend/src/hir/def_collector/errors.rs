@@ -22,6 +22,25 @@ pub enum DefCollectorErrorKind {
     PathResolutionError(PathResolutionError),
     #[error("Non-struct type used in impl")]
     NonStructTypeInImpl { span: Span },
+    #[error("Trait implemented more than once for the same type")]
+    DuplicateTraitImpl { trait_name: String, typ: String, first_span: Span, second_span: Span },
+    #[error("Trait impl is missing a required method")]
+    MissingTraitMethod { trait_name: String, method_name: String, span: Span },
+    #[error("Method is not a member of the trait being implemented")]
+    MethodNotInTrait { trait_name: String, impl_method: Ident },
+    #[error("Trait impl method has the wrong number of parameters")]
+    MismatchTraitImplNumParameters {
+        trait_name: String,
+        method_name: Ident,
+        expected: usize,
+        actual: usize,
+    },
+    MismatchTraitImplMethodSignature {
+        trait_name: String,
+        method_name: Ident,
+        expected: String,
+        actual: String,
+    },
 }
 
 impl DefCollectorErrorKind {
@@ -101,6 +120,59 @@ impl From<DefCollectorErrorKind> for Diagnostic {
                 "Only struct types may have implementation methods".into(),
                 span,
             ),
+            DefCollectorErrorKind::DuplicateTraitImpl {
+                trait_name,
+                typ,
+                first_span,
+                second_span,
+            } => {
+                let mut diag = Diagnostic::simple_error(
+                    format!("conflicting implementations of trait `{trait_name}` for type `{typ}`"),
+                    "first implementation found here".to_string(),
+                    first_span,
+                );
+                diag.add_secondary("second implementation found here".to_string(), second_span);
+                diag
+            }
+            DefCollectorErrorKind::MissingTraitMethod { trait_name, method_name, span } => {
+                Diagnostic::simple_error(
+                    format!("method `{method_name}` from trait `{trait_name}` is not implemented"),
+                    format!("missing `{method_name}` in implementation"),
+                    span,
+                )
+            }
+            DefCollectorErrorKind::MethodNotInTrait { trait_name, impl_method } => {
+                let method_name = &impl_method.0.contents;
+                Diagnostic::simple_error(
+                    format!("method `{method_name}` is not a member of trait `{trait_name}`"),
+                    format!("not a member of `{trait_name}`"),
+                    impl_method.span(),
+                )
+            }
+            DefCollectorErrorKind::MismatchTraitImplNumParameters {
+                trait_name,
+                method_name,
+                expected,
+                actual,
+            } => {
+                let plural = if expected == 1 { "" } else { "s" };
+                let primary_message = format!(
+                    "method `{method_name}` of trait `{trait_name}` needs {expected} parameter{plural}, but {actual} were given"
+                );
+                Diagnostic::simple_error(primary_message, String::new(), method_name.span())
+            }
+            DefCollectorErrorKind::MismatchTraitImplMethodSignature {
+                trait_name,
+                method_name,
+                expected,
+                actual,
+            } => Diagnostic::simple_error(
+                format!(
+                    "method `{method_name}` has an incompatible signature for trait `{trait_name}`"
+                ),
+                format!("expected `{expected}`, found `{actual}`"),
+                method_name.span(),
+            ),
         }
     }
 }
@@ -2,8 +2,8 @@ use crate::graph::CrateId;
 use crate::hir::def_collector::dc_crate::DefCollector;
 use crate::hir::Context;
 use crate::node_interner::{FuncId, NodeInterner};
-use crate::parser::{parse_program, ParsedModule};
-use crate::token::Attribute;
+use crate::parser::{parse_program, SortedModule};
+use crate::token::{Attribute, TestScope};
 use arena::{Arena, Index};
 use fm::{FileId, FileManager};
 use noirc_errors::FileDiagnostic;
@@ -124,16 +124,18 @@ impl CrateDefMap {
     }
 
     /// Go through all modules in this crate, and find all functions in
-    /// each module with the #[test] attribute
+    /// each module with the #[test] attribute along with their expected outcome
     pub fn get_all_test_functions<'a>(
         &'a self,
         interner: &'a NodeInterner,
-    ) -> impl Iterator<Item = FuncId> + 'a {
+    ) -> impl Iterator<Item = (FuncId, TestScope)> + 'a {
         self.modules.iter().flat_map(|(_, module)| {
-            module
-                .value_definitions()
-                .filter_map(|id| id.as_function())
-                .filter(|id| interner.function_meta(id).attributes == Some(Attribute::Test))
+            module.value_definitions().filter_map(|id| id.as_function()).filter_map(|id| {
+                match interner.function_meta(&id).attributes {
+                    Some(Attribute::Test(scope)) => Some((id, scope)),
+                    _ => None,
+                }
+            })
         })
     }
 
@@ -192,11 +194,11 @@ pub fn parse_file(
     fm: &mut FileManager,
     file_id: FileId,
     all_errors: &mut Vec<FileDiagnostic>,
-) -> ParsedModule {
+) -> SortedModule {
     let file = fm.fetch_file(file_id);
     let (program, errors) = parse_program(file.source());
     all_errors.extend(errors.into_iter().map(|error| error.in_file(file_id)));
-    program
+    program.into_sorted()
 }
 
 impl std::ops::Index<LocalModuleId> for CrateDefMap {
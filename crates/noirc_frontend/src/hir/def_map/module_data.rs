@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use fm::FileId;
 
 use crate::{
-    node_interner::{FuncId, StmtId, StructId},
+    node_interner::{EnumId, FuncId, StmtId, StructId, TraitId},
     Ident,
 };
 
@@ -65,6 +65,14 @@ impl ModuleData {
         self.declare(name, ModuleDefId::TypeId(id))
     }
 
+    pub fn declare_enum(&mut self, name: Ident, id: EnumId) -> Result<(), (Ident, Ident)> {
+        self.declare(name, ModuleDefId::EnumId(id))
+    }
+
+    pub fn declare_trait(&mut self, name: Ident, id: TraitId) -> Result<(), (Ident, Ident)> {
+        self.declare(name, ModuleDefId::TraitId(id))
+    }
+
     pub fn declare_child_module(
         &mut self,
         name: Ident,
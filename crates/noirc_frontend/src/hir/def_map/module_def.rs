@@ -1,4 +1,4 @@
-use crate::node_interner::{FuncId, StmtId, StructId};
+use crate::node_interner::{EnumId, FuncId, StmtId, StructId, TraitId};
 
 use super::ModuleId;
 
@@ -8,6 +8,8 @@ pub enum ModuleDefId {
     ModuleId(ModuleId),
     FunctionId(FuncId),
     TypeId(StructId),
+    EnumId(EnumId),
+    TraitId(TraitId),
     GlobalId(StmtId),
 }
 
@@ -26,6 +28,20 @@ impl ModuleDefId {
         }
     }
 
+    pub fn as_enum(&self) -> Option<EnumId> {
+        match self {
+            ModuleDefId::EnumId(enum_id) => Some(*enum_id),
+            _ => None,
+        }
+    }
+
+    pub fn as_trait(&self) -> Option<TraitId> {
+        match self {
+            ModuleDefId::TraitId(trait_id) => Some(*trait_id),
+            _ => None,
+        }
+    }
+
     pub fn as_global(&self) -> Option<StmtId> {
         match self {
             ModuleDefId::GlobalId(stmt_id) => Some(*stmt_id),
@@ -39,6 +55,8 @@ impl ModuleDefId {
         match self {
             ModuleDefId::FunctionId(_) => "function",
             ModuleDefId::TypeId(_) => "type",
+            ModuleDefId::EnumId(_) => "enum",
+            ModuleDefId::TraitId(_) => "trait",
             ModuleDefId::ModuleId(_) => "module",
             ModuleDefId::GlobalId(_) => "global",
         }
@@ -97,6 +115,34 @@ impl TryFromModuleDefId for StructId {
     }
 }
 
+impl TryFromModuleDefId for EnumId {
+    fn try_from(id: ModuleDefId) -> Option<Self> {
+        id.as_enum()
+    }
+
+    fn dummy_id() -> Self {
+        EnumId::dummy_id()
+    }
+
+    fn description() -> String {
+        "enum".to_string()
+    }
+}
+
+impl TryFromModuleDefId for TraitId {
+    fn try_from(id: ModuleDefId) -> Option<Self> {
+        id.as_trait()
+    }
+
+    fn dummy_id() -> Self {
+        TraitId::dummy_id()
+    }
+
+    fn description() -> String {
+        "trait".to_string()
+    }
+}
+
 impl TryFromModuleDefId for StmtId {
     fn try_from(id: ModuleDefId) -> Option<Self> {
         id.as_global()
@@ -1,3 +1,4 @@
+pub mod comptime;
 pub mod def_collector;
 pub mod def_map;
 pub mod resolution;
@@ -64,6 +64,14 @@ pub enum ResolverError {
     MutableReferenceToImmutableVariable { variable: String, span: Span },
     #[error("Mutable references to array indices are unsupported")]
     MutableReferenceToArrayElement { span: Span },
+    #[error("No such variant in enum")]
+    NoSuchVariant { variant: Ident, enum_definition: Ident },
+    #[error("Incorrect amount of fields given to enum variant")]
+    IncorrectVariantFieldCount { span: Span, variant: String, expected: usize, actual: usize },
+    #[error("`{keyword}` is only allowed within a loop")]
+    JumpOutsideOfLoop { keyword: &'static str, span: Span },
+    #[error("While loops in constrained functions must have a maximum number of iterations")]
+    UnboundedWhileLoop { span: Span },
 }
 
 impl ResolverError {
@@ -268,6 +276,39 @@ impl From<ResolverError> for Diagnostic {
             ResolverError::MutableReferenceToArrayElement { span } => {
                 Diagnostic::simple_error("Mutable references to array elements are currently unsupported".into(), "Try storing the element in a fresh variable first".into(), span)
             },
+            ResolverError::NoSuchVariant { variant, enum_definition } => {
+                let mut error = Diagnostic::simple_error(
+                    format!("no variant named {variant} in enum {enum_definition}"),
+                    String::new(),
+                    variant.span(),
+                );
+
+                error.add_secondary(
+                    format!("{enum_definition} defined here with no {variant} variant"),
+                    enum_definition.span(),
+                );
+                error
+            }
+            ResolverError::IncorrectVariantFieldCount { span, variant, expected, actual } => {
+                let expected_plural = if expected == 1 { "" } else { "s" };
+                let actual_plural = if actual == 1 { "was" } else { "were" };
+
+                Diagnostic::simple_error(
+                    format!("The variant {variant} has {expected} field{expected_plural} but {actual} {actual_plural} given here"),
+                    "Incorrect number of fields".into(),
+                    span,
+                )
+            }
+            ResolverError::JumpOutsideOfLoop { keyword, span } => Diagnostic::simple_error(
+                format!("`{keyword}` is only allowed within a loop"),
+                String::new(),
+                span,
+            ),
+            ResolverError::UnboundedWhileLoop { span } => Diagnostic::simple_error(
+                "While loops in constrained functions must have a maximum number of iterations".into(),
+                "Try adding a bound with `while condition max N { .. }`".into(),
+                span,
+            ),
         }
     }
 }
@@ -13,19 +13,21 @@
 // XXX: Resolver does not check for unused functions
 use crate::hir_def::expr::{
     HirArrayLiteral, HirBinaryOp, HirBlockExpression, HirCallExpression, HirCastExpression,
-    HirConstructorExpression, HirExpression, HirForExpression, HirIdent, HirIfExpression,
-    HirIndexExpression, HirInfixExpression, HirLambda, HirLiteral, HirMemberAccess,
-    HirMethodCallExpression, HirPrefixExpression,
+    HirConstructorExpression, HirEnumConstructorExpression, HirExpression, HirForExpression,
+    HirIdent, HirIfExpression, HirIndexExpression, HirInfixExpression, HirLambda, HirLiteral,
+    HirMatchExpression, HirMatchPattern, HirMemberAccess, HirMethodCallExpression,
+    HirPrefixExpression, HirWhileExpression,
 };
 use crate::token::Attribute;
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
 use crate::graph::CrateId;
+use crate::hir::comptime::{ArrayLength, DeferredArrayLength, Interpreter};
 use crate::hir::def_map::{ModuleDefId, TryFromModuleDefId, MAIN_FUNCTION};
 use crate::hir_def::stmt::{HirAssignStatement, HirLValue, HirPattern};
 use crate::node_interner::{
-    DefinitionId, DefinitionKind, ExprId, FuncId, NodeInterner, StmtId, StructId,
+    DefinitionId, DefinitionKind, ExprId, FuncId, NodeInterner, StmtId, StructId, TraitId,
 };
 use crate::{
     hir::{def_map::CrateDefMap, resolution::path_resolver::PathResolver},
@@ -33,9 +35,9 @@ use crate::{
     Statement,
 };
 use crate::{
-    ArrayLiteral, ContractFunctionType, Generics, LValue, NoirStruct, Path, Pattern, Shared,
-    StructType, Type, TypeBinding, TypeVariable, UnaryOp, UnresolvedGenerics, UnresolvedType,
-    UnresolvedTypeExpression, ERROR_IDENT,
+    ArrayLiteral, ContractFunctionType, EnumType, Generics, LValue, MatchPattern, NoirEnum,
+    NoirStruct, Path, Pattern, Shared, StructType, Type, TypeBinding, TypeVariable, UnaryOp,
+    UnresolvedGenerics, UnresolvedType, UnresolvedTypeExpression, ERROR_IDENT,
 };
 use fm::FileId;
 use iter_extended::vecmap;
@@ -47,6 +49,7 @@ use crate::hir::scope::{
 use crate::hir_def::{
     function::{FuncMeta, HirFunction, Param},
     stmt::{HirConstrainStatement, HirLetStatement, HirStatement},
+    traits::TraitConstraint,
 };
 
 use super::errors::ResolverError;
@@ -74,18 +77,36 @@ pub struct Resolver<'a> {
     /// Set to the current type if we're resolving an impl
     self_type: Option<Type>,
 
+    /// Set to the current trait if we're resolving the methods declared within a trait
+    trait_id: Option<TraitId>,
+
     /// Contains a mapping of the current struct or functions's generics to
     /// unique type variables if we're resolving a struct. Empty otherwise.
     /// This is a Vec rather than a map to preserve the order a functions generics
     /// were declared in.
     generics: Vec<(Rc<String>, TypeVariable, Span)>,
 
-    /// Lambdas share the function scope of the function they're defined in,
-    /// so to identify whether they use any variables from the parent function
-    /// we keep track of the scope index a variable is declared in. When a lambda
-    /// is declared we push a scope and set this lambda_index to the scope index.
-    /// Any variable from a scope less than that must be from the parent function.
-    lambda_index: usize,
+    /// The lambdas currently being resolved, innermost last.
+    lambda_stack: Vec<LambdaContext>,
+
+    /// The number of loops around the expression being resolved within the current function
+    /// or lambda, which `break` and `continue` require to be non-zero.
+    loop_depth: usize,
+
+    /// True if the function being resolved is unconstrained, in which case its while loops
+    /// need not be bounded.
+    in_unconstrained_function: bool,
+}
+
+/// Lambdas share the function scope of the function they're defined in,
+/// so to identify whether they use any variables from the parent function
+/// we keep track of the scope index a variable is declared in. When a lambda
+/// is declared we push a scope and record its index in the lambda's context.
+/// Any variable from a scope less than that must be from the parent function
+/// and is captured by the lambda.
+struct LambdaContext {
+    captures: Vec<HirIdent>,
+    scope_index: usize,
 }
 
 /// ResolverMetas are tagged onto each definition to track how many times they are used
@@ -109,9 +130,12 @@ impl<'a> Resolver<'a> {
             scopes: ScopeForest::default(),
             interner,
             self_type: None,
+            trait_id: None,
             generics: Vec::new(),
             errors: Vec::new(),
-            lambda_index: 0,
+            lambda_stack: Vec::new(),
+            loop_depth: 0,
+            in_unconstrained_function: false,
             file,
         }
     }
@@ -120,12 +144,40 @@ impl<'a> Resolver<'a> {
         self.self_type = self_type;
     }
 
+    pub fn set_trait_id(&mut self, trait_id: Option<TraitId>) {
+        self.trait_id = trait_id;
+    }
+
     fn push_err(&mut self, err: ResolverError) {
         self.errors.push(err);
     }
 
-    fn current_lambda_index(&self) -> usize {
-        self.scopes.current_scope_index()
+    /// Records a variable declared in the scope at `scope_index` as captured by each lambda
+    /// being resolved which it is declared outside of.
+    ///
+    /// Closures capture variables by value, so capturing a mutable variable is an error
+    /// as any later mutation would not be visible to the closure.
+    fn capture_variable(&mut self, ident: HirIdent, scope_index: usize) {
+        let definition = self.interner.definition(ident.id);
+        if !matches!(definition.kind, DefinitionKind::Local(_)) {
+            return;
+        }
+        let mutable = definition.mutable;
+
+        let mut is_captured = false;
+        for lambda in self.lambda_stack.iter_mut().rev() {
+            if scope_index >= lambda.scope_index {
+                break;
+            }
+            if !lambda.captures.iter().any(|capture| capture.id == ident.id) {
+                lambda.captures.push(ident);
+            }
+            is_captured = true;
+        }
+
+        if is_captured && mutable {
+            self.push_err(ResolverError::CapturedMutableVariable { span: ident.location.span });
+        }
     }
 
     /// Resolving a function involves interning the metadata
@@ -186,6 +238,19 @@ impl<'a> Resolver<'a> {
         ret
     }
 
+    fn in_loop<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.loop_depth += 1;
+        let ret = f(self);
+        self.loop_depth -= 1;
+        ret
+    }
+
+    fn check_in_loop(&mut self, keyword: &'static str, span: Span) {
+        if self.loop_depth == 0 {
+            self.push_err(ResolverError::JumpOutsideOfLoop { keyword, span });
+        }
+    }
+
     fn add_variable_decl(
         &mut self,
         name: Ident,
@@ -208,8 +273,13 @@ impl<'a> Resolver<'a> {
             return self.add_global_variable_decl(name, definition);
         }
 
-        let id = self.interner.push_definition(name.0.contents.clone(), mutable, definition);
         let location = Location::new(name.span(), self.file);
+        let id = self.interner.push_definition(
+            name.0.contents.clone(),
+            mutable,
+            definition,
+            Some(location),
+        );
         let ident = HirIdent { location, id };
         let resolver_meta = ResolverMeta { num_times_used: 0, ident, warn_if_unused };
 
@@ -252,8 +322,13 @@ impl<'a> Resolver<'a> {
             ident = hir_let_stmt.ident();
             resolver_meta = ResolverMeta { num_times_used: 0, ident, warn_if_unused: true };
         } else {
-            let id = self.interner.push_definition(name.0.contents.clone(), false, definition);
             let location = Location::new(name.span(), self.file);
+            let id = self.interner.push_definition(
+                name.0.contents.clone(),
+                false,
+                definition,
+                Some(location),
+            );
             ident = HirIdent { location, id };
             resolver_meta = ResolverMeta { num_times_used: 0, ident, warn_if_unused: true };
         }
@@ -291,10 +366,12 @@ impl<'a> Resolver<'a> {
         let variable = scope_tree.find(&name.0.contents);
 
         let location = Location::new(name.span(), self.file);
-        if let Some((variable_found, _)) = variable {
+        if let Some((variable_found, scope_index)) = variable {
             variable_found.num_times_used += 1;
             let id = variable_found.ident.id;
-            Ok(HirIdent { location, id })
+            let ident = HirIdent { location, id };
+            self.capture_variable(ident, scope_index);
+            Ok(ident)
         } else {
             Err(ResolverError::VariableNotDeclared {
                 name: name.0.contents.clone(),
@@ -310,6 +387,7 @@ impl<'a> Resolver<'a> {
                 HirFunction::empty()
             }
             FunctionKind::Normal => {
+                self.in_unconstrained_function = func.def.is_unconstrained;
                 let expr_id = self.intern_block(func.def.body);
                 self.interner.push_expr_location(expr_id, func.def.span, self.file);
                 HirFunction::unchecked_from_expr(expr_id)
@@ -343,10 +421,11 @@ impl<'a> Resolver<'a> {
             UnresolvedType::Tuple(fields) => {
                 Type::Tuple(vecmap(fields, |field| self.resolve_type_inner(field, new_variables)))
             }
-            UnresolvedType::Function(args, ret) => {
+            UnresolvedType::Function(args, ret, env) => {
                 let args = vecmap(args, |arg| self.resolve_type_inner(arg, new_variables));
                 let ret = Box::new(self.resolve_type_inner(*ret, new_variables));
-                Type::Function(args, ret)
+                let env = Box::new(self.resolve_type_inner(*env, new_variables));
+                Type::Function(args, ret, env)
             }
             UnresolvedType::Vec(mut args, span) => {
                 let arg = if args.len() != 1 {
@@ -400,29 +479,58 @@ impl<'a> Resolver<'a> {
         }
 
         let span = path.span();
+        if let Some(enum_type) = self.try_lookup_enum(&path) {
+            let name = enum_type.borrow().to_string();
+            let expected_generic_count = enum_type.borrow().generics.len();
+            let args =
+                self.resolve_generic_args(args, name, expected_generic_count, span, new_variables);
+            return Type::Enum(enum_type, args);
+        }
+
         match self.lookup_struct_or_error(path) {
             Some(struct_type) => {
-                let mut args = vecmap(args, |arg| self.resolve_type_inner(arg, new_variables));
+                let name = struct_type.borrow().to_string();
                 let expected_generic_count = struct_type.borrow().generics.len();
-
-                if args.len() != expected_generic_count {
-                    self.push_err(ResolverError::IncorrectGenericCount {
-                        span,
-                        struct_type: struct_type.borrow().to_string(),
-                        actual: args.len(),
-                        expected: expected_generic_count,
-                    });
-
-                    // Fix the generic count so we can continue typechecking
-                    args.resize_with(expected_generic_count, || Type::Error);
-                }
-
+                let args = self.resolve_generic_args(
+                    args,
+                    name,
+                    expected_generic_count,
+                    span,
+                    new_variables,
+                );
                 Type::Struct(struct_type, args)
             }
             None => Type::Error,
         }
     }
 
+    /// Resolve the generic arguments given to a struct or enum type, issuing an error
+    /// if the amount of arguments differs from the amount the type expects.
+    fn resolve_generic_args(
+        &mut self,
+        args: Vec<UnresolvedType>,
+        type_name: String,
+        expected_generic_count: usize,
+        span: Span,
+        new_variables: &mut Generics,
+    ) -> Vec<Type> {
+        let mut args = vecmap(args, |arg| self.resolve_type_inner(arg, new_variables));
+
+        if args.len() != expected_generic_count {
+            self.push_err(ResolverError::IncorrectGenericCount {
+                span,
+                struct_type: type_name,
+                actual: args.len(),
+                expected: expected_generic_count,
+            });
+
+            // Fix the generic count so we can continue typechecking
+            args.resize_with(expected_generic_count, || Type::Error);
+        }
+
+        args
+    }
+
     fn lookup_generic_or_global_type(&mut self, path: &Path) -> Option<Type> {
         if path.segments.len() == 1 {
             let name = &path.last_segment().0.contents;
@@ -434,7 +542,8 @@ impl<'a> Resolver<'a> {
         // If we cannot find a local generic of the same name, try to look up a global
         match self.path_resolver.resolve(self.def_maps, path.clone()) {
             Ok(ModuleDefId::GlobalId(id)) => {
-                Some(Type::Constant(self.eval_global_as_array_length(id)))
+                let expression = self.interner.let_statement(&id).expression;
+                Some(self.eval_array_length(expression))
             }
             _ => None,
         }
@@ -469,14 +578,35 @@ impl<'a> Resolver<'a> {
                 })
             }
             UnresolvedTypeExpression::Constant(int, _) => Type::Constant(int),
-            UnresolvedTypeExpression::BinaryOperation(lhs, op, rhs, _) => {
+            UnresolvedTypeExpression::BinaryOperation(lhs, op, rhs, span) => {
                 let (lhs_span, rhs_span) = (lhs.span(), rhs.span());
                 let lhs = self.convert_expression_type(*lhs);
                 let rhs = self.convert_expression_type(*rhs);
 
                 match (lhs, rhs) {
-                    (Type::Constant(lhs), Type::Constant(rhs)) => {
-                        Type::Constant(op.function()(lhs, rhs))
+                    (Type::Constant(lhs), Type::Constant(rhs)) => match op.function()(lhs, rhs) {
+                        Some(length) => Type::Constant(length),
+                        // The division by zero is reported once the length is evaluated
+                        None if rhs == 0 => {
+                            let location = Location::new(span, self.file);
+                            let (lhs, rhs) = (Type::Constant(lhs), Type::Constant(rhs));
+                            self.defer_array_length(
+                                ArrayLength::BinaryOperation(lhs, op, rhs),
+                                location,
+                            )
+                        }
+                        None => {
+                            self.push_err(ResolverError::IntegerTooLarge { span });
+                            Type::Error
+                        }
+                    },
+                    // Either side is a length which will only be known once evaluated
+                    (lhs @ Type::TypeVariable(_), rhs) | (lhs, rhs @ Type::TypeVariable(_)) => {
+                        let location = Location::new(span, self.file);
+                        self.defer_array_length(
+                            ArrayLength::BinaryOperation(lhs, op, rhs),
+                            location,
+                        )
                     }
                     (lhs, _) => {
                         let span =
@@ -486,6 +616,10 @@ impl<'a> Resolver<'a> {
                     }
                 }
             }
+            UnresolvedTypeExpression::Expression(expr) => {
+                let expr = self.resolve_expression(*expr);
+                self.eval_array_length(expr)
+            }
         }
     }
 
@@ -582,6 +716,22 @@ impl<'a> Resolver<'a> {
         (generics, fields, self.errors)
     }
 
+    pub fn resolve_enum_variants(
+        mut self,
+        unresolved: NoirEnum,
+    ) -> (Generics, Vec<(Ident, Vec<Type>)>, Vec<ResolverError>) {
+        let generics = self.add_generics(&unresolved.generics);
+
+        // Check whether the enum definition has globals in the local module and add them to the scope
+        self.resolve_local_globals();
+
+        let variants = vecmap(unresolved.variants, |(name, fields)| {
+            (name, vecmap(fields, |typ| self.resolve_type(typ)))
+        });
+
+        (generics, variants, self.errors)
+    }
+
     fn resolve_local_globals(&mut self) {
         for (stmt_id, global_info) in self.interner.get_all_globals() {
             if global_info.local_id == self.path_resolver.local_module_id() {
@@ -644,13 +794,15 @@ impl<'a> Resolver<'a> {
             self.push_err(ResolverError::DistinctNotAllowed { ident: func.name_ident().clone() });
         }
 
-        if attributes == Some(Attribute::Test) && !parameters.is_empty() {
+        if matches!(attributes, Some(Attribute::Test(_))) && !parameters.is_empty() {
             self.push_err(ResolverError::TestFunctionHasParameters {
                 span: func.name_ident().span(),
             });
         }
 
-        let mut typ = Type::Function(parameter_types, return_type);
+        let trait_constraints = self.resolve_trait_constraints(func);
+
+        let mut typ = Type::Function(parameter_types, return_type, Box::new(Type::Unit));
 
         if !generics.is_empty() {
             typ = Type::Forall(generics, Box::new(typ));
@@ -670,7 +822,28 @@ impl<'a> Resolver<'a> {
             return_visibility: func.def.return_visibility,
             return_distinctness: func.def.return_distinctness,
             has_body: !func.def.body.is_empty(),
+            trait_id: self.trait_id,
+            trait_constraints,
+        }
+    }
+
+    /// Resolve each bound in the function's where clause. Methods declared within a trait are
+    /// additionally bounded by `Self: Trait` so that they may call the trait's other methods.
+    fn resolve_trait_constraints(&mut self, func: &NoirFunction) -> Vec<TraitConstraint> {
+        let mut constraints = Vec::new();
+
+        for constraint in func.def.where_clause.iter().cloned() {
+            let typ = self.resolve_type(constraint.typ);
+            if let Some(trait_id) = self.lookup_trait_or_error(constraint.trait_name) {
+                constraints.push(TraitConstraint { typ, trait_id });
+            }
         }
+
+        if let (Some(trait_id), Some(self_type)) = (self.trait_id, &self.self_type) {
+            constraints.push(TraitConstraint { typ: self_type.clone(), trait_id });
+        }
+
+        constraints
     }
 
     /// True if the 'pub' keyword is allowed on parameters in this function
@@ -767,11 +940,12 @@ impl<'a> Resolver<'a> {
                     Self::find_numeric_generics_in_type(field, found);
                 }
             }
-            Type::Function(parameters, return_type) => {
+            Type::Function(parameters, return_type, env) => {
                 for parameter in parameters {
                     Self::find_numeric_generics_in_type(parameter, found);
                 }
                 Self::find_numeric_generics_in_type(return_type, found);
+                Self::find_numeric_generics_in_type(env, found);
             }
             Type::Struct(struct_type, generics) => {
                 for (i, generic) in generics.iter().enumerate() {
@@ -784,19 +958,37 @@ impl<'a> Resolver<'a> {
                     }
                 }
             }
+            Type::Enum(enum_type, generics) => {
+                for (i, generic) in generics.iter().enumerate() {
+                    if let Type::NamedGeneric(type_variable, name) = generic {
+                        if enum_type.borrow().generic_is_numeric(i) {
+                            found.insert(name.to_string(), type_variable.clone());
+                        }
+                    } else {
+                        Self::find_numeric_generics_in_type(generic, found);
+                    }
+                }
+            }
             Type::Vec(element) => Self::find_numeric_generics_in_type(element, found),
             Type::MutableReference(element) => Self::find_numeric_generics_in_type(element, found),
         }
     }
 
-    pub fn resolve_global_let(&mut self, let_stmt: crate::LetStatement) -> HirStatement {
-        let expression = self.resolve_expression(let_stmt.expression);
-        let definition = DefinitionKind::Global(expression);
+    /// Resolves a global declared beforehand by `declare_globals`, whose definition and
+    /// placeholder expression are kept so that the globals referring to it remain valid.
+    pub fn resolve_global_let(
+        &mut self,
+        let_stmt: crate::LetStatement,
+        global: StmtId,
+    ) -> HirStatement {
+        let declared = self.interner.let_statement(&global);
+        let expression = self.resolve_expression_kind(let_stmt.expression);
+        self.interner.replace_expr(&declared.expression, expression);
 
         HirStatement::Let(HirLetStatement {
-            pattern: self.resolve_pattern(let_stmt.pattern, definition),
+            pattern: declared.pattern,
             r#type: self.resolve_type(let_stmt.r#type),
-            expression,
+            expression: declared.expression,
         })
     }
 
@@ -817,6 +1009,14 @@ impl<'a> Resolver<'a> {
             }
             Statement::Expression(expr) => HirStatement::Expression(self.resolve_expression(expr)),
             Statement::Semi(expr) => HirStatement::Semi(self.resolve_expression(expr)),
+            Statement::Break(span) => {
+                self.check_in_loop("break", span);
+                HirStatement::Break
+            }
+            Statement::Continue(span) => {
+                self.check_in_loop("continue", span);
+                HirStatement::Continue
+            }
             Statement::Assign(assign_stmt) => {
                 let identifier = self.resolve_lvalue(assign_stmt.lvalue);
                 let expression = self.resolve_expression(assign_stmt.expression);
@@ -854,7 +1054,15 @@ impl<'a> Resolver<'a> {
     }
 
     pub fn resolve_expression(&mut self, expr: Expression) -> ExprId {
-        let hir_expr = match expr.kind {
+        let span = expr.span;
+        let hir_expr = self.resolve_expression_kind(expr);
+        let expr_id = self.interner.push_expr(hir_expr);
+        self.interner.push_expr_location(expr_id, span, self.file);
+        expr_id
+    }
+
+    fn resolve_expression_kind(&mut self, expr: Expression) -> HirExpression {
+        match expr.kind {
             ExpressionKind::Literal(literal) => HirExpression::Literal(match literal {
                 Literal::Bool(b) => HirLiteral::Bool(b),
                 Literal::Array(ArrayLiteral::Standard(elements)) => {
@@ -862,14 +1070,7 @@ impl<'a> Resolver<'a> {
                     HirLiteral::Array(HirArrayLiteral::Standard(elements))
                 }
                 Literal::Array(ArrayLiteral::Repeated { repeated_element, length }) => {
-                    let span = length.span;
-                    let length = UnresolvedTypeExpression::from_expr(*length, span).unwrap_or_else(
-                        |error| {
-                            self.errors.push(ResolverError::ParserError(Box::new(error)));
-                            UnresolvedTypeExpression::Constant(0, span)
-                        },
-                    );
-
+                    let length = UnresolvedTypeExpression::from_expr(*length);
                     let length = self.convert_expression_type(length);
                     let repeated_element = self.resolve_expression(*repeated_element);
 
@@ -883,8 +1084,20 @@ impl<'a> Resolver<'a> {
                 // Otherwise, then it is referring to an Identifier
                 // This lookup allows support of such statements: let x = foo::bar::SOME_GLOBAL + 10;
                 // If the expression is a singular indent, we search the resolver's current scope as normal.
-                let hir_ident = self.get_ident_from_path(path);
-                HirExpression::Ident(hir_ident)
+                // Paths to enum variants without any fields, such as `Option::None`, construct the variant.
+                match self.lookup_enum_variant(&path) {
+                    Ok(Some((enum_type, variant_index))) => self.resolve_enum_constructor(
+                        enum_type,
+                        variant_index,
+                        Vec::new(),
+                        expr.span,
+                    ),
+                    Ok(None) => HirExpression::Ident(self.get_ident_from_path(path)),
+                    Err(error) => {
+                        self.push_err(error);
+                        HirExpression::Error
+                    }
+                }
             }
             ExpressionKind::Prefix(prefix) => {
                 let operator = prefix.operator;
@@ -909,11 +1122,35 @@ impl<'a> Resolver<'a> {
                 })
             }
             ExpressionKind::Call(call_expr) => {
-                // Get the span and name of path for error reporting
-                let func = self.resolve_expression(*call_expr.func);
-                let arguments = vecmap(call_expr.arguments, |arg| self.resolve_expression(arg));
-                let location = Location::new(expr.span, self.file);
-                HirExpression::Call(HirCallExpression { func, arguments, location })
+                let variant = match &call_expr.func.kind {
+                    ExpressionKind::Variable(path) => self.lookup_enum_variant(path),
+                    _ => Ok(None),
+                };
+
+                match variant {
+                    Ok(Some((enum_type, variant_index))) => {
+                        let arguments =
+                            vecmap(call_expr.arguments, |arg| self.resolve_expression(arg));
+                        self.resolve_enum_constructor(
+                            enum_type,
+                            variant_index,
+                            arguments,
+                            expr.span,
+                        )
+                    }
+                    Ok(None) => {
+                        // Get the span and name of path for error reporting
+                        let func = self.resolve_expression(*call_expr.func);
+                        let arguments =
+                            vecmap(call_expr.arguments, |arg| self.resolve_expression(arg));
+                        let location = Location::new(expr.span, self.file);
+                        HirExpression::Call(HirCallExpression { func, arguments, location })
+                    }
+                    Err(error) => {
+                        self.push_err(error);
+                        HirExpression::Error
+                    }
+                }
             }
             ExpressionKind::MethodCall(call_expr) => {
                 let method = call_expr.method_name;
@@ -945,7 +1182,7 @@ impl<'a> Resolver<'a> {
                         false,
                         DefinitionKind::Local(None),
                     );
-                    (decl, this.resolve_expression(block))
+                    (decl, this.in_loop(|this| this.resolve_expression(block)))
                 });
 
                 HirExpression::For(HirForExpression {
@@ -955,11 +1192,38 @@ impl<'a> Resolver<'a> {
                     identifier,
                 })
             }
+            ExpressionKind::While(while_expr) => {
+                // Lambdas are always compiled as constrained functions
+                let unconstrained = self.in_unconstrained_function && self.lambda_stack.is_empty();
+                if while_expr.max_iterations.is_none() && !unconstrained {
+                    let span = while_expr.condition.span;
+                    self.push_err(ResolverError::UnboundedWhileLoop { span });
+                }
+
+                let condition = self.resolve_expression(while_expr.condition);
+                let max_iterations =
+                    while_expr.max_iterations.map(|max| self.convert_expression_type(max));
+                let block = self.in_loop(|this| this.resolve_expression(while_expr.block));
+                HirExpression::While(HirWhileExpression { condition, max_iterations, block })
+            }
             ExpressionKind::If(if_expr) => HirExpression::If(HirIfExpression {
                 condition: self.resolve_expression(if_expr.condition),
                 consequence: self.resolve_expression(if_expr.consequence),
                 alternative: if_expr.alternative.map(|e| self.resolve_expression(e)),
             }),
+            ExpressionKind::Match(match_expr) => {
+                let expression = self.resolve_expression(match_expr.expression);
+
+                // Each rule is resolved in its own scope since the pattern may bind variables
+                let rules = vecmap(match_expr.rules, |(pattern, branch)| {
+                    self.in_new_scope(|this| {
+                        let pattern = this.resolve_match_pattern(pattern);
+                        (pattern, this.resolve_expression(branch))
+                    })
+                });
+
+                HirExpression::Match(HirMatchExpression { expression, rules })
+            }
             ExpressionKind::Index(indexed_expr) => HirExpression::Index(HirIndexExpression {
                 collection: self.resolve_expression(indexed_expr.collection),
                 index: self.resolve_expression(indexed_expr.index),
@@ -1004,8 +1268,11 @@ impl<'a> Resolver<'a> {
             // We must stay in the same function scope as the parent function to allow for closures
             // to capture variables. This is currently limited to immutable variables.
             ExpressionKind::Lambda(lambda) => self.in_new_scope(|this| {
-                let new_index = this.current_lambda_index();
-                let old_index = std::mem::replace(&mut this.lambda_index, new_index);
+                let scope_index = this.scopes.current_scope_index();
+                this.lambda_stack.push(LambdaContext { captures: Vec::new(), scope_index });
+
+                // The body of a lambda is not part of any loop it is defined in
+                let loop_depth = std::mem::take(&mut this.loop_depth);
 
                 let parameters = vecmap(lambda.parameters, |(pattern, typ)| {
                     let parameter = DefinitionKind::Local(None);
@@ -1014,15 +1281,12 @@ impl<'a> Resolver<'a> {
 
                 let return_type = this.resolve_inferred_type(lambda.return_type);
                 let body = this.resolve_expression(lambda.body);
+                this.loop_depth = loop_depth;
 
-                this.lambda_index = old_index;
-                HirExpression::Lambda(HirLambda { parameters, return_type, body })
+                let captures = this.lambda_stack.pop().unwrap().captures;
+                HirExpression::Lambda(HirLambda { parameters, return_type, body, captures })
             }),
-        };
-
-        let expr_id = self.interner.push_expr(hir_expr);
-        self.interner.push_expr_location(expr_id, expr.span, self.file);
-        expr_id
+        }
     }
 
     fn resolve_pattern(&mut self, pattern: Pattern, definition: DefinitionKind) -> HirPattern {
@@ -1092,6 +1356,83 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    fn resolve_match_pattern(&mut self, pattern: MatchPattern) -> HirMatchPattern {
+        match pattern {
+            MatchPattern::Variant(path, fields, span) => {
+                let variant = match self.lookup_enum_variant(&path) {
+                    Ok(Some(variant)) => Some(variant),
+                    Ok(None) => {
+                        let expected = "enum variant".into();
+                        let got = path.to_string();
+                        self.push_err(ResolverError::Expected { span: path.span(), expected, got });
+                        None
+                    }
+                    Err(error) => {
+                        self.push_err(error);
+                        None
+                    }
+                };
+
+                let actual = fields.len();
+                let fields = vecmap(fields, |field| {
+                    self.resolve_pattern(field, DefinitionKind::Local(None))
+                });
+
+                match variant {
+                    Some((enum_type, variant_index)) => {
+                        self.check_variant_field_count(&enum_type, variant_index, actual, span);
+                        let generics = enum_type.borrow().instantiate(self.interner);
+                        let typ = Type::Enum(enum_type, generics);
+                        HirMatchPattern::Variant(typ, variant_index, fields, span)
+                    }
+                    // The fields are still resolved on error so that any variables they
+                    // define do not issue further errors when used within the branch.
+                    None => HirMatchPattern::Variant(Type::Error, 0, fields, span),
+                }
+            }
+            MatchPattern::Catchall(pattern) => HirMatchPattern::Catchall(
+                self.resolve_pattern(pattern, DefinitionKind::Local(None)),
+            ),
+        }
+    }
+
+    fn resolve_enum_constructor(
+        &mut self,
+        enum_type: Shared<EnumType>,
+        variant_index: usize,
+        arguments: Vec<ExprId>,
+        span: Span,
+    ) -> HirExpression {
+        self.check_variant_field_count(&enum_type, variant_index, arguments.len(), span);
+        let enum_generics = enum_type.borrow().instantiate(self.interner);
+
+        HirExpression::EnumConstructor(HirEnumConstructorExpression {
+            r#type: enum_type,
+            enum_generics,
+            variant_index,
+            arguments,
+        })
+    }
+
+    fn check_variant_field_count(
+        &mut self,
+        enum_type: &Shared<EnumType>,
+        variant_index: usize,
+        actual: usize,
+        span: Span,
+    ) {
+        let expected = enum_type.borrow().variant_field_count(variant_index);
+        if actual != expected {
+            let variant = enum_type.borrow().variant_name(variant_index).to_string();
+            self.push_err(ResolverError::IncorrectVariantFieldCount {
+                span,
+                variant,
+                expected,
+                actual,
+            });
+        }
+    }
+
     /// Resolve all the fields of a struct constructor expression.
     /// Ensures all fields are present, none are repeated, and all
     /// are part of the struct.
@@ -1183,6 +1524,59 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    /// Returns the enum the given path refers to, if any. No error is issued
+    /// if the path does not refer to an enum.
+    fn try_lookup_enum(&mut self, path: &Path) -> Option<Shared<EnumType>> {
+        match self.resolve_path(path.clone()) {
+            Ok(ModuleDefId::EnumId(enum_id)) => Some(self.interner.get_enum(enum_id)),
+            _ => None,
+        }
+    }
+
+    /// Checks whether the given path refers to a variant of an enum, e.g. `Option::Some`,
+    /// returning the enum and the index of the variant if so. Ok(None) is returned for
+    /// any path whose prefix does not name an enum.
+    fn lookup_enum_variant(
+        &mut self,
+        path: &Path,
+    ) -> Result<Option<(Shared<EnumType>, usize)>, ResolverError> {
+        if path.segments.len() < 2 {
+            return Ok(None);
+        }
+
+        let mut enum_path = path.clone();
+        let variant = enum_path.pop();
+
+        let is_self_type = enum_path.as_ident().map_or(false, |i| i == SELF_TYPE_NAME);
+        let enum_type = match &self.self_type {
+            Some(Type::Enum(enum_type, _)) if is_self_type => enum_type.clone(),
+            _ => match self.try_lookup_enum(&enum_path) {
+                Some(enum_type) => enum_type,
+                None => return Ok(None),
+            },
+        };
+
+        let variant_index = enum_type.borrow().variant_index(&variant.0.contents);
+        match variant_index {
+            Some(index) => Ok(Some((enum_type, index))),
+            None => {
+                let enum_definition = enum_type.borrow().name.clone();
+                Err(ResolverError::NoSuchVariant { variant, enum_definition })
+            }
+        }
+    }
+
+    /// Lookup a given trait by name.
+    pub fn lookup_trait_or_error(&mut self, path: Path) -> Option<TraitId> {
+        match self.lookup(path) {
+            Ok(trait_id) => Some(trait_id),
+            Err(error) => {
+                self.push_err(error);
+                None
+            }
+        }
+    }
+
     /// Looks up a given type by name.
     /// This will also instantiate any struct types found.
     fn lookup_type_or_error(&mut self, path: Path) -> Option<Type> {
@@ -1221,39 +1615,37 @@ impl<'a> Resolver<'a> {
         self.interner.push_expr(hir_block)
     }
 
-    fn eval_global_as_array_length(&mut self, global: StmtId) -> u64 {
-        let stmt = match self.interner.statement(&global) {
-            HirStatement::Let(let_expr) => let_expr,
-            other => {
-                dbg!(other);
-                return 0;
+    /// Evaluates an array length, deferring its evaluation if it depends on globals
+    /// or functions which aren't resolved yet.
+    fn eval_array_length(&mut self, length: ExprId) -> Type {
+        match Interpreter::new(self.interner).evaluate(length) {
+            Ok(value) => match value.to_array_length() {
+                Some(length) => Type::Constant(length),
+                None if value.is_too_large_for_array_length() => {
+                    let span = self.interner.expr_span(&length);
+                    self.push_err(ResolverError::IntegerTooLarge { span });
+                    Type::Error
+                }
+                None => {
+                    let span = self.interner.expr_span(&length);
+                    self.push_err(ResolverError::InvalidArrayLengthExpr { span });
+                    Type::Error
+                }
+            },
+            Err(_) => {
+                let location = self.interner.expr_location(&length);
+                self.defer_array_length(ArrayLength::Expression(length), location)
             }
-        };
-
-        let length = stmt.expression;
-        let span = self.interner.expr_span(&length);
-        let result = self.try_eval_array_length_id(length, span);
-
-        match result.map(|length| length.try_into()) {
-            Ok(Ok(length_value)) => return length_value,
-            Ok(Err(_cast_err)) => self.push_err(ResolverError::IntegerTooLarge { span }),
-            Err(Some(error)) => self.push_err(error),
-            Err(None) => (),
         }
-        0
     }
 
-    fn try_eval_array_length_id(
-        &self,
-        rhs: ExprId,
-        span: Span,
-    ) -> Result<u128, Option<ResolverError>> {
-        match self.interner.expression(&rhs) {
-            HirExpression::Literal(HirLiteral::Integer(int)) => {
-                int.try_into_u128().ok_or(Some(ResolverError::IntegerTooLarge { span }))
-            }
-            _other => Err(Some(ResolverError::InvalidArrayLengthExpr { span })),
-        }
+    fn defer_array_length(&mut self, length: ArrayLength, location: Location) -> Type {
+        let type_variable =
+            Shared::new(TypeBinding::Unbound(self.interner.next_type_variable_id()));
+        let deferred =
+            DeferredArrayLength { length, type_variable: type_variable.clone(), location };
+        self.interner.push_deferred_array_length(deferred);
+        Type::TypeVariable(type_variable)
     }
 
     fn in_contract(&self) -> bool {
@@ -1348,7 +1740,7 @@ mod test {
         );
 
         let mut errors = Vec::new();
-        for func in program.functions {
+        for func in program.into_sorted().functions {
             let id = interner.push_fn(HirFunction::empty());
             interner.push_function_definition(func.name().to_string(), id);
             let resolver = Resolver::new(&mut interner, &path_resolver, &def_maps, file);
@@ -1524,6 +1916,91 @@ mod test {
         assert!(errors.is_empty());
     }
 
+    #[test]
+    fn resolve_closures_capturing_variables() {
+        let src = r#"
+            fn main(x : Field) {
+                let y = x + 1;
+                let add = |z| {
+                    let nested = |w| w + x + y;
+                    nested(z)
+                };
+                assert(add(x) == 3);
+            }
+        "#;
+
+        let errors = resolve_src_code(src, vec!["main"]);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn resolve_closure_capturing_mutable_variable() {
+        let src = r#"
+            fn main(x : Field) {
+                let mut y = x;
+                let f = |z| z + y;
+                y = f(x);
+            }
+        "#;
+
+        let errors = resolve_src_code(src, vec!["main"]);
+        assert_eq!(errors.len(), 1, "Expected 1 error, got: {errors:?}");
+        assert!(matches!(errors[0], ResolverError::CapturedMutableVariable { .. }));
+    }
+
+    #[test]
+    fn resolve_loops_with_jumps() {
+        let src = r#"
+            fn main(x : Field) {
+                let mut i = 0;
+                while i != x max 10 {
+                    i += 1;
+                    if i == 3 { continue; }
+                    for j in 0..4 {
+                        if j == i { break }
+                    }
+                }
+                assert(count(x) == i);
+            }
+
+            unconstrained fn count(n : Field) -> Field {
+                let mut i = 0;
+                while i != n {
+                    i += 1;
+                }
+                i
+            }
+        "#;
+
+        let errors = resolve_src_code(src, vec!["main", "count"]);
+        assert!(errors.is_empty(), "Expected no errors, got: {errors:?}");
+    }
+
+    #[test]
+    fn resolve_jumps_outside_of_loops() {
+        let src = r#"
+            fn main(x : Field) {
+                if x == 0 {
+                    break;
+                }
+                for i in 0..4 {
+                    let f = |y| {
+                        continue;
+                        y
+                    };
+                    assert(f(i) == i);
+                }
+                while x != 0 {}
+            }
+        "#;
+
+        let errors = resolve_src_code(src, vec!["main"]);
+        assert_eq!(errors.len(), 3, "Expected 3 errors, got: {errors:?}");
+        assert!(matches!(errors[0], ResolverError::JumpOutsideOfLoop { keyword: "break", .. }));
+        assert!(matches!(errors[1], ResolverError::JumpOutsideOfLoop { keyword: "continue", .. }));
+        assert!(matches!(errors[2], ResolverError::UnboundedWhileLoop { .. }));
+    }
+
     fn path_unresolved_error(err: ResolverError, expected_unresolved_path: &str) {
         match err {
             ResolverError::PathResolutionError(PathResolutionError::Unresolved(name)) => {
@@ -1,3 +1,4 @@
+use iter_extended::vecmap;
 use noirc_errors::CustomDiagnostic as Diagnostic;
 use noirc_errors::Span;
 use thiserror::Error;
@@ -35,6 +36,12 @@ pub enum TypeCheckError {
     },
     #[error("Cannot infer type of expression, type annotations needed before this point")]
     TypeAnnotationsNeeded { span: Span },
+    #[error("The trait {trait_name} is not implemented for {typ}")]
+    TraitNotImplemented { typ: String, trait_name: String, span: Span },
+    #[error("Multiple traits implemented for {typ} provide a method named {method_name}")]
+    AmbiguousTraitMethod { method_name: String, typ: String, traits: Vec<String>, span: Span },
+    #[error("Match expression does not cover every variant")]
+    NonExhaustiveMatch { missing: Vec<String>, span: Span },
     #[error("{0}")]
     ResolverError(ResolverError),
 }
@@ -106,6 +113,32 @@ impl From<TypeCheckError> for Diagnostic {
                 "Type must be known at this point".to_string(),
                 span,
             ),
+            TypeCheckError::TraitNotImplemented { typ, trait_name, span } => {
+                Diagnostic::simple_error(
+                    format!("The trait `{trait_name}` is not implemented for type `{typ}`"),
+                    format!("required by a bound on `{trait_name}`"),
+                    span,
+                )
+            }
+            TypeCheckError::AmbiguousTraitMethod { method_name, typ, traits, span } => {
+                let traits = vecmap(traits, |name| format!("`{name}`"));
+                Diagnostic::simple_error(
+                    format!(
+                        "multiple applicable methods named `{method_name}` found for type `{typ}`"
+                    ),
+                    format!("provided by traits {}", traits.join(", ")),
+                    span,
+                )
+            }
+            TypeCheckError::NonExhaustiveMatch { missing, span } => {
+                let plural = if missing.len() == 1 { "" } else { "s" };
+                let missing = missing.join(", ");
+                Diagnostic::simple_error(
+                    format!("Non-exhaustive match, pattern{plural} {missing} not covered"),
+                    "Add a branch for each missing pattern or a catch-all branch".to_string(),
+                    span,
+                )
+            }
             TypeCheckError::ResolverError(error) => error.into(),
         }
     }
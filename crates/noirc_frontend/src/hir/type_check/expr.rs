@@ -5,12 +5,12 @@ use crate::{
     hir::resolution::resolver::verify_mutable_reference,
     hir_def::{
         expr::{
-            self, HirArrayLiteral, HirBinaryOp, HirExpression, HirLiteral, HirMethodCallExpression,
-            HirPrefixExpression,
+            self, HirArrayLiteral, HirBinaryOp, HirExpression, HirLiteral, HirMatchPattern,
+            HirMethodCallExpression, HirPrefixExpression,
         },
         types::Type,
     },
-    node_interner::{ExprId, FuncId},
+    node_interner::{DefinitionId, DefinitionKind, ExprId, FuncId},
     CompTime, Shared, TypeBinding, UnaryOp,
 };
 
@@ -34,6 +34,17 @@ impl<'interner> TypeChecker<'interner> {
                 // variable to handle generic functions.
                 let t = self.interner.id_type(ident.id);
                 let (typ, bindings) = t.instantiate(self.interner);
+
+                // Any trait bounds on a generic function must hold for the types it is called with
+                if ident.id != DefinitionId::dummy_id() {
+                    if let DefinitionKind::Function(func_id) =
+                        self.interner.definition(ident.id).kind
+                    {
+                        let span = self.interner.expr_span(expr_id);
+                        self.add_trait_constraints(&func_id, &bindings, span);
+                    }
+                }
+
                 self.interner.store_instantiation_bindings(*expr_id, bindings);
                 typ
             }
@@ -197,6 +208,20 @@ impl<'interner> TypeChecker<'interner> {
                 self.check_expression(&for_expr.block);
                 Type::Unit
             }
+            HirExpression::While(while_expr) => {
+                let condition_type = self.check_expression(&while_expr.condition);
+                let span = self.interner.expr_span(&while_expr.condition);
+
+                let bool_type = Type::Bool(CompTime::new(self.interner));
+                self.unify(&condition_type, &bool_type, span, || TypeCheckError::TypeMismatch {
+                    expected_typ: Type::Bool(CompTime::No(None)).to_string(),
+                    expr_typ: condition_type.to_string(),
+                    expr_span: span,
+                });
+
+                self.check_expression(&while_expr.block);
+                Type::Unit
+            }
             HirExpression::Block(block_expr) => {
                 let mut block_type = Type::Unit;
 
@@ -231,13 +256,20 @@ impl<'interner> TypeChecker<'interner> {
                 self.type_check_prefix_operand(&prefix_expr.operator, &rhs_type, span)
             }
             HirExpression::If(if_expr) => self.check_if_expr(&if_expr, expr_id),
+            HirExpression::Match(match_expr) => self.check_match_expr(match_expr, expr_id),
             HirExpression::Constructor(constructor) => self.check_constructor(constructor, expr_id),
+            HirExpression::EnumConstructor(constructor) => self.check_enum_constructor(constructor),
             HirExpression::MemberAccess(access) => self.check_member_access(access, *expr_id),
             HirExpression::Error => Type::Error,
             HirExpression::Tuple(elements) => {
                 Type::Tuple(vecmap(&elements, |elem| self.check_expression(elem)))
             }
             HirExpression::Lambda(lambda) => {
+                let captured_vars =
+                    vecmap(lambda.captures, |capture| self.interner.id_type(capture.id));
+                let env_type =
+                    if captured_vars.is_empty() { Type::Unit } else { Type::Tuple(captured_vars) };
+
                 let params = vecmap(lambda.parameters, |(pattern, typ)| {
                     self.bind_pattern(&pattern, typ.clone());
                     typ
@@ -253,7 +285,7 @@ impl<'interner> TypeChecker<'interner> {
                         expr_span: span,
                     }
                 });
-                Type::Function(params, Box::new(lambda.return_type))
+                Type::Function(params, Box::new(lambda.return_type), Box::new(env_type))
             }
         };
 
@@ -272,9 +304,9 @@ impl<'interner> TypeChecker<'interner> {
         argument_types: &mut [(Type, noirc_errors::Span)],
     ) {
         let expected_object_type = match function_type {
-            Type::Function(args, _) => args.get(0),
+            Type::Function(args, _, _) => args.get(0),
             Type::Forall(_, typ) => match typ.as_ref() {
-                Type::Function(args, _) => args.get(0),
+                Type::Function(args, _, _) => args.get(0),
                 typ => unreachable!("Unexpected type for function: {typ}"),
             },
             typ => unreachable!("Unexpected type for function: {typ}"),
@@ -420,6 +452,7 @@ impl<'interner> TypeChecker<'interner> {
             }
 
             let (function_type, instantiation_bindings) = func_meta.typ.instantiate(self.interner);
+            self.add_trait_constraints(func_id, &instantiation_bindings, span);
 
             self.interner.store_instantiation_bindings(*function_ident_id, instantiation_bindings);
             self.interner.push_expr_type(function_ident_id, function_type.clone());
@@ -509,6 +542,111 @@ impl<'interner> TypeChecker<'interner> {
         Type::Struct(typ, generics)
     }
 
+    fn check_enum_constructor(&mut self, constructor: expr::HirEnumConstructorExpression) -> Type {
+        let typ = constructor.r#type;
+        let generics = constructor.enum_generics;
+
+        let fields = typ.borrow().variant_fields(constructor.variant_index, &generics);
+
+        // An incorrect argument count is reported during name resolution,
+        // so any extra arguments are only checked on their own here.
+        for (i, arg) in constructor.arguments.iter().enumerate() {
+            let arg_type = self.check_expression(arg);
+
+            if let Some(param_type) = fields.get(i) {
+                let span = self.interner.expr_span(arg);
+                self.make_subtype_of(&arg_type, param_type, span, || {
+                    TypeCheckError::TypeMismatch {
+                        expected_typ: param_type.to_string(),
+                        expr_typ: arg_type.to_string(),
+                        expr_span: span,
+                    }
+                });
+            }
+        }
+
+        Type::Enum(typ, generics)
+    }
+
+    fn check_match_expr(&mut self, match_expr: expr::HirMatchExpression, expr_id: &ExprId) -> Type {
+        let scrutinee_type = self.check_expression(&match_expr.expression);
+
+        let mut result_type: Option<Type> = None;
+        let mut covered_variants = Vec::new();
+        let mut has_catchall = false;
+
+        for (pattern, branch) in &match_expr.rules {
+            match pattern {
+                HirMatchPattern::Variant(typ, variant_index, fields, span) => {
+                    self.unify(typ, &scrutinee_type, *span, || TypeCheckError::TypeMismatch {
+                        expected_typ: scrutinee_type.to_string(),
+                        expr_typ: typ.to_string(),
+                        expr_span: *span,
+                    });
+
+                    let field_types = match typ {
+                        Type::Enum(enum_type, generics) => {
+                            enum_type.borrow().variant_fields(*variant_index, generics)
+                        }
+                        _ => Vec::new(),
+                    };
+
+                    // A field count mismatch is already reported during name resolution
+                    for (i, field) in fields.iter().enumerate() {
+                        let field_type = field_types.get(i).cloned().unwrap_or(Type::Error);
+                        self.bind_pattern(field, field_type);
+                    }
+
+                    covered_variants.push(*variant_index);
+                }
+                HirMatchPattern::Catchall(pattern) => {
+                    self.bind_pattern(pattern, scrutinee_type.clone());
+                    has_catchall = true;
+                }
+            }
+
+            let branch_type = self.check_expression(branch);
+
+            match &result_type {
+                None => result_type = Some(branch_type),
+                Some(expected) => {
+                    let span = self.interner.expr_span(branch);
+                    self.unify(&branch_type, expected, span, || {
+                        TypeCheckError::TypeMismatch {
+                            expected_typ: expected.to_string(),
+                            expr_typ: branch_type.to_string(),
+                            expr_span: span,
+                        }
+                        .add_context("Expected the types of all match branches to be equal")
+                    });
+                }
+            }
+        }
+
+        if !has_catchall {
+            let span = self.interner.expr_span(expr_id);
+            let missing: Vec<String> = match scrutinee_type.follow_bindings() {
+                Type::Enum(enum_type, _) => {
+                    let enum_type = enum_type.borrow();
+                    let variants = 0..enum_type.num_variants();
+                    let missing = variants.filter(|index| !covered_variants.contains(index));
+                    vecmap(missing, |index| enum_type.variant_name(index).to_string())
+                }
+                Type::Error => Vec::new(),
+                // Only variant patterns were given, each of which has already issued
+                // a type mismatch against the non-enum type being matched on.
+                _ if !match_expr.rules.is_empty() => Vec::new(),
+                _ => vec!["_".to_string()],
+            };
+
+            if !missing.is_empty() {
+                self.errors.push(TypeCheckError::NonExhaustiveMatch { missing, span });
+            }
+        }
+
+        result_type.unwrap_or(Type::Unit)
+    }
+
     fn check_member_access(&mut self, mut access: expr::HirMemberAccess, expr_id: ExprId) -> Type {
         let lhs_type = self.check_expression(&access.lhs).follow_bindings();
         let span = self.interner.expr_span(&expr_id);
@@ -720,8 +858,19 @@ impl<'interner> TypeChecker<'interner> {
         expr_id: &ExprId,
     ) -> Option<FuncId> {
         match &object_type {
-            Type::Struct(typ, _args) => {
-                match self.interner.lookup_method(typ.borrow().id, method_name) {
+            Type::Struct(..) | Type::Enum(..) => {
+                let method = match &object_type {
+                    Type::Struct(typ, _args) => {
+                        self.interner.lookup_method(typ.borrow().id, method_name)
+                    }
+                    Type::Enum(typ, _args) => {
+                        self.interner.lookup_enum_method(typ.borrow().id, method_name)
+                    }
+                    _ => unreachable!(),
+                };
+                match method
+                    .or_else(|| self.lookup_trait_method(&object_type, method_name, expr_id))
+                {
                     Some(method_id) => Some(method_id),
                     None => {
                         self.errors.push(TypeCheckError::Unstructured {
@@ -738,9 +887,29 @@ impl<'interner> TypeChecker<'interner> {
             // checking its arguments as we can't even resolve the name of the function
             Type::Error => None,
 
+            // Methods on generic types can only come from the trait bounds on that generic
+            Type::NamedGeneric(..) => {
+                match self.lookup_method_in_trait_constraints(&object_type, method_name) {
+                    Some(method_id) => Some(method_id),
+                    None => {
+                        self.errors.push(TypeCheckError::Unstructured {
+                            span: self.interner.expr_span(expr_id),
+                            msg: format!(
+                                "No method named '{method_name}' found for type '{object_type}'",
+                            ),
+                        });
+                        None
+                    }
+                }
+            }
+
             // In the future we could support methods for non-struct types if we have a context
             // (in the interner?) essentially resembling HashMap<Type, Methods>
-            other => match self.interner.lookup_primitive_method(other, method_name) {
+            other => match self
+                .interner
+                .lookup_primitive_method(other, method_name)
+                .or_else(|| self.lookup_trait_method(other, method_name, expr_id))
+            {
                 Some(method_id) => Some(method_id),
                 None => {
                     self.errors.push(TypeCheckError::Unstructured {
@@ -753,6 +922,46 @@ impl<'interner> TypeChecker<'interner> {
         }
     }
 
+    /// Search the traits implemented for `object_type` for a method with the given name,
+    /// reporting an error if more than one of them provides one.
+    fn lookup_trait_method(
+        &mut self,
+        object_type: &Type,
+        method_name: &str,
+        expr_id: &ExprId,
+    ) -> Option<FuncId> {
+        let methods = self.interner.lookup_trait_methods(object_type, method_name);
+        if methods.len() > 1 {
+            self.errors.push(TypeCheckError::AmbiguousTraitMethod {
+                method_name: method_name.to_owned(),
+                typ: object_type.to_string(),
+                traits: vecmap(&methods, |(trait_id, _)| {
+                    self.interner.get_trait(*trait_id).name.to_string()
+                }),
+                span: self.interner.expr_span(expr_id),
+            });
+        }
+        methods.first().map(|(_, method)| *method)
+    }
+
+    /// Search the trait bounds of the current function for a trait declaring the given method
+    /// that is implemented by `object_type`.
+    fn lookup_method_in_trait_constraints(
+        &self,
+        object_type: &Type,
+        method_name: &str,
+    ) -> Option<FuncId> {
+        let func_id = self.current_function?;
+        let meta = self.interner.function_meta(&func_id);
+
+        meta.trait_constraints.iter().filter(|constraint| &constraint.typ == object_type).find_map(
+            |constraint| {
+                let the_trait = self.interner.get_trait(constraint.trait_id);
+                the_trait.find_method(method_name).map(|method| method.id)
+            },
+        )
+    }
+
     fn bind_function_type(&mut self, function: Type, args: Vec<(Type, Span)>, span: Span) -> Type {
         // Could do a single unification for the entire function type, but matching beforehand
         // lets us issue a more precise error on the individual argument that fails to type check.
@@ -764,12 +973,13 @@ impl<'interner> TypeChecker<'interner> {
 
                 let ret = self.interner.next_type_variable();
                 let args = vecmap(args, |(arg, _)| arg);
-                let expected = Type::Function(args, Box::new(ret.clone()));
+                let env = Box::new(self.interner.next_type_variable());
+                let expected = Type::Function(args, Box::new(ret.clone()), env);
                 *binding.borrow_mut() = TypeBinding::Bound(expected);
 
                 ret
             }
-            Type::Function(parameters, ret) => {
+            Type::Function(parameters, ret, _env) => {
                 if parameters.len() != args.len() {
                     let empty_or_s = if parameters.len() == 1 { "" } else { "s" };
                     let was_or_were = if args.len() == 1 { "was" } else { "were" };
@@ -885,6 +1095,7 @@ impl<'interner> TypeChecker<'interner> {
             // These types are not supported in binary operations
             (Array(..), _) | (_, Array(..)) => Err(make_error("Arrays cannot be used in an infix operation".to_string())),
             (Struct(..), _) | (_, Struct(..)) => Err(make_error("Structs cannot be used in an infix operation".to_string())),
+            (Enum(..), _) | (_, Enum(..)) => Err(make_error("Enums cannot be used in an infix operation".to_string())),
             (Tuple(_), _) | (_, Tuple(_)) => Err(make_error("Tuples cannot be used in an infix operation".to_string())),
 
             (Unit, _) | (_,Unit) => Ok(Unit),
@@ -18,8 +18,9 @@ pub use errors::TypeCheckError;
 use noirc_errors::Span;
 
 use crate::{
+    hir_def::traits::TraitConstraint,
     node_interner::{ExprId, FuncId, NodeInterner, StmtId},
-    Type,
+    Type, TypeBindings,
 };
 
 type TypeCheckFn = Box<dyn FnOnce() -> Result<(), TypeCheckError>>;
@@ -29,6 +30,11 @@ pub struct TypeChecker<'interner> {
     current_function: Option<FuncId>,
     interner: &'interner mut NodeInterner,
     errors: Vec<TypeCheckError>,
+
+    /// Trait bounds required by the generic functions called within the current function.
+    /// These are checked once the whole function body has been type checked, since the
+    /// types they apply to may not be known until then.
+    trait_constraints: Vec<(TraitConstraint, Span)>,
 }
 
 /// Type checks a function and assigns the
@@ -82,6 +88,7 @@ impl<'interner> TypeChecker<'interner> {
             current_function: Some(current_function),
             interner,
             errors: vec![],
+            trait_constraints: vec![],
         }
     }
 
@@ -94,15 +101,58 @@ impl<'interner> TypeChecker<'interner> {
         body: &ExprId,
     ) -> (Type, Vec<TypeCheckFn>, Vec<TypeCheckError>) {
         let body_type = self.check_expression(body);
+        self.verify_trait_constraints();
         (body_type, self.delayed_type_checks, self.errors)
     }
 
+    /// Remember the trait bounds of the given function, instantiated with the given bindings,
+    /// so that they can later be checked against the types the function was called with.
+    fn add_trait_constraints(&mut self, func_id: &FuncId, bindings: &TypeBindings, span: Span) {
+        let meta = self.interner.function_meta(func_id);
+
+        for constraint in meta.trait_constraints {
+            let typ = constraint.typ.substitute(bindings);
+            let constraint = TraitConstraint { typ, trait_id: constraint.trait_id };
+            self.trait_constraints.push((constraint, span));
+        }
+    }
+
+    /// Check that each trait bound required within the current function is satisfied, either
+    /// by an impl for a concrete type or by a bound on one of the current function's generics.
+    fn verify_trait_constraints(&mut self) {
+        let in_scope_constraints = self
+            .current_function
+            .map(|function| self.interner.function_meta(&function).trait_constraints)
+            .unwrap_or_default();
+
+        for (constraint, span) in std::mem::take(&mut self.trait_constraints) {
+            let typ = constraint.typ.follow_bindings();
+
+            let is_satisfied = match &typ {
+                // Avoid issuing errors for types which have already failed to type check
+                // or which could not be inferred.
+                Type::Error | Type::TypeVariable(_) => true,
+                Type::NamedGeneric(..) => in_scope_constraints.iter().any(|in_scope| {
+                    in_scope.trait_id == constraint.trait_id && in_scope.typ == typ
+                }),
+                _ => self.interner.lookup_trait_implementation(&typ, constraint.trait_id).is_some(),
+            };
+
+            if !is_satisfied {
+                let trait_name = self.interner.get_trait(constraint.trait_id).name.to_string();
+                let typ = typ.to_string();
+                self.errors.push(TypeCheckError::TraitNotImplemented { typ, trait_name, span });
+            }
+        }
+    }
+
     pub fn check_global(id: &StmtId, interner: &'interner mut NodeInterner) -> Vec<TypeCheckError> {
         let mut this = Self {
             delayed_type_checks: Vec::new(),
             current_function: None,
             interner,
             errors: vec![],
+            trait_constraints: vec![],
         };
         this.check_statement(id);
         this.errors
@@ -177,7 +227,7 @@ mod test {
         // let z = x + y;
         //
         // Push x variable
-        let x_id = interner.push_definition("x".into(), false, DefinitionKind::Local(None));
+        let x_id = interner.push_definition("x".into(), false, DefinitionKind::Local(None), None);
 
         // Safety: The FileId in a location isn't used for tests
         let file = FileId::default();
@@ -186,11 +236,11 @@ mod test {
         let x = HirIdent { id: x_id, location };
 
         // Push y variable
-        let y_id = interner.push_definition("y".into(), false, DefinitionKind::Local(None));
+        let y_id = interner.push_definition("y".into(), false, DefinitionKind::Local(None), None);
         let y = HirIdent { id: y_id, location };
 
         // Push z variable
-        let z_id = interner.push_definition("z".into(), false, DefinitionKind::Local(None));
+        let z_id = interner.push_definition("z".into(), false, DefinitionKind::Local(None), None);
         let z = HirIdent { id: z_id, location };
 
         // Push x and y as expressions
@@ -222,7 +272,12 @@ mod test {
 
         let name = HirIdent {
             location,
-            id: interner.push_definition("test_func".into(), false, DefinitionKind::Local(None)),
+            id: interner.push_definition(
+                "test_func".into(),
+                false,
+                DefinitionKind::Local(None),
+                None,
+            ),
         };
 
         // Add function meta
@@ -233,7 +288,11 @@ mod test {
             location,
             contract_function_type: None,
             is_unconstrained: false,
-            typ: Type::Function(vec![Type::field(None), Type::field(None)], Box::new(Type::Unit)),
+            typ: Type::Function(
+                vec![Type::field(None), Type::field(None)],
+                Box::new(Type::Unit),
+                Box::new(Type::Unit),
+            ),
             parameters: vec![
                 Param(Identifier(x), Type::field(None), noirc_abi::AbiVisibility::Private),
                 Param(Identifier(y), Type::field(None), noirc_abi::AbiVisibility::Private),
@@ -242,6 +301,8 @@ mod test {
             return_visibility: noirc_abi::AbiVisibility::Private,
             return_distinctness: noirc_abi::AbiDistinctness::DuplicationAllowed,
             has_body: true,
+            trait_id: None,
+            trait_constraints: Vec::new(),
         };
         interner.push_fn_meta(func_meta, func_id);
 
@@ -375,7 +436,7 @@ mod test {
             },
         );
 
-        let func_meta = vecmap(program.functions, |nf| {
+        let func_meta = vecmap(program.into_sorted().functions, |nf| {
             let resolver = Resolver::new(&mut interner, &path_resolver, &def_maps, file);
             let (hir_func, func_meta, resolver_errors) = resolver.resolve_function(nf, main_id);
             assert_eq!(resolver_errors, vec![]);
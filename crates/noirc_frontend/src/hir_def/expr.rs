@@ -1,12 +1,12 @@
 use acvm::FieldElement;
 use fm::FileId;
-use noirc_errors::Location;
+use noirc_errors::{Location, Span};
 
 use crate::node_interner::{DefinitionId, ExprId, FuncId, NodeInterner, StmtId};
 use crate::{BinaryOp, BinaryOpKind, Ident, Shared, UnaryOp};
 
 use super::stmt::HirPattern;
-use super::types::{StructType, Type};
+use super::types::{EnumType, StructType, Type};
 
 /// A HirExpression is the result of an Expression in the AST undergoing
 /// name resolution. It is almost identical to the Expression AST node, but
@@ -22,12 +22,15 @@ pub enum HirExpression {
     Infix(HirInfixExpression),
     Index(HirIndexExpression),
     Constructor(HirConstructorExpression),
+    EnumConstructor(HirEnumConstructorExpression),
     MemberAccess(HirMemberAccess),
     Call(HirCallExpression),
     MethodCall(HirMethodCallExpression),
     Cast(HirCastExpression),
     For(HirForExpression),
+    While(HirWhileExpression),
     If(HirIfExpression),
+    Match(HirMatchExpression),
     Tuple(Vec<ExprId>),
     Lambda(HirLambda),
     Error,
@@ -55,6 +58,15 @@ pub struct HirForExpression {
     pub block: ExprId,
 }
 
+#[derive(Debug, Clone)]
+pub struct HirWhileExpression {
+    pub condition: ExprId,
+    /// The maximum number of iterations, as an array-length-like type.
+    /// This is only None for loops within unconstrained functions.
+    pub max_iterations: Option<Type>,
+    pub block: ExprId,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct HirBinaryOp {
     pub kind: BinaryOpKind,
@@ -175,6 +187,31 @@ pub struct HirConstructorExpression {
     pub fields: Vec<(Ident, ExprId)>,
 }
 
+/// Constructs the variant at `variant_index` of an enum, as in `Option::Some(x)`
+#[derive(Debug, Clone)]
+pub struct HirEnumConstructorExpression {
+    pub r#type: Shared<EnumType>,
+    pub enum_generics: Vec<Type>,
+    pub variant_index: usize,
+    pub arguments: Vec<ExprId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HirMatchExpression {
+    pub expression: ExprId,
+    pub rules: Vec<(HirMatchPattern, ExprId)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum HirMatchPattern {
+    /// Matches the variant at the given index of the given enum type,
+    /// binding each of the variant's fields to the given patterns.
+    Variant(Type, usize, Vec<HirPattern>, Span),
+
+    /// Matches any value, binding it to the given pattern.
+    Catchall(HirPattern),
+}
+
 /// Indexing, as in `array[index]`
 #[derive(Debug, Clone)]
 pub struct HirIndexExpression {
@@ -196,4 +233,8 @@ pub struct HirLambda {
     pub parameters: Vec<(HirPattern, Type)>,
     pub return_type: Type,
     pub body: ExprId,
+    /// The variables declared outside of the lambda which are used within its body,
+    /// including those used by any lambda nested within it.
+    /// These make up the environment of the closure, in this order.
+    pub captures: Vec<HirIdent>,
 }
@@ -4,7 +4,8 @@ use noirc_errors::{Location, Span};
 
 use super::expr::{HirBlockExpression, HirExpression, HirIdent};
 use super::stmt::HirPattern;
-use crate::node_interner::{ExprId, NodeInterner};
+use super::traits::TraitConstraint;
+use crate::node_interner::{ExprId, NodeInterner, TraitId};
 use crate::{token::Attribute, FunctionKind};
 use crate::{ContractFunctionType, Type};
 
@@ -141,6 +142,13 @@ pub struct FuncMeta {
 
     // This flag is needed for the attribute check pass
     pub has_body: bool,
+
+    /// If this function is a method declared within a trait, this is the id of that trait.
+    /// Calls to such a method are dispatched to the matching impl during monomorphization.
+    pub trait_id: Option<TraitId>,
+
+    /// The trait bounds on this function's generics, from `<T: Trait>` or a `where` clause.
+    pub trait_constraints: Vec<TraitConstraint>,
 }
 
 impl FuncMeta {
@@ -172,9 +180,9 @@ impl FuncMeta {
     /// Gives the (uninstantiated) return type of this function.
     pub fn return_type(&self) -> &Type {
         match &self.typ {
-            Type::Function(_, ret) => ret,
+            Type::Function(_, ret, _) => ret,
             Type::Forall(_, typ) => match typ.as_ref() {
-                Type::Function(_, ret) => ret,
+                Type::Function(_, ret, _) => ret,
                 _ => unreachable!(),
             },
             _ => unreachable!(),
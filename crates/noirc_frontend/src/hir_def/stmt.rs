@@ -15,6 +15,8 @@ pub enum HirStatement {
     Assign(HirAssignStatement),
     Expression(ExprId),
     Semi(ExprId),
+    Break,
+    Continue,
     Error,
 }
 
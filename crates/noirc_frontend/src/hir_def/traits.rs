@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use noirc_errors::Span;
+
+use crate::{
+    node_interner::{FuncId, TraitId},
+    Ident, Type, TypeVariable, TypeVariableId,
+};
+
+/// A resolved trait definition. Each method declared within the trait is
+/// interned as its own function which is generic over the implicit `Self` type.
+#[derive(Debug, Clone)]
+pub struct Trait {
+    /// A unique id representing this trait. Used to check if two
+    /// traits are equal.
+    pub id: TraitId,
+
+    pub name: Ident,
+
+    /// The methods declared by this trait, in declaration order.
+    pub methods: Vec<TraitFunction>,
+
+    /// The type variable standing in for `Self` within each of the trait's methods.
+    pub self_type_typevar_id: TypeVariableId,
+    pub self_type_typevar: TypeVariable,
+
+    pub span: Span,
+}
+
+/// A method declared within a trait, possibly with a default implementation.
+#[derive(Debug, Clone)]
+pub struct TraitFunction {
+    pub name: Ident,
+    pub id: FuncId,
+    pub has_default: bool,
+}
+
+/// An `impl Trait for Type` block after name resolution.
+#[derive(Debug, Clone)]
+pub struct TraitImpl {
+    pub trait_id: TraitId,
+
+    /// The type the trait is implemented for. This may refer to
+    /// the generics declared on the impl itself.
+    pub typ: Type,
+
+    /// The methods defined within the impl, keyed by name.
+    pub methods: HashMap<String, FuncId>,
+
+    pub span: Span,
+}
+
+/// A resolved bound on a type such as `T: Eq`, requiring `typ` to implement the trait.
+#[derive(Debug, Clone)]
+pub struct TraitConstraint {
+    pub typ: Type,
+    pub trait_id: TraitId,
+}
+
+impl Trait {
+    pub fn find_method(&self, name: &str) -> Option<&TraitFunction> {
+        self.methods.iter().find(|method| method.name.0.contents == name)
+    }
+}
+
+impl std::fmt::Display for Trait {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
@@ -10,7 +10,10 @@ use iter_extended::vecmap;
 use noirc_abi::AbiType;
 use noirc_errors::Span;
 
-use crate::{node_interner::StructId, Ident, Signedness};
+use crate::{
+    node_interner::{EnumId, StructId},
+    Ident, Signedness,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub enum Type {
@@ -42,6 +45,10 @@ pub enum Type {
     /// represents the generic arguments (if any) to this struct type.
     Struct(Shared<StructType>, Vec<Type>),
 
+    /// A user-defined enum type. As with structs, the `Shared<EnumType>` refers to
+    /// the shared definition of the enum and the `Vec<Type>` to its generic arguments.
+    Enum(Shared<EnumType>, Vec<Type>),
+
     /// A tuple type with the given list of fields in the order they appear in source code.
     Tuple(Vec<Type>),
 
@@ -68,8 +75,9 @@ pub enum Type {
     /// like `fn foo<T, U>(...) {}`. Unlike TypeVariables, they cannot be bound over.
     NamedGeneric(TypeVariable, Rc<String>),
 
-    /// A functions with arguments, and a return type.
-    Function(Vec<Type>, Box<Type>),
+    /// A functions with arguments, a return type, and the type of the environment it captures.
+    /// The environment of functions which aren't closures is the unit type.
+    Function(Vec<Type>, Box<Type>, Box<Type>),
 
     /// A variable-sized Vector type.
     /// Unlike arrays, this type can have a dynamic size and can grow/shrink dynamically via .push,
@@ -224,6 +232,111 @@ impl std::fmt::Display for StructType {
     }
 }
 
+/// Represents an enum type in the type system. Like StructType, each instance is
+/// shared across all Type::Enum variants that represent the same enum type.
+#[derive(Debug, Eq)]
+pub struct EnumType {
+    /// A unique id representing this enum type. Used to check if two
+    /// enum types are equal.
+    pub id: EnumId,
+
+    pub name: Ident,
+
+    /// Variants are ordered by declaration and their index is used as the variant's tag.
+    /// The types of each variant's fields should only be accessed through
+    /// variant_fields() since it will handle applying generic arguments.
+    variants: Vec<(Ident, Vec<Type>)>,
+
+    pub generics: Generics,
+    pub span: Span,
+}
+
+impl std::hash::Hash for EnumType {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl PartialEq for EnumType {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl EnumType {
+    pub fn new(
+        id: EnumId,
+        name: Ident,
+        span: Span,
+        variants: Vec<(Ident, Vec<Type>)>,
+        generics: Generics,
+    ) -> EnumType {
+        EnumType { id, variants, name, span, generics }
+    }
+
+    /// As with structs, an enum's variants are resolved after the enum itself
+    /// is created to allow enums to refer to each other regardless of declaration order.
+    pub fn set_variants(&mut self, variants: Vec<(Ident, Vec<Type>)>) {
+        assert!(self.variants.is_empty());
+        self.variants = variants;
+    }
+
+    pub fn num_variants(&self) -> usize {
+        self.variants.len()
+    }
+
+    /// Returns the index of the variant with the given name, if any.
+    pub fn variant_index(&self, variant_name: &str) -> Option<usize> {
+        self.variants.iter().position(|(name, _)| name.0.contents == variant_name)
+    }
+
+    pub fn variant_name(&self, variant_index: usize) -> &Ident {
+        &self.variants[variant_index].0
+    }
+
+    /// Returns the number of fields held by the given variant
+    pub fn variant_field_count(&self, variant_index: usize) -> usize {
+        self.variants[variant_index].1.len()
+    }
+
+    /// Returns the types of each field of the given variant, after
+    /// being applied to the given generic arguments.
+    pub fn variant_fields(&self, variant_index: usize, generic_args: &[Type]) -> Vec<Type> {
+        assert_eq!(self.generics.len(), generic_args.len());
+
+        let substitutions = self
+            .generics
+            .iter()
+            .zip(generic_args)
+            .map(|((old_id, old_var), new)| (*old_id, (old_var.clone(), new.clone())))
+            .collect();
+
+        vecmap(&self.variants[variant_index].1, |typ| typ.substitute(&substitutions))
+    }
+
+    /// True if the given index is the same index as a generic type of this enum
+    /// which is expected to be a numeric generic.
+    pub fn generic_is_numeric(&self, index_of_generic: usize) -> bool {
+        let target_id = self.generics[index_of_generic].0;
+        self.variants
+            .iter()
+            .flat_map(|(_, fields)| fields)
+            .any(|field| field.contains_numeric_typevar(target_id))
+    }
+
+    /// Instantiate this enum type, returning a Vec of the new generic args (in
+    /// the same order as self.generics)
+    pub fn instantiate(&self, interner: &mut NodeInterner) -> Vec<Type> {
+        vecmap(&self.generics, |_| interner.next_type_variable())
+    }
+}
+
+impl std::fmt::Display for EnumType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
 /// A shared, mutable reference to some T.
 /// Wrapper is required for Hash impl of RefCell.
 #[derive(Debug, Eq, PartialOrd, Ord)]
@@ -587,9 +700,10 @@ impl Type {
             Type::Tuple(fields) => {
                 fields.iter().any(|field| field.contains_numeric_typevar(target_id))
             }
-            Type::Function(parameters, return_type) => {
+            Type::Function(parameters, return_type, env) => {
                 parameters.iter().any(|parameter| parameter.contains_numeric_typevar(target_id))
                     || return_type.contains_numeric_typevar(target_id)
+                    || env.contains_numeric_typevar(target_id)
             }
             Type::Struct(struct_type, generics) => {
                 generics.iter().enumerate().any(|(i, generic)| {
@@ -600,6 +714,13 @@ impl Type {
                     }
                 })
             }
+            Type::Enum(enum_type, generics) => generics.iter().enumerate().any(|(i, generic)| {
+                if named_generic_id_matches_target(generic) {
+                    enum_type.borrow().generic_is_numeric(i)
+                } else {
+                    generic.contains_numeric_typevar(target_id)
+                }
+            }),
             Type::Vec(element) => element.contains_numeric_typevar(target_id),
             Type::MutableReference(element) => element.contains_numeric_typevar(target_id),
         }
@@ -645,6 +766,14 @@ impl std::fmt::Display for Type {
                     write!(f, "{}<{}>", s.borrow(), args.join(", "))
                 }
             }
+            Type::Enum(e, args) => {
+                let args = vecmap(args, |arg| arg.to_string());
+                if args.is_empty() {
+                    write!(f, "{}", e.borrow())
+                } else {
+                    write!(f, "{}<{}>", e.borrow(), args.join(", "))
+                }
+            }
             Type::Tuple(elements) => {
                 let elements = vecmap(elements, ToString::to_string);
                 write!(f, "({})", elements.join(", "))
@@ -664,9 +793,12 @@ impl std::fmt::Display for Type {
                 let typevars = vecmap(typevars, |(var, _)| var.to_string());
                 write!(f, "forall {}. {}", typevars.join(" "), typ)
             }
-            Type::Function(args, ret) => {
+            Type::Function(args, ret, env) => {
                 let args = vecmap(args, ToString::to_string);
-                write!(f, "fn({}) -> {}", args.join(", "), ret)
+                match env.as_ref() {
+                    Type::Unit => write!(f, "fn({}) -> {}", args.join(", "), ret),
+                    env => write!(f, "fn[{}]({}) -> {}", env, args.join(", "), ret),
+                }
             }
             Type::Vec(element) => {
                 write!(f, "Vec<{element}>")
@@ -912,7 +1044,7 @@ impl Type {
 
     /// `try_unify` is a bit of a misnomer since although errors are not committed,
     /// any unified bindings are on success.
-    fn try_unify(&self, other: &Type, span: Span) -> Result<(), SpanKind> {
+    pub(crate) fn try_unify(&self, other: &Type, span: Span) -> Result<(), SpanKind> {
         use Type::*;
         match (self, other) {
             (Error, _) | (_, Error) => Ok(()),
@@ -966,6 +1098,17 @@ impl Type {
                 }
             }
 
+            (Enum(enum_a, args_a), Enum(enum_b, args_b)) => {
+                if enum_a == enum_b {
+                    for (a, b) in args_a.iter().zip(args_b) {
+                        a.try_unify(b, span)?;
+                    }
+                    Ok(())
+                } else {
+                    Err(SpanKind::None)
+                }
+            }
+
             (FieldElement(comptime_a), FieldElement(comptime_b)) => {
                 comptime_a.unify(comptime_b, span)
             }
@@ -992,12 +1135,13 @@ impl Type {
                 }
             }
 
-            (Function(params_a, ret_a), Function(params_b, ret_b)) => {
+            (Function(params_a, ret_a, env_a), Function(params_b, ret_b, env_b)) => {
                 if params_a.len() == params_b.len() {
                     for (a, b) in params_a.iter().zip(params_b) {
                         a.try_unify(b, span)?;
                     }
 
+                    env_a.try_unify(env_b, span)?;
                     ret_b.try_unify(ret_a, span)
                 } else {
                     Err(SpanKind::None)
@@ -1100,6 +1244,17 @@ impl Type {
                 }
             }
 
+            (Enum(enum_a, args_a), Enum(enum_b, args_b)) => {
+                if enum_a == enum_b && args_a.len() == args_b.len() {
+                    for (a, b) in args_a.iter().zip(args_b) {
+                        a.is_subtype_of(b, span)?;
+                    }
+                    Ok(())
+                } else {
+                    Err(SpanKind::None)
+                }
+            }
+
             (FieldElement(comptime_a), FieldElement(comptime_b)) => {
                 comptime_a.is_subtype_of(comptime_b, span)
             }
@@ -1126,12 +1281,14 @@ impl Type {
                 }
             }
 
-            (Function(params_a, ret_a), Function(params_b, ret_b)) => {
+            (Function(params_a, ret_a, env_a), Function(params_b, ret_b, env_b)) => {
                 if params_a.len() == params_b.len() {
                     for (a, b) in params_a.iter().zip(params_b) {
                         a.is_subtype_of(b, span)?;
                     }
 
+                    env_a.is_subtype_of(env_b, span)?;
+
                     // return types are contravariant, so this must be ret_b <: ret_a instead of the reverse
                     ret_b.is_subtype_of(ret_a, span)
                 } else {
@@ -1222,11 +1379,15 @@ impl Type {
                 let fields = vecmap(fields, |(name, typ)| (name, typ.as_abi_type()));
                 AbiType::Struct { fields }
             }
-            Type::Tuple(_) => todo!("as_abi_type not yet implemented for tuple types"),
+            Type::Enum(..) => unreachable!("enums cannot be used in the abi"),
+            Type::Tuple(fields) => {
+                let fields = vecmap(fields, |typ| typ.as_abi_type());
+                AbiType::Tuple { fields }
+            }
             Type::TypeVariable(_) => unreachable!(),
             Type::NamedGeneric(..) => unreachable!(),
             Type::Forall(..) => unreachable!(),
-            Type::Function(_, _) => unreachable!(),
+            Type::Function(..) => unreachable!(),
             Type::MutableReference(_) => unreachable!("&mut cannot be used in the abi"),
             Type::Vec(_) => unreachable!("Vecs cannot be used in the abi"),
         }
@@ -1324,6 +1485,10 @@ impl Type {
                 let args = vecmap(args, |arg| arg.substitute(type_bindings));
                 Type::Struct(fields.clone(), args)
             }
+            Type::Enum(def, args) => {
+                let args = vecmap(args, |arg| arg.substitute(type_bindings));
+                Type::Enum(def.clone(), args)
+            }
             Type::Tuple(fields) => {
                 let fields = vecmap(fields, |field| field.substitute(type_bindings));
                 Type::Tuple(fields)
@@ -1337,10 +1502,11 @@ impl Type {
                 let typ = Box::new(typ.substitute(type_bindings));
                 Type::Forall(typevars.clone(), typ)
             }
-            Type::Function(args, ret) => {
+            Type::Function(args, ret, env) => {
                 let args = vecmap(args, |arg| arg.substitute(type_bindings));
                 let ret = Box::new(ret.substitute(type_bindings));
-                Type::Function(args, ret)
+                let env = Box::new(env.substitute(type_bindings));
+                Type::Function(args, ret, env)
             }
             Type::Vec(element) => Type::Vec(Box::new(element.substitute(type_bindings))),
             Type::MutableReference(element) => {
@@ -1361,7 +1527,9 @@ impl Type {
         match self {
             Type::Array(len, elem) => len.occurs(target_id) || elem.occurs(target_id),
             Type::String(len) => len.occurs(target_id),
-            Type::Struct(_, generic_args) => generic_args.iter().any(|arg| arg.occurs(target_id)),
+            Type::Struct(_, generic_args) | Type::Enum(_, generic_args) => {
+                generic_args.iter().any(|arg| arg.occurs(target_id))
+            }
             Type::Tuple(fields) => fields.iter().any(|field| field.occurs(target_id)),
             Type::PolymorphicInteger(_, binding)
             | Type::NamedGeneric(binding, _)
@@ -1372,8 +1540,10 @@ impl Type {
             Type::Forall(typevars, typ) => {
                 !typevars.iter().any(|(id, _)| *id == target_id) && typ.occurs(target_id)
             }
-            Type::Function(args, ret) => {
-                args.iter().any(|arg| arg.occurs(target_id)) || ret.occurs(target_id)
+            Type::Function(args, ret, env) => {
+                args.iter().any(|arg| arg.occurs(target_id))
+                    || ret.occurs(target_id)
+                    || env.occurs(target_id)
             }
             Type::Vec(element) => element.occurs(target_id),
             Type::MutableReference(element) => element.occurs(target_id),
@@ -1404,6 +1574,10 @@ impl Type {
                 let args = vecmap(args, |arg| arg.follow_bindings());
                 Struct(def.clone(), args)
             }
+            Enum(def, args) => {
+                let args = vecmap(args, |arg| arg.follow_bindings());
+                Enum(def.clone(), args)
+            }
             Tuple(args) => Tuple(vecmap(args, |arg| arg.follow_bindings())),
 
             TypeVariable(var) | PolymorphicInteger(_, var) | NamedGeneric(var, _) => {
@@ -1413,10 +1587,11 @@ impl Type {
                 self.clone()
             }
 
-            Function(args, ret) => {
+            Function(args, ret, env) => {
                 let args = vecmap(args, |arg| arg.follow_bindings());
                 let ret = Box::new(ret.follow_bindings());
-                Function(args, ret)
+                let env = Box::new(env.follow_bindings());
+                Function(args, ret, env)
             }
             Vec(element) => Vec(Box::new(element.follow_bindings())),
             MutableReference(element) => MutableReference(Box::new(element.follow_bindings())),
@@ -1429,17 +1604,102 @@ impl Type {
             }
         }
     }
+
+    /// Structurally matches `self`, which may contain NamedGenerics (e.g. the type
+    /// of a generic trait impl), against `other`. On success, `bindings` will contain
+    /// the type each NamedGeneric within `self` would need to be bound to for the two
+    /// types to be equal. No type variables are bound by this function.
+    pub fn match_generics(&self, other: &Type, bindings: &mut TypeBindings) -> bool {
+        self.match_generics_inner(&other.follow_bindings(), bindings)
+    }
+
+    /// Prerequisite: other = other.follow_bindings()
+    fn match_generics_inner(&self, other: &Type, bindings: &mut TypeBindings) -> bool {
+        use Type::*;
+        match (self, other) {
+            (NamedGeneric(binding, _), other) => match &*binding.borrow() {
+                TypeBinding::Bound(typ) => typ.match_generics_inner(other, bindings),
+                TypeBinding::Unbound(id) => match bindings.get(id) {
+                    Some((_, existing)) => existing == other,
+                    None => {
+                        bindings.insert(*id, (binding.clone(), other.clone()));
+                        true
+                    }
+                },
+            },
+
+            (TypeVariable(binding), other) | (PolymorphicInteger(_, binding), other) => {
+                match &*binding.borrow() {
+                    TypeBinding::Bound(typ) => typ.match_generics_inner(other, bindings),
+                    TypeBinding::Unbound(_) => false,
+                }
+            }
+
+            (Array(len_a, elem_a), Array(len_b, elem_b)) => {
+                len_a.match_generics_inner(len_b, bindings)
+                    && elem_a.match_generics_inner(elem_b, bindings)
+            }
+
+            (String(len_a), String(len_b)) => len_a.match_generics_inner(len_b, bindings),
+
+            (Tuple(elements_a), Tuple(elements_b)) => {
+                elements_a.len() == elements_b.len()
+                    && elements_a
+                        .iter()
+                        .zip(elements_b)
+                        .all(|(a, b)| a.match_generics_inner(b, bindings))
+            }
+
+            (Struct(def_a, args_a), Struct(def_b, args_b)) => {
+                def_a.borrow().id == def_b.borrow().id
+                    && args_a.iter().zip(args_b).all(|(a, b)| a.match_generics_inner(b, bindings))
+            }
+
+            (Enum(def_a, args_a), Enum(def_b, args_b)) => {
+                def_a.borrow().id == def_b.borrow().id
+                    && args_a.iter().zip(args_b).all(|(a, b)| a.match_generics_inner(b, bindings))
+            }
+
+            // Integer literals whose type has not yet been decided default to Field
+            (FieldElement(_), FieldElement(_) | PolymorphicInteger(_, _)) => true,
+
+            (Integer(_, sign_a, bits_a), Integer(_, sign_b, bits_b)) => {
+                sign_a == sign_b && bits_a == bits_b
+            }
+
+            (Bool(_), Bool(_)) | (Unit, Unit) => true,
+
+            (Constant(a), Constant(b)) => a == b,
+
+            (Function(params_a, ret_a, env_a), Function(params_b, ret_b, env_b)) => {
+                params_a.len() == params_b.len()
+                    && params_a
+                        .iter()
+                        .zip(params_b)
+                        .all(|(a, b)| a.match_generics_inner(b, bindings))
+                    && ret_a.match_generics_inner(ret_b, bindings)
+                    && env_a.match_generics_inner(env_b, bindings)
+            }
+
+            (Vec(elem_a), Vec(elem_b)) | (MutableReference(elem_a), MutableReference(elem_b)) => {
+                elem_a.match_generics_inner(elem_b, bindings)
+            }
+
+            _ => false,
+        }
+    }
 }
 
 impl BinaryTypeOperator {
-    /// Return the actual rust numeric function associated with this operator
-    pub fn function(self) -> fn(u64, u64) -> u64 {
+    /// Return the actual rust numeric function associated with this operator.
+    /// The function returns `None` on overflow, underflow or division by zero.
+    pub fn function(self) -> fn(u64, u64) -> Option<u64> {
         match self {
-            BinaryTypeOperator::Addition => |a, b| a.wrapping_add(b),
-            BinaryTypeOperator::Subtraction => |a, b| a.wrapping_sub(b),
-            BinaryTypeOperator::Multiplication => |a, b| a.wrapping_mul(b),
-            BinaryTypeOperator::Division => |a, b| a.wrapping_div(b),
-            BinaryTypeOperator::Modulo => |a, b| a.wrapping_rem(b), // % b,
+            BinaryTypeOperator::Addition => |a, b| a.checked_add(b),
+            BinaryTypeOperator::Subtraction => |a, b| a.checked_sub(b),
+            BinaryTypeOperator::Multiplication => |a, b| a.checked_mul(b),
+            BinaryTypeOperator::Division => |a, b| a.checked_div(b),
+            BinaryTypeOperator::Modulo => |a, b| a.checked_rem(b),
         }
     }
 }
@@ -17,6 +17,7 @@ pub struct Lexer<'a> {
     char_iter: Peekable<Zip<Chars<'a>, RangeFrom<u32>>>,
     position: Position,
     done: bool,
+    skip_comments: bool,
 }
 
 pub type SpannedTokenResult = Result<SpannedToken, LexerErrorKind>;
@@ -37,15 +38,24 @@ impl<'a> Lexer<'a> {
         (Tokens(tokens), errors)
     }
 
-    fn new(source: &'a str) -> Self {
+    pub fn new(source: &'a str) -> Self {
         Lexer {
             // We zip with the character index here to ensure the first char has index 0
             char_iter: source.chars().zip(0..).peekable(),
             position: 0,
             done: false,
+            skip_comments: true,
         }
     }
 
+    /// Comments are skipped by default as they are insignificant to the parser.
+    /// Tools such as the formatter which need to preserve them can instead have
+    /// them issued as `Token::LineComment`s.
+    pub fn skip_comments(mut self, flag: bool) -> Self {
+        self.skip_comments = flag;
+        self
+    }
+
     /// Iterates the cursor and returns the char at the new cursor position
     fn next_char(&mut self) -> Option<char> {
         let (c, index) = self.char_iter.next()?;
@@ -169,7 +179,18 @@ impl<'a> Lexer<'a> {
                 }
             }
             Token::Bang => self.single_double_peek_token('=', prev_token, Token::NotEqual),
-            Token::Assign => self.single_double_peek_token('=', prev_token, Token::Equal),
+            Token::Assign => {
+                let start = self.position;
+                if self.peek_char_is('=') {
+                    self.next_char();
+                    Ok(Token::Equal.into_span(start, start + 1))
+                } else if self.peek_char_is('>') {
+                    self.next_char();
+                    Ok(Token::FatArrow.into_span(start, start + 1))
+                } else {
+                    Ok(prev_token.into_single_span(start))
+                }
+            }
             Token::Minus => self.single_double_peek_token('>', prev_token, Token::Arrow),
             Token::Colon => self.single_double_peek_token(':', prev_token, Token::DoubleColon),
             Token::Slash => {
@@ -257,10 +278,8 @@ impl<'a> Lexer<'a> {
         }
         self.next_char();
 
-        let (word, start, end) = self.eat_while(None, |ch| {
-            (ch.is_ascii_alphabetic() || ch.is_numeric() || ch == '_' || ch == '(' || ch == ')')
-                && (ch != ']')
-        });
+        // Attributes may not span multiple lines
+        let (word, start, end) = self.eat_while(None, |ch| ch != ']' && ch != '\n');
 
         if !self.peek_char_is(']') {
             return Err(LexerErrorKind::UnexpectedCharacter {
@@ -331,8 +350,15 @@ impl<'a> Lexer<'a> {
     }
 
     fn parse_comment(&mut self) -> SpannedTokenResult {
-        let _ = self.eat_while(None, |ch| ch != '\n');
-        self.next_token()
+        // The opening `//` has already been consumed
+        let start = self.position - 1;
+        let (comment, _, end) = self.eat_while(None, |ch| ch != '\n');
+
+        if self.skip_comments {
+            self.next_token()
+        } else {
+            Ok(Token::LineComment(comment).into_span(start, end))
+        }
     }
 
     /// Skips white space. They are not significant in the source language
@@ -354,7 +380,7 @@ impl<'a> Iterator for Lexer<'a> {
 
 #[test]
 fn test_single_double_char() {
-    let input = "! != + ( ) { } [ ] | , ; : :: < <= > >= & - -> . .. % / * = == << >>";
+    let input = "! != + ( ) { } [ ] | , ; : :: < <= > >= & - -> . .. % / * = == => << >>";
 
     let expected = vec![
         Token::Bang,
@@ -385,6 +411,7 @@ fn test_single_double_char() {
         Token::Star,
         Token::Assign,
         Token::Equal,
+        Token::FatArrow,
         Token::ShiftLeft,
         Token::Greater,
         Token::Greater,
@@ -416,6 +443,34 @@ fn test_custom_gate_syntax() {
     }
 }
 
+#[test]
+fn test_test_attributes() {
+    use super::token::TestScope;
+
+    let input = r#"#[test] #[test(should_fail)] #[test(should_fail_with = "not equal")]"#;
+
+    let expected = vec![
+        Token::Attribute(Attribute::Test(TestScope::None)),
+        Token::Attribute(Attribute::Test(TestScope::ShouldFail { reason: None })),
+        Token::Attribute(Attribute::Test(TestScope::ShouldFail {
+            reason: Some("not equal".to_string()),
+        })),
+    ];
+
+    let mut lexer = Lexer::new(input);
+    for token in expected.into_iter() {
+        let got = lexer.next_token().unwrap();
+        assert_eq!(got, token);
+    }
+}
+
+#[test]
+fn test_malformed_test_attribute() {
+    let input = "#[test(should_pass)]";
+    let mut lexer = Lexer::new(input);
+    assert!(lexer.next_token().is_err());
+}
+
 #[test]
 fn test_int_type() {
     let input = "u16 i16 i108 u104.5";
@@ -480,6 +535,32 @@ fn test_comment() {
     }
 }
 
+#[test]
+fn test_preserved_comment() {
+    let input = "let x = 5; // five
+        // hello";
+
+    let expected = vec![
+        Token::Keyword(Keyword::Let),
+        Token::Ident("x".to_string()),
+        Token::Assign,
+        Token::Int(FieldElement::from(5_i128)),
+        Token::Semicolon,
+        Token::LineComment(" five".to_string()),
+        Token::LineComment(" hello".to_string()),
+        Token::EOF,
+    ];
+
+    let mut lexer = Lexer::new(input).skip_comments(false);
+    for token in expected.into_iter() {
+        let first_lexer_output = lexer.next_token().unwrap();
+        assert_eq!(first_lexer_output, token);
+    }
+
+    let comment = Lexer::new(input).skip_comments(false).nth(5).unwrap().unwrap();
+    assert_eq!(comment.to_span().start(), 11);
+}
+
 #[test]
 fn test_eat_string_literal() {
     let input = "let _word = \"hello\"";
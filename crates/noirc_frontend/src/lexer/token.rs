@@ -18,6 +18,8 @@ pub enum Token {
     Keyword(Keyword),
     IntType(IntType),
     Attribute(Attribute),
+    /// The contents of a `// comment`, only issued if the lexer is not skipping comments
+    LineComment(String),
     /// <
     Less,
     /// <=
@@ -66,6 +68,8 @@ pub enum Token {
     RightBracket,
     /// ->
     Arrow,
+    /// =>
+    FatArrow,
     /// |
     Pipe,
     /// #
@@ -149,6 +153,7 @@ impl fmt::Display for Token {
             Token::Str(ref b) => write!(f, "{b}"),
             Token::Keyword(k) => write!(f, "{k}"),
             Token::Attribute(ref a) => write!(f, "{a}"),
+            Token::LineComment(ref comment) => write!(f, "//{comment}"),
             Token::IntType(ref i) => write!(f, "{i}"),
             Token::Less => write!(f, "<"),
             Token::LessEqual => write!(f, "<="),
@@ -174,6 +179,7 @@ impl fmt::Display for Token {
             Token::LeftBracket => write!(f, "["),
             Token::RightBracket => write!(f, "]"),
             Token::Arrow => write!(f, "->"),
+            Token::FatArrow => write!(f, "=>"),
             Token::Pipe => write!(f, "|"),
             Token::Pound => write!(f, "#"),
             Token::Comma => write!(f, ","),
@@ -325,7 +331,43 @@ pub enum Attribute {
     Foreign(String),
     Builtin(String),
     Oracle(String),
-    Test,
+    Test(TestScope),
+}
+
+/// Describes the expected outcome of a `#[test]` function.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, PartialOrd, Ord)]
+pub enum TestScope {
+    /// The test is expected to execute successfully.
+    None,
+    /// The test is expected to fail, optionally with a failure message containing `reason`.
+    ShouldFail { reason: Option<String> },
+}
+
+impl TestScope {
+    /// Parses the arguments of a `#[test(...)]` attribute,
+    /// e.g. `should_fail` or `should_fail_with = "reason"`.
+    fn lookup_str(string: &str) -> Option<TestScope> {
+        let string = string.trim();
+        if string == "should_fail" {
+            return Some(TestScope::ShouldFail { reason: None });
+        }
+
+        let reason = string.strip_prefix("should_fail_with")?.trim_start().strip_prefix('=')?;
+        let reason = reason.trim().strip_prefix('"')?.strip_suffix('"')?;
+        Some(TestScope::ShouldFail { reason: Some(reason.to_owned()) })
+    }
+}
+
+impl fmt::Display for TestScope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TestScope::None => write!(f, ""),
+            TestScope::ShouldFail { reason: None } => write!(f, "(should_fail)"),
+            TestScope::ShouldFail { reason: Some(reason) } => {
+                write!(f, "(should_fail_with = {reason:?})")
+            }
+        }
+    }
 }
 
 impl fmt::Display for Attribute {
@@ -334,7 +376,7 @@ impl fmt::Display for Attribute {
             Attribute::Foreign(ref k) => write!(f, "#[foreign({k})]"),
             Attribute::Builtin(ref k) => write!(f, "#[builtin({k})]"),
             Attribute::Oracle(ref k) => write!(f, "#[oracle({k})]"),
-            Attribute::Test => write!(f, "#[test]"),
+            Attribute::Test(ref scope) => write!(f, "#[test{scope}]"),
         }
     }
 }
@@ -343,20 +385,27 @@ impl Attribute {
     /// If the string is a fixed attribute return that, else
     /// return the custom attribute
     pub(crate) fn lookup_attribute(word: &str, span: Span) -> Result<Token, LexerErrorKind> {
+        // Test attributes are handled separately as `should_fail_with` may contain any character
+        if word == "test" {
+            return Ok(Token::Attribute(Attribute::Test(TestScope::None)));
+        }
+        if let Some(arguments) = word.strip_prefix("test(").and_then(|rest| rest.strip_suffix(')'))
+        {
+            return match TestScope::lookup_str(arguments) {
+                Some(scope) => Ok(Token::Attribute(Attribute::Test(scope))),
+                None => {
+                    Err(LexerErrorKind::MalformedFuncAttribute { span, found: word.to_owned() })
+                }
+            };
+        }
+
         let word_segments: Vec<&str> = word
             .split(|c| c == '(' || c == ')')
             .filter(|string_segment| !string_segment.is_empty())
             .collect();
 
         if word_segments.len() != 2 {
-            if word_segments.len() == 1 && word_segments[0] == "test" {
-                return Ok(Token::Attribute(Attribute::Test));
-            } else {
-                return Err(LexerErrorKind::MalformedFuncAttribute {
-                    span,
-                    found: word.to_owned(),
-                });
-            }
+            return Err(LexerErrorKind::MalformedFuncAttribute { span, found: word.to_owned() });
         }
 
         let attribute_type = word_segments[0];
@@ -402,7 +451,7 @@ impl AsRef<str> for Attribute {
             Attribute::Foreign(string) => string,
             Attribute::Builtin(string) => string,
             Attribute::Oracle(string) => string,
-            Attribute::Test => "",
+            Attribute::Test(_) => "",
         }
     }
 }
@@ -416,14 +465,17 @@ pub enum Keyword {
     As,
     Assert,
     Bool,
+    Break,
     Char,
     CompTime,
     Constrain,
     Contract,
+    Continue,
     Crate,
     Dep,
     Distinct,
     Else,
+    Enum,
     Field,
     Fn,
     For,
@@ -432,6 +484,7 @@ pub enum Keyword {
     If,
     In,
     Let,
+    Match,
     Mod,
     Mut,
     Open,
@@ -439,9 +492,11 @@ pub enum Keyword {
     String,
     Return,
     Struct,
+    Trait,
     Unconstrained,
     Use,
     Vec,
+    Where,
     While,
 }
 
@@ -451,14 +506,17 @@ impl fmt::Display for Keyword {
             Keyword::As => write!(f, "as"),
             Keyword::Assert => write!(f, "assert"),
             Keyword::Bool => write!(f, "bool"),
+            Keyword::Break => write!(f, "break"),
             Keyword::Char => write!(f, "char"),
             Keyword::CompTime => write!(f, "comptime"),
             Keyword::Constrain => write!(f, "constrain"),
             Keyword::Contract => write!(f, "contract"),
+            Keyword::Continue => write!(f, "continue"),
             Keyword::Crate => write!(f, "crate"),
             Keyword::Dep => write!(f, "dep"),
             Keyword::Distinct => write!(f, "distinct"),
             Keyword::Else => write!(f, "else"),
+            Keyword::Enum => write!(f, "enum"),
             Keyword::Field => write!(f, "Field"),
             Keyword::Fn => write!(f, "fn"),
             Keyword::For => write!(f, "for"),
@@ -467,6 +525,7 @@ impl fmt::Display for Keyword {
             Keyword::If => write!(f, "if"),
             Keyword::In => write!(f, "in"),
             Keyword::Let => write!(f, "let"),
+            Keyword::Match => write!(f, "match"),
             Keyword::Mod => write!(f, "mod"),
             Keyword::Mut => write!(f, "mut"),
             Keyword::Open => write!(f, "open"),
@@ -474,9 +533,11 @@ impl fmt::Display for Keyword {
             Keyword::String => write!(f, "str"),
             Keyword::Return => write!(f, "return"),
             Keyword::Struct => write!(f, "struct"),
+            Keyword::Trait => write!(f, "trait"),
             Keyword::Unconstrained => write!(f, "unconstrained"),
             Keyword::Use => write!(f, "use"),
             Keyword::Vec => write!(f, "Vec"),
+            Keyword::Where => write!(f, "where"),
             Keyword::While => write!(f, "while"),
         }
     }
@@ -489,14 +550,17 @@ impl Keyword {
             "as" => Keyword::As,
             "assert" => Keyword::Assert,
             "bool" => Keyword::Bool,
+            "break" => Keyword::Break,
             "char" => Keyword::Char,
             "comptime" => Keyword::CompTime,
             "constrain" => Keyword::Constrain,
             "contract" => Keyword::Contract,
+            "continue" => Keyword::Continue,
             "crate" => Keyword::Crate,
             "dep" => Keyword::Dep,
             "distinct" => Keyword::Distinct,
             "else" => Keyword::Else,
+            "enum" => Keyword::Enum,
             "Field" => Keyword::Field,
             "fn" => Keyword::Fn,
             "for" => Keyword::For,
@@ -505,6 +569,7 @@ impl Keyword {
             "if" => Keyword::If,
             "in" => Keyword::In,
             "let" => Keyword::Let,
+            "match" => Keyword::Match,
             "mod" => Keyword::Mod,
             "mut" => Keyword::Mut,
             "open" => Keyword::Open,
@@ -512,9 +577,11 @@ impl Keyword {
             "str" => Keyword::String,
             "return" => Keyword::Return,
             "struct" => Keyword::Struct,
+            "trait" => Keyword::Trait,
             "unconstrained" => Keyword::Unconstrained,
             "use" => Keyword::Use,
             "Vec" => Keyword::Vec,
+            "where" => Keyword::Where,
             "while" => Keyword::While,
 
             "true" => return Some(Token::Bool(true)),
@@ -24,7 +24,7 @@ pub mod hir_def;
 pub use lexer::token;
 
 // Parser API
-pub use parser::{parse_program, ParsedModule};
+pub use parser::{parse_program, ParsedModule, SortedModule};
 
 // AST API
 pub use ast::*;
@@ -25,6 +25,7 @@ pub enum Expression {
     Index(Index),
     Cast(Cast),
     For(For),
+    While(While),
     If(If),
     Tuple(Vec<Expression>),
     ExtractTupleField(Box<Expression>, usize),
@@ -34,6 +35,23 @@ pub enum Expression {
     Constrain(Box<Expression>, Location),
     Assign(Assign),
     Semi(Box<Expression>),
+    Break,
+    Continue,
+}
+
+impl Expression {
+    /// Returns the source location of the expression, for the expressions which keep one.
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            Expression::Ident(ident) => ident.location,
+            Expression::Binary(binary) => Some(binary.location),
+            Expression::Index(index) => Some(index.location),
+            Expression::Call(call) => Some(call.location),
+            Expression::While(while_expr) => Some(while_expr.location),
+            Expression::Constrain(_, location) => Some(*location),
+            _ => None,
+        }
+    }
 }
 
 /// A definition is either a local (variable), function, or is a built-in
@@ -77,6 +95,19 @@ pub struct For {
     pub block: Box<Expression>,
 }
 
+#[derive(Debug, Clone)]
+pub struct While {
+    pub condition: Box<Expression>,
+
+    /// The maximum number of times the block may run, which is always known for loops within
+    /// constrained functions. Running the block more times than this is a failed constraint.
+    pub max_iterations: Option<u64>,
+    pub block: Box<Expression>,
+
+    /// The location reported when the loop runs more than `max_iterations` times
+    pub location: Location,
+}
+
 #[derive(Debug, Clone)]
 pub enum Literal {
     Array(ArrayLiteral),
@@ -22,7 +22,7 @@ use crate::{
     },
     node_interner::{self, DefinitionKind, NodeInterner, StmtId},
     token::Attribute,
-    CompTime, FunctionKind, TypeBinding, TypeBindings,
+    BinaryOpKind, CompTime, FunctionKind, TypeBinding, TypeBindings,
 };
 
 use self::ast::{Definition, FuncId, Function, LocalId, Program};
@@ -58,10 +58,22 @@ struct Monomorphizer<'interner> {
     /// Used to reference existing definitions in the HIR
     interner: &'interner NodeInterner,
 
+    /// The closures currently being monomorphized, innermost last. Variables captured by
+    /// the innermost closure are read from its environment rather than referenced directly.
+    lambda_envs_stack: Vec<LambdaContext>,
+
     next_local_id: u32,
     next_function_id: u32,
 }
 
+/// The environment of a closure being monomorphized
+struct LambdaContext {
+    /// The parameter holding the environment tuple of the closure
+    env_ident: ast::Ident,
+    /// The variables captured by the closure, in the order of the environment's fields
+    captures: Vec<node_interner::DefinitionId>,
+}
+
 type HirType = crate::Type;
 
 /// Starting from the given `main` function, monomorphize the entire program,
@@ -100,6 +112,7 @@ impl<'interner> Monomorphizer<'interner> {
             locals: HashMap::new(),
             queue: VecDeque::new(),
             finished_functions: BTreeMap::new(),
+            lambda_envs_stack: Vec::new(),
             next_local_id: 0,
             next_function_id: 0,
             interner,
@@ -127,6 +140,7 @@ impl<'interner> Monomorphizer<'interner> {
         id: node_interner::FuncId,
         expr_id: node_interner::ExprId,
         typ: &HirType,
+        bindings: Option<TypeBindings>,
     ) -> Definition {
         let typ = typ.follow_bindings();
         match self.globals.get(&id).and_then(|inner_map| inner_map.get(&typ)) {
@@ -150,7 +164,7 @@ impl<'interner> Monomorphizer<'interner> {
                         Definition::Builtin(opcode)
                     }
                     FunctionKind::Normal => {
-                        let id = self.queue_function(id, expr_id, typ);
+                        let id = self.queue_function(id, expr_id, typ, bindings);
                         Definition::Function(id)
                     }
                     FunctionKind::Oracle => {
@@ -320,6 +334,21 @@ impl<'interner> Monomorphizer<'interner> {
                 })
             }
 
+            HirExpression::While(while_expr) => {
+                let max_iterations = while_expr.max_iterations.map(|max| {
+                    max.evaluate_to_u64().expect(
+                        "Maximum number of iterations is unknown when evaluating numeric generic",
+                    )
+                });
+
+                ast::Expression::While(ast::While {
+                    condition: Box::new(self.expr(while_expr.condition)),
+                    max_iterations,
+                    block: Box::new(self.expr(while_expr.block)),
+                    location: self.interner.expr_location(&expr),
+                })
+            }
+
             HirExpression::If(if_expr) => {
                 let cond = self.expr(if_expr.condition);
                 let then = self.expr(if_expr.consequence);
@@ -337,6 +366,8 @@ impl<'interner> Monomorphizer<'interner> {
                 ast::Expression::Tuple(fields)
             }
             HirExpression::Constructor(constructor) => self.constructor(constructor, expr),
+            HirExpression::EnumConstructor(constructor) => self.enum_constructor(constructor, expr),
+            HirExpression::Match(match_expr) => self.match_expr(match_expr, expr),
 
             HirExpression::Lambda(lambda) => self.lambda(lambda),
 
@@ -383,7 +414,10 @@ impl<'interner> Monomorphizer<'interner> {
             | ast::Type::Bool
             | ast::Type::Unit
             | ast::Type::Function(_, _)
-            | ast::Type::MutableReference(_) => {
+            | ast::Type::MutableReference(_)
+            | ast::Type::Array(_, _)
+            | ast::Type::String(_)
+            | ast::Type::Vec(_) => {
                 ast::Expression::Literal(ast::Literal::Array(ast::ArrayLiteral {
                     contents: array_contents,
                     element_type,
@@ -400,10 +434,6 @@ impl<'interner> Monomorphizer<'interner> {
                     Self::aos_to_soa(contents, element_type)
                 },
             )),
-
-            ast::Type::Array(_, _) | ast::Type::String(_) | ast::Type::Vec(_) => {
-                unreachable!("Nested arrays, arrays of strings, and Vecs are not supported")
-            }
         }
     }
 
@@ -430,7 +460,10 @@ impl<'interner> Monomorphizer<'interner> {
             | ast::Type::Bool
             | ast::Type::Unit
             | ast::Type::Function(_, _)
-            | ast::Type::MutableReference(_) => {
+            | ast::Type::MutableReference(_)
+            | ast::Type::Array(_, _)
+            | ast::Type::String(_)
+            | ast::Type::Vec(_) => {
                 ast::Expression::Index(ast::Index { collection, index, element_type, location })
             }
 
@@ -444,10 +477,6 @@ impl<'interner> Monomorphizer<'interner> {
                     Self::aos_to_soa_index(collection, index.clone(), element_type, location)
                 }))
             }
-
-            ast::Type::Array(_, _) | ast::Type::String(_) | ast::Type::Vec(_) => {
-                unreachable!("Nested arrays and arrays of strings or Vecs are not supported")
-            }
         }
     }
 
@@ -462,6 +491,8 @@ impl<'interner> Monomorphizer<'interner> {
             HirStatement::Assign(assign) => self.assign(assign),
             HirStatement::Expression(expr) => self.expr(expr),
             HirStatement::Semi(expr) => ast::Expression::Semi(Box::new(self.expr(expr))),
+            HirStatement::Break => ast::Expression::Break,
+            HirStatement::Continue => ast::Expression::Continue,
             HirStatement::Error => unreachable!(),
         }
     }
@@ -521,6 +552,136 @@ impl<'interner> Monomorphizer<'interner> {
         ast::Expression::Block(new_exprs)
     }
 
+    /// Enums are represented as a tuple of their variant tag followed by a tuple of fields for
+    /// each variant. Each variant other than the one being constructed is zeroed.
+    fn enum_constructor(
+        &mut self,
+        constructor: HirEnumConstructorExpression,
+        id: node_interner::ExprId,
+    ) -> ast::Expression {
+        let typ = self.interner.id_type(id);
+        let variants = unwrap_enum_type(&typ);
+        let location = self.interner.expr_location(&id);
+
+        let tag = FieldElement::from(constructor.variant_index as u128);
+        let mut fields =
+            vec![ast::Expression::Literal(ast::Literal::Integer(tag, ast::Type::Field))];
+        let mut arguments = Some(vecmap(constructor.arguments, |arg| self.expr(arg)));
+
+        for (i, variant_fields) in variants.iter().enumerate() {
+            if i == constructor.variant_index {
+                fields.push(ast::Expression::Tuple(arguments.take().unwrap()));
+            } else {
+                let variant_type = ast::Type::Tuple(vecmap(variant_fields, Self::convert_type));
+                fields.push(self.zeroed_value_of_type(&variant_type, location));
+            }
+        }
+
+        ast::Expression::Tuple(fields)
+    }
+
+    /// Lowers a match expression into a chain of if expressions comparing the variant
+    /// tag of the matched value. The final rule needs no condition since the match is
+    /// known to be exhaustive, as are any rules following a catch-all rule.
+    fn match_expr(
+        &mut self,
+        match_expr: HirMatchExpression,
+        id: node_interner::ExprId,
+    ) -> ast::Expression {
+        let scrutinee_type = self.interner.id_type(match_expr.expression);
+        let result_type = Self::convert_type(&self.interner.id_type(id));
+        let location = self.interner.expr_location(&id);
+
+        let fresh_id = self.next_local_id();
+        let value = ast::Expression::Let(ast::Let {
+            id: fresh_id,
+            mutable: false,
+            name: "_".into(),
+            expression: Box::new(self.expr(match_expr.expression)),
+        });
+
+        let variable = ast::Ident {
+            location: None,
+            mutable: false,
+            definition: Definition::Local(fresh_id),
+            name: "_".into(),
+            typ: Self::convert_type(&scrutinee_type),
+        };
+
+        let mut rules = match_expr.rules;
+        if let Some(catchall) =
+            rules.iter().position(|(pattern, _)| matches!(pattern, HirMatchPattern::Catchall(_)))
+        {
+            rules.truncate(catchall + 1);
+        }
+
+        let mut rules = rules.into_iter().rev();
+        let mut result = match rules.next() {
+            Some((pattern, branch)) => {
+                self.match_branch(pattern, branch, &variable, &scrutinee_type)
+            }
+            None => return ast::Expression::Block(vec![value]),
+        };
+
+        for (pattern, branch) in rules {
+            let variant_index = match &pattern {
+                HirMatchPattern::Variant(_, variant_index, _, _) => *variant_index,
+                HirMatchPattern::Catchall(_) => {
+                    unreachable!("Only the last rule may be a catch-all")
+                }
+            };
+
+            let tag = ast::Expression::ExtractTupleField(
+                Box::new(ast::Expression::Ident(variable.clone())),
+                0,
+            );
+            let expected_tag = FieldElement::from(variant_index as u128);
+            let expected_tag = ast::Literal::Integer(expected_tag, ast::Type::Field);
+
+            let condition = ast::Expression::Binary(ast::Binary {
+                lhs: Box::new(tag),
+                operator: BinaryOpKind::Equal,
+                rhs: Box::new(ast::Expression::Literal(expected_tag)),
+                location,
+            });
+
+            let consequence = self.match_branch(pattern, branch, &variable, &scrutinee_type);
+
+            result = ast::Expression::If(ast::If {
+                condition: Box::new(condition),
+                consequence: Box::new(consequence),
+                alternative: Some(Box::new(result)),
+                typ: result_type.clone(),
+            });
+        }
+
+        ast::Expression::Block(vec![value, result])
+    }
+
+    /// Binds the variables of a match rule's pattern before evaluating its branch
+    fn match_branch(
+        &mut self,
+        pattern: HirMatchPattern,
+        branch: node_interner::ExprId,
+        variable: &ast::Ident,
+        scrutinee_type: &HirType,
+    ) -> ast::Expression {
+        let value = ast::Expression::Ident(variable.clone());
+
+        let bindings = match pattern {
+            HirMatchPattern::Variant(_, variant_index, fields, _) => {
+                let field_types = unwrap_enum_type(scrutinee_type).swap_remove(variant_index);
+                let value = ast::Expression::ExtractTupleField(Box::new(value), variant_index + 1);
+                self.unpack_tuple_pattern(value, fields.into_iter().zip(field_types))
+            }
+            HirMatchPattern::Catchall(pattern) => {
+                self.unpack_pattern(pattern, value, scrutinee_type)
+            }
+        };
+
+        ast::Expression::Block(vec![bindings, self.expr(branch)])
+    }
+
     fn block(&mut self, statement_ids: Vec<StmtId>) -> ast::Expression {
         ast::Expression::Block(vecmap(statement_ids, |id| self.statement(id)))
     }
@@ -611,6 +772,18 @@ impl<'interner> Monomorphizer<'interner> {
         Some(ast::Ident { location: Some(ident.location), mutable, definition, name, typ })
     }
 
+    /// A local variable, which is read from the environment of the current closure if it
+    /// was captured by it
+    fn local_variable(&mut self, ident: &HirIdent) -> ast::Expression {
+        if let Some(lambda) = self.lambda_envs_stack.last() {
+            if let Some(index) = lambda.captures.iter().position(|id| *id == ident.id) {
+                let env = Box::new(ast::Expression::Ident(lambda.env_ident.clone()));
+                return ast::Expression::ExtractTupleField(env, index);
+            }
+        }
+        ast::Expression::Ident(self.local_ident(ident).unwrap())
+    }
+
     fn ident(&mut self, ident: HirIdent, expr_id: node_interner::ExprId) -> ast::Expression {
         let definition = self.interner.definition(ident.id);
         match &definition.kind {
@@ -620,16 +793,19 @@ impl<'interner> Monomorphizer<'interner> {
                 let name = definition.name.clone();
                 let typ = self.interner.id_type(expr_id);
 
-                let definition = self.lookup_function(*func_id, expr_id, &typ);
+                // Calls to trait methods are dispatched to the impl for the concrete Self type
+                let (func_id, bindings) = match self.resolve_trait_method(*func_id, expr_id, &typ) {
+                    Some((impl_method_id, bindings)) => (impl_method_id, Some(bindings)),
+                    None => (*func_id, None),
+                };
+
+                let definition = self.lookup_function(func_id, expr_id, &typ, bindings);
                 let typ = Self::convert_type(&typ);
                 let ident = ast::Ident { location, mutable, definition, name, typ };
                 ast::Expression::Ident(ident)
             }
             DefinitionKind::Global(expr_id) => self.expr(*expr_id),
-            DefinitionKind::Local(_) => {
-                let ident = self.local_ident(&ident).unwrap();
-                ast::Expression::Ident(ident)
-            }
+            DefinitionKind::Local(_) => self.local_variable(&ident),
             DefinitionKind::GenericType(type_variable) => {
                 let value = match &*type_variable.borrow() {
                     TypeBinding::Unbound(_) => {
@@ -686,15 +862,36 @@ impl<'interner> Monomorphizer<'interner> {
                 ast::Type::Tuple(fields)
             }
 
+            HirType::Enum(def, args) => {
+                let def = def.borrow();
+                let mut fields = vec![ast::Type::Field];
+
+                for variant_index in 0..def.num_variants() {
+                    let variant_fields = def.variant_fields(variant_index, args);
+                    let variant_fields = vecmap(variant_fields, |field| Self::convert_type(&field));
+                    fields.push(ast::Type::Tuple(variant_fields));
+                }
+
+                ast::Type::Tuple(fields)
+            }
+
             HirType::Tuple(fields) => {
                 let fields = vecmap(fields, Self::convert_type);
                 ast::Type::Tuple(fields)
             }
 
-            HirType::Function(args, ret) => {
+            HirType::Function(args, ret, env) => {
                 let args = vecmap(args, Self::convert_type);
                 let ret = Box::new(Self::convert_type(ret));
-                ast::Type::Function(args, ret)
+                match Self::convert_env_type(env) {
+                    None => ast::Type::Function(args, ret),
+                    // Closures are a tuple of their environment and of a function
+                    // taking that environment as its first parameter
+                    Some(env) => {
+                        let args = std::iter::once(env.clone()).chain(args).collect();
+                        ast::Type::Tuple(vec![env, ast::Type::Function(args, ret)])
+                    }
+                }
             }
 
             HirType::Vec(element) => {
@@ -713,8 +910,20 @@ impl<'interner> Monomorphizer<'interner> {
         }
     }
 
+    /// Converts the environment of a function type, returning None if the function
+    /// doesn't capture anything.
+    fn convert_env_type(env: &HirType) -> Option<ast::Type> {
+        match env.follow_bindings() {
+            // An environment left unbound by type checking was never unified with that of
+            // a closure, so the function is a plain function.
+            HirType::Unit | HirType::TypeVariable(_) => None,
+            env => Some(Self::convert_type(&env)),
+        }
+    }
+
     /// Converts arrays of structs (AOS) into structs of arrays (SOA).
     /// This is required since our SSA pass does not support arrays of structs.
+    /// Nested arrays and arrays of Vecs are kept as such.
     fn aos_to_soa_type(length: u64, element: ast::Type) -> ast::Type {
         match element {
             ast::Type::Field
@@ -722,15 +931,14 @@ impl<'interner> Monomorphizer<'interner> {
             | ast::Type::Bool
             | ast::Type::Unit
             | ast::Type::Function(_, _)
-            | ast::Type::MutableReference(_) => ast::Type::Array(length, Box::new(element)),
+            | ast::Type::MutableReference(_)
+            | ast::Type::Array(_, _)
+            | ast::Type::String(_)
+            | ast::Type::Vec(_) => ast::Type::Array(length, Box::new(element)),
 
             ast::Type::Tuple(elements) => {
                 ast::Type::Tuple(vecmap(elements, |typ| Self::aos_to_soa_type(length, typ)))
             }
-
-            ast::Type::Array(_, _) | ast::Type::String(_) | ast::Type::Vec(_) => {
-                unreachable!("Nested arrays and arrays of strings are not supported")
-            }
         }
     }
 
@@ -739,13 +947,43 @@ impl<'interner> Monomorphizer<'interner> {
         call: HirCallExpression,
         id: node_interner::ExprId,
     ) -> ast::Expression {
+        let func_type = self.interner.id_type(call.func);
         let func = Box::new(self.expr(call.func));
-        let arguments = vecmap(&call.arguments, |id| self.expr(*id));
+        let mut arguments = vecmap(&call.arguments, |id| self.expr(*id));
         let return_type = self.interner.id_type(id);
         let return_type = Self::convert_type(&return_type);
         let location = call.location;
 
-        self.try_evaluate_call(&func, &call.arguments, &return_type)
+        if let HirType::Function(_, _, env) = func_type.follow_bindings() {
+            if Self::convert_env_type(&env).is_some() {
+                // The closure is bound to a local so that it is only evaluated once
+                // while extracting both its environment and its function.
+                let closure_id = self.next_local_id();
+                let name = "closure".to_owned();
+                let closure = ast::Expression::Let(ast::Let {
+                    id: closure_id,
+                    mutable: false,
+                    name: name.clone(),
+                    expression: func,
+                });
+
+                let closure_ident = Box::new(ast::Expression::Ident(ast::Ident {
+                    location: None,
+                    mutable: false,
+                    definition: Definition::Local(closure_id),
+                    name,
+                    typ: Self::convert_type(&func_type),
+                }));
+
+                arguments.insert(0, ast::Expression::ExtractTupleField(closure_ident.clone(), 0));
+                let func = Box::new(ast::Expression::ExtractTupleField(closure_ident, 1));
+                let call =
+                    ast::Expression::Call(ast::Call { func, arguments, return_type, location });
+                return ast::Expression::Block(vec![closure, call]);
+            }
+        }
+
+        self.try_evaluate_call(&func, &call.arguments, &return_type, location)
             .unwrap_or(ast::Expression::Call(ast::Call { func, arguments, return_type, location }))
     }
 
@@ -760,6 +998,7 @@ impl<'interner> Monomorphizer<'interner> {
         func: &ast::Expression,
         arguments: &[node_interner::ExprId],
         result_type: &ast::Type,
+        location: Location,
     ) -> Option<ast::Expression> {
         if let ast::Expression::Ident(ident) = func {
             if let Definition::Builtin(opcode) = &ident.definition {
@@ -776,7 +1015,7 @@ impl<'interner> Monomorphizer<'interner> {
                         ast::Type::Field,
                     )));
                 } else if opcode == "zeroed" {
-                    return Some(self.zeroed_value_of_type(result_type));
+                    return Some(self.zeroed_value_of_type(result_type, location));
                 }
 
                 let modulus = FieldElement::modulus();
@@ -813,22 +1052,73 @@ impl<'interner> Monomorphizer<'interner> {
         ast::Expression::Literal(ast::Literal::Array(arr_literal))
     }
 
+    /// Queue the given function to be monomorphized. Unless explicit `bindings` are given,
+    /// the function's generics are instantiated using the bindings of the call at `expr_id`.
     fn queue_function(
         &mut self,
         id: node_interner::FuncId,
         expr_id: node_interner::ExprId,
         function_type: HirType,
+        bindings: Option<TypeBindings>,
     ) -> FuncId {
         let new_id = self.next_function_id();
         self.define_global(id, function_type, new_id);
 
-        let bindings = self.interner.get_instantiation_bindings(expr_id);
-        let bindings = self.follow_bindings(bindings);
+        let bindings = match bindings {
+            Some(bindings) => self.follow_bindings(&bindings),
+            None => self.follow_bindings(self.interner.get_instantiation_bindings(expr_id)),
+        };
 
         self.queue.push_back((id, new_id, bindings));
         new_id
     }
 
+    /// If `id` refers to a method declared within a trait, find the method within the impl of
+    /// that trait for the Self type the call at `expr_id` was instantiated with. Returns the id of
+    /// the impl's method along with the bindings needed to instantiate it at `function_type`.
+    ///
+    /// Returns None if `id` is not a trait method, or if the impl does not override the trait's
+    /// default implementation, in which case the trait method itself should be called.
+    fn resolve_trait_method(
+        &self,
+        id: node_interner::FuncId,
+        expr_id: node_interner::ExprId,
+        function_type: &HirType,
+    ) -> Option<(node_interner::FuncId, TypeBindings)> {
+        let trait_id = self.interner.function_meta(&id).trait_id?;
+        let the_trait = self.interner.get_trait(trait_id);
+
+        let bindings = self.interner.get_instantiation_bindings(expr_id);
+        let self_type = match bindings.get(&the_trait.self_type_typevar_id) {
+            Some((_, self_type)) => self_type.follow_bindings(),
+            None => unreachable!("Expected trait method to be instantiated with a Self type"),
+        };
+
+        let (trait_impl, _) =
+            self.interner.lookup_trait_implementation(&self_type, trait_id).unwrap_or_else(|| {
+                unreachable!("No impl of trait {} found for type {}", the_trait, self_type)
+            });
+
+        let method_name = self.interner.function_name(&id);
+        let impl_method_id = *trait_impl.methods.get(method_name)?;
+
+        // Match the (possibly generic) type of the impl's method against the type the trait
+        // method was instantiated with to find what the impl's generics are bound to.
+        let impl_method_type = match self.interner.function_meta(&impl_method_id).typ {
+            HirType::Forall(_, typ) => *typ,
+            typ => typ,
+        };
+
+        // The type checker ensures the impl's method has the signature of the trait's method
+        let mut impl_bindings = TypeBindings::new();
+        if !impl_method_type.match_generics(function_type, &mut impl_bindings) {
+            unreachable!(
+                "ICE: method `{method_name}` of type {impl_method_type} does not match its instantiation {function_type}"
+            );
+        }
+        Some((impl_method_id, impl_bindings))
+    }
+
     /// Follow any type variable links within the given TypeBindings to produce
     /// a new TypeBindings that won't be changed when bindings are pushed or popped
     /// during {perform,undo}_monomorphization_bindings.
@@ -933,6 +1223,14 @@ impl<'interner> Monomorphizer<'interner> {
     }
 
     fn lambda(&mut self, lambda: HirLambda) -> ast::Expression {
+        if lambda.captures.is_empty() {
+            self.lambda_no_capture(lambda)
+        } else {
+            self.lambda_with_captures(lambda)
+        }
+    }
+
+    fn lambda_no_capture(&mut self, lambda: HirLambda) -> ast::Expression {
         let ret_type = Self::convert_type(&lambda.return_type);
         let lambda_name = "lambda";
         let parameter_types = vecmap(&lambda.parameters, |(_, typ)| Self::convert_type(typ));
@@ -965,10 +1263,72 @@ impl<'interner> Monomorphizer<'interner> {
         })
     }
 
+    /// Converts a closure into a function taking its environment as an extra first parameter.
+    /// The closure evaluates to a tuple of its environment, built from the captured variables
+    /// at the point the closure is created, and of that function.
+    fn lambda_with_captures(&mut self, lambda: HirLambda) -> ast::Expression {
+        let ret_type = Self::convert_type(&lambda.return_type);
+        let lambda_name = "lambda";
+        let mut parameter_types = vecmap(&lambda.parameters, |(_, typ)| Self::convert_type(typ));
+
+        // The environment is built before entering the closure, so variables captured
+        // by an enclosing closure are read from that closure's environment instead.
+        let env_fields = vecmap(&lambda.captures, |capture| self.local_variable(capture));
+        let env_type = ast::Type::Tuple(vecmap(&lambda.captures, |capture| {
+            Self::convert_type(&self.interner.id_type(capture.id))
+        }));
+        let env_tuple = ast::Expression::Tuple(env_fields);
+
+        let env_id = self.next_local_id();
+        let env_name = "env".to_owned();
+        let env_ident = ast::Ident {
+            location: None,
+            mutable: false,
+            definition: Definition::Local(env_id),
+            name: env_name.clone(),
+            typ: env_type.clone(),
+        };
+
+        // Manually convert to Parameters type so we can reuse the self.parameters method
+        let parameters = Parameters(vecmap(lambda.parameters, |(pattern, typ)| {
+            Param(pattern, typ, noirc_abi::AbiVisibility::Private)
+        }));
+
+        let captures = vecmap(&lambda.captures, |capture| capture.id);
+        self.lambda_envs_stack.push(LambdaContext { env_ident, captures });
+        let mut parameters = self.parameters(parameters);
+        let body = self.expr(lambda.body);
+        self.lambda_envs_stack.pop();
+
+        parameters.insert(0, (env_id, false, env_name, env_type.clone()));
+        parameter_types.insert(0, env_type);
+
+        let id = self.next_function_id();
+        let return_type = ret_type.clone();
+        let name = lambda_name.to_owned();
+        let unconstrained = false;
+
+        let function = ast::Function { id, name, parameters, body, return_type, unconstrained };
+        self.push_function(id, function);
+
+        let typ = ast::Type::Function(parameter_types, Box::new(ret_type));
+
+        let name = lambda_name.to_owned();
+        let function = ast::Expression::Ident(ast::Ident {
+            definition: Definition::Function(id),
+            mutable: false,
+            location: None,
+            name,
+            typ,
+        });
+
+        ast::Expression::Tuple(vec![env_tuple, function])
+    }
+
     /// Implements std::unsafe::zeroed by returning an appropriate zeroed
     /// ast literal or collection node for the given type. Note that for functions
     /// there is no obvious zeroed value so this should be considered unsafe to use.
-    fn zeroed_value_of_type(&mut self, typ: &ast::Type) -> ast::Expression {
+    fn zeroed_value_of_type(&mut self, typ: &ast::Type, location: Location) -> ast::Expression {
         match typ {
             ast::Type::Field | ast::Type::Integer(..) => {
                 ast::Expression::Literal(ast::Literal::Integer(0_u128.into(), typ.clone()))
@@ -978,7 +1338,7 @@ impl<'interner> Monomorphizer<'interner> {
             // anyway.
             ast::Type::Unit => ast::Expression::Literal(ast::Literal::Bool(false)),
             ast::Type::Array(length, element_type) => {
-                let element = self.zeroed_value_of_type(element_type.as_ref());
+                let element = self.zeroed_value_of_type(element_type.as_ref(), location);
                 ast::Expression::Literal(ast::Literal::Array(ast::ArrayLiteral {
                     contents: vec![element; *length as usize],
                     element_type: element_type.as_ref().clone(),
@@ -987,19 +1347,31 @@ impl<'interner> Monomorphizer<'interner> {
             ast::Type::String(length) => {
                 ast::Expression::Literal(ast::Literal::Str("\0".repeat(*length as usize)))
             }
-            ast::Type::Tuple(fields) => {
-                ast::Expression::Tuple(vecmap(fields, |field| self.zeroed_value_of_type(field)))
-            }
+            ast::Type::Tuple(fields) => ast::Expression::Tuple(vecmap(fields, |field| {
+                self.zeroed_value_of_type(field, location)
+            })),
             ast::Type::Function(parameter_types, ret_type) => {
-                self.create_zeroed_function(parameter_types, ret_type)
+                self.create_zeroed_function(parameter_types, ret_type, location)
+            }
+            // The zeroed Vec is empty
+            ast::Type::Vec(_) => {
+                let name = "vec_new".to_owned();
+                let func = Box::new(ast::Expression::Ident(ast::Ident {
+                    definition: Definition::Builtin(name.clone()),
+                    mutable: false,
+                    location: None,
+                    name,
+                    typ: ast::Type::Function(vec![], Box::new(typ.clone())),
+                }));
+                let return_type = typ.clone();
+                ast::Expression::Call(ast::Call { func, arguments: vec![], return_type, location })
             }
-            ast::Type::Vec(_) => panic!("Cannot create a zeroed Vec value. This type is currently unimplemented and meant to be unusable outside of unconstrained functions"),
             ast::Type::MutableReference(element) => {
                 use crate::UnaryOp::MutableReference;
-                let rhs = Box::new(self.zeroed_value_of_type(element));
+                let rhs = Box::new(self.zeroed_value_of_type(element, location));
                 let result_type = typ.clone();
                 ast::Expression::Unary(ast::Unary { rhs, result_type, operator: MutableReference })
-            },
+            }
         }
     }
 
@@ -1013,6 +1385,7 @@ impl<'interner> Monomorphizer<'interner> {
         &mut self,
         parameter_types: &[ast::Type],
         ret_type: &ast::Type,
+        location: Location,
     ) -> ast::Expression {
         let lambda_name = "zeroed_lambda";
 
@@ -1020,7 +1393,7 @@ impl<'interner> Monomorphizer<'interner> {
             (self.next_local_id(), false, "_".into(), parameter_type.clone())
         });
 
-        let body = self.zeroed_value_of_type(ret_type);
+        let body = self.zeroed_value_of_type(ret_type, location);
 
         let id = self.next_function_id();
         let return_type = ret_type.clone();
@@ -1062,6 +1435,26 @@ fn unwrap_struct_type(typ: &HirType) -> Vec<(String, HirType)> {
     }
 }
 
+/// Returns the field types of each variant of the given enum type, in declaration order
+fn unwrap_enum_type(typ: &HirType) -> Vec<Vec<HirType>> {
+    match typ {
+        HirType::Enum(def, args) => {
+            let def = def.borrow();
+            vecmap(0..def.num_variants(), |variant_index| def.variant_fields(variant_index, args))
+        }
+        // Generics are bound to the types they are instantiated with during monomorphization
+        HirType::TypeVariable(binding) | HirType::NamedGeneric(binding, _) => {
+            match &*binding.borrow() {
+                TypeBinding::Bound(binding) => unwrap_enum_type(binding),
+                TypeBinding::Unbound(_) => {
+                    unreachable!("unwrap_enum_type: expected enum, found unbound type {typ}")
+                }
+            }
+        }
+        other => unreachable!("unwrap_enum_type: expected enum, found {:?}", other),
+    }
+}
+
 fn perform_instantiation_bindings(bindings: &TypeBindings) {
     for (var, binding) in bindings.values() {
         *var.borrow_mut() = TypeBinding::Bound(binding.clone());
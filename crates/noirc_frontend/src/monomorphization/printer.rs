@@ -47,6 +47,7 @@ impl AstPrinter {
                 write!(f, " as {})", cast.r#type)
             }
             Expression::For(for_expr) => self.print_for(for_expr, f),
+            Expression::While(while_expr) => self.print_while(while_expr, f),
             Expression::If(if_expr) => self.print_if(if_expr, f),
             Expression::Tuple(tuple) => self.print_tuple(tuple, f),
             Expression::ExtractTupleField(expr, index) => {
@@ -71,6 +72,8 @@ impl AstPrinter {
                 self.print_expr(expr, f)?;
                 write!(f, ";")
             }
+            Expression::Break => write!(f, "break"),
+            Expression::Continue => write!(f, "continue"),
         }
     }
 
@@ -190,6 +193,25 @@ impl AstPrinter {
         write!(f, "}}")
     }
 
+    fn print_while(
+        &mut self,
+        while_expr: &super::ast::While,
+        f: &mut Formatter,
+    ) -> Result<(), std::fmt::Error> {
+        write!(f, "while ")?;
+        self.print_expr(&while_expr.condition, f)?;
+        if let Some(max_iterations) = while_expr.max_iterations {
+            write!(f, " max {max_iterations}")?;
+        }
+        write!(f, " {{")?;
+
+        self.indent_level += 1;
+        self.print_expr_expect_block(&while_expr.block, f)?;
+        self.indent_level -= 1;
+        self.next_line(f)?;
+        write!(f, "}}")
+    }
+
     fn print_if(
         &mut self,
         if_expr: &super::ast::If,
@@ -7,15 +7,17 @@ use noirc_errors::{Location, Span, Spanned};
 
 use crate::ast::Ident;
 use crate::graph::CrateId;
-use crate::hir::def_collector::dc_crate::UnresolvedStruct;
+use crate::hir::comptime::DeferredArrayLength;
+use crate::hir::def_collector::dc_crate::{UnresolvedEnum, UnresolvedStruct};
 use crate::hir::def_map::{LocalModuleId, ModuleId};
 use crate::hir::StorageSlot;
 use crate::hir_def::stmt::HirLetStatement;
-use crate::hir_def::types::{StructType, Type};
+use crate::hir_def::types::{EnumType, StructType, Type};
 use crate::hir_def::{
-    expr::HirExpression,
+    expr::{HirExpression, HirIdent},
     function::{FuncMeta, HirFunction},
     stmt::HirStatement,
+    traits::{Trait, TraitImpl},
 };
 use crate::{Shared, TypeBinding, TypeBindings, TypeVariable, TypeVariableId};
 
@@ -52,6 +54,21 @@ pub struct NodeInterner {
     // methods from impls to the type.
     structs: HashMap<StructId, Shared<StructType>>,
 
+    // Enum map.
+    //
+    // Like structs, each enum definition is shared across every type node referring
+    // to it and is mutated once its variants are resolved.
+    enums: HashMap<EnumId, Shared<EnumType>>,
+
+    // Trait map.
+    //
+    // Each trait definition is resolved once, before any of the functions
+    // which may be bounded by it.
+    traits: HashMap<TraitId, Trait>,
+
+    /// All `impl Trait for Type` blocks, grouped by the trait they implement.
+    trait_implementations: HashMap<TraitId, Vec<TraitImpl>>,
+
     /// Map from ExprId (referring to a Function/Method call) to its corresponding TypeBindings,
     /// filled out during type checking from instantiated variables. Used during monomorphization
     /// to map call site types back onto function parameter types, and undo this binding as needed.
@@ -68,8 +85,15 @@ pub struct NodeInterner {
     /// A map from a struct type and method name to a function id for the method.
     struct_methods: HashMap<(StructId, String), FuncId>,
 
+    /// A map from an enum type and method name to a function id for the method.
+    enum_methods: HashMap<(EnumId, String), FuncId>,
+
     /// Methods on primitive types defined in the stdlib.
     primitive_methods: HashMap<(TypeMethodKey, String), FuncId>,
+
+    /// Array lengths which could not be evaluated during name resolution since they depend
+    /// on globals or functions which weren't resolved yet.
+    deferred_array_lengths: Vec<DeferredArrayLength>,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
@@ -132,6 +156,30 @@ impl StructId {
     }
 }
 
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct EnumId(pub ModuleId);
+
+impl EnumId {
+    //dummy id for error reporting
+    // This can be anything, as the program will ultimately fail
+    // after resolution
+    pub fn dummy_id() -> EnumId {
+        EnumId(ModuleId { krate: CrateId::dummy_id(), local_id: LocalModuleId::dummy_id() })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct TraitId(pub ModuleId);
+
+impl TraitId {
+    //dummy id for error reporting
+    // This can be anything, as the program will ultimately fail
+    // after resolution
+    pub fn dummy_id() -> TraitId {
+        TraitId(ModuleId { krate: CrateId::dummy_id(), local_id: LocalModuleId::dummy_id() })
+    }
+}
+
 macro_rules! into_index {
     ($id_type:ty) => {
         impl From<$id_type> for Index {
@@ -181,6 +229,9 @@ pub struct DefinitionInfo {
     pub name: String,
     pub mutable: bool,
     pub kind: DefinitionKind,
+    /// The location of the identifier which declared this definition. Functions are
+    /// declared before being resolved so their location is found in their FuncMeta instead.
+    pub location: Option<Location>,
 }
 
 impl DefinitionInfo {
@@ -243,12 +294,17 @@ impl Default for NodeInterner {
             definitions: vec![],
             id_to_type: HashMap::new(),
             structs: HashMap::new(),
+            enums: HashMap::new(),
+            traits: HashMap::new(),
+            trait_implementations: HashMap::new(),
             instantiation_bindings: HashMap::new(),
             field_indices: HashMap::new(),
             next_type_variable_id: 0,
             globals: HashMap::new(),
             struct_methods: HashMap::new(),
+            enum_methods: HashMap::new(),
             primitive_methods: HashMap::new(),
+            deferred_array_lengths: Vec::new(),
         };
 
         // An empty block expression is used often, we add this into the `node` on startup
@@ -310,6 +366,28 @@ impl NodeInterner {
         f(&mut value);
     }
 
+    pub fn push_empty_enum(&mut self, type_id: EnumId, typ: &UnresolvedEnum) {
+        self.enums.insert(
+            type_id,
+            Shared::new(EnumType::new(
+                type_id,
+                typ.enum_def.name.clone(),
+                typ.enum_def.span,
+                Vec::new(),
+                vecmap(&typ.enum_def.generics, |_| {
+                    // Temporary type variable ids, see push_empty_struct
+                    let id = TypeVariableId(0);
+                    (id, Shared::new(TypeBinding::Unbound(id)))
+                }),
+            )),
+        );
+    }
+
+    pub fn update_enum(&mut self, type_id: EnumId, f: impl FnOnce(&mut EnumType)) {
+        let mut value = self.enums.get_mut(&type_id).unwrap().borrow_mut();
+        f(&mut value);
+    }
+
     /// Returns the interned statement corresponding to `stmt_id`
     pub fn update_statement(&mut self, stmt_id: &StmtId, f: impl FnOnce(&mut HirStatement)) {
         let def =
@@ -387,18 +465,19 @@ impl NodeInterner {
         name: String,
         mutable: bool,
         definition: DefinitionKind,
+        location: Option<Location>,
     ) -> DefinitionId {
         let id = DefinitionId(self.definitions.len());
         if let DefinitionKind::Function(func_id) = definition {
             self.function_definition_ids.insert(func_id, id);
         }
 
-        self.definitions.push(DefinitionInfo { name, mutable, kind: definition });
+        self.definitions.push(DefinitionInfo { name, mutable, kind: definition, location });
         id
     }
 
     pub fn push_function_definition(&mut self, name: String, func: FuncId) -> DefinitionId {
-        self.push_definition(name, false, DefinitionKind::Function(func))
+        self.push_definition(name, false, DefinitionKind::Function(func), None)
     }
 
     /// Returns the interned HIR function corresponding to `func_id`
@@ -496,6 +575,18 @@ impl NodeInterner {
         self.structs[&id].clone()
     }
 
+    pub fn get_enum(&self, id: EnumId) -> Shared<EnumType> {
+        self.enums[&id].clone()
+    }
+
+    pub fn push_trait(&mut self, id: TraitId, the_trait: Trait) {
+        self.traits.insert(id, the_trait);
+    }
+
+    pub fn get_trait(&self, id: TraitId) -> &Trait {
+        &self.traits[&id]
+    }
+
     pub fn get_global(&self, stmt_id: &StmtId) -> Option<GlobalInfo> {
         self.globals.get(stmt_id).cloned()
     }
@@ -509,6 +600,57 @@ impl NodeInterner {
         self.id_to_type.get(&index.into()).cloned().unwrap_or(Type::Error)
     }
 
+    /// Returns the location where the given definition was declared, if it is known.
+    pub fn definition_location(&self, id: DefinitionId) -> Option<Location> {
+        let definition = self.definition(id);
+        match definition.kind {
+            DefinitionKind::Function(func_id) => {
+                self.func_meta.get(&func_id).map(|meta| meta.name.location)
+            }
+            _ => definition.location,
+        }
+    }
+
+    /// Returns every identifier expression along with the definition it refers to.
+    pub fn identifier_references(&self) -> impl Iterator<Item = (ExprId, &HirIdent)> + '_ {
+        self.nodes.iter().filter_map(|(index, node)| match node {
+            Node::Expression(HirExpression::Ident(ident)) => Some((ExprId(index), ident)),
+            _ => None,
+        })
+    }
+
+    /// Returns the identifier expression whose location contains `location`, if there is one.
+    pub fn find_identifier_at(&self, location: Location) -> Option<(ExprId, &HirIdent)> {
+        self.identifier_references().find(|(_, ident)| ident.location.contains(&location))
+    }
+
+    /// Returns the definition declared by the identifier whose location contains `location`, if there is one.
+    pub fn find_declaration_at(&self, location: Location) -> Option<DefinitionId> {
+        (0..self.definitions.len()).map(DefinitionId).find(|id| {
+            self.definition_location(*id).map_or(false, |declared| declared.contains(&location))
+        })
+    }
+
+    /// Returns the innermost function whose body contains `location`, if there is one.
+    pub fn find_function_at(&self, location: Location) -> Option<FuncId> {
+        let body_spans = self.func_meta.iter().filter_map(|(id, meta)| {
+            let in_same_file = meta.has_body && meta.location.file == location.file;
+            in_same_file.then(|| (*id, self.expr_span(self.function(id).as_expr())))
+        });
+        body_spans
+            .filter(|(_, span)| span.contains(&location.span))
+            .min_by_key(|(_, span)| span.end() - span.start())
+            .map(|(id, _)| id)
+    }
+
+    /// Returns the locations of every identifier referring to the given definition.
+    pub fn find_references(&self, id: DefinitionId) -> Vec<Location> {
+        self.identifier_references()
+            .filter(|(_, ident)| ident.id == id)
+            .map(|(_, ident)| ident.location)
+            .collect()
+    }
+
     /// Returns the span of an item stored in the Interner
     pub fn id_location(&self, index: impl Into<Index>) -> Location {
         self.id_to_location.get(&index.into()).copied().unwrap()
@@ -526,6 +668,14 @@ impl NodeInterner {
         TypeVariableId(id)
     }
 
+    pub fn push_deferred_array_length(&mut self, length: DeferredArrayLength) {
+        self.deferred_array_lengths.push(length);
+    }
+
+    pub fn take_deferred_array_lengths(&mut self) -> Vec<DeferredArrayLength> {
+        std::mem::take(&mut self.deferred_array_lengths)
+    }
+
     pub fn next_type_variable(&mut self) -> Type {
         let binding = TypeBinding::Unbound(self.next_type_variable_id());
         Type::TypeVariable(Shared::new(binding))
@@ -569,6 +719,10 @@ impl NodeInterner {
                 let key = (struct_type.borrow().id, method_name);
                 self.struct_methods.insert(key, method_id)
             }
+            Type::Enum(enum_type, _generics) => {
+                let key = (enum_type.borrow().id, method_name);
+                self.enum_methods.insert(key, method_id)
+            }
             Type::Error => None,
 
             other => {
@@ -585,11 +739,83 @@ impl NodeInterner {
         self.struct_methods.get(&(id, method_name.to_owned())).copied()
     }
 
+    /// Search by name for a method on the given enum
+    pub fn lookup_enum_method(&self, id: EnumId, method_name: &str) -> Option<FuncId> {
+        self.enum_methods.get(&(id, method_name.to_owned())).copied()
+    }
+
     /// Looks up a given method name on the given primitive type.
     pub fn lookup_primitive_method(&self, typ: &Type, method_name: &str) -> Option<FuncId> {
         get_type_method_key(typ)
             .and_then(|key| self.primitive_methods.get(&(key, method_name.to_owned())).copied())
     }
+
+    /// Adds an implementation of a trait for a type. If an impl of the same trait overlapping
+    /// with it already exists, either because it is more general or because it is more specific,
+    /// it is left untouched and the span of the existing impl is returned instead.
+    pub fn add_trait_implementation(&mut self, trait_impl: TraitImpl) -> Result<(), Span> {
+        let impls = self.trait_implementations.entry(trait_impl.trait_id).or_default();
+
+        for existing in impls.iter() {
+            let overlaps = existing.typ.match_generics(&trait_impl.typ, &mut TypeBindings::new())
+                || trait_impl.typ.match_generics(&existing.typ, &mut TypeBindings::new());
+            if overlaps {
+                return Err(existing.span);
+            }
+        }
+
+        impls.push(trait_impl);
+        Ok(())
+    }
+
+    /// Searches for an impl of the given trait which applies to `typ`, returning it along with
+    /// the bindings needed to instantiate any generics of the impl to match `typ`.
+    pub fn lookup_trait_implementation(
+        &self,
+        typ: &Type,
+        trait_id: TraitId,
+    ) -> Option<(&TraitImpl, TypeBindings)> {
+        let impls = self.trait_implementations.get(&trait_id)?;
+
+        impls.iter().find_map(|trait_impl| {
+            let mut bindings = TypeBindings::new();
+            trait_impl.typ.match_generics(typ, &mut bindings).then_some((trait_impl, bindings))
+        })
+    }
+
+    /// Searches every trait implemented for `typ` for a method with the given name, returning
+    /// each trait providing one along with the method, sorted by the name of the trait. If an
+    /// impl does not define the method itself, the trait's default implementation is used instead.
+    pub fn lookup_trait_methods(&self, typ: &Type, method_name: &str) -> Vec<(TraitId, FuncId)> {
+        let mut methods: Vec<_> = self
+            .trait_implementations
+            .keys()
+            .filter_map(|trait_id| {
+                let method = self.lookup_trait_method_for(*trait_id, typ, method_name)?;
+                Some((*trait_id, method))
+            })
+            .collect();
+        methods.sort_by_key(|(trait_id, _)| self.get_trait(*trait_id).name.to_string());
+        methods
+    }
+
+    /// Look up a method by name within the impl of the given trait for `typ`, falling back
+    /// to the trait's default implementation if the impl does not define it.
+    pub fn lookup_trait_method_for(
+        &self,
+        trait_id: TraitId,
+        typ: &Type,
+        method_name: &str,
+    ) -> Option<FuncId> {
+        let (trait_impl, _) = self.lookup_trait_implementation(typ, trait_id)?;
+
+        if let Some(method) = trait_impl.methods.get(method_name) {
+            return Some(*method);
+        }
+
+        let method = self.get_trait(trait_id).find_method(method_name)?;
+        method.has_default.then_some(method.id)
+    }
 }
 
 /// These are the primitive type variants that we support adding methods to
@@ -619,7 +845,7 @@ fn get_type_method_key(typ: &Type) -> Option<TypeMethodKey> {
         Type::String(_) => Some(String),
         Type::Unit => Some(Unit),
         Type::Tuple(_) => Some(Tuple),
-        Type::Function(_, _) => Some(Function),
+        Type::Function(..) => Some(Function),
         Type::Vec(_) => Some(Vec),
         Type::MutableReference(element) => get_type_method_key(element),
 
@@ -629,6 +855,7 @@ fn get_type_method_key(typ: &Type) -> Option<TypeMethodKey> {
         | Type::Forall(_, _)
         | Type::Constant(_)
         | Type::Error
-        | Type::Struct(_, _) => None,
+        | Type::Struct(_, _)
+        | Type::Enum(_, _) => None,
     }
 }
@@ -1,5 +1,4 @@
 use crate::lexer::token::Token;
-use crate::Expression;
 use small_ord_set::SmallOrdSet;
 use thiserror::Error;
 
@@ -19,8 +18,6 @@ pub enum ParserErrorReason {
     MissingSeparatingSemi,
     #[error("constrain keyword is deprecated")]
     ConstrainDeprecated,
-    #[error("Expression is invalid in an array-length type: '{0}'. Only unsigned integer constants, globals, generics, +, -, *, /, and % may be used in this context.")]
-    InvalidArrayLengthExpression(Expression),
     #[error("Early 'return' is unsupported")]
     EarlyReturn,
 }
@@ -17,8 +17,8 @@ use crate::token::{Keyword, Token};
 use crate::{ast::ImportStatement, Expression, NoirStruct};
 use crate::{
     BlockExpression, ExpressionKind, ForExpression, Ident, IndexExpression, LetStatement,
-    MethodCallExpression, NoirFunction, NoirImpl, Path, PathKind, Pattern, Recoverable, Statement,
-    UnresolvedType, UseTree,
+    MethodCallExpression, NoirEnum, NoirFunction, NoirImpl, NoirTrait, NoirTraitImpl, Path,
+    PathKind, Pattern, Recoverable, Statement, UnresolvedType, UseTree,
 };
 
 use acvm::FieldElement;
@@ -40,12 +40,34 @@ pub(crate) enum TopLevelStatement {
     Module(Ident),
     Import(UseTree),
     Struct(NoirStruct),
+    Enum(NoirEnum),
     Impl(NoirImpl),
-    SubModule(SubModule),
+    Trait(NoirTrait),
+    TraitImpl(NoirTraitImpl),
+    SubModule(ParsedSubModule),
     Global(LetStatement),
     Error,
 }
 
+impl TopLevelStatement {
+    /// Converts this statement into a module item, or `None` if it failed to parse.
+    fn into_item_kind(self) -> Option<ItemKind> {
+        match self {
+            TopLevelStatement::Function(f) => Some(ItemKind::Function(f)),
+            TopLevelStatement::Module(m) => Some(ItemKind::ModuleDecl(m)),
+            TopLevelStatement::Import(i) => Some(ItemKind::Import(i)),
+            TopLevelStatement::Struct(s) => Some(ItemKind::Struct(s)),
+            TopLevelStatement::Enum(e) => Some(ItemKind::Enum(e)),
+            TopLevelStatement::Impl(i) => Some(ItemKind::Impl(i)),
+            TopLevelStatement::Trait(t) => Some(ItemKind::Trait(t)),
+            TopLevelStatement::TraitImpl(t) => Some(ItemKind::TraitImpl(t)),
+            TopLevelStatement::SubModule(s) => Some(ItemKind::Submodule(s)),
+            TopLevelStatement::Global(c) => Some(ItemKind::Global(c)),
+            TopLevelStatement::Error => None,
+        }
+    }
+}
+
 // Helper trait that gives us simpler type signatures for return types:
 // e.g. impl Parser<T> versus impl Parser<Token, T, Error = Simple<Token>>
 pub trait NoirParser<T>: Parser<Token, T, Error = ParserError> + Sized + Clone {}
@@ -215,31 +237,108 @@ fn force<'a, T: 'a>(parser: impl NoirParser<T> + 'a) -> impl NoirParser<Option<T
 }
 
 /// A ParsedModule contains an entire Ast for one file.
+///
+/// Items are kept in the order they were written so that tools such as the formatter
+/// can print the module back out. Name resolution instead works on a [SortedModule].
 #[derive(Clone, Debug, Default)]
 pub struct ParsedModule {
+    pub items: Vec<Item>,
+}
+
+impl ParsedModule {
+    /// Groups the items of this module by their kind.
+    pub fn into_sorted(self) -> SortedModule {
+        let mut module = SortedModule::default();
+
+        for item in self.items {
+            match item.kind {
+                ItemKind::Function(func) => module.push_function(func),
+                ItemKind::ModuleDecl(mod_name) => module.push_module_decl(mod_name),
+                ItemKind::Import(use_tree) => module.push_import(use_tree),
+                ItemKind::Struct(typ) => module.push_type(typ),
+                ItemKind::Enum(noir_enum) => module.push_enum(noir_enum),
+                ItemKind::Impl(r#impl) => module.push_impl(r#impl),
+                ItemKind::Trait(noir_trait) => module.push_trait(noir_trait),
+                ItemKind::TraitImpl(trait_impl) => module.push_trait_impl(trait_impl),
+                ItemKind::Submodule(submodule) => module.push_submodule(submodule.into_sorted()),
+                ItemKind::Global(global) => module.push_global(global),
+            }
+        }
+
+        module
+    }
+}
+
+/// A single top-level item of a module along with the span of its source code.
+#[derive(Clone, Debug)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub enum ItemKind {
+    Function(NoirFunction),
+    /// A module declaration like `mod foo;`
+    ModuleDecl(Ident),
+    Import(UseTree),
+    Struct(NoirStruct),
+    Enum(NoirEnum),
+    Impl(NoirImpl),
+    Trait(NoirTrait),
+    TraitImpl(NoirTraitImpl),
+    Submodule(ParsedSubModule),
+    Global(LetStatement),
+}
+
+/// A submodule defined via `mod name { contents }` in some larger file.
+/// These submodules always share the same file as some larger ParsedModule
+#[derive(Clone, Debug)]
+pub struct ParsedSubModule {
+    pub name: Ident,
+    pub contents: ParsedModule,
+    pub is_contract: bool,
+}
+
+impl ParsedSubModule {
+    pub fn into_sorted(self) -> SortedSubModule {
+        SortedSubModule {
+            name: self.name,
+            contents: self.contents.into_sorted(),
+            is_contract: self.is_contract,
+        }
+    }
+}
+
+/// The items of a ParsedModule grouped by their kind, with any
+/// use trees desugared into individual import statements.
+#[derive(Clone, Debug, Default)]
+pub struct SortedModule {
     pub imports: Vec<ImportStatement>,
     pub functions: Vec<NoirFunction>,
     pub types: Vec<NoirStruct>,
+    pub enums: Vec<NoirEnum>,
     pub impls: Vec<NoirImpl>,
+    pub traits: Vec<NoirTrait>,
+    pub trait_impls: Vec<NoirTraitImpl>,
     pub globals: Vec<LetStatement>,
 
     /// Module declarations like `mod foo;`
     pub module_decls: Vec<Ident>,
 
     /// Full submodules as in `mod foo { ... definitions ... }`
-    pub submodules: Vec<SubModule>,
+    pub submodules: Vec<SortedSubModule>,
 }
 
-/// A submodule defined via `mod name { contents }` in some larger file.
-/// These submodules always share the same file as some larger ParsedModule
+/// A [ParsedSubModule] whose contents have been sorted.
 #[derive(Clone, Debug)]
-pub struct SubModule {
+pub struct SortedSubModule {
     pub name: Ident,
-    pub contents: ParsedModule,
+    pub contents: SortedModule,
     pub is_contract: bool,
 }
 
-impl ParsedModule {
+impl SortedModule {
     fn push_function(&mut self, func: NoirFunction) {
         self.functions.push(func);
     }
@@ -248,10 +347,22 @@ impl ParsedModule {
         self.types.push(typ);
     }
 
+    fn push_enum(&mut self, noir_enum: NoirEnum) {
+        self.enums.push(noir_enum);
+    }
+
     fn push_impl(&mut self, r#impl: NoirImpl) {
         self.impls.push(r#impl);
     }
 
+    fn push_trait(&mut self, noir_trait: NoirTrait) {
+        self.traits.push(noir_trait);
+    }
+
+    fn push_trait_impl(&mut self, trait_impl: NoirTraitImpl) {
+        self.trait_impls.push(trait_impl);
+    }
+
     fn push_import(&mut self, import_stmt: UseTree) {
         self.imports.extend(import_stmt.desugar(None));
     }
@@ -260,7 +371,7 @@ impl ParsedModule {
         self.module_decls.push(mod_name);
     }
 
-    fn push_submodule(&mut self, submodule: SubModule) {
+    fn push_submodule(&mut self, submodule: SortedSubModule) {
         self.submodules.push(submodule);
     }
 
@@ -448,7 +559,10 @@ impl std::fmt::Display for TopLevelStatement {
             TopLevelStatement::Module(m) => write!(f, "mod {m}"),
             TopLevelStatement::Import(tree) => write!(f, "use {tree}"),
             TopLevelStatement::Struct(s) => s.fmt(f),
+            TopLevelStatement::Enum(e) => e.fmt(f),
             TopLevelStatement::Impl(i) => i.fmt(f),
+            TopLevelStatement::Trait(t) => t.fmt(f),
+            TopLevelStatement::TraitImpl(i) => i.fmt(f),
             TopLevelStatement::SubModule(s) => s.fmt(f),
             TopLevelStatement::Global(c) => c.fmt(f),
             TopLevelStatement::Error => write!(f, "error"),
@@ -458,39 +572,26 @@ impl std::fmt::Display for TopLevelStatement {
 
 impl std::fmt::Display for ParsedModule {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for decl in &self.module_decls {
-            writeln!(f, "mod {decl};")?;
-        }
-
-        for import in &self.imports {
-            write!(f, "{import}")?;
-        }
-
-        for global_const in &self.globals {
-            write!(f, "{global_const}")?;
-        }
-
-        for type_ in &self.types {
-            write!(f, "{type_}")?;
-        }
-
-        for function in &self.functions {
-            write!(f, "{function}")?;
-        }
-
-        for impl_ in &self.impls {
-            write!(f, "{impl_}")?;
-        }
-
-        for submodule in &self.submodules {
-            write!(f, "{submodule}")?;
+        for item in &self.items {
+            match &item.kind {
+                ItemKind::Function(func) => write!(f, "{func}")?,
+                ItemKind::ModuleDecl(mod_name) => writeln!(f, "mod {mod_name};")?,
+                ItemKind::Import(use_tree) => write!(f, "use {use_tree}")?,
+                ItemKind::Struct(typ) => write!(f, "{typ}")?,
+                ItemKind::Enum(noir_enum) => write!(f, "{noir_enum}")?,
+                ItemKind::Impl(r#impl) => write!(f, "{impl}")?,
+                ItemKind::Trait(noir_trait) => write!(f, "{noir_trait}")?,
+                ItemKind::TraitImpl(trait_impl) => write!(f, "{trait_impl}")?,
+                ItemKind::Submodule(submodule) => write!(f, "{submodule}")?,
+                ItemKind::Global(global) => write!(f, "{global}")?,
+            }
         }
 
         Ok(())
     }
 }
 
-impl std::fmt::Display for SubModule {
+impl std::fmt::Display for ParsedSubModule {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "mod {} {{", self.name)?;
 
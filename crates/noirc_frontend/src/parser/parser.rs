@@ -25,9 +25,9 @@
 //! current parser to try alternative parsers in a `choice` expression.
 use super::{
     foldl_with_span, labels::ParsingRuleLabel, parameter_name_recovery, parameter_recovery,
-    parenthesized, then_commit, then_commit_ignore, top_level_statement_recovery, ExprParser,
-    ForRange, NoirParser, ParsedModule, ParserError, ParserErrorReason, Precedence, SubModule,
-    TopLevelStatement,
+    parenthesized, spanned, then_commit, then_commit_ignore, top_level_statement_recovery,
+    ExprParser, ForRange, Item, NoirParser, ParsedModule, ParsedSubModule, ParserError,
+    ParserErrorReason, Precedence, TopLevelStatement,
 };
 use crate::ast::{Expression, ExpressionKind, LetStatement, Statement, UnresolvedType};
 use crate::lexer::Lexer;
@@ -35,8 +35,10 @@ use crate::parser::{force, ignore_then_commit, statement_recovery};
 use crate::token::{Attribute, Keyword, Token, TokenKind};
 use crate::{
     BinaryOp, BinaryOpKind, BlockExpression, CompTime, ConstrainStatement, FunctionDefinition,
-    Ident, IfExpression, InfixExpression, LValue, Lambda, NoirFunction, NoirImpl, NoirStruct, Path,
-    PathKind, Pattern, Recoverable, UnaryOp, UnresolvedTypeExpression, UseTree, UseTreeKind,
+    Ident, IfExpression, InfixExpression, LValue, Lambda, MatchExpression, MatchPattern, NoirEnum,
+    NoirFunction, NoirImpl, NoirStruct, NoirTrait, NoirTraitImpl, Path, PathKind, Pattern,
+    Recoverable, TraitConstraint, TraitItem, UnaryOp, UnresolvedTypeExpression, UseTree,
+    UseTreeKind, WhileExpression,
 };
 
 use chumsky::prelude::*;
@@ -71,17 +73,10 @@ fn module() -> impl NoirParser<ParsedModule> {
     recursive(|module_parser| {
         empty()
             .map(|_| ParsedModule::default())
-            .then(top_level_statement(module_parser).repeated())
-            .foldl(|mut program, statement| {
-                match statement {
-                    TopLevelStatement::Function(f) => program.push_function(f),
-                    TopLevelStatement::Module(m) => program.push_module_decl(m),
-                    TopLevelStatement::Import(i) => program.push_import(i),
-                    TopLevelStatement::Struct(s) => program.push_type(s),
-                    TopLevelStatement::Impl(i) => program.push_impl(i),
-                    TopLevelStatement::SubModule(s) => program.push_submodule(s),
-                    TopLevelStatement::Global(c) => program.push_global(c),
-                    TopLevelStatement::Error => (),
+            .then(spanned(top_level_statement(module_parser)).repeated())
+            .foldl(|mut program, (statement, span)| {
+                if let Some(kind) = statement.into_item_kind() {
+                    program.items.push(Item { kind, span });
                 }
                 program
             })
@@ -90,6 +85,9 @@ fn module() -> impl NoirParser<ParsedModule> {
 
 /// top_level_statement: function_definition
 ///                    | struct_definition
+///                    | enum_definition
+///                    | trait_definition
+///                    | trait_implementation
 ///                    | implementation
 ///                    | submodule
 ///                    | module_declaration
@@ -101,6 +99,9 @@ fn top_level_statement(
     choice((
         function_definition(false).map(TopLevelStatement::Function),
         struct_definition(),
+        enum_definition(),
+        trait_definition(),
+        trait_implementation(),
         implementation(),
         submodule(module_parser.clone()),
         contract(module_parser),
@@ -111,7 +112,7 @@ fn top_level_statement(
     .recover_via(top_level_statement_recovery())
 }
 
-/// global_declaration: 'global' ident global_type_annotation '=' literal
+/// global_declaration: 'global' ident global_type_annotation '=' expression
 fn global_declaration() -> impl NoirParser<TopLevelStatement> {
     let p = ignore_then_commit(
         keyword(Keyword::Global).labelled(ParsingRuleLabel::Global),
@@ -119,7 +120,7 @@ fn global_declaration() -> impl NoirParser<TopLevelStatement> {
     );
     let p = then_commit(p, global_type_annotation());
     let p = then_commit_ignore(p, just(Token::Assign));
-    let p = then_commit(p, literal_or_collection(expression()).map_with_span(Expression::new));
+    let p = then_commit(p, expression());
     p.map(LetStatement::new_let).map(TopLevelStatement::Global)
 }
 
@@ -131,7 +132,7 @@ fn submodule(module_parser: impl NoirParser<ParsedModule>) -> impl NoirParser<To
         .then(module_parser)
         .then_ignore(just(Token::RightBrace))
         .map(|(name, contents)| {
-            TopLevelStatement::SubModule(SubModule { name, contents, is_contract: false })
+            TopLevelStatement::SubModule(ParsedSubModule { name, contents, is_contract: false })
         })
 }
 
@@ -143,30 +144,41 @@ fn contract(module_parser: impl NoirParser<ParsedModule>) -> impl NoirParser<Top
         .then(module_parser)
         .then_ignore(just(Token::RightBrace))
         .map(|(name, contents)| {
-            TopLevelStatement::SubModule(SubModule { name, contents, is_contract: true })
+            TopLevelStatement::SubModule(ParsedSubModule { name, contents, is_contract: true })
         })
 }
 
-/// function_definition: attribute function_modifiers 'fn' ident generics '(' function_parameters ')' function_return_type block
-///                      function_modifiers 'fn' ident generics '(' function_parameters ')' function_return_type block
+/// function_definition: attribute function_modifiers 'fn' ident function_generics '(' function_parameters ')' function_return_type where_clause block
+///                      function_modifiers 'fn' ident function_generics '(' function_parameters ')' function_return_type where_clause block
 fn function_definition(allow_self: bool) -> impl NoirParser<NoirFunction> {
     attribute()
         .or_not()
         .then(function_modifiers())
         .then_ignore(keyword(Keyword::Fn))
         .then(ident())
-        .then(generics())
+        .then(function_generics())
         .then(parenthesized(function_parameters(allow_self)))
         .then(function_return_type())
+        .then(where_clause())
         .then(block(expression()))
         .map(
             |(
                 (
-                    ((((attribute, (is_unconstrained, is_open)), name), generics), parameters),
-                    ((return_distinctness, return_visibility), return_type),
+                    (
+                        (
+                            (((attribute, (is_unconstrained, is_open)), name), (generics, bounds)),
+                            parameters,
+                        ),
+                        ((return_distinctness, return_visibility), return_type),
+                    ),
+                    mut where_clause,
                 ),
                 body,
             )| {
+                // Bounds written inline on the generics (`<T: Trait>`) are equivalent to
+                // declaring them in the where clause, so merge them together here.
+                where_clause.splice(0..0, bounds);
+
                 FunctionDefinition {
                     span: name.0.span(),
                     name,
@@ -179,6 +191,7 @@ fn function_definition(allow_self: bool) -> impl NoirParser<NoirFunction> {
                     return_type,
                     return_visibility,
                     return_distinctness,
+                    where_clause,
                 }
                 .into()
             },
@@ -210,6 +223,58 @@ fn generics() -> impl NoirParser<Vec<Ident>> {
         .map(|opt| opt.unwrap_or_default())
 }
 
+/// generic_parameter: ident
+///                  | ident ':' trait_bounds
+///
+/// function_generics: '<' generic_parameter (',' generic_parameter)* '>'
+///                  | %empty
+///
+/// Returns the generics declared along with a trait constraint for each bound on them.
+fn function_generics() -> impl NoirParser<(Vec<Ident>, Vec<TraitConstraint>)> {
+    ident()
+        .then(just(Token::Colon).ignore_then(trait_bounds()).or_not())
+        .separated_by(just(Token::Comma))
+        .allow_trailing()
+        .at_least(1)
+        .delimited_by(just(Token::Less), just(Token::Greater))
+        .or_not()
+        .map(|generics| {
+            let mut names = Vec::new();
+            let mut constraints = Vec::new();
+
+            for (name, bounds) in generics.unwrap_or_default() {
+                for trait_name in bounds.unwrap_or_default() {
+                    let typ = UnresolvedType::Named(Path::from_ident(name.clone()), vec![]);
+                    constraints.push(TraitConstraint { typ, trait_name });
+                }
+                names.push(name);
+            }
+
+            (names, constraints)
+        })
+}
+
+/// trait_bounds: path ('+' path)*
+fn trait_bounds() -> impl NoirParser<Vec<Path>> {
+    path().separated_by(just(Token::Plus)).at_least(1)
+}
+
+/// where_clause: 'where' trait_constraint (',' trait_constraint)*
+///             | %empty
+///
+/// trait_constraint: type ':' trait_bounds
+fn where_clause() -> impl NoirParser<Vec<TraitConstraint>> {
+    let constraint =
+        parse_type().then_ignore(just(Token::Colon)).then(trait_bounds()).map(|(typ, bounds)| {
+            vecmap(bounds, |trait_name| TraitConstraint { typ: typ.clone(), trait_name })
+        });
+
+    keyword(Keyword::Where)
+        .ignore_then(constraint.separated_by(just(Token::Comma)).allow_trailing().at_least(1))
+        .or_not()
+        .map(|constraints| constraints.unwrap_or_default().into_iter().flatten().collect())
+}
+
 fn struct_definition() -> impl NoirParser<TopLevelStatement> {
     use self::Keyword::Struct;
     use Token::*;
@@ -230,6 +295,41 @@ fn struct_definition() -> impl NoirParser<TopLevelStatement> {
     )
 }
 
+/// enum_definition: 'enum' ident generics '{' enum_variants '}'
+fn enum_definition() -> impl NoirParser<TopLevelStatement> {
+    use self::Keyword::Enum;
+    use Token::*;
+
+    let variants = enum_variants().delimited_by(just(LeftBrace), just(RightBrace)).recover_with(
+        nested_delimiters(
+            LeftBrace,
+            RightBrace,
+            [(LeftParen, RightParen), (LeftBracket, RightBracket)],
+            |_| vec![],
+        ),
+    );
+
+    keyword(Enum).ignore_then(ident()).then(generics()).then(variants).map_with_span(
+        |((name, generics), variants), span| {
+            TopLevelStatement::Enum(NoirEnum { name, generics, variants, span })
+        },
+    )
+}
+
+/// enum_variants: (ident ('(' type (',' type)* ')')?),*
+fn enum_variants() -> impl NoirParser<Vec<(Ident, Vec<UnresolvedType>)>> {
+    let fields = parse_type()
+        .separated_by(just(Token::Comma))
+        .allow_trailing()
+        .delimited_by(just(Token::LeftParen), just(Token::RightParen));
+
+    ident()
+        .then(fields.or_not())
+        .map(|(name, fields)| (name, fields.unwrap_or_default()))
+        .separated_by(just(Token::Comma))
+        .allow_trailing()
+}
+
 fn lambda_return_type() -> impl NoirParser<UnresolvedType> {
     just(Token::Arrow)
         .ignore_then(parse_type())
@@ -339,6 +439,62 @@ fn self_parameter() -> impl NoirParser<(Pattern, UnresolvedType, AbiVisibility)>
         })
 }
 
+/// trait_definition: 'trait' ident '{' trait_function_declaration* '}'
+fn trait_definition() -> impl NoirParser<TopLevelStatement> {
+    keyword(Keyword::Trait)
+        .ignore_then(ident())
+        .then_ignore(just(Token::LeftBrace))
+        .then(trait_function_declaration().repeated())
+        .then_ignore(just(Token::RightBrace))
+        .map_with_span(|(name, items), span| {
+            TopLevelStatement::Trait(NoirTrait { name, items, span })
+        })
+}
+
+/// trait_function_declaration: 'fn' ident function_generics '(' function_parameters ')' function_return_type where_clause ';'
+///                           | 'fn' ident function_generics '(' function_parameters ')' function_return_type where_clause block
+fn trait_function_declaration() -> impl NoirParser<TraitItem> {
+    let body = just(Token::Semicolon).to(None).or(block(expression()).map(Some));
+
+    keyword(Keyword::Fn)
+        .ignore_then(ident())
+        .then(function_generics())
+        .then(parenthesized(function_parameters(true)))
+        .then(function_return_type().map(|(_, return_type)| return_type))
+        .then(where_clause())
+        .then(body)
+        .map(
+            |(
+                ((((name, (generics, bounds)), parameters), return_type), mut where_clause),
+                body,
+            )| {
+                where_clause.splice(0..0, bounds);
+                TraitItem::Function { name, generics, parameters, return_type, where_clause, body }
+            },
+        )
+}
+
+/// trait_implementation: 'impl' generics path 'for' type '{' function_definition* '}'
+fn trait_implementation() -> impl NoirParser<TopLevelStatement> {
+    keyword(Keyword::Impl)
+        .ignore_then(generics())
+        .then(path())
+        .then_ignore(keyword(Keyword::For))
+        .then(parse_type().map_with_span(|typ, span| (typ, span)))
+        .then_ignore(just(Token::LeftBrace))
+        .then(function_definition(true).repeated())
+        .then_ignore(just(Token::RightBrace))
+        .map(|(((generics, trait_name), (object_type, type_span)), methods)| {
+            TopLevelStatement::TraitImpl(NoirTraitImpl {
+                trait_name,
+                object_type,
+                type_span,
+                generics,
+                methods,
+            })
+        })
+}
+
 fn implementation() -> impl NoirParser<TopLevelStatement> {
     keyword(Keyword::Impl)
         .ignore_then(generics())
@@ -498,10 +654,24 @@ where
         declaration(expr_parser.clone()),
         assignment(expr_parser.clone()),
         return_statement(expr_parser.clone()),
+        break_statement(),
+        continue_statement(),
         expr_parser.map(Statement::Expression),
     ))
 }
 
+fn break_statement() -> impl NoirParser<Statement> {
+    keyword(Keyword::Break)
+        .labelled(ParsingRuleLabel::Statement)
+        .map_with_span(|_, span| Statement::Break(span))
+}
+
+fn continue_statement() -> impl NoirParser<Statement> {
+    keyword(Keyword::Continue)
+        .labelled(ParsingRuleLabel::Statement)
+        .map_with_span(|_, span| Statement::Continue(span))
+}
+
 fn constrain<'a, P>(expr_parser: P) -> impl NoirParser<Statement> + 'a
 where
     P: ExprParser + 'a,
@@ -741,7 +911,7 @@ fn array_type(type_parser: impl NoirParser<UnresolvedType>) -> impl NoirParser<U
 fn type_expression() -> impl NoirParser<UnresolvedTypeExpression> {
     recursive(|expr| expression_with_precedence(Precedence::lowest_type_precedence(), expr, true))
         .labelled(ParsingRuleLabel::TypeExpression)
-        .try_map(UnresolvedTypeExpression::from_expr)
+        .map(UnresolvedTypeExpression::from_expr)
 }
 
 fn tuple_type<T>(type_parser: T) -> impl NoirParser<UnresolvedType>
@@ -757,11 +927,21 @@ where
     T: NoirParser<UnresolvedType>,
 {
     let args = parenthesized(type_parser.clone().separated_by(just(Token::Comma)).allow_trailing());
+
+    // The environment captured by a closure, e.g. `fn[Env](Field) -> Field`.
+    // Functions which capture nothing have an empty environment.
+    let env = type_parser
+        .clone()
+        .delimited_by(just(Token::LeftBracket), just(Token::RightBracket))
+        .or_not()
+        .map(|env| env.unwrap_or(UnresolvedType::Unit));
+
     keyword(Keyword::Fn)
-        .ignore_then(args)
+        .ignore_then(env)
+        .then(args)
         .then_ignore(just(Token::Arrow))
         .then(type_parser)
-        .map(|(args, ret)| UnresolvedType::Function(args, Box::new(ret)))
+        .map(|((env, args), ret)| UnresolvedType::Function(args, Box::new(ret), Box::new(env)))
 }
 
 fn mutable_reference_type<T>(type_parser: T) -> impl NoirParser<UnresolvedType>
@@ -950,6 +1130,40 @@ where
     })
 }
 
+/// match_expr: 'match' expression '{' (match_pattern '=>' expression),* '}'
+fn match_expr<'a, P>(expr_parser: P) -> impl NoirParser<ExpressionKind> + 'a
+where
+    P: ExprParser + 'a,
+{
+    let rule = match_pattern().then_ignore(just(Token::FatArrow)).then(expr_parser.clone());
+
+    let rules = rule
+        .separated_by(just(Token::Comma))
+        .allow_trailing()
+        .delimited_by(just(Token::LeftBrace), just(Token::RightBrace));
+
+    keyword(Keyword::Match).ignore_then(expr_parser).then(rules).map(|(expression, rules)| {
+        ExpressionKind::Match(Box::new(MatchExpression { expression, rules }))
+    })
+}
+
+/// match_pattern: path ('(' pattern (',' pattern)* ')')?
+///              | ident
+fn match_pattern() -> impl NoirParser<MatchPattern> {
+    let fields = pattern()
+        .separated_by(just(Token::Comma))
+        .allow_trailing()
+        .delimited_by(just(Token::LeftParen), just(Token::RightParen));
+
+    path().then(fields.or_not()).map_with_span(|(path, fields), span| {
+        match (path.to_ident(), fields) {
+            // A lone identifier binds the whole value rather than naming a variant
+            (Some(name), None) => MatchPattern::Catchall(Pattern::Identifier(name)),
+            (_, fields) => MatchPattern::Variant(path, fields.unwrap_or_default(), span),
+        }
+    })
+}
+
 fn lambda<'a>(
     expr_parser: impl NoirParser<Expression> + 'a,
 ) -> impl NoirParser<ExpressionKind> + 'a {
@@ -974,6 +1188,28 @@ where
         .map_with_span(|((identifier, range), block), span| range.into_for(identifier, block, span))
 }
 
+/// while_expr: 'while' expression ('max' type_expression)? block
+///
+/// `max` is a contextual keyword, so it remains usable as an identifier elsewhere.
+fn while_expr<'a, P>(expr_parser: P) -> impl NoirParser<ExpressionKind> + 'a
+where
+    P: ExprParser + 'a,
+{
+    let max_keyword = filter_map(|span, found: Token| match found {
+        Token::Ident(ref word) if word == "max" => Ok(span),
+        _ => Err(ParserError::expected_label(ParsingRuleLabel::Expression, found, span)),
+    });
+    let max_iterations = max_keyword.ignore_then(type_expression());
+
+    keyword(Keyword::While)
+        .ignore_then(expr_parser.clone())
+        .then(max_iterations.or_not())
+        .then(block_expr(expr_parser))
+        .map(|((condition, max_iterations), block)| {
+            ExpressionKind::While(Box::new(WhileExpression { condition, max_iterations, block }))
+        })
+}
+
 /// The 'range' of a for loop. Either an actual range `start .. end` or an array expression.
 fn for_range<P>(expr_parser: P) -> impl NoirParser<ForRange>
 where
@@ -1069,7 +1305,9 @@ where
 {
     choice((
         if_expr(expr_parser.clone()),
+        match_expr(expr_parser.clone()),
         for_expr(expr_parser.clone()),
+        while_expr(expr_parser.clone()),
         array_expr(expr_parser.clone()),
         constructor(expr_parser.clone()),
         lambda(expr_parser.clone()),
@@ -1083,15 +1321,18 @@ where
     .labelled(ParsingRuleLabel::Atom)
 }
 
-/// Atoms within type expressions are limited to only variables, literals, and parenthesized
-/// type expressions.
+/// Atoms within type expressions are limited to only variables, literals, calls, and
+/// parenthesized type expressions.
 fn type_expression_atom<'a, P>(expr_parser: P) -> impl NoirParser<Expression> + 'a
 where
     P: ExprParser + 'a,
 {
-    variable()
-        .or(literal())
-        .map_with_span(Expression::new)
+    let call = path()
+        .map_with_span(|path, span| Expression::new(ExpressionKind::Variable(path), span))
+        .then(parenthesized(expression_list(expr_parser.clone())))
+        .map_with_span(|(function, arguments), span| Expression::call(function, arguments, span));
+
+    call.or(variable().or(literal()).map_with_span(Expression::new))
         .or(parenthesized(expr_parser))
         .labelled(ParsingRuleLabel::Atom)
 }
@@ -1146,10 +1387,6 @@ fn literal() -> impl NoirParser<ExpressionKind> {
     })
 }
 
-fn literal_or_collection(expr_parser: impl ExprParser) -> impl NoirParser<ExpressionKind> {
-    choice((literal(), constructor(expr_parser.clone()), array_expr(expr_parser)))
-}
-
 #[cfg(test)]
 mod test {
     use noirc_errors::CustomDiagnostic;
@@ -1460,6 +1697,62 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_while_loop() {
+        let loops = parse_all(
+            while_expr(expression()),
+            vec![
+                "while i < len max 32 { i += 1; }",
+                "while done == false max N + 1 {}",
+                "while x {}",
+                "while max < 10 max max_iterations(LEN) { max += 1; }",
+            ],
+        );
+        let bounds = vecmap(loops, |kind| match kind {
+            ExpressionKind::While(while_loop) => while_loop.max_iterations.is_some(),
+            _ => unreachable!("expected a while loop"),
+        });
+        assert_eq!(bounds, vec![true, true, false, true]);
+
+        parse_all_failing(
+            while_expr(expression()),
+            vec!["while max 10 {}", "while x max {}", "while x max 10", "while i < 10 max 5; {}"],
+        );
+    }
+
+    #[test]
+    fn parse_break_and_continue() {
+        let block = parse_with(
+            block(expression()),
+            "{ for i in 0..10 { if i == 2 { continue; } if i == 5 { break } } }",
+        )
+        .unwrap();
+        let loop_body = match &block.0[..] {
+            [Statement::Expression(expr)] => match &expr.kind {
+                ExpressionKind::For(for_loop) => &for_loop.block,
+                _ => unreachable!("expected a for loop"),
+            },
+            _ => unreachable!("expected a single statement"),
+        };
+        let statements = match &loop_body.kind {
+            ExpressionKind::Block(block) => vecmap(&block.0, |statement| match statement {
+                Statement::Expression(expr) => match &expr.kind {
+                    ExpressionKind::If(if_expr) => match &if_expr.consequence.kind {
+                        ExpressionKind::Block(block) => block.0.clone(),
+                        _ => unreachable!("expected a block"),
+                    },
+                    _ => unreachable!("expected an if expression"),
+                },
+                _ => unreachable!("expected an expression statement"),
+            }),
+            _ => unreachable!("expected a block"),
+        };
+        assert!(matches!(&statements[0][..], [Statement::Continue(_)]));
+        assert!(matches!(&statements[1][..], [Statement::Break(_)]));
+
+        parse_all_failing(block(expression()), vec!["{ break 1; }", "{ let x = continue; }"]);
+    }
+
     #[test]
     fn parse_function() {
         parse_all(
@@ -1480,6 +1773,87 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_function_with_trait_bounds() {
+        let cases = vec![
+            "fn hash<T: Hash>(x: T) -> Field { x.hash() }",
+            "fn f<T: Eq + Default, U>(x: T, y: U) {}",
+            "fn f<T>(x: T) where T: Eq {}",
+            "fn f<T, U>(x: T, y: U) -> Field where T: Eq + Hash, U: Default, { 0 }",
+        ];
+        let functions = parse_all(function_definition(false), cases);
+
+        let bound_counts = vecmap(&functions, |function| function.def.where_clause.len());
+        assert_eq!(bound_counts, vec![1, 2, 1, 3]);
+
+        parse_all_failing(
+            function_definition(false),
+            vec!["fn f<T:>(x: T) {}", "fn f<T>(x: T) where {}", "fn f<T>(x: T) where T {}"],
+        );
+    }
+
+    #[test]
+    fn parse_function_types_with_environments() {
+        let cases =
+            vec!["fn(Field) -> Field", "fn[Env](Field, u8) -> Field", "fn[(Field, u8)]() -> ()"];
+        let types = parse_all(parse_type(), cases.clone());
+        assert_eq!(vecmap(types, |typ| typ.to_string()), cases);
+
+        parse_all_failing(parse_type(), vec!["fn[](Field) -> Field", "fn[Env] -> Field"]);
+    }
+
+    #[test]
+    fn parse_compile_time_expressions() {
+        parse_all(
+            global_declaration(),
+            vec![
+                "global TREE_SIZE: u32 = 1 << DEPTH",
+                "global TABLE = make_table(8)",
+                "global ORIGIN = Point { x: 0, y: 0 }",
+            ],
+        );
+
+        let cases = vec!["[Field; tree_size(DEPTH)]", "[u8; 2 * size(N, 3)]", "[u8; N + 1]"];
+        let types = parse_all(parse_type(), cases);
+        let lengths = vecmap(types, |typ| match typ {
+            UnresolvedType::Array(Some(length), _) => length,
+            other => panic!("Expected an array type, found {other}"),
+        });
+        assert!(matches!(lengths[0], UnresolvedTypeExpression::Expression(_)));
+        assert!(matches!(lengths[1], UnresolvedTypeExpression::Expression(_)));
+        assert!(matches!(lengths[2], UnresolvedTypeExpression::BinaryOperation(..)));
+    }
+
+    #[test]
+    fn parse_traits() {
+        let cases = vec![
+            "trait Empty {}",
+            "trait Hash { fn hash(self) -> Field; }",
+            "trait Eq { fn eq(self, other: Self) -> bool; fn neq(self, other: Self) -> bool { !self.eq(other) } }",
+            "trait Default { fn default() -> Self; }",
+            "trait Foo { fn foo<T: Hash>(self, x: T) -> Field; fn bar<U>(u: U) where U: Eq; }",
+        ];
+        parse_all(trait_definition(), cases);
+
+        let failing = vec!["trait { }", "trait Foo { fn foo(self) }", "trait Foo { x: Field }"];
+        parse_all_failing(trait_definition(), failing);
+    }
+
+    #[test]
+    fn parse_trait_impls() {
+        let cases = vec![
+            "impl Hash for Field { fn hash(self) -> Field { self } }",
+            "impl Default for Foo { fn default() -> Self { Foo { x: 0 } } }",
+            "impl<T> Eq for Bar<T> { fn eq(self, other: Self) -> bool { true } }",
+            "impl std::hash::Hash for [Field; 2] {}",
+        ];
+        parse_all(trait_implementation(), cases);
+
+        // An impl without a trait is a regular impl and should not be parsed as a trait impl
+        let failing = vec!["impl Foo { }", "impl Hash for { }"];
+        parse_all_failing(trait_implementation(), failing);
+    }
+
     #[test]
     fn parse_parenthesized_expression() {
         parse_all(atom(expression()), vec!["(0)", "(x+a)", "({(({{({(nested)})}}))})"]);
@@ -1640,6 +2014,44 @@ mod test {
         parse_all_failing(struct_definition(), failing);
     }
 
+    #[test]
+    fn parse_enums() {
+        let cases = vec![
+            "enum Foo { }",
+            "enum Bar { A, B, }",
+            "enum Shape { Circle(Field), Rectangle(Field, Field), Empty }",
+            "enum Option<T> { Some(T), None }",
+        ];
+        parse_all(enum_definition(), cases);
+
+        let failing = vec!["enum {  }", "enum Foo { A: Field }", "enum Foo { A(x: Field) }"];
+        parse_all_failing(enum_definition(), failing);
+    }
+
+    #[test]
+    fn parse_match_expr() {
+        let cases = vec![
+            "match x { }",
+            "match x { Foo::A => 1, Foo::B => 2 }",
+            "match shape { Shape::Circle(r) => r, Shape::Rectangle(w, _) => w, }",
+            "match foo.bar() { Option::Some((a, b)) => { a + b }, _ => 0 }",
+            "match x { other => other }",
+        ];
+
+        let parsed = parse_all(match_expr(expression()), cases);
+
+        // A lone identifier should bind the value instead of naming a variant
+        match parsed.last() {
+            Some(ExpressionKind::Match(match_expr)) => {
+                assert!(matches!(match_expr.rules[0].0, MatchPattern::Catchall(_)));
+            }
+            _ => unreachable!(),
+        }
+
+        let failing = vec!["match x", "match { A => 1 }", "match x { A = 1 }", "match x { A => }"];
+        parse_all_failing(match_expr(expression()), failing);
+    }
+
     #[test]
     fn parse_member_access() {
         let cases = vec!["a.b", "a + b.c", "foo.bar as i32"];